@@ -0,0 +1,48 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> params: vec4<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var tile_textures: texture_2d_array<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var tile_sampler: sampler;
+@group(#{MATERIAL_BIND_GROUP}) @binding(3) var biome_atlas: texture_2d_array<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(4) var atlas_sampler: sampler;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    var color = vec4<f32>(1.0);
+#ifdef VERTEX_COLORS
+    color = mesh.color;
+#endif
+
+    let texture_uv = mesh.world_position.xy / params.y;
+
+#ifdef VERTEX_UVS_B
+    // Textured mode: the atlas already carries each biome's colour and detail
+    if params.z > 0.5 {
+        let atlas_layer = i32(round(mesh.uv_b.x));
+        return vec4<f32>(textureSample(biome_atlas, atlas_sampler, texture_uv, atlas_layer).rgb, color.a);
+    }
+#endif
+
+    let detail_strength = params.x;
+    if detail_strength <= 0.0 {
+        return color;
+    }
+
+    // uv.x is this tile's texture layer, uv.y the layer of its dominant neighbour
+    let own_layer = i32(round(mesh.uv.x));
+    let neighbour_layer = i32(round(mesh.uv.y));
+
+    let own_detail = textureSample(tile_textures, tile_sampler, texture_uv, own_layer).rgb;
+    let neighbour_detail = textureSample(tile_textures, tile_sampler, texture_uv, neighbour_layer).rgb;
+
+    // Fade toward the neighbouring texture near tile edges so borders don't read as hard seams
+    let tile_position = fract(mesh.world_position.xy);
+    let edge_distance = min(
+        min(tile_position.x, 1.0 - tile_position.x),
+        min(tile_position.y, 1.0 - tile_position.y),
+    );
+    let neighbour_weight = (1.0 - smoothstep(0.0, 0.5, edge_distance)) * 0.5;
+    let detail = mix(own_detail, neighbour_detail, neighbour_weight) * 2.0;
+
+    return vec4<f32>(color.rgb * mix(vec3<f32>(1.0), detail, detail_strength), color.a);
+}
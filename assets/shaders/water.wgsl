@@ -0,0 +1,30 @@
+#import bevy_sprite::{mesh2d_vertex_output::VertexOutput, mesh2d_view_bindings::globals}
+
+// x: brightness swing of the waves, y: wave speed, z: foam strength
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> params: vec4<f32>;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    var color = vec4<f32>(0.1, 0.2, 0.6, 1.0);
+#ifdef VERTEX_COLORS
+    color = mesh.color;
+#endif
+
+    let position = mesh.world_position.xy;
+    let time = globals.time * params.y;
+
+    // Two wave trains crossing at an angle so the pattern never lines up with the tile grid
+    let waves = sin(position.x * 0.9 + position.y * 0.4 + time)
+        * sin(position.y * 0.7 - position.x * 0.3 + time * 1.3);
+    var rgb = color.rgb * (1.0 + waves * params.x);
+
+#ifdef VERTEX_UVS_A
+    // uv.x is 1 on corners touching land and fades to 0 across the tile, so
+    // foam hugs the shore; the band surges in and out over time
+    let surge = 0.5 + 0.5 * sin(time * 1.7 + (position.x + position.y) * 1.5);
+    let foam = smoothstep(1.0 - 0.5 * surge, 1.0, mesh.uv.x);
+    rgb = mix(rgb, vec3<f32>(0.9, 0.95, 1.0), foam * params.z);
+#endif
+
+    return vec4<f32>(rgb, color.a);
+}
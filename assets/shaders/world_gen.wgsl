@@ -0,0 +1,262 @@
+// Noise fields for one band of rows, summed the same way `WorldSampler` does on
+// the CPU: fBm terrain over the continental layer, then temperature and moisture
+// from latitude, height and their own noise. Only OpenSimplex layers over noise
+// elevation are covered; other worlds are generated on the CPU.
+
+struct Params {
+    world_size: u32,
+    first_row: u32,
+    rows: u32,
+    // Written ahead of the fields so a readback can tell which band it holds
+    band: u32,
+    octaves: u32,
+    scaling_factor: f32,
+    terrain_scale: f32,
+    continental_scale: f32,
+    temperature_scale: f32,
+    moisture_scale: f32,
+    warp_strength: f32,
+    max_elevation: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// 256 entries for each of the terrain, continental, temperature, moisture and
+// warp generators, taken from the CPU noise so both hash lattice points alike
+@group(0) @binding(1) var<storage, read> permutations: array<u32>;
+// Band number, then elevation, temperature and moisture for each tile
+@group(0) @binding(2) var<storage, read_write> fields: array<f32>;
+
+const TERRAIN: u32 = 0u;
+const CONTINENTAL: u32 = 1u;
+const TEMPERATURE: u32 = 2u;
+const MOISTURE: u32 = 3u;
+const WARP: u32 = 4u;
+
+const TAU: f32 = 6.283185307179586;
+const SEA_BIAS: f32 = 0.075;
+
+const STRETCH: f32 = -0.138196601125011;
+const SQUISH: f32 = 0.309016994374947;
+const NORM: f32 = 0.14556233553707026;
+const DIAG: f32 = 0.5773502691896258;
+const DIAG2: f32 = 0.5;
+// Signs of y, z and w for each pair of corner gradients, three bits a pair
+const CORNER_SIGNS: u32 = 0xfab888u;
+
+// The 64 gradients of the CPU OpenSimplex: 32 edges, then 16 corners twice
+fn lattice_gradient(index: u32) -> vec4<f32> {
+    let i = index % 64u;
+    if i < 32u {
+        // One axis is zero and the other three are signed diagonals
+        let a = select(DIAG, -DIAG, (i & 4u) != 0u);
+        let b = select(DIAG, -DIAG, (i & 2u) != 0u);
+        let c = select(DIAG, -DIAG, (i & 1u) != 0u);
+        switch i / 8u {
+            case 0u: {
+                return vec4<f32>(0.0, a, b, c);
+            }
+            case 1u: {
+                return vec4<f32>(a, 0.0, b, c);
+            }
+            case 2u: {
+                return vec4<f32>(a, b, 0.0, c);
+            }
+            default: {
+                return vec4<f32>(a, b, c, 0.0);
+            }
+        }
+    }
+
+    let corner = i % 16u;
+    let signs = (CORNER_SIGNS >> (3u * (corner / 2u))) & 7u;
+    return vec4<f32>(
+        select(DIAG2, -DIAG2, (corner & 1u) != 0u),
+        select(DIAG2, -DIAG2, (signs & 1u) != 0u),
+        select(DIAG2, -DIAG2, (signs & 2u) != 0u),
+        select(DIAG2, -DIAG2, (signs & 4u) != 0u),
+    );
+}
+
+fn lattice_hash(table: u32, vertex: vec4<i32>) -> u32 {
+    let base = table * 256u;
+    var index = u32(vertex.x & 255);
+    index = permutations[base + index] ^ u32(vertex.y & 255);
+    index = permutations[base + index] ^ u32(vertex.z & 255);
+    index = permutations[base + index] ^ u32(vertex.w & 255);
+    return permutations[base + index];
+}
+
+fn contribution(table: u32, cell: vec4<f32>, relative: vec4<f32>, offset: vec4<f32>) -> f32 {
+    let position = relative - vec4<f32>(SQUISH * dot(offset, vec4<f32>(1.0))) - offset;
+    let t = 2.0 - dot(position, position);
+    if t <= 0.0 {
+        return 0.0;
+    }
+
+    let gradient = lattice_gradient(lattice_hash(table, vec4<i32>(cell + offset)));
+    return t * t * t * t * dot(position, gradient);
+}
+
+// 4D OpenSimplex, region by region as the CPU noise crate walks it
+fn open_simplex(table: u32, point: vec4<f32>) -> f32 {
+    let stretched = point + vec4<f32>(dot(point, vec4<f32>(1.0)) * STRETCH);
+    let cell = floor(stretched);
+    let origin = cell + vec4<f32>(dot(cell, vec4<f32>(1.0)) * SQUISH);
+    let region_sum = dot(stretched - cell, vec4<f32>(1.0));
+    let relative = point - origin;
+
+    var value = 0.0;
+    if region_sum <= 1.0 {
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 0.0, 0.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 0.0, 0.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 1.0, 0.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 0.0, 1.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 0.0, 0.0, 1.0));
+    } else if region_sum >= 3.0 {
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 1.0, 1.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 1.0, 0.0, 1.0));
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 0.0, 1.0, 1.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 1.0, 1.0, 1.0));
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 1.0, 1.0, 1.0));
+    } else {
+        if region_sum <= 2.0 {
+            value += contribution(table, cell, relative, vec4<f32>(1.0, 0.0, 0.0, 0.0));
+            value += contribution(table, cell, relative, vec4<f32>(0.0, 1.0, 0.0, 0.0));
+            value += contribution(table, cell, relative, vec4<f32>(0.0, 0.0, 1.0, 0.0));
+            value += contribution(table, cell, relative, vec4<f32>(0.0, 0.0, 0.0, 1.0));
+        } else {
+            value += contribution(table, cell, relative, vec4<f32>(1.0, 1.0, 1.0, 0.0));
+            value += contribution(table, cell, relative, vec4<f32>(1.0, 1.0, 0.0, 1.0));
+            value += contribution(table, cell, relative, vec4<f32>(1.0, 0.0, 1.0, 1.0));
+            value += contribution(table, cell, relative, vec4<f32>(0.0, 1.0, 1.0, 1.0));
+        }
+        // Both rectified simplexes share the six two-axis vertices
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 1.0, 0.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 0.0, 1.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(1.0, 0.0, 0.0, 1.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 1.0, 1.0, 0.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 1.0, 0.0, 1.0));
+        value += contribution(table, cell, relative, vec4<f32>(0.0, 0.0, 1.0, 1.0));
+    }
+
+    return value * NORM;
+}
+
+// Cosine and sine of a tile's angle around the torus. Whole quarter turns are
+// taken exactly, so tiles on the axes get the zeros the CPU's f64 trig gives
+// rather than f32 rounding of either sign, which lands them in other noise cells.
+fn torus_angle(i: u32) -> vec2<f32> {
+    let quarter = i * 4u / params.world_size;
+    let rest = i * 4u - quarter * params.world_size;
+    let angle = f32(rest) / f32(params.world_size) * (TAU / 4.0);
+    let c = cos(angle);
+    let s = sin(angle);
+    switch quarter {
+        case 0u: { return vec2<f32>(c, s); }
+        case 1u: { return vec2<f32>(-s, c); }
+        case 2u: { return vec2<f32>(-c, -s); }
+        default: { return vec2<f32>(s, -c); }
+    }
+}
+
+fn torus_position(x: u32, y: u32) -> vec4<f32> {
+    return vec4<f32>(torus_angle(x), torus_angle(y)) * params.scaling_factor;
+}
+
+fn warped(position: vec4<f32>) -> vec4<f32> {
+    if params.warp_strength <= 0.0 {
+        return position;
+    }
+
+    let scaled = position * params.terrain_scale;
+    let reach = params.warp_strength * params.scaling_factor;
+    return position + vec4<f32>(
+        open_simplex(WARP, scaled),
+        open_simplex(WARP, scaled + vec4<f32>(17.3)),
+        open_simplex(WARP, scaled + vec4<f32>(34.6)),
+        open_simplex(WARP, scaled + vec4<f32>(51.9)),
+    ) * reach;
+}
+
+fn land_strength(continental: f32) -> f32 {
+    if continental <= -1.0 || continental > 1.0 {
+        return 0.0;
+    }
+    if continental <= -0.5 {
+        return 0.1;
+    }
+    if continental <= 0.0 {
+        return 0.5;
+    }
+    if continental <= 0.5 {
+        return 0.8;
+    }
+    return 1.0;
+}
+
+fn sample_elevation(x: u32, y: u32) -> f32 {
+    let position = warped(torus_position(x, y));
+
+    var scale = params.terrain_scale;
+    var amplitude = 1.0;
+    var terrain = 0.0;
+    var max_amplitude = 0.0;
+    for (var octave = 0u; octave < params.octaves; octave++) {
+        terrain += open_simplex(TERRAIN, position * scale) * amplitude;
+        max_amplitude += amplitude;
+        scale *= 2.0;
+        amplitude /= 2.0;
+    }
+
+    let continental = open_simplex(CONTINENTAL, position * params.continental_scale);
+    let land = terrain / max_amplitude * land_strength(continental);
+    let normalized = (continental - SEA_BIAS) + land;
+    return (normalized + 1.0) / 2.0 * params.max_elevation;
+}
+
+// Distance from the equator in [0, 1]
+fn latitude(y: u32) -> f32 {
+    let half_world = f32(params.world_size) / 2.0;
+    return abs((f32(y) - half_world) / half_world);
+}
+
+fn sample_temperature(x: u32, y: u32, elevation: f32) -> f32 {
+    let position = torus_position(x, y);
+    let from_latitude = 30.0 - 40.0 * latitude(y);
+    let from_elevation = -pow(elevation / params.max_elevation, 1.5) * 15.0;
+    let noise = open_simplex(TEMPERATURE, position * params.temperature_scale) * 5.0;
+    return from_latitude + from_elevation + noise;
+}
+
+fn sample_moisture(x: u32, y: u32, elevation: f32) -> f32 {
+    let position = torus_position(x, y);
+    let base = (open_simplex(MOISTURE, position * params.moisture_scale) + 1.0) / 2.0;
+    let lat = latitude(y);
+
+    let equator_wet = exp(-lat * 3.0);
+    let subtropical_dry = exp(-((lat - 0.3) * (lat - 0.3)) / 0.02);
+    let from_latitude = equator_wet - 0.4 * subtropical_dry;
+    let from_elevation = -(elevation / params.max_elevation) * 0.25;
+
+    return clamp(base + from_latitude + from_elevation, 0.0, 1.0);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let tile = id.x;
+    if tile == 0u {
+        fields[0] = f32(params.band);
+    }
+    if tile >= params.rows * params.world_size {
+        return;
+    }
+
+    let x = tile % params.world_size;
+    let y = params.first_row + tile / params.world_size;
+    let elevation = sample_elevation(x, y);
+
+    let out = 1u + tile * 3u;
+    fields[out] = elevation;
+    fields[out + 1u] = sample_temperature(x, y, elevation);
+    fields[out + 2u] = sample_moisture(x, y, elevation);
+}
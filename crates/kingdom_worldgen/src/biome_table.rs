@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+use crate::world::Biome;
+
+// Compiled in from the game's assets/biomes.ron, so headless runs and builds
+// shipped without the assets folder still know every biome
+const BUILT_IN_BIOME_TABLE: &str = include_str!("../../../assets/biomes.ron");
+// The cheapest a biome may be to cross, as a multiple of open grassland: the
+// price of walking a road
+pub const MIN_MOVEMENT_COST: f32 = 0.5;
+
+// Bounds a tile's climate must fall within. Minimums are inclusive, maximums
+// exclusive; a bound left out is open.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClimateRange {
+    pub min_temperature: Option<f32>,
+    pub max_temperature: Option<f32>,
+    pub min_moisture: Option<f32>,
+    pub max_moisture: Option<f32>,
+    // Fractions of the maximum elevation
+    pub min_elevation: Option<f32>,
+    pub max_elevation: Option<f32>,
+    // Also requires the tile to stand above the world's mountain threshold
+    pub above_mountain_threshold: bool,
+}
+
+impl ClimateRange {
+    fn contains(&self, temperature: f32, moisture: f32, elevation: f32, mountain: f32) -> bool {
+        let within = |value: f32, min: Option<f32>, max: Option<f32>| {
+            min.is_none_or(|min| value >= min) && max.is_none_or(|max| value < max)
+        };
+
+        within(temperature, self.min_temperature, self.max_temperature)
+            && within(moisture, self.min_moisture, self.max_moisture)
+            && within(elevation, self.min_elevation, self.max_elevation)
+            && (!self.above_mountain_threshold || elevation > mountain)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BiomeDefinition {
+    pub biome: Biome,
+    pub color: (f32, f32, f32),
+    // Multiple of the cost of crossing open grassland
+    pub movement_cost: f32,
+    // How much food a tile yields to farmers, 0.0 for none
+    pub fertility: f32,
+    // Land climates classified as this biome; empty for biomes other passes place
+    #[serde(default)]
+    pub climates: Vec<ClimateRange>,
+}
+
+// Biome thresholds, colours, movement costs and fertility, read from
+// biomes.ron. Land is classified by the first definition, in file order,
+// with a climate range that holds it.
+#[derive(Debug)]
+pub struct BiomeTable {
+    definitions: Vec<BiomeDefinition>,
+    // Index into `definitions` for each biome, by `Biome as usize`
+    by_biome: [usize; Biome::ALL.len()],
+}
+
+impl Default for BiomeTable {
+    fn default() -> Self {
+        BiomeTable::parse(BUILT_IN_BIOME_TABLE).unwrap()
+    }
+}
+
+impl BiomeTable {
+    // Every biome needs exactly one definition, with a finite movement cost of
+    // at least MIN_MOVEMENT_COST
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let definitions: Vec<BiomeDefinition> =
+            ron::from_str(text).map_err(|err| err.to_string())?;
+
+        let mut by_biome = [None; Biome::ALL.len()];
+        for (index, definition) in definitions.iter().enumerate() {
+            let cost = definition.movement_cost;
+            if !cost.is_finite() || cost < MIN_MOVEMENT_COST {
+                return Err(format!(
+                    "{} has movement cost {}, below the minimum of {}",
+                    definition.biome.name(),
+                    cost,
+                    MIN_MOVEMENT_COST
+                ));
+            }
+            let slot = &mut by_biome[definition.biome as usize];
+            if slot.is_some() {
+                return Err(format!("{} is defined twice", definition.biome.name()));
+            }
+            *slot = Some(index);
+        }
+        let mut indices = [0; Biome::ALL.len()];
+        for biome in Biome::ALL {
+            indices[biome as usize] = by_biome[biome as usize]
+                .ok_or_else(|| format!("{} has no definition", biome.name()))?;
+        }
+
+        Ok(BiomeTable {
+            definitions,
+            by_biome: indices,
+        })
+    }
+
+    // Biome for a land tile; `elevation` is a fraction of the maximum elevation.
+    // Climates no definition covers fall back to grassland.
+    pub fn classify(
+        &self,
+        temperature: f32,
+        moisture: f32,
+        elevation: f32,
+        mountain_threshold: f32,
+    ) -> Biome {
+        self.definitions
+            .iter()
+            .find(|definition| {
+                definition.climates.iter().any(|climate| {
+                    climate.contains(temperature, moisture, elevation, mountain_threshold)
+                })
+            })
+            .map_or(Biome::Grassland, |definition| definition.biome)
+    }
+
+    pub fn color(&self, biome: Biome) -> [f32; 4] {
+        let (r, g, b) = self.definition(biome).color;
+        [r, g, b, 1.0]
+    }
+
+    pub fn movement_cost(&self, biome: Biome) -> f32 {
+        self.definition(biome).movement_cost
+    }
+
+    pub fn fertility(&self, biome: Biome) -> f32 {
+        self.definition(biome).fertility
+    }
+
+    fn definition(&self, biome: Biome) -> &BiomeDefinition {
+        &self.definitions[self.by_biome[biome as usize]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTAIN_THRESHOLD: f32 = 0.8;
+
+    #[test]
+    fn built_in_table_defines_every_biome() {
+        let table = BiomeTable::default();
+        for biome in Biome::ALL {
+            assert_eq!(table.definition(biome).biome, biome);
+        }
+    }
+
+    #[test]
+    fn climates_classify_by_the_first_matching_definition() {
+        let table = BiomeTable::default();
+        let classify = |temperature, moisture, elevation| {
+            table.classify(temperature, moisture, elevation, MOUNTAIN_THRESHOLD)
+        };
+
+        assert_eq!(classify(-20.0, 0.5, 0.3), Biome::Ice);
+        // Cold enough for snow only on high ground, and ice still wins below -10
+        assert_eq!(classify(-5.0, 0.5, 0.8), Biome::Snow);
+        assert_eq!(classify(-15.0, 0.5, 0.8), Biome::Ice);
+        assert_eq!(classify(10.0, 0.3, 0.3), Biome::Grassland);
+        assert_eq!(classify(25.0, 0.1, 0.3), Biome::HotDesert);
+    }
+
+    #[test]
+    fn tables_missing_or_repeating_a_biome_are_rejected() {
+        let definition = |biome: &str| {
+            format!("(biome: {biome}, color: (0.0, 0.0, 0.0), movement_cost: 1.0, fertility: 0.0)")
+        };
+
+        let missing = format!("[{}]", definition("Ocean"));
+        assert!(BiomeTable::parse(&missing).is_err());
+
+        let all: Vec<String> = Biome::ALL
+            .iter()
+            .map(|biome| definition(&format!("{biome:?}")))
+            .collect();
+        assert!(BiomeTable::parse(&format!("[{}]", all.join(","))).is_ok());
+
+        let repeated = format!("[{},{}]", all.join(","), definition("Ocean"));
+        assert!(BiomeTable::parse(&repeated).is_err());
+    }
+
+    #[test]
+    fn movement_costs_below_a_road_are_rejected() {
+        let table = |cost: &str| {
+            let all: Vec<String> = Biome::ALL
+                .iter()
+                .map(|biome| {
+                    let cost = if *biome == Biome::Grassland { cost } else { "1.0" };
+                    format!(
+                        "(biome: {biome:?}, color: (0.0, 0.0, 0.0), movement_cost: {cost}, fertility: 0.0)"
+                    )
+                })
+                .collect();
+            BiomeTable::parse(&format!("[{}]", all.join(",")))
+        };
+
+        assert!(table("0.5").is_ok());
+        assert!(table("0.1").is_err());
+        assert!(table("-1.0").is_err());
+        assert!(table("inf").is_err());
+        assert!(table("NaN").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+// Generates a whole world to files, without the game. The crate's binary and the
+// game's `--generate` both come here:
+//
+//     kingdom_worldgen [--seed N] [--size 4096] [--out map.png] [--preset world.ron]
+//                      [--biomes biomes.ron] [generation flags]
+//
+// Writes the biome map to the out path, the 16-bit heightmap beside it as
+// `<name>_heightmap.png` and the stats as `<name>.json`. A seed that is not a
+// number is hashed as a phrase. A preset is a world saved from the setup screen;
+// its seed and settings stand unless a flag overrides them, and whatever neither
+// sets keeps the default. Without either the seed is random, and the size is the
+// game's default when left out.
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::{
+    biome_table::BiomeTable,
+    export::write_map_images,
+    generate_logical_world,
+    survey::survey_world,
+    world_data::{GenerationMode, NoiseKind, WorldData, parse_seed},
+};
+
+const DEFAULT_OUT: &str = "map.png";
+// The same sizes the game's setup screen accepts
+const DEFAULT_WORLD_SIZE: i32 = 8192;
+const MIN_WORLD_SIZE: i32 = 256;
+const MAX_WORLD_SIZE: i32 = 16384;
+
+// Flags taking a `WorldData` field, in the setup screen's order, with the kind of
+// value each reads
+const GENERATION_FLAGS: &[(&str, &str)] = &[
+    ("--terrain-scale", "number"),
+    ("--continental-scale", "number"),
+    ("--octaves", "count"),
+    ("--sea-threshold", "number"),
+    ("--mountain-threshold", "number"),
+    ("--temperature-scale", "number"),
+    ("--moisture-scale", "number"),
+    ("--scaling-factor", "number"),
+    ("--erosion-iterations", "count"),
+    ("--erosion-strength", "number"),
+    ("--warp-strength", "number"),
+    ("--mode", "noise|tectonic"),
+    ("--plates", "count"),
+    ("--wind-strength", "number"),
+    ("--trade-wind-limit", "number"),
+    ("--westerly-limit", "number"),
+    ("--terrain-noise", "noise kind"),
+    ("--continental-noise", "noise kind"),
+    ("--temperature-noise", "noise kind"),
+    ("--moisture-noise", "noise kind"),
+    ("--heightmap", "image path"),
+];
+
+// What one run writes beside its images, for picking worlds out of a batch
+#[derive(Serialize)]
+struct Stats {
+    seed: u32,
+    world_size: i32,
+    land_share: f32,
+    biome_count: usize,
+    continents: usize,
+    score: f32,
+    // Share of the map's tiles each biome covers, leaving out those it lacks
+    biomes: BTreeMap<&'static str, f32>,
+    generation_secs: f32,
+}
+
+// `args` is the whole command line, program name first
+pub fn run(args: &[String]) -> Result<(), String> {
+    if args.iter().any(|arg| arg == "--help") {
+        print_usage();
+        return Ok(());
+    }
+
+    let preset = flag_value(args, "--preset");
+    let mut world_data = match &preset {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+            ron::from_str(&text).map_err(|err| format!("{}: {}", path, err))?
+        }
+        None => WorldData::default(),
+    };
+    match flag_value(args, "--seed") {
+        Some(text) => (world_data.seed, world_data.seed_text) = parse_seed(&text),
+        None if preset.is_none() => world_data.seed = rand::random(),
+        None => {}
+    }
+    read_generation_flags(args, &mut world_data)?;
+    if let Some(path) = flag_value(args, "--biomes") {
+        let text = fs::read_to_string(&path).map_err(|err| format!("{}: {}", path, err))?;
+        let table = BiomeTable::parse(&text).map_err(|err| format!("{}: {}", path, err))?;
+        world_data.biomes = Arc::new(table);
+    }
+    let world_size = match flag_value(args, "--size") {
+        Some(text) => parse_world_size(&text)?,
+        None => DEFAULT_WORLD_SIZE,
+    };
+    let out = PathBuf::from(flag_value(args, "--out").unwrap_or(DEFAULT_OUT.to_string()));
+
+    let started = Instant::now();
+    let world_map = generate_logical_world(&world_data, world_size, &(), None)
+        .ok_or("world generation was cancelled")?;
+    let generation_secs = started.elapsed().as_secs_f32();
+
+    let stem = out
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("map");
+    let heightmap_path = out.with_file_name(format!("{}_heightmap.png", stem));
+    let stats_path = out.with_extension("json");
+    if let Some(parent) = out.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|err| format!("{}: {}", parent.display(), err))?;
+    }
+    write_map_images(&world_map, &world_data.biomes, &out, &heightmap_path)?;
+
+    let mut biomes: BTreeMap<&'static str, f32> = BTreeMap::new();
+    let share = 1.0 / world_map.tile_count() as f32;
+    for biome in world_map.biome() {
+        *biomes.entry(biome.name()).or_default() += share;
+    }
+
+    let survey = survey_world(&world_map, world_data.seed);
+    let stats = Stats {
+        seed: world_data.seed,
+        world_size,
+        land_share: survey.land_share,
+        biome_count: survey.biome_count,
+        continents: survey.continents,
+        score: survey.score,
+        biomes,
+        generation_secs,
+    };
+    write_stats(&stats, &stats_path)?;
+
+    println!("{}: score {:.2}", world_data.seed_label(), stats.score);
+    println!("Wrote {}", out.display());
+    println!("Wrote {}", heightmap_path.display());
+    println!("Wrote {}", stats_path.display());
+    Ok(())
+}
+
+fn print_usage() {
+    println!(
+        "Flags: --seed N, --size 4096, --out map.png, --preset world.ron, --biomes biomes.ron"
+    );
+    for (flag, value) in GENERATION_FLAGS {
+        println!("  {} <{}>", flag, value);
+    }
+    println!("Noise kinds: opensimplex, perlin, ridged, worley");
+}
+
+// Value given after a flag, as in `--seed 42`
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|index| args.get(index + 1))
+        .cloned()
+}
+
+fn parsed_flag<T: FromStr>(args: &[String], flag: &str, value: &mut T) -> Result<(), String> {
+    if let Some(text) = flag_value(args, flag) {
+        *value = text
+            .parse()
+            .map_err(|_| format!("{} can't be {:?}", flag, text))?;
+    }
+
+    Ok(())
+}
+
+fn read_generation_flags(args: &[String], data: &mut WorldData) -> Result<(), String> {
+    parsed_flag(args, "--terrain-scale", &mut data.terrain_scale)?;
+    parsed_flag(args, "--continental-scale", &mut data.continental_scale)?;
+    parsed_flag(args, "--octaves", &mut data.num_of_octaves)?;
+    parsed_flag(args, "--sea-threshold", &mut data.sea_threshold)?;
+    parsed_flag(args, "--mountain-threshold", &mut data.mountain_threshold)?;
+    parsed_flag(args, "--temperature-scale", &mut data.temperature_scale)?;
+    parsed_flag(args, "--moisture-scale", &mut data.moisture_scale)?;
+    parsed_flag(args, "--scaling-factor", &mut data.scaling_factor)?;
+    parsed_flag(args, "--erosion-iterations", &mut data.erosion_iterations)?;
+    parsed_flag(args, "--erosion-strength", &mut data.erosion_strength)?;
+    parsed_flag(args, "--warp-strength", &mut data.warp_strength)?;
+    parsed_flag(args, "--plates", &mut data.plate_count)?;
+    parsed_flag(args, "--wind-strength", &mut data.wind_strength)?;
+    parsed_flag(args, "--trade-wind-limit", &mut data.trade_wind_limit)?;
+    parsed_flag(args, "--westerly-limit", &mut data.westerly_limit)?;
+
+    if let Some(text) = flag_value(args, "--mode") {
+        data.generation_mode = match text.to_lowercase().as_str() {
+            "noise" => GenerationMode::Noise,
+            "tectonic" => GenerationMode::Tectonic,
+            _ => return Err(format!("--mode can't be {:?}", text)),
+        };
+    }
+    let layers = [
+        ("--terrain-noise", &mut data.terrain_noise),
+        ("--continental-noise", &mut data.continental_noise),
+        ("--temperature-noise", &mut data.temperature_noise),
+        ("--moisture-noise", &mut data.moisture_noise),
+    ];
+    for (flag, kind) in layers {
+        if let Some(text) = flag_value(args, flag) {
+            *kind = parse_noise_kind(&text).ok_or(format!("{} can't be {:?}", flag, text))?;
+        }
+    }
+    if let Some(path) = flag_value(args, "--heightmap") {
+        data.heightmap = Some(path);
+    }
+
+    Ok(())
+}
+
+fn parse_noise_kind(text: &str) -> Option<NoiseKind> {
+    match text.to_lowercase().as_str() {
+        "opensimplex" => Some(NoiseKind::OpenSimplex),
+        "perlin" => Some(NoiseKind::Perlin),
+        "ridged" | "ridgedmultifractal" => Some(NoiseKind::RidgedMultifractal),
+        "worley" => Some(NoiseKind::Worley),
+        _ => None,
+    }
+}
+
+fn parse_world_size(text: &str) -> Result<i32, String> {
+    match text.parse::<i32>() {
+        Ok(size) if size.count_ones() == 1 && (MIN_WORLD_SIZE..=MAX_WORLD_SIZE).contains(&size) => {
+            Ok(size)
+        }
+        _ => Err(format!(
+            "--size must be a power of two from {} to {}",
+            MIN_WORLD_SIZE, MAX_WORLD_SIZE
+        )),
+    }
+}
+
+fn write_stats(stats: &Stats, path: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(stats).map_err(|err| err.to_string())?;
+    fs::write(path, json).map_err(|err| format!("{}: {}", path.display(), err))
+}
@@ -0,0 +1,45 @@
+use crate::world_data::WorldData;
+
+// How far inland a coast's current is felt: probes every COAST_PROBE_STRIDE tiles,
+// COAST_PROBE_STEPS times in each direction
+const COAST_PROBE_STRIDE: i32 = 6;
+const COAST_PROBE_STEPS: i32 = 5;
+// Peak warming or cooling in °C right on the coast
+const CURRENT_STRENGTH: f64 = 6.0;
+
+// Temperature shift for a land tile from the ocean currents along nearby coasts.
+// Outside the tropics warm currents run up west coasts and cold ones down east
+// coasts; inside the tropics it is the other way round, with cold upwelling on
+// west coasts. `is_ocean_at(dx)` tells whether the tile `dx` tiles east is ocean.
+pub fn coastal_current_offset(
+    latitude: f64,
+    world_data: &WorldData,
+    is_ocean_at: impl Fn(i32) -> bool,
+) -> f64 {
+    let west = coast_exposure(-1, &is_ocean_at);
+    let east = coast_exposure(1, &is_ocean_at);
+
+    if west == 0.0 && east == 0.0 {
+        return 0.0;
+    }
+
+    let band_sign = if latitude.abs() < world_data.trade_wind_limit {
+        -1.0
+    } else {
+        1.0
+    };
+
+    CURRENT_STRENGTH * band_sign * (west - east)
+}
+
+// 1.0 when the first probe in `direction` finds ocean, fading to 0.0 at the edge
+// of the probe range
+fn coast_exposure(direction: i32, is_ocean_at: &impl Fn(i32) -> bool) -> f64 {
+    for step in 1..=COAST_PROBE_STEPS {
+        if is_ocean_at(direction * step * COAST_PROBE_STRIDE) {
+            return 1.0 - (step - 1) as f64 / COAST_PROBE_STEPS as f64;
+        }
+    }
+
+    0.0
+}
@@ -0,0 +1,55 @@
+// Map images for tools outside the game: biome colours straight from the biome
+// table, without the game's palette, and 16-bit elevation
+use std::path::Path;
+
+use image::{ExtendedColorType, ImageBuffer, Luma};
+
+use crate::MAX_ELEVATION;
+use crate::biome_table::BiomeTable;
+use crate::world::WorldMap;
+
+// Rows are flipped so north, the top of the world, is the top of the image
+pub fn write_map_images(
+    world_map: &WorldMap,
+    biomes: &BiomeTable,
+    biome_path: &Path,
+    heightmap_path: &Path,
+) -> Result<(), String> {
+    let size = world_map.width;
+    let north_up = |index: usize| {
+        let (x, y) = (index % size as usize, index / size as usize);
+        (size as usize - 1 - y) * size as usize + x
+    };
+
+    let mut colors = vec![0; world_map.tile_count() * 4];
+    let mut heights = vec![0.0; world_map.tile_count()];
+    for (index, (&biome, &elevation)) in world_map
+        .biome()
+        .iter()
+        .zip(world_map.elevation())
+        .enumerate()
+    {
+        let pixel = north_up(index);
+        let color = biomes
+            .color(biome)
+            .map(|channel| (channel.clamp(0.0, 1.0) * 255.0) as u8);
+        colors[pixel * 4..pixel * 4 + 4].copy_from_slice(&color);
+        heights[pixel] = elevation;
+    }
+
+    image::save_buffer(biome_path, &colors, size, size, ExtendedColorType::Rgba8)
+        .map_err(|err| format!("{}: {}", biome_path.display(), err))?;
+    write_heightmap_png(heightmap_path, size, &heights)
+}
+
+// Sea floor to peak over the full 16-bit range, one pixel per height
+pub fn write_heightmap_png(path: &Path, size: u32, heights: &[f32]) -> Result<(), String> {
+    let levels: Vec<u16> = heights
+        .iter()
+        .map(|height| ((*height as f64 / MAX_ELEVATION).clamp(0.0, 1.0) * 65535.0) as u16)
+        .collect();
+    ImageBuffer::<Luma<u16>, _>::from_raw(size, size, levels)
+        .ok_or("heightmap size mismatch")?
+        .save(path)
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
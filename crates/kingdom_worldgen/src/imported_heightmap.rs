@@ -0,0 +1,75 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::MAX_ELEVATION;
+
+// A grayscale image whose brightness is read as elevation, black as 0 and white
+// as MAX_ELEVATION, matching the heightmap export
+pub struct Heightmap {
+    width: u32,
+    height: u32,
+    levels: Vec<u16>,
+}
+
+// The image read last, with the time it was written. Every sampler of a world
+// shares it rather than decoding the file again.
+type LoadedHeightmap = (String, Option<SystemTime>, Arc<Heightmap>);
+
+static LOADED: Mutex<Option<LoadedHeightmap>> = Mutex::new(None);
+
+impl Heightmap {
+    pub fn load(path: &str) -> Result<Arc<Heightmap>, String> {
+        let modified = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let mut loaded = LOADED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((loaded_path, loaded_modified, heightmap)) = loaded.as_ref()
+            && loaded_path == path
+            && *loaded_modified == modified
+        {
+            return Ok(heightmap.clone());
+        }
+
+        let image = image::open(path)
+            .map_err(|err| err.to_string())?
+            .into_luma16();
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err("the image is empty".to_string());
+        }
+
+        let heightmap = Arc::new(Heightmap {
+            width,
+            height,
+            levels: image.into_raw(),
+        });
+        *loaded = Some((path.to_string(), modified, heightmap.clone()));
+
+        Ok(heightmap)
+    }
+
+    // The image is stretched over the world with bilinear filtering. Its rows run
+    // from the north edge down, the map's from the south edge up.
+    pub fn elevation_at(&self, x: i32, y: i32, world_size: i32) -> f64 {
+        let (width, height) = (self.width, self.height);
+        let level = |column: u32, row: u32| {
+            let index = row.min(height - 1) * width + column.min(width - 1);
+            self.levels[index as usize] as f64 / 65535.0
+        };
+
+        let size = world_size as f64;
+        let x = x.rem_euclid(world_size) as f64 + 0.5;
+        let y = (world_size - 1 - y.rem_euclid(world_size)) as f64 + 0.5;
+        let u = (x / size * width as f64 - 0.5).max(0.0);
+        let v = (y / size * height as f64 - 0.5).max(0.0);
+        let (column, row) = (u as u32, v as u32);
+        let (fx, fy) = (u.fract(), v.fract());
+
+        let top = level(column, row) * (1.0 - fx) + level(column + 1, row) * fx;
+        let bottom = level(column, row + 1) * (1.0 - fx) + level(column + 1, row + 1) * fx;
+        (top * (1.0 - fy) + bottom * fy) * MAX_ELEVATION
+    }
+}
@@ -0,0 +1,815 @@
+// World generation without the game: noise layers, the climate model, biome
+// classification and erosion, from a `WorldData` and a world size to squares.
+// Whole worlds come out of `generate_logical_world` and single chunks of a
+// streamed world out of `generate_chunk_data`; both give the same tiles for the
+// same seed.
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use parallel::*;
+use progress::{GenerationProgress, WorldGenStage};
+use world::{Biome, Square, WorldMap};
+use world_data::WorldData;
+
+pub mod biome_table;
+pub mod cli;
+pub mod currents;
+pub mod export;
+pub mod imported_heightmap;
+pub mod noise_layer;
+pub mod parallel;
+pub mod progress;
+pub mod resources;
+pub mod sampler;
+pub mod survey;
+pub mod tectonics;
+pub mod vegetation;
+pub mod wind;
+pub mod world;
+pub mod world_data;
+
+// Tiles of the neighbouring chunks generated around each chunk, so its edges mesh
+// against theirs
+pub const HALO: i32 = 1;
+pub const MAX_ELEVATION: f64 = 100.0;
+
+const WIND_ADVECTION_STEPS: u32 = 12;
+const WIND_STEP: f64 = 3.0;
+const RAIN_LOSS: f32 = 0.4;
+const SHALLOW_OCEAN_DISTANCE: u8 = 3;
+
+// Squares for one chunk plus a `HALO` ring of neighbouring tiles, row-major. Every
+// value is derived from the seed and absolute tile coordinates, so a chunk that is
+// evicted and regenerated later comes back identical, and matches the same tiles
+// of the whole-map pass.
+pub fn generate_chunk_data(
+    chunk_x: i32,
+    chunk_y: i32,
+    chunk_size: i32,
+    sampler: &sampler::WorldSampler,
+) -> Vec<Square> {
+    let world_size = sampler.world_size();
+    let world_data = sampler.world_data();
+    let origin_x = chunk_x * chunk_size - HALO;
+    let origin_y = chunk_y * chunk_size - HALO;
+    let side = chunk_size + 2 * HALO;
+
+    let elevations = ElevationWindow::new(sampler, origin_x, origin_y, side);
+    let is_ocean = |x: i32, y: i32| {
+        is_below_sea_level(
+            elevations.eroded(origin_x + x, origin_y + y) as f64,
+            world_data.sea_threshold,
+        )
+    };
+
+    let vegetation_noise = vegetation::VegetationNoise::new(world_data, world_size);
+    let resource_noise = resources::ResourceNoise::new(world_data, world_size);
+
+    (0..side * side)
+        .into_par_iter()
+        .map(|i| {
+            let (local_x, local_y) = (i % side, i / side);
+            let (x, y) = (origin_x + local_x, origin_y + local_y);
+
+            let mut square = climate_square_from(sampler, world_data, x, y, &elevations);
+            square.biome = streamed_coast_biome(square.biome, local_x, local_y, &is_ocean);
+
+            let t_position = sampler.t_position(x, y);
+            square.vegetation = vegetation_noise.vegetation_at(t_position, &square);
+            square.biome = vegetation::biome_with_vegetation(square.biome, square.vegetation);
+            square.resources = resource_noise.resource_at(t_position, &square);
+
+            square
+        })
+        .collect()
+}
+
+// Where the per-tile stages read elevation: as sampled, which climate is worked
+// out from, and after thermal erosion, which biomes, coasts and rain shadows see
+trait Elevations: Sync {
+    fn raw(&self, x: i32, y: i32) -> f64;
+    fn eroded(&self, x: i32, y: i32) -> f32;
+}
+
+// Straight from the noise, for surveys that skip erosion
+impl Elevations for sampler::WorldSampler {
+    fn raw(&self, x: i32, y: i32) -> f64 {
+        self.elevation_at(x, y)
+    }
+
+    fn eroded(&self, x: i32, y: i32) -> f32 {
+        self.elevation_at(x, y) as f32
+    }
+}
+
+// Tiles a chunk's climate looks at beyond its own: the far end of a wind chain,
+// which also covers the coastal current probes and the shallow-water cutoff
+const CLIMATE_REACH: i32 = WIND_ADVECTION_STEPS as i32 * WIND_STEP as i32;
+
+// Elevation around a streamed chunk, eroded as the whole-map pass would leave it.
+// A tile after n erosion passes depends on the tiles up to 2n away, so the window
+// is eroded with that much padding, which is then dropped.
+struct ElevationWindow {
+    min_x: i32,
+    min_y: i32,
+    side: i32,
+    raw: Vec<f64>,
+    eroded: Vec<f32>,
+}
+
+impl ElevationWindow {
+    fn new(sampler: &sampler::WorldSampler, origin_x: i32, origin_y: i32, side: i32) -> Self {
+        let world_data = sampler.world_data();
+        let iterations = world_data.erosion_iterations;
+        let margin = 2 * iterations as i32;
+        let (min_x, min_y) = (origin_x - CLIMATE_REACH, origin_y - CLIMATE_REACH);
+        let side = side + 2 * CLIMATE_REACH;
+        let padded_side = side + 2 * margin;
+
+        let padded: Vec<f64> = (0..padded_side * padded_side)
+            .into_par_iter()
+            .map(|i| {
+                let x = min_x - margin + i % padded_side;
+                let y = min_y - margin + i / padded_side;
+                sampler.elevation_at(x, y)
+            })
+            .collect();
+        let mut eroded: Vec<f32> = padded.iter().map(|&elevation| elevation as f32).collect();
+        for _iteration in 0..iterations {
+            thermal_erosion_pass(&mut eroded, padded_side, false, world_data.erosion_strength);
+        }
+
+        let inner = |i: i32| ((i / side + margin) * padded_side + i % side + margin) as usize;
+        ElevationWindow {
+            min_x,
+            min_y,
+            side,
+            raw: (0..side * side).map(|i| padded[inner(i)]).collect(),
+            eroded: (0..side * side).map(|i| eroded[inner(i)]).collect(),
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        ((y - self.min_y) * self.side + x - self.min_x) as usize
+    }
+}
+
+impl Elevations for ElevationWindow {
+    fn raw(&self, x: i32, y: i32) -> f64 {
+        self.raw[self.index(x, y)]
+    }
+
+    fn eroded(&self, x: i32, y: i32) -> f32 {
+        self.eroded[self.index(x, y)]
+    }
+}
+
+// One tile through every per-tile stage up to biome assignment: currents, wind
+// moisture and the climate biome, before coasts and vegetation are worked out.
+// Erosion is left out, so this is for surveying a world rather than showing it.
+pub fn climate_square(
+    sampler: &sampler::WorldSampler,
+    world_data: &WorldData,
+    x: i32,
+    y: i32,
+) -> Square {
+    climate_square_from(sampler, world_data, x, y, sampler)
+}
+
+fn climate_square_from(
+    sampler: &sampler::WorldSampler,
+    world_data: &WorldData,
+    x: i32,
+    y: i32,
+    elevations: &impl Elevations,
+) -> Square {
+    let mut square = sampler.square_with_elevation(x, y, elevations.raw(x, y));
+    if !is_below_sea_level(square.elevation as f64, world_data.sea_threshold) {
+        square.temperature +=
+            currents::coastal_current_offset(sampler.signed_latitude(y), world_data, |dx| {
+                is_below_sea_level(elevations.raw(x + dx, y), world_data.sea_threshold)
+            }) as f32;
+    }
+    square.elevation = elevations.eroded(x, y);
+    square.moisture = streamed_wind_moisture(sampler, x, y, &square, world_data, elevations);
+    square.biome = biome_from_climate(
+        world_data,
+        square.temperature,
+        square.moisture,
+        square.elevation,
+    );
+
+    square
+}
+
+// Same result as `apply_wind_moisture` for one tile: each step only looks at the
+// single tile upwind, so the whole history is a chain that can be walked back
+// from its far end.
+fn streamed_wind_moisture(
+    sampler: &sampler::WorldSampler,
+    x: i32,
+    y: i32,
+    square: &Square,
+    world_data: &WorldData,
+    elevations: &impl Elevations,
+) -> f32 {
+    let wind_strength = world_data.wind_strength.clamp(0.0, 1.0) as f32;
+
+    let mut chain = vec![(square.elevation, square.moisture)];
+    let (mut chain_x, mut chain_y) = (x, y);
+    for _step in 0..WIND_ADVECTION_STEPS {
+        let latitude = sampler.signed_latitude(chain_y);
+        let (dx, dy) = wind::upwind_offset(latitude, world_data, WIND_STEP);
+        chain_x += dx;
+        chain_y += dy;
+
+        let moisture = sampler.moisture_at(chain_x, chain_y, elevations.raw(chain_x, chain_y));
+        chain.push((elevations.eroded(chain_x, chain_y), moisture as f32));
+    }
+
+    let (_, mut moisture) = chain[chain.len() - 1];
+    for pair in chain.windows(2).rev() {
+        let (elevation, base) = pair[0];
+        let (upwind_elevation, _) = pair[1];
+
+        moisture = base + (moisture - base) * wind_strength;
+        let height_diff = (elevation - upwind_elevation) / MAX_ELEVATION as f32;
+
+        if height_diff > 0.0 {
+            moisture -= height_diff * RAIN_LOSS;
+        }
+
+        moisture = moisture.clamp(0.0, 1.0);
+    }
+
+    moisture
+}
+
+// Same rules as `apply_coast_pass`, with the land distance measured directly. A
+// breadth-first ring over 4-neighbours reaches exactly the tiles within that
+// Manhattan distance of land.
+fn streamed_coast_biome(
+    biome: Biome,
+    local_x: i32,
+    local_y: i32,
+    is_ocean: &impl Fn(i32, i32) -> bool,
+) -> Biome {
+    let reach = SHALLOW_OCEAN_DISTANCE as i32;
+
+    if biome == Biome::Ocean {
+        for dy in -reach..=reach {
+            let span = reach - dy.abs();
+            for dx in -span..=span {
+                if !is_ocean(local_x + dx, local_y + dy) {
+                    return Biome::ShallowOcean;
+                }
+            }
+        }
+        return Biome::Ocean;
+    }
+
+    // Frozen shorelines keep their ice rather than getting a beach
+    if matches!(biome, Biome::Ice | Biome::Snow) {
+        return biome;
+    }
+
+    let borders_ocean = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .iter()
+        .any(|(dx, dy)| is_ocean(local_x + dx, local_y + dy));
+
+    if borders_ocean { Biome::Coast } else { biome }
+}
+
+fn apply_wind_moisture(
+    world_map: &mut WorldMap,
+    size: i32,
+    world_data: &WorldData,
+    progress: &impl GenerationProgress,
+) {
+    // Moisture is advected along the prevailing wind a few tiles per step, losing
+    // water wherever the air is forced uphill. That leaves rain shadows on the
+    // downwind side of ranges, whichever way the local wind band blows.
+    let base_moisture = world_map.moisture().to_vec();
+    let wind_strength = world_data.wind_strength.clamp(0.0, 1.0) as f32;
+    let half_size = size as f64 / 2.0;
+
+    for step in 0..WIND_ADVECTION_STEPS {
+        if progress.is_cancelled() {
+            return;
+        }
+        progress.report(
+            WorldGenStage::Climate,
+            step as f32 / WIND_ADVECTION_STEPS as f32,
+        );
+
+        let moisture: Vec<f32> = (0..size * size)
+            .into_par_iter()
+            .map(|i| {
+                let x = i % size;
+                let y = i / size;
+                let latitude = (y as f64 - half_size) / half_size;
+                let (dx, dy) = wind::upwind_offset(latitude, world_data, WIND_STEP);
+
+                let upwind = wrapped_index(x + dx, y + dy, size);
+                let base = base_moisture[i as usize];
+                let elevation = world_map.elevation();
+
+                let mut moisture = base + (world_map.moisture()[upwind] - base) * wind_strength;
+                let rise = elevation[i as usize] - elevation[upwind];
+                let height_diff = rise / MAX_ELEVATION as f32;
+
+                if height_diff > 0.0 {
+                    moisture -= height_diff * RAIN_LOSS;
+                }
+
+                moisture.clamp(0.0, 1.0)
+            })
+            .collect();
+
+        world_map.moisture_mut().copy_from_slice(&moisture);
+    }
+}
+
+// Elevation, temperature and moisture for every tile, row by row, as the sampler
+// gives them before currents, erosion and wind, when they were sampled elsewhere
+// such as on the GPU
+#[derive(Default)]
+pub struct NoiseFields {
+    elevation: Vec<f32>,
+    temperature: Vec<f32>,
+    moisture: Vec<f32>,
+}
+
+impl NoiseFields {
+    pub fn push(&mut self, elevation: f32, temperature: f32, moisture: f32) {
+        self.elevation.push(elevation);
+        self.temperature.push(temperature);
+        self.moisture.push(moisture);
+    }
+
+    pub fn square(&self, index: usize) -> Square {
+        Square {
+            elevation: self.elevation[index],
+            biome: Biome::Ocean, // Temporary, will be set later
+            temperature: self.temperature[index],
+            moisture: self.moisture[index],
+            vegetation: 0.0,
+            resources: world::ResourceKind::None,
+        }
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.elevation.len()
+    }
+}
+
+// Builds the whole map up front: noise, currents, erosion, wind-borne rain, biomes
+// and vegetation. Noise fields already sampled on the GPU replace the first step.
+// Returns None once cancelled.
+pub fn generate_logical_world(
+    world_data: &WorldData,
+    world_size: i32,
+    progress: &impl GenerationProgress,
+    fields: Option<&NoiseFields>,
+) -> Option<WorldMap> {
+    tracing::info!(
+        "Generating world: seed {}, terrain scale {}, continental scale {}, \
+         temperature scale {}, moisture scale {}, {} octaves, sea threshold {}, \
+         mountain threshold {}, scaling factor {}, heightmap {:?}",
+        world_data.seed,
+        world_data.terrain_scale,
+        world_data.continental_scale,
+        world_data.temperature_scale,
+        world_data.moisture_scale,
+        world_data.num_of_octaves,
+        world_data.sea_threshold,
+        world_data.mountain_threshold,
+        world_data.scaling_factor,
+        world_data.heightmap
+    );
+    let sampler = sampler::WorldSampler::new(world_data, world_size);
+
+    progress.report(WorldGenStage::Elevation, 0.0);
+    let rows_done = AtomicU32::new(0);
+
+    let squares: Vec<Square> = (0..world_size * world_size)
+        .into_par_iter()
+        .map(|i: i32| {
+            if let Some(fields) = fields {
+                return fields.square(i as usize);
+            }
+
+            if i % world_size == world_size - 1 {
+                let rows = rows_done.fetch_add(1, Ordering::Relaxed) + 1;
+                // Erosion takes the last fifth of the elevation stage
+                progress.report(
+                    WorldGenStage::Elevation,
+                    rows as f32 / world_size as f32 * 0.8,
+                );
+            }
+
+            sampler.square(i % world_size, i / world_size)
+        })
+        .collect();
+    if progress.is_cancelled() {
+        return None;
+    }
+
+    let mut world_map = WorldMap::from_squares(world_size as u32, world_size as u32, squares);
+
+    // Currents follow the coastline before erosion reshapes it, as they do for a
+    // streamed chunk
+    let raw_elevation = world_map.elevation().to_vec();
+    world_map
+        .temperature_mut()
+        .par_iter_mut()
+        .enumerate()
+        .filter(|(i, _)| !is_below_sea_level(raw_elevation[*i] as f64, world_data.sea_threshold))
+        .for_each(|(i, temperature)| {
+            let (x, y) = (i as i32 % world_size, i as i32 / world_size);
+            *temperature +=
+                currents::coastal_current_offset(sampler.signed_latitude(y), world_data, |dx| {
+                    let elevation = raw_elevation[wrapped_index(x + dx, y, world_size)];
+                    is_below_sea_level(elevation as f64, world_data.sea_threshold)
+                }) as f32;
+        });
+
+    apply_thermal_erosion(
+        world_map.elevation_mut(),
+        world_size,
+        world_data.erosion_iterations,
+        world_data.erosion_strength,
+        progress,
+    );
+
+    apply_wind_moisture(&mut world_map, world_size, world_data, progress);
+    if progress.is_cancelled() {
+        return None;
+    }
+
+    progress.report(WorldGenStage::Biomes, 0.0);
+    let biomes: Vec<Biome> = (0..world_map.tile_count())
+        .into_par_iter()
+        .map(|i| {
+            biome_from_climate(
+                world_data,
+                world_map.temperature()[i],
+                world_map.moisture()[i],
+                world_map.elevation()[i],
+            )
+        })
+        .collect();
+    world_map.biome_mut().copy_from_slice(&biomes);
+
+    progress.report(WorldGenStage::Biomes, 0.3);
+    apply_coast_pass(world_map.biome_mut(), world_size);
+
+    progress.report(WorldGenStage::Biomes, 0.5);
+
+    let vegetation_noise = vegetation::VegetationNoise::new(world_data, world_size);
+    let resource_noise = resources::ResourceNoise::new(world_data, world_size);
+    let finished: Vec<Square> = (0..world_map.tile_count())
+        .into_par_iter()
+        .map(|i| {
+            let t_position = sampler.t_position(i as i32 % world_size, i as i32 / world_size);
+            let mut square = world_map.square(i);
+
+            square.vegetation = vegetation_noise.vegetation_at(t_position, &square);
+            square.biome = vegetation::biome_with_vegetation(square.biome, square.vegetation);
+            square.resources = resource_noise.resource_at(t_position, &square);
+            square
+        })
+        .collect();
+    for (i, square) in finished.iter().enumerate() {
+        world_map.set_square(i, square);
+    }
+    progress.report(WorldGenStage::Biomes, 1.0);
+
+    Some(world_map)
+}
+
+fn apply_thermal_erosion(
+    elevation: &mut [f32],
+    size: i32,
+    iterations: u32,
+    strength: f64,
+    progress: &impl GenerationProgress,
+) {
+    for iteration in 0..iterations {
+        if progress.is_cancelled() {
+            return;
+        }
+        progress.report(
+            WorldGenStage::Elevation,
+            0.8 + 0.2 * iteration as f32 / iterations as f32,
+        );
+
+        thermal_erosion_pass(elevation, size, true, strength);
+    }
+}
+
+// One pass over a `side`×`side` grid. Material slides from a tile to its steepest
+// lower neighbour whenever the drop exceeds the talus threshold. Outflows are
+// computed first and gathered second so every tile reads the same elevations
+// within a pass. The whole map wraps at its edges; a streamed window stops there.
+fn thermal_erosion_pass(elevation: &mut [f32], side: i32, wrap: bool, strength: f64) {
+    let talus = 0.25 * (MAX_ELEVATION / 100.0) as f32;
+    let strength = strength as f32;
+    let offsets = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    let neighbour = |x: i32, y: i32| {
+        if wrap {
+            Some(wrapped_index(x, y, side))
+        } else {
+            ((0..side).contains(&x) && (0..side).contains(&y)).then(|| (y * side + x) as usize)
+        }
+    };
+
+    let outflows: Vec<(usize, f32)> = (0..side * side)
+        .into_par_iter()
+        .map(|i| {
+            let x = i % side;
+            let y = i / side;
+            let here = elevation[i as usize];
+
+            let mut target = i as usize;
+            let mut steepest = talus;
+
+            for (dx, dy) in offsets {
+                let Some(n) = neighbour(x + dx, y + dy) else {
+                    continue;
+                };
+                let drop = here - elevation[n];
+                if drop > steepest {
+                    steepest = drop;
+                    target = n;
+                }
+            }
+
+            (target, (steepest - talus) * 0.5 * strength)
+        })
+        .collect();
+
+    let deltas: Vec<f32> = (0..side * side)
+        .into_par_iter()
+        .map(|i| {
+            let x = i % side;
+            let y = i / side;
+            let (_, outflow) = outflows[i as usize];
+
+            let mut inflow = 0.0;
+            for (dx, dy) in offsets {
+                let Some(n) = neighbour(x + dx, y + dy) else {
+                    continue;
+                };
+                let (target, amount) = outflows[n];
+                if target == i as usize {
+                    inflow += amount;
+                }
+            }
+
+            inflow - outflow
+        })
+        .collect();
+
+    elevation
+        .par_iter_mut()
+        .zip(deltas.par_iter())
+        .for_each(|(elevation, delta)| *elevation += delta);
+}
+
+fn apply_coast_pass(biome: &mut [Biome], size: i32) {
+    let neighbours = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+    // Distance (in tiles) from each ocean tile to the nearest land, grown one ring per
+    // pass up to the shallow-water cutoff. Land is 0, unreached ocean stays at u8::MAX.
+    let mut land_distance: Vec<u8> = biome
+        .par_iter()
+        .map(|biome| if *biome == Biome::Ocean { u8::MAX } else { 0 })
+        .collect();
+
+    for ring in 1..=SHALLOW_OCEAN_DISTANCE {
+        land_distance = (0..size * size)
+            .into_par_iter()
+            .map(|i| {
+                let distance = land_distance[i as usize];
+                if distance != u8::MAX {
+                    return distance;
+                }
+
+                let (x, y) = (i % size, i / size);
+                let touches_ring = neighbours
+                    .iter()
+                    .any(|(dx, dy)| land_distance[wrapped_index(x + dx, y + dy, size)] == ring - 1);
+
+                if touches_ring { ring } else { u8::MAX }
+            })
+            .collect();
+    }
+
+    let biomes: Vec<Biome> = (0..size * size)
+        .into_par_iter()
+        .map(|i| {
+            let biome = biome[i as usize];
+            let distance = land_distance[i as usize];
+
+            if biome == Biome::Ocean {
+                return if distance <= SHALLOW_OCEAN_DISTANCE {
+                    Biome::ShallowOcean
+                } else {
+                    Biome::Ocean
+                };
+            }
+
+            // Frozen shorelines keep their ice rather than getting a beach
+            if matches!(biome, Biome::Ice | Biome::Snow) {
+                return biome;
+            }
+
+            let (x, y) = (i % size, i / size);
+            let borders_ocean = neighbours
+                .iter()
+                .any(|(dx, dy)| land_distance[wrapped_index(x + dx, y + dy, size)] != 0);
+
+            if borders_ocean { Biome::Coast } else { biome }
+        })
+        .collect();
+
+    biome.copy_from_slice(&biomes);
+}
+
+// `sea_threshold` is the world's, as a fraction of the highest peak
+pub fn is_below_sea_level(elevation: f64, sea_threshold: f64) -> bool {
+    elevation < MAX_ELEVATION * sea_threshold
+}
+
+fn wrapped_index(x: i32, y: i32, size: i32) -> usize {
+    (y.rem_euclid(size) * size + x.rem_euclid(size)) as usize
+}
+
+// Land biomes come from the biome table; everything below sea level starts as ocean
+fn biome_from_climate(world_data: &WorldData, temp_c: f32, moisture: f32, elevation: f32) -> Biome {
+    if is_below_sea_level(elevation as f64, world_data.sea_threshold) {
+        return Biome::Ocean;
+    }
+
+    world_data.biomes.classify(
+        temp_c,
+        moisture,
+        elevation / MAX_ELEVATION as f32,
+        world_data.mountain_threshold as f32,
+    )
+}
+
+fn get_land_strength(elevation: f64) -> f64 {
+    match elevation {
+        -1.0 => 0.0,
+        -1.0..=-0.5 => 0.1,
+        -0.5..=0.0 => 0.5,
+        0.0..=0.5 => 0.8,
+        0.5..=1.0 => 1.0,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD_SIZE: i32 = 128;
+    const CHUNK_SIZE: i32 = 16;
+
+    // Streamed chunks, HALO ring included, against the same tiles of the whole map
+    fn assert_chunks_match_whole_map(world_data: &WorldData) {
+        let world_map = generate_logical_world(world_data, WORLD_SIZE, &(), None).unwrap();
+        let sampler = sampler::WorldSampler::new(world_data, WORLD_SIZE);
+        let side = CHUNK_SIZE + 2 * HALO;
+
+        for (chunk_x, chunk_y) in [(0, 0), (3, 5), (7, 7)] {
+            let squares = generate_chunk_data(chunk_x, chunk_y, CHUNK_SIZE, &sampler);
+            for (i, square) in squares.iter().enumerate() {
+                let x = chunk_x * CHUNK_SIZE - HALO + i as i32 % side;
+                let y = chunk_y * CHUNK_SIZE - HALO + i as i32 / side;
+                let whole = world_map.square(wrapped_index(x, y, WORLD_SIZE));
+                assert_eq!(square.elevation, whole.elevation, "elevation at {x}, {y}");
+                assert_eq!(square.moisture, whole.moisture, "moisture at {x}, {y}");
+                assert_eq!(square.biome, whole.biome, "biome at {x}, {y}");
+                assert_eq!(square.resources, whole.resources, "resources at {x}, {y}");
+            }
+        }
+    }
+
+    // One peak on flat ground, eroded on a wrapping grid
+    fn eroded_peak(iterations: u32) -> Vec<f32> {
+        let side = 16;
+        let mut elevation = vec![50.0; (side * side) as usize];
+        elevation[wrapped_index(8, 8, side)] = 90.0;
+        for _iteration in 0..iterations {
+            thermal_erosion_pass(&mut elevation, side, true, 0.5);
+        }
+        elevation
+    }
+
+    #[test]
+    fn erosion_moves_material_without_creating_any() {
+        let before: f32 = eroded_peak(0).iter().sum();
+        let after: f32 = eroded_peak(20).iter().sum();
+        assert!((before - after).abs() < 1e-2, "{before} became {after}");
+    }
+
+    #[test]
+    fn erosion_wears_peaks_down() {
+        let peak = |elevation: Vec<f32>| elevation[wrapped_index(8, 8, 16)];
+        assert!(peak(eroded_peak(1)) < peak(eroded_peak(0)));
+        assert!(peak(eroded_peak(20)) < peak(eroded_peak(1)));
+    }
+
+    #[test]
+    fn erosion_leaves_gentle_slopes_alone() {
+        let side = 16;
+        let mut elevation: Vec<f32> = (0..side * side).map(|i| (i % side) as f32 * 0.1).collect();
+        let before = elevation.clone();
+        thermal_erosion_pass(&mut elevation, side, false, 0.5);
+        assert_eq!(elevation, before);
+    }
+
+    #[test]
+    fn streamed_chunks_match_the_whole_map() {
+        assert_chunks_match_whole_map(&WorldData {
+            seed: 11,
+            ..WorldData::default()
+        });
+    }
+
+    #[test]
+    fn streamed_chunks_match_the_whole_map_after_erosion() {
+        let world_data = WorldData {
+            seed: 11,
+            erosion_iterations: 10,
+            ..WorldData::default()
+        };
+        assert_chunks_match_whole_map(&world_data);
+
+        // And the erosion really moved material, or the match would prove nothing
+        let eroded = generate_logical_world(&world_data, WORLD_SIZE, &(), None).unwrap();
+        let sampler = sampler::WorldSampler::new(&world_data, WORLD_SIZE);
+        let moved = (0..WORLD_SIZE * WORLD_SIZE)
+            .filter(|&i| {
+                let raw = sampler.elevation_at(i % WORLD_SIZE, i / WORLD_SIZE) as f32;
+                eroded.elevation()[i as usize] != raw
+            })
+            .count();
+        assert!(moved > 0);
+    }
+
+    #[test]
+    fn a_higher_sea_threshold_floods_more_land() {
+        let ocean_tiles = |sea_threshold: f64| {
+            let world_data = WorldData {
+                seed: 11,
+                sea_threshold,
+                ..WorldData::default()
+            };
+            let world_map = generate_logical_world(&world_data, WORLD_SIZE, &(), None).unwrap();
+            world_map
+                .biome()
+                .iter()
+                .filter(|biome| matches!(biome, Biome::Ocean | Biome::ShallowOcean))
+                .count()
+        };
+
+        assert!(ocean_tiles(0.42) < ocean_tiles(0.48));
+        assert!(ocean_tiles(0.48) < ocean_tiles(0.54));
+    }
+
+    #[test]
+    fn streamed_chunks_match_the_whole_map_with_a_raised_sea() {
+        assert_chunks_match_whole_map(&WorldData {
+            seed: 11,
+            sea_threshold: 0.53,
+            ..WorldData::default()
+        });
+    }
+
+    #[test]
+    fn streamed_chunks_follow_an_imported_heightmap() {
+        // Black along the west edge of the image up to white along the east
+        let path = std::env::temp_dir().join("kingdom_worldgen_ramp.png");
+        let ramp = image::ImageBuffer::from_fn(32, 32, |column, _| {
+            image::Luma([(column * 65535 / 31) as u16])
+        });
+        ramp.save(&path).unwrap();
+        let world_data = WorldData {
+            seed: 11,
+            heightmap: Some(path.to_string_lossy().into_owned()),
+            ..WorldData::default()
+        };
+        assert_chunks_match_whole_map(&world_data);
+
+        // The first tile of a chunk by the west edge and of one far to the east
+        let sampler = sampler::WorldSampler::new(&world_data, WORLD_SIZE);
+        let corner = (HALO * (CHUNK_SIZE + 2 * HALO) + HALO) as usize;
+        let west = &generate_chunk_data(0, 2, CHUNK_SIZE, &sampler)[corner];
+        let east = &generate_chunk_data(6, 2, CHUNK_SIZE, &sampler)[corner];
+        assert!(matches!(west.biome, Biome::Ocean | Biome::ShallowOcean));
+        assert!(east.elevation > world_data.sea_level() as f32);
+    }
+}
@@ -0,0 +1,15 @@
+// Generates a whole world to files, without the game; see `cli` for the flags:
+//
+//     cargo run --release -p kingdom_worldgen -- [--seed N] [--size 4096] [--out map.png]
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    match kingdom_worldgen::cli::run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("World generation failed: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
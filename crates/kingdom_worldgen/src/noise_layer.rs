@@ -0,0 +1,92 @@
+use noise::{MultiFractal, NoiseFn, OpenSimplex, Perlin, RidgedMulti};
+
+use crate::world_data::NoiseKind;
+
+const RIDGED_OCTAVES: usize = 3;
+
+// One noise field sampled on the 4D torus, returning values in roughly [-1, 1]
+pub trait LayerNoise: Send + Sync {
+    fn sample(&self, point: [f64; 4]) -> f64;
+}
+
+impl LayerNoise for OpenSimplex {
+    fn sample(&self, point: [f64; 4]) -> f64 {
+        self.get(point)
+    }
+}
+
+impl LayerNoise for Perlin {
+    fn sample(&self, point: [f64; 4]) -> f64 {
+        self.get(point)
+    }
+}
+
+impl LayerNoise for RidgedMulti<OpenSimplex> {
+    fn sample(&self, point: [f64; 4]) -> f64 {
+        self.get(point)
+    }
+}
+
+// `noise::Worley` keeps its distance function behind an `Rc`, so it can't be shared
+// with the rayon workers. This is the same idea: distance to the nearest of one
+// hashed feature point per unit cell, checking only the 16 cells on the point's
+// side of each axis.
+pub struct CellNoise {
+    seed: u32,
+}
+
+impl LayerNoise for CellNoise {
+    fn sample(&self, point: [f64; 4]) -> f64 {
+        let cell = point.map(|p| p.floor() as i64);
+        let near = [0, 1, 2, 3].map(|axis| {
+            if point[axis] - cell[axis] as f64 >= 0.5 {
+                1
+            } else {
+                -1
+            }
+        });
+
+        let mut nearest = f64::MAX;
+        for corner in 0..16 {
+            let mut candidate = cell;
+            for axis in 0..4 {
+                if corner & (1 << axis) != 0 {
+                    candidate[axis] += near[axis];
+                }
+            }
+
+            let mut distance = 0.0;
+            for axis in 0..4 {
+                let offset = cell_hash(self.seed, candidate, axis);
+                let delta = candidate[axis] as f64 + offset - point[axis];
+                distance += delta * delta;
+            }
+            nearest = nearest.min(distance);
+        }
+
+        // Feature points are rarely further than one cell away
+        (nearest.sqrt() * 2.0 - 1.0).clamp(-1.0, 1.0)
+    }
+}
+
+// Offset in [0, 1) of a cell's feature point along one axis
+fn cell_hash(seed: u32, cell: [i64; 4], axis: usize) -> f64 {
+    let mut h = (seed as u64) ^ (axis as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    for value in cell {
+        h ^= value as u64;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+    }
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+pub fn layer_noise(kind: NoiseKind, seed: u32) -> Box<dyn LayerNoise> {
+    match kind {
+        NoiseKind::OpenSimplex => Box::new(OpenSimplex::new(seed)),
+        NoiseKind::Perlin => Box::new(Perlin::new(seed)),
+        NoiseKind::RidgedMultifractal => {
+            Box::new(RidgedMulti::<OpenSimplex>::new(seed).set_octaves(RIDGED_OCTAVES))
+        }
+        NoiseKind::Worley => Box::new(CellNoise { seed }),
+    }
+}
@@ -0,0 +1,50 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorldGenStage {
+    #[default]
+    Elevation,
+    Climate,
+    Biomes,
+}
+
+impl WorldGenStage {
+    pub const COUNT: f32 = 3.0;
+    pub const ALL: [WorldGenStage; 3] = [
+        WorldGenStage::Elevation,
+        WorldGenStage::Climate,
+        WorldGenStage::Biomes,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WorldGenStage::Elevation => "Carving continents",
+            WorldGenStage::Climate => "Simulating climate",
+            WorldGenStage::Biomes => "Assigning biomes",
+        }
+    }
+
+    pub fn index(&self) -> f32 {
+        match self {
+            WorldGenStage::Elevation => 0.0,
+            WorldGenStage::Climate => 1.0,
+            WorldGenStage::Biomes => 2.0,
+        }
+    }
+}
+
+// Where a whole-world generation reports how far it has got, and learns whether
+// to stop. Shared with the passes' worker threads.
+pub trait GenerationProgress: Sync {
+    fn report(&self, stage: WorldGenStage, fraction: f32);
+
+    // Checked between passes; generation stops at the next one
+    fn is_cancelled(&self) -> bool;
+}
+
+// Nobody to report to and no way to cancel, as for tests and command-line tools
+impl GenerationProgress for () {
+    fn report(&self, _stage: WorldGenStage, _fraction: f32) {}
+
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,88 @@
+use std::f64::consts::TAU;
+
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::{
+    MAX_ELEVATION,
+    world::{Biome, ResourceKind, Square},
+    world_data::WorldData,
+};
+
+// Rough diameter of a deposit patch, in tiles
+const DEPOSIT_SIZE_TILES: f64 = 30.0;
+
+pub struct ResourceNoise {
+    iron: OpenSimplex,
+    stone: OpenSimplex,
+    gold: OpenSimplex,
+    fertile_soil: OpenSimplex,
+    timber: OpenSimplex,
+    scale: f64,
+}
+
+impl ResourceNoise {
+    pub fn new(world_data: &WorldData, world_size: i32) -> Self {
+        ResourceNoise {
+            iron: OpenSimplex::new(world_data.seed.wrapping_add(4)),
+            stone: OpenSimplex::new(world_data.seed.wrapping_add(5)),
+            gold: OpenSimplex::new(world_data.seed.wrapping_add(6)),
+            fertile_soil: OpenSimplex::new(world_data.seed.wrapping_add(7)),
+            timber: OpenSimplex::new(world_data.seed.wrapping_add(8)),
+            scale: world_size as f64 / (DEPOSIT_SIZE_TILES * world_data.scaling_factor * TAU),
+        }
+    }
+
+    pub fn resource_at(&self, t_position: (f64, f64, f64, f64), square: &Square) -> ResourceKind {
+        if matches!(
+            square.biome,
+            Biome::Ocean | Biome::ShallowOcean | Biome::Ice | Biome::Snow
+        ) {
+            return ResourceKind::None;
+        }
+
+        let (nx, ny, nz, nw) = t_position;
+        let point = [
+            nx * self.scale,
+            ny * self.scale,
+            nz * self.scale,
+            nw * self.scale,
+        ];
+        let height = square.elevation as f64 / MAX_ELEVATION;
+
+        if height > 0.65 && self.gold.get(point) > 0.6 {
+            return ResourceKind::Gold;
+        }
+
+        if height > 0.55 && self.iron.get(point) > 0.45 {
+            return ResourceKind::Iron;
+        }
+
+        if (height > 0.58 || square.biome == Biome::Alpine) && self.stone.get(point) > 0.25 {
+            return ResourceKind::Stone;
+        }
+
+        let forested = matches!(
+            square.biome,
+            Biome::Forest
+                | Biome::BorealForest
+                | Biome::Taiga
+                | Biome::TemperateForest
+                | Biome::TemperateRainforest
+                | Biome::SubtropicalForest
+                | Biome::TropicalRainforest
+        );
+        if forested && self.timber.get(point) > 0.1 {
+            return ResourceKind::Timber;
+        }
+
+        let arable = matches!(
+            square.biome,
+            Biome::Grassland | Biome::TemperateForest | Biome::Savanna | Biome::SubtropicalForest
+        );
+        if arable && square.moisture > 0.4 && self.fertile_soil.get(point) > 0.3 {
+            return ResourceKind::FertileSoil;
+        }
+
+        ResourceKind::None
+    }
+}
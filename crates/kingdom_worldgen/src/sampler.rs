@@ -0,0 +1,287 @@
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::{
+    MAX_ELEVATION, get_land_strength,
+    imported_heightmap::Heightmap,
+    noise_layer::{LayerNoise, layer_noise},
+    tectonics,
+    world::{Biome, ResourceKind, Square},
+    world_data::{GenerationMode, WorldData},
+};
+
+// Evaluates the noise fields for any single tile from the seed and its coordinates
+// alone, so the whole-map pass and streamed chunks produce the same values. The
+// generators are built once here; a streamed world keeps one sampler for all its
+// chunks.
+pub struct WorldSampler {
+    world_data: WorldData,
+    world_size: i32,
+    terrain: Box<dyn LayerNoise>,
+    continental: Box<dyn LayerNoise>,
+    temperature: Box<dyn LayerNoise>,
+    moisture: Box<dyn LayerNoise>,
+    warp: OpenSimplex,
+    plates: Vec<tectonics::Plate>,
+    // Elevation read from the world's heightmap image in place of the noise
+    imported: Option<Arc<Heightmap>>,
+}
+
+impl WorldSampler {
+    pub fn new(world_data: &WorldData, world_size: i32) -> Self {
+        let plates = match world_data.generation_mode {
+            GenerationMode::Tectonic => {
+                tectonics::seed_plates(world_data.seed, world_data.plate_count)
+            }
+            GenerationMode::Noise => Vec::new(),
+        };
+        // An unreadable image falls back to noise terrain rather than failing the world
+        let imported = world_data.heightmap.as_deref().and_then(|path| {
+            Heightmap::load(path)
+                .inspect_err(|err| tracing::error!("Could not import heightmap {}: {}", path, err))
+                .ok()
+        });
+
+        WorldSampler {
+            world_data: world_data.clone(),
+            world_size,
+            terrain: layer_noise(world_data.terrain_noise, world_data.seed),
+            continental: layer_noise(
+                world_data.continental_noise,
+                world_data.seed.wrapping_add(1),
+            ),
+            temperature: layer_noise(
+                world_data.temperature_noise,
+                world_data.seed.wrapping_add(2),
+            ),
+            moisture: layer_noise(world_data.moisture_noise, world_data.seed.wrapping_add(3)),
+            warp: OpenSimplex::new(world_data.seed.wrapping_add(11)),
+            plates,
+            imported,
+        }
+    }
+
+    pub fn world_data(&self) -> &WorldData {
+        &self.world_data
+    }
+
+    pub fn world_size(&self) -> i32 {
+        self.world_size
+    }
+
+    // Point on the 4D torus for a tile; coordinates outside the world wrap around
+    pub fn t_position(&self, x: i32, y: i32) -> (f64, f64, f64, f64) {
+        let size = self.world_size as f64;
+        let u = x.rem_euclid(self.world_size) as f64 / size * TAU;
+        let v = y.rem_euclid(self.world_size) as f64 / size * TAU;
+        let scaling_factor = self.world_data.scaling_factor;
+
+        (
+            u.cos() * scaling_factor,
+            u.sin() * scaling_factor,
+            v.cos() * scaling_factor,
+            v.sin() * scaling_factor,
+        )
+    }
+
+    // Signed latitude in [-1, 1], negative in the half of the map below the equator
+    pub fn signed_latitude(&self, y: i32) -> f64 {
+        let half_world = self.world_size as f64 / 2.0;
+        (y.rem_euclid(self.world_size) as f64 - half_world) / half_world
+    }
+
+    // Distance from the equator in [0, 1]
+    pub fn latitude(&self, y: i32) -> f64 {
+        self.signed_latitude(y).abs()
+    }
+
+    pub fn elevation_at(&self, x: i32, y: i32) -> f64 {
+        if let Some(heightmap) = &self.imported {
+            return heightmap.elevation_at(x, y, self.world_size);
+        }
+
+        let (nx, ny, nz, nw) = self.warped(self.t_position(x, y));
+
+        let mut scale_terrain = self.world_data.terrain_scale;
+        let mut amplitude = 1.0;
+        let mut elevation_terrain = 0.0;
+        let mut max_possible_amplitude = 0.0;
+
+        for _i in 0..self.world_data.num_of_octaves {
+            elevation_terrain += self.terrain.sample([
+                nx * scale_terrain,
+                ny * scale_terrain,
+                nz * scale_terrain,
+                nw * scale_terrain,
+            ]) * amplitude;
+            max_possible_amplitude += amplitude;
+
+            scale_terrain *= 2.0;
+            amplitude /= 2.0;
+        }
+
+        let elevation_continental = match self.world_data.generation_mode {
+            GenerationMode::Noise => {
+                let scale_continental = self.world_data.continental_scale;
+                self.continental.sample([
+                    nx * scale_continental,
+                    ny * scale_continental,
+                    nz * scale_continental,
+                    nw * scale_continental,
+                ])
+            }
+            GenerationMode::Tectonic => tectonics::plate_elevation(
+                &self.plates,
+                x.rem_euclid(self.world_size) as f64 / self.world_size as f64,
+                y.rem_euclid(self.world_size) as f64 / self.world_size as f64,
+            ),
+        };
+
+        let sea_bias = 0.075;
+
+        let elevation_normalized = (elevation_continental - sea_bias)
+            + ((elevation_terrain / max_possible_amplitude)
+                * get_land_strength(elevation_continental));
+
+        ((elevation_normalized + 1.0) / 2.0) * MAX_ELEVATION
+    }
+
+    // Domain warp: push the sample point along a second noise field before reading
+    // elevation, which bends coastlines and ridges out of the plain fBm blobs
+    fn warped(&self, t_position: (f64, f64, f64, f64)) -> (f64, f64, f64, f64) {
+        if self.world_data.warp_strength <= 0.0 {
+            return t_position;
+        }
+
+        let (nx, ny, nz, nw) = t_position;
+        let scale = self.world_data.terrain_scale;
+        let reach = self.world_data.warp_strength * self.world_data.scaling_factor;
+        // Each axis reads the same field at a far-apart offset so the axes move independently
+        let offset = |axis: f64| {
+            let shift = axis * 17.3;
+            self.warp.get([
+                nx * scale + shift,
+                ny * scale + shift,
+                nz * scale + shift,
+                nw * scale + shift,
+            ]) * reach
+        };
+
+        (
+            nx + offset(0.0),
+            ny + offset(1.0),
+            nz + offset(2.0),
+            nw + offset(3.0),
+        )
+    }
+
+    pub fn temperature_at(&self, x: i32, y: i32, elevation: f64) -> f64 {
+        let (nx, ny, nz, nw) = self.t_position(x, y);
+        let scale_temperature = self.world_data.temperature_scale;
+
+        let temperature_latitude = 30.0 - 40.0 * self.latitude(y);
+
+        let h = elevation / MAX_ELEVATION;
+        let temperature_elevation = -h.powf(1.5) * 15.0;
+
+        let temperature_noise_amplitude = 5.0;
+
+        let temperature_noise = self.temperature.sample([
+            nx * scale_temperature,
+            ny * scale_temperature,
+            nz * scale_temperature,
+            nw * scale_temperature,
+        ]) * temperature_noise_amplitude;
+
+        temperature_latitude + temperature_elevation + temperature_noise
+    }
+
+    // Moisture before the prevailing wind carries it anywhere
+    pub fn moisture_at(&self, x: i32, y: i32, elevation: f64) -> f64 {
+        let (nx, ny, nz, nw) = self.t_position(x, y);
+        let scale_moisture = self.world_data.moisture_scale;
+
+        let moisture_noise = self.moisture.sample([
+            nx * scale_moisture,
+            ny * scale_moisture,
+            nz * scale_moisture,
+            nw * scale_moisture,
+        ]);
+
+        let moisture_base = (moisture_noise + 1.0) / 2.0;
+        let latitude = self.latitude(y);
+
+        let equator_wet = (-latitude * 3.0).exp();
+        let subtropical_dry = (-((latitude - 0.3).powi(2)) / 0.02).exp();
+
+        let moisture_latitude = equator_wet - 0.4 * subtropical_dry;
+        let moisture_elevation = -(elevation / MAX_ELEVATION) * 0.25;
+
+        (moisture_base + moisture_latitude + moisture_elevation).clamp(0.0, 1.0)
+    }
+
+    pub fn square(&self, x: i32, y: i32) -> Square {
+        self.square_with_elevation(x, y, self.elevation_at(x, y))
+    }
+
+    // Climate for a tile whose elevation has already been sampled
+    pub fn square_with_elevation(&self, x: i32, y: i32, elevation: f64) -> Square {
+        Square {
+            elevation: elevation as f32,
+            biome: Biome::Ocean, // Temporary, will be set later
+            temperature: self.temperature_at(x, y, elevation) as f32,
+            moisture: self.moisture_at(x, y, elevation) as f32,
+            vegetation: 0.0,
+            resources: ResourceKind::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD_SIZE: i32 = 256;
+    const POINTS: [(i32, i32); 4] = [(0, 0), (17, 200), (128, 64), (255, 255)];
+
+    fn world(seed: u32) -> WorldSampler {
+        let world_data = WorldData {
+            seed,
+            ..WorldData::default()
+        };
+        WorldSampler::new(&world_data, WORLD_SIZE)
+    }
+
+    #[test]
+    fn same_seed_samples_the_same_world() {
+        let (first, second) = (world(7), world(7));
+        for (x, y) in POINTS {
+            let (a, b) = (first.square(x, y), second.square(x, y));
+            assert_eq!(a.elevation, b.elevation);
+            assert_eq!(a.temperature, b.temperature);
+            assert_eq!(a.moisture, b.moisture);
+        }
+    }
+
+    #[test]
+    fn other_seeds_sample_other_worlds() {
+        let (first, second) = (world(7), world(8));
+        assert!(
+            POINTS
+                .iter()
+                .any(|&(x, y)| first.elevation_at(x, y) != second.elevation_at(x, y))
+        );
+    }
+
+    #[test]
+    fn coordinates_wrap_around_the_world() {
+        let sampler = world(7);
+        for (x, y) in POINTS {
+            let elevation = sampler.elevation_at(x, y);
+            assert_eq!(sampler.elevation_at(x + WORLD_SIZE, y), elevation);
+            assert_eq!(sampler.elevation_at(x, y - WORLD_SIZE), elevation);
+        }
+    }
+}
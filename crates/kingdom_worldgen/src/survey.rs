@@ -0,0 +1,95 @@
+// Headline numbers for a generated world, for ranking seeds against each other
+use std::collections::{HashSet, VecDeque};
+
+use crate::world::{Biome, WorldMap};
+use crate::wrapped_index;
+
+// Land share that scores best; mostly-ocean and mostly-land worlds both play flat
+const IDEAL_LAND_SHARE: f32 = 0.35;
+// Share of all tiles a land mass needs to count as a continent rather than an island
+const CONTINENT_SHARE: f32 = 0.015;
+// Continents past this many stop adding to the score
+const CONTINENT_SCORE_CAP: usize = 6;
+// Biomes world generation can place; the settled ones at the end of `Biome` never appear
+const NATURAL_BIOMES: usize = 21;
+
+#[derive(Clone, Debug)]
+pub struct WorldSurvey {
+    pub seed: u32,
+    pub land_share: f32,
+    pub biome_count: usize,
+    pub continents: usize,
+    // Higher is more interesting; see `interest_score`
+    pub score: f32,
+}
+
+fn is_water(biome: Biome) -> bool {
+    matches!(biome, Biome::Ocean | Biome::ShallowOcean)
+}
+
+pub fn survey_world(world_map: &WorldMap, seed: u32) -> WorldSurvey {
+    let biomes = world_map.biome();
+    let land = biomes.iter().filter(|&&biome| !is_water(biome)).count();
+    let land_share = land as f32 / biomes.len() as f32;
+    let biome_count = biomes.iter().collect::<HashSet<_>>().len();
+    let continents = count_continents(world_map);
+
+    WorldSurvey {
+        seed,
+        land_share,
+        biome_count,
+        continents,
+        score: interest_score(land_share, biome_count, continents),
+    }
+}
+
+// Flood-fills the land tiles, wrapping at the world edge, and counts the masses
+// big enough to be continents
+fn count_continents(world_map: &WorldMap) -> usize {
+    let size = world_map.width as i32;
+    let biomes = world_map.biome();
+    let continent_tiles = (biomes.len() as f32 * CONTINENT_SHARE) as usize;
+
+    let mut visited: Vec<bool> = biomes.iter().map(|&biome| is_water(biome)).collect();
+    let mut queue = VecDeque::new();
+    let mut continents = 0;
+
+    for start in 0..biomes.len() {
+        if visited[start] {
+            continue;
+        }
+
+        visited[start] = true;
+        queue.push_back(start);
+        let mut tiles = 0;
+
+        while let Some(index) = queue.pop_front() {
+            tiles += 1;
+            let (x, y) = (index as i32 % size, index as i32 / size);
+
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let next = wrapped_index(x + dx, y + dy, size);
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if tiles >= continent_tiles {
+            continents += 1;
+        }
+    }
+
+    continents
+}
+
+// Each term is in [0, 1]: land share near the ideal, climate variety, and enough
+// separate continents for kingdoms to grow apart
+fn interest_score(land_share: f32, biome_count: usize, continents: usize) -> f32 {
+    let land = 1.0 - ((land_share - IDEAL_LAND_SHARE).abs() / IDEAL_LAND_SHARE).min(1.0);
+    let variety = biome_count as f32 / NATURAL_BIOMES as f32;
+    let spread = continents.min(CONTINENT_SCORE_CAP) as f32 / CONTINENT_SCORE_CAP as f32;
+
+    land + variety + spread
+}
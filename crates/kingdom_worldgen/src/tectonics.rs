@@ -0,0 +1,81 @@
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+const CONTINENTAL_PLATE_CHANCE: f64 = 0.4;
+const BOUNDARY_WIDTH: f64 = 0.025;
+
+pub struct Plate {
+    pub x: f64,
+    pub y: f64,
+    pub drift_x: f64,
+    pub drift_y: f64,
+    pub base_elevation: f64,
+}
+
+pub fn seed_plates(seed: u32, plate_count: u32) -> Vec<Plate> {
+    let mut rng = SmallRng::seed_from_u64(seed as u64);
+
+    (0..plate_count.max(2))
+        .map(|_| {
+            let angle = rng.random_range(0.0..std::f64::consts::TAU);
+            let speed = rng.random_range(0.2..1.0);
+            let base_elevation = if rng.random_bool(CONTINENTAL_PLATE_CHANCE) {
+                rng.random_range(0.1..0.35)
+            } else {
+                rng.random_range(-0.6..-0.3)
+            };
+
+            Plate {
+                x: rng.random::<f64>(),
+                y: rng.random::<f64>(),
+                drift_x: angle.cos() * speed,
+                drift_y: angle.sin() * speed,
+                base_elevation,
+            }
+        })
+        .collect()
+}
+
+// Returns a value in the same [-1, 1] range the continental noise layer produces.
+// `u`/`v` are normalized map coordinates in [0, 1); distances wrap around the torus.
+pub fn plate_elevation(plates: &[Plate], u: f64, v: f64) -> f64 {
+    let mut nearest = (f64::MAX, 0);
+    let mut second = (f64::MAX, 0);
+
+    for (i, plate) in plates.iter().enumerate() {
+        let (dx, dy) = (wrap_delta(plate.x - u), wrap_delta(plate.y - v));
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        if distance < nearest.0 {
+            second = nearest;
+            nearest = (distance, i);
+        } else if distance < second.0 {
+            second = (distance, i);
+        }
+    }
+
+    let own = &plates[nearest.1];
+    let other = &plates[second.1];
+
+    // Direction from our plate's centre toward the neighbouring plate's centre
+    let (nx, ny) = (wrap_delta(other.x - own.x), wrap_delta(other.y - own.y));
+    let length = (nx * nx + ny * ny).sqrt().max(f64::EPSILON);
+    let (nx, ny) = (nx / length, ny / length);
+
+    // Positive when the plates move toward each other, negative when they pull apart
+    let convergence = (own.drift_x - other.drift_x) * nx + (own.drift_y - other.drift_y) * ny;
+
+    let boundary_distance = (second.0 - nearest.0) / 2.0;
+    let boundary_falloff = (-boundary_distance / BOUNDARY_WIDTH).exp();
+
+    let boundary_effect = if convergence > 0.0 {
+        convergence * 0.6
+    } else {
+        convergence * 0.4
+    };
+
+    (own.base_elevation + boundary_effect * boundary_falloff).clamp(-1.0, 1.0)
+}
+
+fn wrap_delta(delta: f64) -> f64 {
+    delta - delta.round()
+}
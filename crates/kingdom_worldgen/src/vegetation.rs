@@ -0,0 +1,59 @@
+use std::f64::consts::TAU;
+
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::{
+    world::{Biome, Square},
+    world_data::WorldData,
+};
+
+// Rough diameter of a forest patch or clearing, in tiles
+const PATCH_SIZE_TILES: f64 = 15.0;
+const FOREST_PATCH_THRESHOLD: f32 = 0.6;
+const CLEARING_THRESHOLD: f32 = 0.3;
+
+pub struct VegetationNoise {
+    patches: OpenSimplex,
+    scale: f64,
+}
+
+impl VegetationNoise {
+    pub fn new(world_data: &WorldData, world_size: i32) -> Self {
+        VegetationNoise {
+            patches: OpenSimplex::new(world_data.seed.wrapping_add(9)),
+            scale: world_size as f64 / (PATCH_SIZE_TILES * world_data.scaling_factor * TAU),
+        }
+    }
+
+    pub fn vegetation_at(&self, t_position: (f64, f64, f64, f64), square: &Square) -> f32 {
+        if matches!(
+            square.biome,
+            Biome::Ocean | Biome::ShallowOcean | Biome::Ice | Biome::Snow
+        ) {
+            return 0.0;
+        }
+
+        let (nx, ny, nz, nw) = t_position;
+        let patch = self.patches.get([
+            nx * self.scale,
+            ny * self.scale,
+            nz * self.scale,
+            nw * self.scale,
+        ]) as f32;
+        let patch = (patch + 1.0) / 2.0;
+
+        // Plants need both water and warmth; the cold end of the range is barren
+        let warmth = ((square.temperature + 10.0) / 30.0).clamp(0.0, 1.0);
+        let climate = square.moisture * warmth;
+
+        (climate * (0.6 + 0.8 * patch)).clamp(0.0, 1.0)
+    }
+}
+
+pub fn biome_with_vegetation(biome: Biome, vegetation: f32) -> Biome {
+    match biome {
+        Biome::Grassland | Biome::Savanna if vegetation > FOREST_PATCH_THRESHOLD => Biome::Forest,
+        Biome::TemperateForest if vegetation < CLEARING_THRESHOLD => Biome::Grassland,
+        _ => biome,
+    }
+}
@@ -0,0 +1,29 @@
+use crate::world_data::WorldData;
+
+// Prevailing wind for a signed latitude in [-1, 1] (0 at the equator), as the
+// direction the air moves toward. Trade winds and polar easterlies blow west,
+// westerlies blow east; each band also carries a small meridional component.
+pub fn prevailing_wind(latitude: f64, world_data: &WorldData) -> (f64, f64) {
+    let hemisphere = if latitude < 0.0 { -1.0 } else { 1.0 };
+    let band_latitude = latitude.abs();
+
+    let (zonal, meridional): (f64, f64) = if band_latitude < world_data.trade_wind_limit {
+        (-1.0, -0.3 * hemisphere)
+    } else if band_latitude < world_data.westerly_limit {
+        (1.0, 0.3 * hemisphere)
+    } else {
+        (-1.0, -0.3 * hemisphere)
+    };
+
+    let length = (zonal * zonal + meridional * meridional).sqrt();
+    (zonal / length, meridional / length)
+}
+
+// Tile offset `distance` tiles upwind of a tile, i.e. where its air arrives from.
+pub fn upwind_offset(latitude: f64, world_data: &WorldData, distance: f64) -> (i32, i32) {
+    let (wind_x, wind_y) = prevailing_wind(latitude, world_data);
+    (
+        (-wind_x * distance).round() as i32,
+        (-wind_y * distance).round() as i32,
+    )
+}
@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+
+// Each tile field lives in its own layer, indexed row by row as `y * width + x`,
+// so passes over one field stay on contiguous memory
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+pub struct WorldMap {
+    pub width: u32,
+    pub height: u32,
+    elevation: Vec<f32>,
+    temperature: Vec<f32>,
+    moisture: Vec<f32>,
+    vegetation: Vec<f32>,
+    biome: Vec<Biome>,
+    resources: Vec<ResourceKind>,
+}
+
+impl WorldMap {
+    pub fn from_squares(width: u32, height: u32, squares: Vec<Square>) -> Self {
+        let mut world_map = WorldMap {
+            width,
+            height,
+            elevation: Vec::with_capacity(squares.len()),
+            temperature: Vec::with_capacity(squares.len()),
+            moisture: Vec::with_capacity(squares.len()),
+            vegetation: Vec::with_capacity(squares.len()),
+            biome: Vec::with_capacity(squares.len()),
+            resources: Vec::with_capacity(squares.len()),
+        };
+        for square in squares {
+            world_map.elevation.push(square.elevation);
+            world_map.temperature.push(square.temperature);
+            world_map.moisture.push(square.moisture);
+            world_map.vegetation.push(square.vegetation);
+            world_map.biome.push(square.biome);
+            world_map.resources.push(square.resources);
+        }
+        world_map
+    }
+
+    pub fn tile_count(&self) -> usize {
+        self.biome.len()
+    }
+
+    // Gathers one tile from every layer
+    pub fn square(&self, index: usize) -> Square {
+        Square {
+            biome: self.biome[index],
+            elevation: self.elevation[index],
+            temperature: self.temperature[index],
+            moisture: self.moisture[index],
+            vegetation: self.vegetation[index],
+            resources: self.resources[index],
+        }
+    }
+
+    pub fn set_square(&mut self, index: usize, square: &Square) {
+        self.biome[index] = square.biome;
+        self.elevation[index] = square.elevation;
+        self.temperature[index] = square.temperature;
+        self.moisture[index] = square.moisture;
+        self.vegetation[index] = square.vegetation;
+        self.resources[index] = square.resources;
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = Square> + '_ {
+        (0..self.tile_count()).map(|index| self.square(index))
+    }
+
+    pub fn elevation(&self) -> &[f32] {
+        &self.elevation
+    }
+
+    pub fn elevation_mut(&mut self) -> &mut [f32] {
+        &mut self.elevation
+    }
+
+    pub fn temperature(&self) -> &[f32] {
+        &self.temperature
+    }
+
+    pub fn temperature_mut(&mut self) -> &mut [f32] {
+        &mut self.temperature
+    }
+
+    pub fn moisture(&self) -> &[f32] {
+        &self.moisture
+    }
+
+    pub fn moisture_mut(&mut self) -> &mut [f32] {
+        &mut self.moisture
+    }
+
+    pub fn biome(&self) -> &[Biome] {
+        &self.biome
+    }
+
+    pub fn biome_mut(&mut self) -> &mut [Biome] {
+        &mut self.biome
+    }
+}
+
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+#[derive(Default, Clone)]
+pub struct Square {
+    pub biome: Biome,
+    pub elevation: f32,
+    pub temperature: f32,
+    pub moisture: f32,
+    pub vegetation: f32,
+    pub resources: ResourceKind,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub enum ResourceKind {
+    #[default]
+    None,
+    Iron,
+    Stone,
+    Gold,
+    FertileSoil,
+    Timber,
+}
+
+impl ResourceKind {
+    // In declaration order, so `ALL[kind as usize] == kind`
+    pub const ALL: [ResourceKind; 6] = [
+        ResourceKind::None,
+        ResourceKind::Iron,
+        ResourceKind::Stone,
+        ResourceKind::Gold,
+        ResourceKind::FertileSoil,
+        ResourceKind::Timber,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceKind::None => "None",
+            ResourceKind::Iron => "Iron",
+            ResourceKind::Stone => "Stone",
+            ResourceKind::Gold => "Gold",
+            ResourceKind::FertileSoil => "Fertile Soil",
+            ResourceKind::Timber => "Timber",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Biome {
+    #[default]
+    Ocean,
+    ShallowOcean,
+    Coast,
+    Grassland,
+    Forest,
+    Desert,
+    Hill,
+    Mountain,
+    Ice,
+    Alpine,
+    Snow,
+    Tundra,
+    BorealForest,
+    Taiga,
+    ColdDesert,
+    TemperateForest,
+    TemperateRainforest,
+    HotDesert,
+    Savanna,
+    SubtropicalForest,
+    TropicalRainforest,
+    // Land settled and worked by a growing settlement; world generation never
+    // places these
+    Farmland,
+    Village,
+    Urban,
+}
+
+impl Biome {
+    // In declaration order, so `ALL[biome as usize] == biome`
+    pub const ALL: [Biome; 24] = [
+        Biome::Ocean,
+        Biome::ShallowOcean,
+        Biome::Coast,
+        Biome::Grassland,
+        Biome::Forest,
+        Biome::Desert,
+        Biome::Hill,
+        Biome::Mountain,
+        Biome::Ice,
+        Biome::Alpine,
+        Biome::Snow,
+        Biome::Tundra,
+        Biome::BorealForest,
+        Biome::Taiga,
+        Biome::ColdDesert,
+        Biome::TemperateForest,
+        Biome::TemperateRainforest,
+        Biome::HotDesert,
+        Biome::Savanna,
+        Biome::SubtropicalForest,
+        Biome::TropicalRainforest,
+        Biome::Farmland,
+        Biome::Village,
+        Biome::Urban,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Biome::Ocean => "Ocean",
+            Biome::ShallowOcean => "Shallow Ocean",
+            Biome::Coast => "Coast",
+            Biome::Grassland => "Grassland",
+            Biome::Forest => "Forest",
+            Biome::Desert => "Desert",
+            Biome::Hill => "Hill",
+            Biome::Mountain => "Mountain",
+            Biome::Ice => "Ice",
+            Biome::Alpine => "Alpine",
+            Biome::Snow => "Snow",
+            Biome::Tundra => "Tundra",
+            Biome::BorealForest => "Boreal Forest",
+            Biome::Taiga => "Taiga",
+            Biome::ColdDesert => "Cold Desert",
+            Biome::TemperateForest => "Temperate Forest",
+            Biome::TemperateRainforest => "Temperate Rainforest",
+            Biome::HotDesert => "Hot Desert",
+            Biome::Savanna => "Savanna",
+            Biome::SubtropicalForest => "Subtropical Forest",
+            Biome::TropicalRainforest => "Tropical Rainforest",
+            Biome::Farmland => "Farmland",
+            Biome::Village => "Village",
+            Biome::Urban => "Urban",
+        }
+    }
+}
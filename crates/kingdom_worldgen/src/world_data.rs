@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::MAX_ELEVATION;
+use crate::biome_table::BiomeTable;
+
+// Saved presets store this as RON; fields missing from older files take their
+// defaults
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldData {
+    pub seed: u32,
+    // Phrase typed in the seed field when it was not a number; `seed` is its hash
+    pub seed_text: Option<String>,
+    pub terrain_scale: f64,
+    pub continental_scale: f64,
+    pub num_of_octaves: u32,
+    // Fraction of the maximum elevation the sea rises to
+    pub sea_threshold: f64,
+    // Fraction of the maximum elevation above which cold land turns alpine
+    pub mountain_threshold: f64,
+    pub temperature_scale: f64,
+    pub moisture_scale: f64,
+    pub scaling_factor: f64,
+    pub erosion_iterations: u32,
+    pub erosion_strength: f64,
+    // How far the elevation sample point is pushed by the warp field, as a fraction
+    // of the torus radius; 0.0 disables domain warping
+    pub warp_strength: f64,
+    pub generation_mode: GenerationMode,
+    pub plate_count: u32,
+    pub wind_strength: f64,
+    pub trade_wind_limit: f64,
+    pub westerly_limit: f64,
+    pub terrain_noise: NoiseKind,
+    pub continental_noise: NoiseKind,
+    pub temperature_noise: NoiseKind,
+    pub moisture_noise: NoiseKind,
+    // Grayscale image whose brightness replaces the noise elevation, black as the
+    // deepest sea floor and white as the highest peak
+    pub heightmap: Option<String>,
+    // Biome thresholds, colours and costs the world is built with; not saved, as
+    // it is taken from assets/biomes.ron
+    #[serde(skip)]
+    pub biomes: Arc<BiomeTable>,
+}
+
+impl Default for WorldData {
+    fn default() -> Self {
+        WorldData {
+            seed: 0,
+            seed_text: None,
+            terrain_scale: 0.005,
+            continental_scale: 0.0005,
+            num_of_octaves: 4,
+            sea_threshold: 0.48,
+            mountain_threshold: 0.6,
+            temperature_scale: 0.005,
+            moisture_scale: 0.008,
+            scaling_factor: 100.0,
+            erosion_iterations: 0,
+            erosion_strength: 0.5,
+            warp_strength: 0.0,
+            generation_mode: GenerationMode::Noise,
+            plate_count: 12,
+            wind_strength: 0.85,
+            trade_wind_limit: 0.33,
+            westerly_limit: 0.66,
+            terrain_noise: NoiseKind::OpenSimplex,
+            continental_noise: NoiseKind::OpenSimplex,
+            temperature_noise: NoiseKind::OpenSimplex,
+            moisture_noise: NoiseKind::OpenSimplex,
+            heightmap: None,
+            biomes: Arc::default(),
+        }
+    }
+}
+
+impl WorldData {
+    // The seed as players share it: the phrase, if there was one, and the number
+    // it resolved to
+    pub fn seed_label(&self) -> String {
+        match &self.seed_text {
+            Some(text) => format!("Seed: \"{}\" ({})", text, self.seed),
+            None => format!("Seed: {}", self.seed),
+        }
+    }
+
+    // Elevation of the sea surface
+    pub fn sea_level(&self) -> f64 {
+        MAX_ELEVATION * self.sea_threshold
+    }
+}
+
+// A number is used as the seed itself; any other text is hashed with 32-bit FNV-1a,
+// so a phrase always gives the same world
+pub fn parse_seed(text: &str) -> (u32, Option<String>) {
+    if let Ok(seed) = text.parse::<u32>() {
+        return (seed, None);
+    }
+
+    let hash = text.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+    (hash, Some(text.to_string()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GenerationMode {
+    #[default]
+    Noise,
+    Tectonic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NoiseKind {
+    #[default]
+    OpenSimplex,
+    Perlin,
+    RidgedMultifractal,
+    Worley,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_seeds_are_used_as_they_are() {
+        assert_eq!(parse_seed("12345"), (12345, None));
+        assert_eq!(parse_seed("4294967295"), (u32::MAX, None));
+    }
+
+    #[test]
+    fn text_seeds_hash_the_same_every_time() {
+        let (seed, text) = parse_seed("Misty Isles");
+        assert_eq!(parse_seed("Misty Isles"), (seed, text.clone()));
+        assert_eq!(text.as_deref(), Some("Misty Isles"));
+        assert_ne!(parse_seed("Misty Isle").0, seed);
+        // FNV-1a of the empty string is its offset basis
+        assert_eq!(parse_seed("").0, 0x811c9dc5);
+        // Too big for a u32, so hashed rather than wrapped
+        assert_eq!(parse_seed("4294967296").1.as_deref(), Some("4294967296"));
+    }
+}
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AmbientKind {
+    BirdFlock,
+    WhaleSpout,
+    HeatShimmer,
+}
+
+#[derive(Component)]
+pub struct AmbientEffect {
+    pub kind: AmbientKind,
+    pub age: f32,
+    pub lifetime: f32,
+    pub velocity: Vec2,
+}
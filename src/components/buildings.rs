@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::kingdoms::Era;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum BuildingKind {
+    Farm,
+    Mine,
+    LumberCamp,
+    Fort,
+    Harbour,
+}
+
+impl BuildingKind {
+    pub const ALL: [BuildingKind; 5] = [
+        BuildingKind::Farm,
+        BuildingKind::Mine,
+        BuildingKind::LumberCamp,
+        BuildingKind::Fort,
+        BuildingKind::Harbour,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildingKind::Farm => "Farm",
+            BuildingKind::Mine => "Mine",
+            BuildingKind::LumberCamp => "Lumber camp",
+            BuildingKind::Fort => "Fort",
+            BuildingKind::Harbour => "Harbour",
+        }
+    }
+
+    // Era the kingdom of the settlement it works for must have reached
+    pub fn era(&self) -> Era {
+        match self {
+            BuildingKind::Farm | BuildingKind::LumberCamp | BuildingKind::Fort => Era::Stone,
+            BuildingKind::Mine => Era::Bronze,
+            BuildingKind::Harbour => Era::Iron,
+        }
+    }
+}
+
+// Something the player has built; `tile` is in base world coordinates
+#[derive(Component)]
+pub struct Building {
+    pub kind: BuildingKind,
+    pub tile: IVec2,
+}
+
+// Row of building buttons along the bottom of the screen
+#[derive(Component)]
+pub struct BuildToolbar;
+
+#[derive(Component)]
+pub struct BuildButton(pub BuildingKind);
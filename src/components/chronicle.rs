@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// What a chronicle entry tells of, for filtering the history
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ChronicleKind {
+    Founding,
+    War,
+    Peace,
+    Conquest,
+    Famine,
+    Disaster,
+    Discovery,
+}
+
+impl ChronicleKind {
+    pub const ALL: [ChronicleKind; 7] = [
+        ChronicleKind::Founding,
+        ChronicleKind::War,
+        ChronicleKind::Peace,
+        ChronicleKind::Conquest,
+        ChronicleKind::Famine,
+        ChronicleKind::Disaster,
+        ChronicleKind::Discovery,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChronicleKind::Founding => "Foundings",
+            ChronicleKind::War => "Wars",
+            ChronicleKind::Peace => "Treaties",
+            ChronicleKind::Conquest => "Conquests",
+            ChronicleKind::Famine => "Famines",
+            ChronicleKind::Disaster => "Disasters",
+            ChronicleKind::Discovery => "Discoveries",
+        }
+    }
+}
+
+// One line of history, dated by the game calendar
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChronicleEntry {
+    pub date: String,
+    pub kind: ChronicleKind,
+    pub text: String,
+}
+
+#[derive(Component)]
+pub struct HistoryPanel;
+
+// Scrolling box the entries are listed in
+#[derive(Component)]
+pub struct HistoryList;
+
+#[derive(Component)]
+pub struct HistoryText;
+
+// Cycles the kind of entries shown; the label reads the current filter
+#[derive(Component)]
+pub struct HistoryFilterButton;
+
+#[derive(Component)]
+pub struct HistoryExportButton;
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct DiagnosticsOverlay;
+
+#[derive(Component)]
+pub struct DiagnosticsText;
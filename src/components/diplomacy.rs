@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Treaty {
+    #[default]
+    Peace,
+    Alliance,
+    War,
+}
+
+impl Treaty {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Treaty::Peace => "At peace",
+            Treaty::Alliance => "Allied",
+            Treaty::War => "At war",
+        }
+    }
+}
+
+// How two kingdoms stand with each other. The score runs from -100, bitter
+// enemies, to 100, firm friends.
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct Relation {
+    pub score: f32,
+    pub treaty: Treaty,
+}
+
+#[derive(Component)]
+pub struct DiplomacyPanel;
+
+#[derive(Component)]
+pub struct DiplomacyPanelText;
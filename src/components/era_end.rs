@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+// How the age being watched came to an end
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EraOutcome {
+    // One kingdom holds nearly every settlement in the world
+    Unification,
+    // Every kingdom has fallen, or almost everyone has died
+    Collapse,
+    // The world's people have stayed far below their peak for years
+    DarkAge,
+}
+
+// Shown once when the era ends; a click puts it away and the world runs on
+#[derive(Component)]
+pub struct EraSummaryPanel;
+
+#[derive(Component)]
+pub struct EraSummaryText;
@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::components::settlements::Good;
+use crate::components::world::Biome;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DisasterKind {
+    Drought,
+    Flood,
+    Eruption,
+    HarshWinter,
+}
+
+impl DisasterKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisasterKind::Drought => "Drought",
+            DisasterKind::Flood => "Flood",
+            DisasterKind::Eruption => "Eruption",
+            DisasterKind::HarshWinter => "Harsh winter",
+        }
+    }
+
+    // Share of its usual output a settlement caught in the disaster still
+    // manages, indexed like `Stockpile`
+    pub fn output_share(&self) -> [f32; Good::ALL.len()] {
+        match self {
+            DisasterKind::Drought => [0.3, 0.9, 0.7, 1.0, 1.0, 1.0],
+            DisasterKind::Flood => [0.5, 0.8, 0.9, 1.0, 1.0, 1.0],
+            DisasterKind::Eruption => [0.2, 0.5, 0.2, 0.2, 1.0, 0.5],
+            DisasterKind::HarshWinter => [0.5, 0.5, 0.6, 0.8, 1.0, 1.0],
+        }
+    }
+
+    // Share of the people caught when it strikes who are killed or flee
+    pub fn toll(&self) -> f32 {
+        match self {
+            DisasterKind::Drought => 0.0,
+            DisasterKind::Flood => 0.03,
+            DisasterKind::Eruption => 0.1,
+            DisasterKind::HarshWinter => 0.02,
+        }
+    }
+
+    // How long it lasts, in years
+    pub fn duration(&self) -> f32 {
+        match self {
+            DisasterKind::Drought => 1.0,
+            DisasterKind::Flood => 0.2,
+            DisasterKind::Eruption => 0.5,
+            DisasterKind::HarshWinter => 0.25,
+        }
+    }
+
+    // Tiles from where it struck that it reaches
+    pub fn radius(&self) -> f32 {
+        match self {
+            DisasterKind::Drought => 20.0,
+            DisasterKind::Flood => 8.0,
+            DisasterKind::Eruption => 25.0,
+            DisasterKind::HarshWinter => 30.0,
+        }
+    }
+}
+
+// A disaster under way around `tile`, named after the place it struck
+#[derive(Component)]
+pub struct Disaster {
+    pub kind: DisasterKind,
+    pub tile: IVec2,
+    pub place: String,
+    // Simulated seconds until it passes
+    pub remaining: f32,
+}
+
+// How dry and how low the land under a settlement lies, for the disasters it
+// is prone to
+#[derive(Component)]
+pub struct LocalClimate {
+    pub biome: Biome,
+    pub moisture: f32,
+    // Above the world's sea level
+    pub height: f32,
+}
+
+// Column of notifications under the clock
+#[derive(Component)]
+pub struct NotificationArea;
+
+// One line in the notification column. Fades out over its last seconds.
+#[derive(Component)]
+pub struct Notification {
+    pub remaining: f32,
+}
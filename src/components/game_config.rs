@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 
+use crate::systems::localization::Locale;
+
+// The outline stays clear until the field takes focus
 #[derive(Component)]
+#[require(Outline = Outline::new(Val::Px(2.0), Val::ZERO, Color::NONE))]
 pub struct TextInput;
 
 #[derive(Component)]
@@ -11,9 +15,113 @@ pub struct InputValue {
     pub text: String,
 }
 
+// Shown greyed out while a text input is empty; it is also the value an empty
+// input generates with
+#[derive(Component)]
+pub struct Placeholder(pub String);
+
 #[derive(Component)]
 pub struct GameConfigUI;
 
+// What a typed field accepts. Empty text always passes, since the field then
+// generates with its placeholder.
+#[derive(Component, Clone, Copy)]
+pub enum FieldRule {
+    // Any text; only all-digit text has to fit in a seed
+    Seed,
+    // A number above zero
+    Positive,
+    // A whole number within min..=max
+    Count { min: u32, max: u32 },
+    // A power of two within min..=max
+    PowerOfTwo { min: i32, max: i32 },
+    // Path to a file that exists
+    ExistingFile,
+}
+
+impl FieldRule {
+    // The message to show under the field, or None when the text is acceptable
+    pub fn check(&self, text: &str, locale: &Locale) -> Option<String> {
+        if text.is_empty() {
+            return None;
+        }
+
+        match *self {
+            FieldRule::Seed if !text.bytes().all(|byte| byte.is_ascii_digit()) => None,
+            FieldRule::Seed => match text.parse::<u32>() {
+                Ok(_) => None,
+                Err(_) => Some(locale.format("rule.seed", &[&u32::MAX])),
+            },
+            FieldRule::Positive => match text.parse::<f64>() {
+                Ok(value) if value > 0.0 && value.is_finite() => None,
+                _ => Some(locale.text("rule.positive").to_string()),
+            },
+            FieldRule::Count { min, max } => match text.parse::<u32>() {
+                Ok(value) if (min..=max).contains(&value) => None,
+                _ => Some(locale.format("rule.count", &[&min, &max])),
+            },
+            FieldRule::PowerOfTwo { min, max } => match text.parse::<i32>() {
+                Ok(value) if value.count_ones() == 1 && (min..=max).contains(&value) => None,
+                _ => Some(locale.format("rule.power_of_two", &[&min, &max])),
+            },
+            FieldRule::ExistingFile if std::path::Path::new(text).is_file() => None,
+            FieldRule::ExistingFile => Some(locale.text("rule.existing_file").to_string()),
+        }
+    }
+}
+
+// Set on a field whose text breaks its rule; Generate stays blocked while any
+// field has one
+#[derive(Component)]
+pub struct InvalidInput;
+
+// Red message under an invalid field
+#[derive(Component)]
+pub struct FieldError;
+
+// Drag track for a bounded parameter. Its `InputValue` holds the value as text,
+// so sliders feed the same reading code as the typed fields.
+#[derive(Component)]
+pub struct Slider {
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl Slider {
+    pub fn fraction(&self, value: f64) -> f32 {
+        ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0) as f32
+    }
+}
+
+#[derive(Component)]
+pub struct SliderHandle;
+
+// Button stepping through a fixed list of options, each a string ID and the
+// English shown when the language has no text for it. The field's value is read
+// from `index`, never from the text on the button.
+#[derive(Component)]
+pub struct Choice {
+    pub index: usize,
+    pub options: Vec<(String, String)>,
+}
+
+impl Choice {
+    // An on/off setting lists "off" first, so its index is the setting
+    pub fn toggle(on: bool, off: (&str, &str), on_option: (&str, &str)) -> Self {
+        Choice {
+            index: on as usize,
+            options: [off, on_option]
+                .map(|(id, english)| (id.to_string(), english.to_string()))
+                .to_vec(),
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.index == 1
+    }
+}
+
 #[derive(Component)]
 pub struct SeedField;
 
@@ -29,6 +137,9 @@ pub struct OctaveField;
 #[derive(Component)]
 pub struct SeaThresholdField;
 
+#[derive(Component)]
+pub struct MountainThresholdField;
+
 #[derive(Component)]
 pub struct TemperatureScaleField;
 
@@ -38,9 +149,97 @@ pub struct MoistureScaleField;
 #[derive(Component)]
 pub struct ScalingFactorField;
 
+#[derive(Component)]
+pub struct ErosionIterationsField;
+
+#[derive(Component)]
+pub struct ErosionStrengthField;
+
+#[derive(Component)]
+pub struct WarpStrengthField;
+
+#[derive(Component)]
+pub struct GenerationModeField;
+
+#[derive(Component)]
+pub struct PlateCountField;
+
+#[derive(Component)]
+pub struct WorldSizeField;
+
+#[derive(Component)]
+pub struct ChunkSizeField;
+
+#[derive(Component)]
+pub struct StreamingField;
+
+#[derive(Component)]
+pub struct GpuGenerationField;
+
+#[derive(Component)]
+pub struct MigrationStartField;
+
+#[derive(Component)]
+pub struct FantasyField;
+
+// The difficulties in the order the field's `Choice` lists them
+#[derive(Component)]
+pub struct DifficultyField(pub Vec<String>);
+
+// Path of an image to use as the elevation instead of noise
+#[derive(Component)]
+pub struct HeightmapField;
+
+// Cycling button choosing the noise algorithm for one generation layer
+#[derive(Component)]
+pub struct NoiseKindField;
+
+#[derive(Component)]
+pub struct TerrainNoiseField;
+
+#[derive(Component)]
+pub struct ContinentalNoiseField;
+
+#[derive(Component)]
+pub struct TemperatureNoiseField;
+
+#[derive(Component)]
+pub struct MoistureNoiseField;
+
+#[derive(Component)]
+pub struct PresetButton(pub usize);
+
 #[derive(Component)]
 pub enum GameConfigAction {
     Generate,
+    CompareSeeds,
+    SavePreset,
     Back,
 }
 
+// Name typed for the next saved preset
+#[derive(Component)]
+pub struct PresetNameField;
+
+#[derive(Component)]
+pub struct SavedPresetStatus;
+
+// Holds one row per preset file in the presets folder
+#[derive(Component)]
+pub struct SavedPresetList;
+
+// Row of the saved preset list; clicking it fills every input from the file
+#[derive(Component)]
+pub struct SavedPresetButton(pub String);
+
+#[derive(Component)]
+pub struct SeedComparisonStatus;
+
+// Holds one row per surveyed seed, best first
+#[derive(Component)]
+pub struct SeedComparisonList;
+
+// Row of the comparison list; clicking it copies the seed into the seed field
+#[derive(Component)]
+pub struct SeedCandidate(pub u32);
+
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+// Camera, light and terrain mesh of the 3D heightmap view
+#[derive(Component)]
+pub struct HeightmapViewEntity;
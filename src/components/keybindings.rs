@@ -0,0 +1,20 @@
+use bevy::prelude::*;
+
+use crate::systems::keybindings::InputAction;
+
+// The controls page, opened from the pause menu
+#[derive(Component)]
+pub struct KeybindingsUI;
+
+// Clicking one waits for the next key press and binds it to the action
+#[derive(Component)]
+pub struct RebindButton(pub InputAction);
+
+#[derive(Component)]
+pub struct RebindLabel(pub InputAction);
+
+#[derive(Component)]
+pub enum KeybindingsAction {
+    ResetDefaults,
+    Back,
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Component)]
+pub struct Kingdom {
+    pub name: String,
+    // Tint for its territory on the political map
+    pub color: Color,
+}
+
+// The tongue a kingdom names its land in. Kingdoms are dealt different ones,
+// so their names sound unlike their neighbours'.
+#[derive(Component, PartialEq, Eq, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Culture {
+    #[default]
+    Common,
+    Northern,
+    Southern,
+    Eastern,
+}
+
+impl Culture {
+    pub const ALL: [Culture; 4] = [
+        Culture::Common,
+        Culture::Northern,
+        Culture::Southern,
+        Culture::Eastern,
+    ];
+}
+
+// Ages a kingdom's know-how passes through, earliest first
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub enum Era {
+    #[default]
+    Stone,
+    Bronze,
+    Iron,
+    Medieval,
+}
+
+impl Era {
+    pub const ALL: [Era; 4] = [Era::Stone, Era::Bronze, Era::Iron, Era::Medieval];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Era::Stone => "Stone Age",
+            Era::Bronze => "Bronze Age",
+            Era::Iron => "Iron Age",
+            Era::Medieval => "Middle Ages",
+        }
+    }
+
+    // The era that follows, and the research a kingdom needs in all to reach it
+    pub fn next(&self) -> Option<(Era, f32)> {
+        match self {
+            Era::Stone => Some((Era::Bronze, 1000.0)),
+            Era::Bronze => Some((Era::Iron, 3000.0)),
+            Era::Iron => Some((Era::Medieval, 8000.0)),
+            Era::Medieval => None,
+        }
+    }
+
+    // Grain a field yields against what the first farmers got from it
+    pub fn farm_yield(&self) -> f32 {
+        match self {
+            Era::Stone => 1.0,
+            Era::Bronze => 1.15,
+            Era::Iron => 1.3,
+            Era::Medieval => 1.5,
+        }
+    }
+
+    // How much faster the kingdom's armies, settlers and traders travel
+    pub fn unit_speed(&self) -> f32 {
+        match self {
+            Era::Stone => 1.0,
+            Era::Bronze => 1.1,
+            Era::Iron => 1.25,
+            Era::Medieval => 1.4,
+        }
+    }
+}
+
+// Research a kingdom has built up from its people's work, and the era it has
+// brought it to
+#[derive(Component, Default)]
+pub struct Technology {
+    pub era: Era,
+    pub research: f32,
+}
+
+// Wealth a kingdom has built up, mostly from trade
+#[derive(Component, Default)]
+pub struct Treasury(pub f32);
+
+// Renown a kingdom has won by slaying monsters
+#[derive(Component, Default)]
+pub struct Fame(pub u32);
+
+// Kingdoms run by the simulation rather than the player; for now, all of them
+#[derive(Component)]
+pub struct AiControlled;
+
+// Settlers sent out by a kingdom to found a settlement where they stop
+#[derive(Component)]
+pub struct FoundingParty {
+    pub kingdom: Entity,
+    pub population: f32,
+}
+
+// Track worn between two settlements, tile by tile in base world coordinates
+#[derive(Component)]
+pub struct Road {
+    pub tiles: Vec<IVec2>,
+}
+
+// The kingdom a settlement belongs to
+#[derive(Component)]
+#[relationship(relationship_target = KingdomSettlements)]
+pub struct OwnedBy(pub Entity);
+
+// Every settlement a kingdom holds, kept in step with `OwnedBy`
+#[derive(Component)]
+#[relationship_target(relationship = OwnedBy)]
+pub struct KingdomSettlements(Vec<Entity>);
+
+impl KingdomSettlements {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+// Copies of the territory image tiled around the camera
+#[derive(Component)]
+pub struct TerritoryLayer;
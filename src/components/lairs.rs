@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum LairKind {
+    Dragon,
+    SeaSerpent,
+}
+
+impl LairKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LairKind::Dragon => "Dragon",
+            LairKind::SeaSerpent => "Sea serpent",
+        }
+    }
+
+    // Prefix of the chronicle strings telling of it
+    pub fn key(&self) -> &'static str {
+        match self {
+            LairKind::Dragon => "dragon",
+            LairKind::SeaSerpent => "sea_serpent",
+        }
+    }
+}
+
+// A monster's den: dragons nest among the peaks and sea serpents in the deep
+// ocean, each falling on the settlements around it until it is hunted down
+#[derive(Component)]
+pub struct Lair {
+    pub kind: LairKind,
+    pub tile: IVec2,
+    pub strength: f32,
+    // Seconds until the monster hunts again
+    pub raid_cooldown: f32,
+}
+
+// Soldiers a kingdom sends after a monster that has raided it
+#[derive(Component)]
+pub struct HuntingParty {
+    pub kingdom: Entity,
+    pub lair: Entity,
+    pub strength: f32,
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct LoadingScreenUI;
+
+#[derive(Component)]
+pub struct LoadingBarFill;
+
+#[derive(Component)]
+pub struct LoadingStageText;
+
+#[derive(Component)]
+pub struct LoadingCancelButton;
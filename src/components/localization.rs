@@ -0,0 +1,14 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+// Text shown in the player's language, by string ID. The text spawned with it
+// is the English, shown until the localization system fills it in.
+#[derive(Component)]
+pub struct Localized(pub Cow<'static, str>);
+
+impl Localized {
+    pub fn new(id: impl Into<Cow<'static, str>>) -> Self {
+        Localized(id.into())
+    }
+}
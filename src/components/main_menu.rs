@@ -3,8 +3,18 @@ use bevy::prelude::Component;
 #[derive(Component)]
 pub struct MainMenuUI;
 
+// Shows why the last load failed
+#[derive(Component)]
+pub struct LoadGameStatus;
+
+// The saved world's record, shown under the load button
+#[derive(Component)]
+pub struct SavedWorldRecord;
+
 #[derive(Component)]
 pub enum MainMenuAction{
     NewGame,
+    LoadGame,
+    Options,
     Quit
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct MapLegendUI;
+
+// Which line of the legend a text entity shows
+#[derive(Component, Clone, Copy)]
+pub enum MapLegendText {
+    Title,
+    Min,
+    Max,
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+// Instructions and the measured distance while the measure tool is on
+#[derive(Component)]
+pub struct MeasureUI;
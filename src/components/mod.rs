@@ -1,4 +1,31 @@
 pub mod world;
 pub mod main_menu;
 pub mod game_config;
-pub mod world_gen;
\ No newline at end of file
+pub mod world_gen;
+pub mod color_grading;
+pub mod pause_menu;
+pub mod loading_screen;
+pub mod ambient;
+pub mod map_legend;
+pub mod world_view;
+pub mod weather;
+pub mod heightmap_view;pub mod keybindings;
+pub mod options;
+pub mod settlements;
+pub mod kingdoms;
+pub mod units;
+pub mod trade;
+pub mod diplomacy;
+pub mod buildings;
+pub mod events;
+pub mod wildlife;
+pub mod chronicle;
+pub mod diagnostics;
+pub mod localization;
+pub mod sound;
+pub mod measure;
+pub mod world_stats;
+pub mod nomads;
+pub mod lairs;
+pub mod era_end;
+pub mod timeline;
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+use crate::components::kingdoms::Culture;
+
+// A people with no settlements of their own, driving their herds between a
+// summer and a winter pasture and raiding the kingdoms they pass. Strength is
+// roughly the number of riders.
+#[derive(Component)]
+pub struct Horde {
+    pub name: String,
+    pub culture: Culture,
+    pub strength: f32,
+    // The summer pasture lies nearer the pole than the winter one
+    pub summer_pasture: IVec2,
+    pub winter_pasture: IVec2,
+    // Pasture the horde last set out for, so a way that can't be found is only
+    // searched again when the season turns
+    pub bound_for: Option<IVec2>,
+    pub tributaries: Vec<Tributary>,
+    // Seconds until the horde is ready to raid again
+    pub raid_cooldown: f32,
+}
+
+// A kingdom buying the horde off
+#[derive(Clone, Copy)]
+pub struct Tributary {
+    pub kingdom: Entity,
+    // Years of tribute paid in all
+    pub years: u32,
+    // Last year the kingdom's lands are spared
+    pub until_year: u32,
+}
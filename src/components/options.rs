@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+// The options page, opened from the main menu or the pause menu
+#[derive(Component)]
+pub struct OptionsUI;
+
+// Button that steps its setting to the next choice
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsSetting {
+    Resolution,
+    WindowMode,
+    Vsync,
+    UiScale,
+    WorkerThreads,
+    ExportLayers,
+    Palette,
+    CaptureScale,
+    CaptureOverlays,
+    ColorGrading,
+    AmbientFlavor,
+    Language,
+}
+
+// Drag track for one of the volumes, which it shows as a percentage
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum OptionsVolume {
+    Music,
+    Ambience,
+    Sound,
+}
+
+#[derive(Component)]
+pub struct OptionsValue(pub OptionsSetting);
+
+#[derive(Component)]
+pub struct OptionsBack;
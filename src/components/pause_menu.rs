@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct PauseMenuUI;
+
+// A panel that holds the sim while it is shown, as the pause menu does
+#[derive(Component)]
+pub struct PausesSim;
+
+// Filled in with the world's seed once the menu is spawned
+#[derive(Component)]
+pub struct PauseSeedText;
+
+#[derive(Component)]
+pub struct ClockIndicator;
+
+#[derive(Component)]
+pub enum PauseMenuAction {
+    Resume,
+    Controls,
+    Options,
+    SaveGame,
+    ExportMap,
+    ExportHeightmap,
+    CaptureMap,
+    ReportBug,
+    ReportBugWithScreenshot,
+    MainMenu,
+}
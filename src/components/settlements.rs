@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Population thresholds for the next tier up
+const TOWN_POPULATION: f32 = 400.0;
+const CITY_POPULATION: f32 = 2000.0;
+
+// A town founded when the world starts; `tile` is in base world coordinates
+#[derive(Component)]
+pub struct Settlement {
+    pub name: String,
+    pub tile: IVec2,
+}
+
+// People living in the settlement. Fractional, so slow growth still accumulates
+// from one tick to the next.
+#[derive(Component)]
+pub struct Population(pub f32);
+
+// How many people the land around the settlement can feed
+#[derive(Component)]
+pub struct FoodCapacity(pub f32);
+
+// Goods a settlement produces and stores. Grain, fish and game are all eaten.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Good {
+    Grain,
+    Fish,
+    Game,
+    Timber,
+    Stone,
+    Ore,
+}
+
+impl Good {
+    pub const ALL: [Good; 6] = [
+        Good::Grain,
+        Good::Fish,
+        Good::Game,
+        Good::Timber,
+        Good::Stone,
+        Good::Ore,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Good::Grain => "Grain",
+            Good::Fish => "Fish",
+            Good::Game => "Game",
+            Good::Timber => "Timber",
+            Good::Stone => "Stone",
+            Good::Ore => "Ore",
+        }
+    }
+
+    // Wealth a unit of the good brings when it is traded
+    pub fn value(&self) -> f32 {
+        match self {
+            Good::Grain => 1.0,
+            Good::Fish => 1.2,
+            Good::Game => 1.5,
+            Good::Timber => 1.5,
+            Good::Stone => 2.0,
+            Good::Ore => 4.0,
+        }
+    }
+}
+
+// Amount of each good held, indexed by `Good as usize`
+#[derive(Component, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct Stockpile(pub [f32; Good::ALL.len()]);
+
+impl Stockpile {
+    pub fn get(&self, good: Good) -> f32 {
+        self.0[good as usize]
+    }
+}
+
+// Yearly output of each good when the land around the settlement is fully
+// worked, indexed like `Stockpile`
+#[derive(Component, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct ProductionRates(pub [f32; Good::ALL.len()]);
+
+impl ProductionRates {
+    pub fn get(&self, good: Good) -> f32 {
+        self.0[good as usize]
+    }
+}
+
+// What a settlement mostly lives off, going by what its land and water yield
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Specialization {
+    #[default]
+    Farming,
+    Fishing,
+    Mining,
+}
+
+impl Specialization {
+    pub const ALL: [Specialization; 3] = [
+        Specialization::Farming,
+        Specialization::Fishing,
+        Specialization::Mining,
+    ];
+
+    // Coastal settlements landing more fish than their fields grow live by the
+    // sea; highland ones quarrying and mining more than they farm, by the mine
+    pub fn from_production(production: &ProductionRates) -> Self {
+        let grain = production.get(Good::Grain);
+        if production.get(Good::Fish) > grain {
+            Specialization::Fishing
+        } else if production.get(Good::Ore) + production.get(Good::Stone) > grain {
+            Specialization::Mining
+        } else {
+            Specialization::Farming
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Specialization::Farming => "Farming",
+            Specialization::Fishing => "Fishing",
+            Specialization::Mining => "Mining",
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SettlementTier {
+    Hamlet,
+    Town,
+    City,
+}
+
+impl SettlementTier {
+    pub const ALL: [SettlementTier; 3] = [
+        SettlementTier::Hamlet,
+        SettlementTier::Town,
+        SettlementTier::City,
+    ];
+
+    pub fn from_population(population: f32) -> Self {
+        if population >= CITY_POPULATION {
+            SettlementTier::City
+        } else if population >= TOWN_POPULATION {
+            SettlementTier::Town
+        } else {
+            SettlementTier::Hamlet
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SettlementTier::Hamlet => "Hamlet",
+            SettlementTier::Town => "Town",
+            SettlementTier::City => "City",
+        }
+    }
+
+    // Side of the map marker in tiles
+    pub fn marker_size(&self) -> f32 {
+        match self {
+            SettlementTier::Hamlet => 2.0,
+            SettlementTier::Town => 3.0,
+            SettlementTier::City => 4.5,
+        }
+    }
+}
+
+// Water a coastal settlement fishes, and what it lands a year when every hand
+// is at work and the shoals off its shore are fully stocked
+#[derive(Component, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct FishingGrounds {
+    pub water_tiles: u32,
+    pub full_yield: f32,
+    // Share of their capacity the shoals it fishes hold
+    pub shoals: f32,
+}
+
+// People who left an overcrowded or hungry settlement, on their way to one
+// that can feed them. With no destination they found a settlement of their own.
+#[derive(Component)]
+pub struct MigrantGroup {
+    pub from: Entity,
+    pub destination: Option<Entity>,
+    pub population: f32,
+}
+
+// A field cleared by a settlement. Yield is the grain it adds each year.
+#[derive(Component)]
+pub struct Farm {
+    pub tile: IVec2,
+    pub yield_per_year: f32,
+}
+
+// The settlement a farm belongs to
+#[derive(Component)]
+#[relationship(relationship_target = Farms)]
+pub struct FarmOf(pub Entity);
+
+// Every farm a settlement works; they go with it when it is despawned
+#[derive(Component)]
+#[relationship_target(relationship = FarmOf, linked_spawn)]
+pub struct Farms(Vec<Entity>);
+
+impl Farms {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+// Land around a settlement that could be farmed, best yield first
+#[derive(Component)]
+pub struct FarmlandSurvey(pub Vec<(IVec2, f32)>);
+
+// Share of its usual output a settlement manages while disasters strike it,
+// indexed like `Stockpile`
+#[derive(Component, Clone, Copy, PartialEq)]
+pub struct ProductionModifier(pub [f32; Good::ALL.len()]);
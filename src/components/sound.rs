@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+// Background loop for the land around the camera
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Ambience {
+    Waves,
+    Wind,
+    Birds,
+}
+
+impl Ambience {
+    pub const ALL: [Ambience; 3] = [Ambience::Waves, Ambience::Wind, Ambience::Birds];
+}
+
+// Plays from the main menu through world generation
+#[derive(Component)]
+pub struct MenuMusic;
+
+// Every ambience loops the whole time the world is shown; the one for the land
+// in view fades up and the rest fade out
+#[derive(Component)]
+pub struct AmbientLoop {
+    pub ambience: Ambience,
+    // Fraction of the ambience volume currently playing
+    pub level: f32,
+}
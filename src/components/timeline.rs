@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct TimelineUI;
+
+#[derive(Component)]
+pub struct TimelineCaption;
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+use crate::components::settlements::Good;
+
+// Goods a trader is carrying from one settlement to another
+#[derive(Component)]
+pub struct Caravan {
+    pub from: Entity,
+    pub to: Entity,
+    pub good: Good,
+    pub amount: f32,
+}
+
+// A way caravans have taken between two settlements, kept while it stays in use
+#[derive(Component)]
+pub struct TradeRoute {
+    pub from: Entity,
+    pub to: Entity,
+    pub tiles: Vec<IVec2>,
+    // Goods delivered along the route so far
+    pub volume: f32,
+    // Simulated seconds since the last delivery
+    pub idle: f32,
+}
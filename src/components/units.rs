@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy::tasks::Task;
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum UnitKind {
+    Settler,
+    Trader,
+    Scout,
+    Army,
+    Migrants,
+    Nomads,
+    Hunters,
+}
+
+impl UnitKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            UnitKind::Settler => "Settler",
+            UnitKind::Trader => "Trader",
+            UnitKind::Scout => "Scout",
+            UnitKind::Army => "Army",
+            UnitKind::Migrants => "Migrants",
+            UnitKind::Nomads => "Nomads",
+            UnitKind::Hunters => "Hunters",
+        }
+    }
+
+    // Open tiles crossed per simulated second
+    pub fn speed(&self) -> f32 {
+        match self {
+            UnitKind::Settler => 2.0,
+            UnitKind::Trader => 3.0,
+            UnitKind::Scout => 5.0,
+            UnitKind::Army => 1.5,
+            UnitKind::Migrants => 1.5,
+            UnitKind::Nomads => 2.0,
+            UnitKind::Hunters => 2.5,
+        }
+    }
+}
+
+// An agent on the map. `tile` is in base world coordinates; `progress` is how
+// far it has got toward the next tile of its path.
+#[derive(Component)]
+pub struct Unit {
+    pub tile: IVec2,
+    pub progress: f32,
+}
+
+// Where a unit has been told to go. The path is filled in once it has been
+// searched; `path[next]` is the tile the unit is walking onto.
+#[derive(Component)]
+pub struct MoveOrder {
+    pub goal: IVec2,
+    pub path: Vec<IVec2>,
+    pub next: usize,
+}
+
+impl MoveOrder {
+    pub fn new(goal: IVec2) -> Self {
+        MoveOrder {
+            goal,
+            path: Vec::new(),
+            next: 1,
+        }
+    }
+}
+
+// Soldiers raised by a kingdom. Strength is roughly the number still standing.
+#[derive(Component)]
+pub struct Army {
+    pub kingdom: Entity,
+    pub strength: f32,
+}
+
+// An army camped outside an enemy settlement; the settlement falls once
+// `progress` reaches 1
+#[derive(Component)]
+pub struct Siege {
+    pub settlement: Entity,
+    pub progress: f32,
+}
+
+// Route search running on the task pool for a unit's move order
+#[derive(Component)]
+pub struct PathSearch(pub Task<Option<Vec<IVec2>>>);
@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Storm {
+    // Tile coordinates, wrapped into the world
+    pub position: Vec2,
+    // 0..1; a storm is a hurricane from HURRICANE_INTENSITY up
+    pub intensity: f32,
+    pub age: f32,
+    // Icon rotation in radians, counter-clockwise north of the equator
+    pub spin: f32,
+}
+
+// Dark eye drawn on a storm's icon once it reaches hurricane strength
+#[derive(Component)]
+pub struct StormEye;
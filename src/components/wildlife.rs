@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use crate::components::{settlements::Good, world::Biome};
+
+// The game animal a stretch of land or sea is home to
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Species {
+    Deer,
+    Camel,
+    FishShoal,
+}
+
+impl Species {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Species::Deer => "Deer",
+            Species::Camel => "Camels",
+            Species::FishShoal => "Fish shoals",
+        }
+    }
+
+    // Animals a tile of the biome carries when left alone, and the species they
+    // are. Shallow seas teem with fish; the open ocean holds far fewer.
+    pub fn habitat(biome: Biome) -> Option<(Species, f32)> {
+        match biome {
+            Biome::Forest
+            | Biome::TemperateForest
+            | Biome::TemperateRainforest
+            | Biome::SubtropicalForest
+            | Biome::TropicalRainforest => Some((Species::Deer, 0.6)),
+            Biome::BorealForest | Biome::Taiga => Some((Species::Deer, 0.4)),
+            Biome::Grassland | Biome::Savanna => Some((Species::Deer, 0.2)),
+            Biome::Desert | Biome::HotDesert | Biome::ColdDesert => Some((Species::Camel, 0.1)),
+            Biome::ShallowOcean | Biome::Coast => Some((Species::FishShoal, 1.0)),
+            Biome::Ocean => Some((Species::FishShoal, 0.3)),
+            _ => None,
+        }
+    }
+
+    // What the catch goes into when it is brought home
+    pub fn good(&self) -> Good {
+        match self {
+            Species::Deer | Species::Camel => Good::Game,
+            Species::FishShoal => Good::Fish,
+        }
+    }
+
+    // Tint on the wildlife map, at full strength where the land is fully stocked
+    pub fn color(&self) -> Color {
+        match self {
+            Species::Deer => Color::srgb(0.55, 0.35, 0.15),
+            Species::Camel => Color::srgb(0.95, 0.75, 0.3),
+            Species::FishShoal => Color::srgb(0.2, 0.8, 0.9),
+        }
+    }
+}
+
+// Copies of the wildlife image tiled around the camera
+#[derive(Component)]
+pub struct WildlifeLayer;
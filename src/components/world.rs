@@ -1,45 +1,108 @@
 use bevy::prelude::*;
 
+pub use kingdom_worldgen::world::{Biome, ResourceKind, Square, WorldMap};
+
+#[derive(Component)]
+pub struct BiomeDisplayUI;
+
+// Side panel describing the selected tile; it stays until closed or another
+// tile is picked
+#[derive(Component)]
+pub struct TileInspectorUI;
+
+#[derive(Component)]
+pub struct TileInspectorText;
+
+// Corner notice shown while chunks in view are still being meshed
 #[derive(Component)]
-pub struct WorldMap {
-    pub width: u32,
-    pub height: u32,
-    pub squares: Vec<Square>,
+pub struct ChunkSpinnerUI;
+
+#[derive(Component)]
+pub struct TileInspectorClose;
+
+#[derive(Component)]
+pub struct DominantBiome(pub Biome);
+
+// Overlay quads for the tile under the cursor and the selected tile
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum TileHighlight {
+    Hover,
+    Selected,
 }
 
-#[derive(Component, Default, Clone)]
-pub struct Square {
-    pub biome: Biome,
-    pub elevation: f32,
-    pub temperature: f32,
-    pub moisture: f32,
+// Grid position of a chunk mesh, unwrapped so it matches where the mesh sits in world space
+#[derive(Component, Clone, Copy)]
+pub struct ChunkCoord(pub IVec2);
+
+// Animated water mesh drawn over its parent chunk at the finest level of detail
+#[derive(Component)]
+pub struct ChunkWater;
+
+// Mesh handles for each level of detail of a chunk, finest first, and the one in use
+#[derive(Component)]
+pub struct ChunkLod {
+    pub meshes: Vec<Handle<Mesh>>,
+    pub level: usize,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
-pub enum Biome {
-    #[default]
-    Ocean,
-    Coast,
-    Grassland,
-    Forest,
-    Desert,
-    Hill,
-    Mountain,
-    Ice,
-    Alpine,
-    Snow,
-    Tundra,
-    BorealForest,
-    Taiga,
-    ColdDesert,
-    TemperateForest,
-    TemperateRainforest,
-    HotDesert,
-    Savanna,
-    SubtropicalForest,
-    TropicalRainforest,
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum LandmarkKind {
+    Volcano,
+    Oasis,
+    CraterLake,
 }
 
 #[derive(Component)]
-pub struct BiomeDisplayUI;
+pub struct Landmark {
+    pub kind: LandmarkKind,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum RegionKind {
+    Continent,
+    Island,
+    Ocean,
+    Sea,
+    MountainRange,
+}
+
+// Rare regional goods worth hauling across the world
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum TradeGood {
+    Silk,
+    Spices,
+    Amber,
+    Furs,
+    Ivory,
+    Obsidian,
+    Incense,
+}
 
+impl TradeGood {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TradeGood::Silk => "Silk",
+            TradeGood::Spices => "Spices",
+            TradeGood::Amber => "Amber",
+            TradeGood::Furs => "Furs",
+            TradeGood::Ivory => "Ivory",
+            TradeGood::Obsidian => "Obsidian",
+            TradeGood::Incense => "Incense",
+        }
+    }
+}
+
+// A named connected stretch of land, water or high ground. The bounding box is in
+// tile coordinates and is not wrapped, so a region crossing the world edge can
+// extend past `world_size`.
+#[derive(Component, Clone)]
+pub struct Region {
+    pub kind: RegionKind,
+    pub name: String,
+    pub min: IVec2,
+    pub max: IVec2,
+    pub specialties: Vec<TradeGood>,
+}
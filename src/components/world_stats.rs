@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+use crate::systems::world_gen::statistics::WorldStatistics;
+
+// Figures for the current world, worked out the first time the panel opens
+#[derive(Component, Default)]
+pub struct WorldStatsPanel {
+    pub stats: Option<WorldStatistics>,
+    // Language the rows were last written in
+    pub language: Option<String>,
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct WorldViewUI;
+
+#[derive(Component)]
+pub struct WorldViewCaption;
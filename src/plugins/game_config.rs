@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::{states::game_state::GameState, systems::game_config::*};
+
+// The world setup screen, which turns its inputs into the settings and world
+// data generation starts from when it closes
+pub struct GameConfigPlugin;
+
+impl Plugin for GameConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::WorldGenSetup), setup_game_config)
+            .add_systems(
+                Update,
+                (
+                    game_config_buttons,
+                    game_config_text_input,
+                    cycle_choices,
+                    show_choices,
+                    apply_world_preset,
+                    drag_sliders,
+                    update_slider_handles,
+                    update_text_display,
+                    validate_inputs,
+                    focus_text_inputs,
+                    highlight_focused_input,
+                    poll_seed_comparison.run_if(resource_exists::<SeedComparison>),
+                    pick_seed_candidate,
+                    load_world_preset,
+                )
+                    .run_if(in_state(GameState::WorldGenSetup)),
+            )
+            .add_systems(
+                Update,
+                (read_worldgen_inputs, save_world_preset)
+                    .chain()
+                    .run_if(resource_exists::<SavePresetRequest>)
+                    .run_if(in_state(GameState::WorldGenSetup)),
+            )
+            .add_systems(
+                Update,
+                (
+                    read_worldgen_inputs,
+                    read_world_settings,
+                    start_seed_comparison,
+                )
+                    .chain()
+                    .run_if(resource_exists::<SeedComparisonRequest>)
+                    .run_if(in_state(GameState::WorldGenSetup)),
+            )
+            .add_systems(
+                OnExit(GameState::WorldGenSetup),
+                (
+                    read_worldgen_inputs,
+                    read_world_settings,
+                    read_difficulty,
+                    cleanup_game_config,
+                )
+                    .chain(),
+            );
+    }
+}
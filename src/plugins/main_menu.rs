@@ -0,0 +1,86 @@
+use bevy::{input::InputSystems, prelude::*};
+
+use crate::{
+    states::game_state::GameState,
+    systems::{
+        game_config::{drag_sliders, update_slider_handles, update_text_display},
+        keybindings::*,
+        loading_screen::*,
+        localization::*,
+        main_menu::*,
+        options::*,
+        save_game::{LoadGameTask, poll_loading_game},
+        startup::*,
+    },
+};
+
+// The startup splash, the main menu and the options and key binding screens
+// shared by the main menu and the pause menu, plus the UI language
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Loading),
+            (setup_splash_screen, start_startup_loading),
+        )
+        .add_systems(
+            Update,
+            track_startup_loading.run_if(in_state(GameState::Loading)),
+        )
+        .add_systems(OnExit(GameState::Loading), cleanup_loading_screen)
+        .add_systems(OnEnter(GameState::MainMenu), setup_main_menu)
+        .add_systems(
+            Update,
+            (
+                main_menu_buttons,
+                show_saved_record.run_if(resource_exists::<Locale>),
+                poll_loading_game.run_if(resource_exists::<LoadGameTask>),
+            )
+                .run_if(in_state(GameState::MainMenu)),
+        )
+        .add_systems(OnExit(GameState::MainMenu), cleanup_main_menu)
+        .add_systems(
+            Update,
+            (
+                options_buttons,
+                (
+                    drag_sliders,
+                    update_slider_handles,
+                    update_text_display,
+                    read_volume_sliders,
+                ),
+                apply_language.run_if(resource_exists_and_changed::<GameOptions>),
+                update_options_labels,
+            )
+                .chain()
+                .run_if(in_state(GameState::MainMenu).or(in_state(GameState::Playing))),
+        )
+        .add_systems(
+            Update,
+            (
+                apply_options.run_if(resource_exists_and_changed::<GameOptions>),
+                fit_camera_viewport,
+            )
+                .chain(),
+        )
+        .add_systems(
+            Update,
+            localize_texts
+                .after(update_options_labels)
+                .run_if(resource_exists::<Locale>),
+        )
+        .add_systems(
+            PreUpdate,
+            capture_rebind
+                .after(InputSystems)
+                .run_if(resource_exists::<PendingRebind>),
+        )
+        .add_systems(
+            Update,
+            (keybindings_buttons, update_rebind_labels)
+                .chain()
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
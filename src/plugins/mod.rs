@@ -0,0 +1,6 @@
+pub mod game_config;
+pub mod main_menu;
+pub mod simulation;
+pub mod world_gen;
+pub mod world_render;
+pub mod sound;
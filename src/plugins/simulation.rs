@@ -0,0 +1,480 @@
+use bevy::prelude::*;
+
+use crate::{
+    states::game_state::GameState,
+    systems::{
+        bug_report::*,
+        buildings::*,
+        chronicle::*,
+        clock::*,
+        combat::*,
+        development::*,
+        diplomacy::*,
+        economy::*,
+        era_end::*,
+        event_scripts::*,
+        events::*,
+        farming::*,
+        growth::*,
+        heightmap_view::HeightmapView,
+        kingdom_ai::*,
+        kingdoms::*,
+        lairs::*,
+        migration::*,
+        nomads::*,
+        notifications::*,
+        pathfinding::*,
+        pause_menu::*,
+        save_game::*,
+        season::*,
+        settlements::*,
+        technology::*,
+        timeline::*,
+        trade::*,
+        units::*,
+        weather::*,
+        wildlife::*,
+        world::{select_tile, wrap_camera},
+        world_record::*,
+    },
+};
+
+// Everything that happens in the world once play starts: the clock and
+// seasons, settlements and kingdoms, units, trade, war, farming, disasters and
+// the chronicle, along with their panels and the pause menu that stops them
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Season>()
+            .init_resource::<Pause>()
+            .init_resource::<GameClock>()
+            .init_resource::<SelectedUnit>()
+            .init_resource::<KingdomAi>()
+            .init_resource::<TradeTimer>()
+            .init_resource::<MigrationTimer>()
+            .init_resource::<ResearchTimer>()
+            .init_resource::<Diplomacy>()
+            .init_resource::<WarCouncil>()
+            .init_resource::<FarmClearing>()
+            .init_resource::<LandDevelopment>()
+            .init_resource::<BuildMode>()
+            .init_resource::<WorldEvents>()
+            .init_resource::<Notifications>()
+            .init_resource::<Chronicle>()
+            .init_resource::<StormSeason>()
+            .init_resource::<EraWatch>()
+            .init_resource::<WorldRecord>()
+            .init_resource::<TerritoryHistory>()
+            .add_systems(
+                FixedUpdate,
+                advance_clock
+                    .before(advance_season)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                advance_season
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnEnter(GameState::Playing), setup_clock_indicator)
+            .add_systems(
+                Update,
+                (
+                    toggle_pause,
+                    pause_for_panels,
+                    change_speed,
+                    toggle_pause_menu,
+                    pause_menu_buttons,
+                    update_clock_indicator,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, show_pause_seed.run_if(in_state(GameState::Playing)))
+            .add_systems(OnExit(GameState::Playing), (cleanup_pause, cleanup_clock))
+            .add_systems(
+                Update,
+                write_bug_report
+                    .after(pause_menu_buttons)
+                    .run_if(resource_exists::<BugReportRequest>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                save_game
+                    .after(pause_menu_buttons)
+                    .run_if(resource_exists::<SaveGameRequest>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    // A loaded game brings its own units
+                    (
+                        spawn_settlements,
+                        found_kingdoms,
+                        spawn_scouts,
+                        simulate_migration.run_if(migration_start),
+                        spawn_hordes,
+                        spawn_lairs.run_if(fantasy_world),
+                    )
+                        .chain()
+                        .run_if(not(resource_exists::<SavedTerritory>)),
+                    setup_territory,
+                    restore_territory.run_if(resource_exists::<SavedTerritory>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Update,
+                update_settlement_markers
+                    .after(wrap_camera)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .init_asset::<GrowthModelsAsset>()
+            .init_asset_loader::<GrowthModelsLoader>()
+            .add_systems(Update, apply_growth_model.run_if(resource_exists::<Growth>))
+            .add_systems(
+                FixedUpdate,
+                (update_stockpiles, specialize_settlements, grow_population)
+                    .chain()
+                    .after(advance_season)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                FixedUpdate,
+                claim_territory
+                    .after(grow_population)
+                    .run_if(resource_exists::<TerritoryMap>)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                (
+                    poll_territory_survey,
+                    record_territory,
+                    toggle_timeline,
+                    timeline_controls,
+                    update_territory_layer,
+                    draw_borders,
+                )
+                    .chain()
+                    .after(wrap_camera)
+                    .run_if(resource_exists::<TerritoryMap>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                (cleanup_settlements, cleanup_kingdoms, cleanup_timeline),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                // After the roads of a migration start are down
+                build_path_grid.after(simulate_migration),
+            )
+            .add_systems(
+                Update,
+                poll_path_grid
+                    .run_if(resource_exists::<PathGridTask>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_path_grid)
+            .add_systems(
+                Update,
+                (
+                    select_unit.before(select_tile),
+                    (start_path_searches, poll_path_searches).chain(),
+                    update_unit_sprites.after(wrap_camera),
+                )
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                advance_units
+                    .after(advance_clock)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    plan_expansion.after(grow_population),
+                    found_settlements.after(advance_units),
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                draw_roads
+                    .after(wrap_camera)
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    dispatch_caravans.after(update_stockpiles),
+                    deliver_caravans.after(advance_units),
+                    fade_trade_routes,
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                draw_trade_routes
+                    .after(wrap_camera)
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                (cleanup_units, cleanup_kingdom_ai, cleanup_trade),
+            )
+            .add_systems(
+                FixedUpdate,
+                update_diplomacy
+                    .after(claim_territory)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnEnter(GameState::Playing), setup_diplomacy_panel)
+            .add_systems(
+                Update,
+                (toggle_diplomacy_panel, update_diplomacy_panel)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_diplomacy)
+            .add_systems(
+                FixedUpdate,
+                (
+                    raise_armies.after(update_diplomacy),
+                    (
+                        resolve_battles,
+                        besiege_settlements,
+                        dissolve_fallen_kingdoms,
+                    )
+                        .chain()
+                        .after(advance_units),
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_combat)
+            .add_systems(
+                FixedUpdate,
+                (survey_farm_plots, clear_farmland)
+                    .chain()
+                    .before(update_stockpiles)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                update_farm_sprites
+                    .after(wrap_camera)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_farming)
+            .add_systems(
+                FixedUpdate,
+                develop_land
+                    .after(grow_population)
+                    .after(clear_farmland)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_development)
+            .add_systems(
+                FixedUpdate,
+                (
+                    send_migrants.after(update_stockpiles),
+                    settle_migrants.after(advance_units),
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_migration)
+            .add_systems(
+                FixedUpdate,
+                (
+                    drive_herds.after(advance_season),
+                    (raid_borders, settle_hordes).chain().after(advance_units),
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                FixedUpdate,
+                (raid_from_lairs, hunt_monsters)
+                    .chain()
+                    .after(advance_units)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                update_lair_sprites
+                    .after(wrap_camera)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_lairs)
+            .add_systems(OnEnter(GameState::Playing), setup_era_summary)
+            .add_systems(
+                FixedUpdate,
+                (
+                    tally_population.after(grow_population),
+                    watch_for_era_end
+                        .after(tally_population)
+                        .after(dissolve_fallen_kingdoms)
+                        .run_if(observing),
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                close_era_summary.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                (cleanup_era_watch, cleanup_world_record),
+            )
+            .add_systems(
+                FixedUpdate,
+                advance_research
+                    .after(grow_population)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_technology)
+            .add_systems(OnEnter(GameState::Playing), setup_build_toolbar)
+            .add_systems(
+                Update,
+                (
+                    pick_building,
+                    place_building.before(select_unit).before(select_tile),
+                    draw_build_targets,
+                    update_building_sprites.after(wrap_camera),
+                )
+                    .chain()
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_buildings)
+            .add_systems(OnEnter(GameState::Playing), setup_notification_area)
+            .add_systems(
+                Update,
+                update_notifications.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (survey_local_climate, roll_disasters, update_disasters)
+                    .chain()
+                    .before(update_stockpiles)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .init_asset::<EventScriptAsset>()
+            .init_asset_loader::<EventScriptLoader>()
+            .add_systems(
+                FixedUpdate,
+                run_scripted_events
+                    .after(survey_local_climate)
+                    .before(update_stockpiles)
+                    .run_if(resource_exists::<EventScripts>)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                cleanup_event_scripts.run_if(resource_exists::<EventScripts>),
+            )
+            .add_systems(
+                Update,
+                draw_disasters
+                    .after(wrap_camera)
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                (cleanup_events, cleanup_notifications),
+            )
+            .add_systems(OnEnter(GameState::Playing), setup_wildlife)
+            .add_systems(
+                Update,
+                (poll_wildlife_survey, update_wildlife_layer)
+                    .chain()
+                    .after(wrap_camera)
+                    .run_if(resource_exists::<WildlifeMap>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                update_wildlife
+                    .before(update_stockpiles)
+                    .run_if(resource_exists::<WildlifeMap>)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_wildlife)
+            .add_systems(OnEnter(GameState::Playing), setup_history_panel)
+            .add_systems(
+                Update,
+                (
+                    toggle_history_panel,
+                    history_panel_buttons,
+                    update_history_panel,
+                    scroll_history_panel,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                start_chronicle_export
+                    .after(history_panel_buttons)
+                    .run_if(resource_exists::<ChronicleExportRequest>)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                poll_chronicle_export.run_if(resource_exists::<ChronicleExportTask>),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_chronicle)
+            .add_systems(
+                FixedUpdate,
+                (spawn_storms, move_storms)
+                    .chain()
+                    .after(advance_season)
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(simulation_running),
+            )
+            .add_systems(
+                Update,
+                update_storm_icons
+                    .after(wrap_camera)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_storms);
+
+        #[cfg(feature = "dev-tools")]
+        app.init_resource::<crate::systems::dev_tools::AiOverlay>()
+            .add_systems(
+                Update,
+                (
+                    crate::systems::dev_tools::dev_hotkeys,
+                    crate::systems::dev_tools::draw_ai_overlay.after(wrap_camera),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
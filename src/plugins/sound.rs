@@ -0,0 +1,33 @@
+use bevy::audio::AddAudioSource;
+use bevy::prelude::*;
+
+use crate::{
+    states::game_state::GameState,
+    systems::{options::GameOptions, sound::*, world::LoadedChunks},
+};
+
+// Menu music, button clicks and the ambient loops under the map, all
+// synthesized as they play
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_source::<SynthSound>()
+            .add_systems(OnEnter(GameState::MainMenu), start_menu_music)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (stop_menu_music, start_ambient_loops),
+            )
+            .add_systems(OnExit(GameState::Playing), stop_ambient_loops)
+            .add_systems(
+                Update,
+                (
+                    play_click_sounds.run_if(resource_exists::<SoundLibrary>),
+                    apply_music_volume.run_if(resource_exists_and_changed::<GameOptions>),
+                    update_ambient_loops
+                        .run_if(in_state(GameState::Playing))
+                        .run_if(resource_exists::<LoadedChunks>),
+                ),
+            );
+    }
+}
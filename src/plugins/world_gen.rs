@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+
+use crate::{
+    states::game_state::GameState,
+    systems::{
+        diagnostics::register_worldgen_diagnostics,
+        loading_screen::*,
+        world::{WorldSettings, update_biome_display, wrap_camera},
+        world_gen::{
+            WorldGenTask,
+            biome_table::{BiomeTableAsset, BiomeTableLoader, Biomes, reload_biome_table},
+            cancel_world_generation,
+            landmarks::{spawn_landmarks, update_landmark_markers},
+            poll_world_generation,
+            regenerate_world,
+            regions::{
+                RegionTask, cleanup_regions, poll_region_identification,
+                start_region_identification, update_region_labels,
+            },
+            start_world_generation,
+        },
+    },
+};
+
+// Generating the world behind the loading screen, then placing its landmarks
+// and naming its regions once play starts
+pub struct WorldGenPlugin;
+
+impl Plugin for WorldGenPlugin {
+    fn build(&self, app: &mut App) {
+        register_worldgen_diagnostics(app);
+        app.init_resource::<WorldSettings>()
+            .init_asset::<BiomeTableAsset>()
+            .init_asset_loader::<BiomeTableLoader>()
+            .add_systems(Update, reload_biome_table.run_if(resource_exists::<Biomes>))
+            .add_systems(
+                OnEnter(GameState::WorldGenerating),
+                (setup_loading_screen, start_world_generation),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_loading_screen,
+                    poll_world_generation.run_if(resource_exists::<WorldGenTask>),
+                    cancel_world_generation,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::WorldGenerating)),
+            )
+            .add_systems(
+                OnExit(GameState::WorldGenerating),
+                cleanup_loading_screen,
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (spawn_landmarks, start_region_identification),
+            )
+            .add_systems(
+                Update,
+                (
+                    poll_region_identification.run_if(resource_exists::<RegionTask>),
+                    update_region_labels,
+                )
+                    .chain()
+                    .after(wrap_camera)
+                    .before(update_biome_display)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                update_landmark_markers
+                    .after(wrap_camera)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, regenerate_world.run_if(in_state(GameState::Playing)))
+            .add_systems(OnExit(GameState::Playing), cleanup_regions);
+
+        #[cfg(feature = "gpu-worldgen")]
+        {
+            use crate::systems::world_gen::gpu::*;
+
+            register_gpu_world_generation(app);
+            app.add_systems(
+                Update,
+                drive_gpu_world_generation
+                    .run_if(resource_exists::<GpuWorldGenJob>)
+                    .after(update_loading_screen)
+                    .before(poll_world_generation)
+                    .run_if(in_state(GameState::WorldGenerating)),
+            )
+            .add_systems(OnExit(GameState::WorldGenerating), cleanup_gpu_world_generation);
+        }
+    }
+}
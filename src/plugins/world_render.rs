@@ -0,0 +1,248 @@
+use bevy::{
+    diagnostic::FrameTimeDiagnosticsPlugin, platform::collections::HashMap, prelude::*,
+    sprite_render::Material2dPlugin,
+};
+
+use crate::{
+    states::game_state::GameState,
+    systems::{
+        ambient::*, color_grading::*, diagnostics::*, export::*, heightmap_view::*, map_capture::*,
+        map_legend::*, measure::*, options::GameOptions, palette::*,
+        pause_menu::pause_menu_buttons, tile_material::*, water_material::*, world::*,
+        world_stats::*, world_view::*,
+    },
+};
+
+// The camera, the streamed chunk meshes and every view laid over them: map
+// overlays, the tile inspector, measure tool and world statistics, grading, the
+// world and heightmap views and map export
+pub struct WorldRenderPlugin;
+
+impl Plugin for WorldRenderPlugin {
+    fn build(&self, app: &mut App) {
+        register_world_diagnostics(app);
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .add_plugins(Material2dPlugin::<BiomeTileMaterial>::default())
+            .add_plugins(Material2dPlugin::<WaterMaterial>::default())
+            .insert_resource(CameraChunk::default())
+            .insert_resource(LoadedChunks {
+                chunks: HashMap::new(),
+                chunk_data: HashMap::new(),
+                sampler: None,
+                pending: HashMap::new(),
+                stale: HashMap::new(),
+                edits: HashMap::new(),
+            })
+            .init_resource::<MapOverlay>()
+            .init_resource::<TileRenderMode>()
+            .init_resource::<Hillshade>()
+            .init_resource::<ChunkPrefetch>()
+            .init_resource::<TileGrid>()
+            .init_resource::<SelectedTile>()
+            .init_resource::<MeasureTool>()
+            .init_resource::<CameraControls>()
+            .init_resource::<AmbientFlavor>()
+            .insert_gizmo_config(
+                DefaultGizmoConfigGroup,
+                GizmoConfig {
+                    line: GizmoLineConfig {
+                        width: 1.0,
+                        ..default()
+                    },
+                    ..default()
+                },
+            )
+            .insert_gizmo_config(
+                ChunkGridGizmos,
+                GizmoConfig {
+                    line: GizmoLineConfig {
+                        width: 3.0,
+                        ..default()
+                    },
+                    ..default()
+                },
+            )
+            .add_systems(Startup, setup_camera)
+            .add_systems(
+                Update,
+                (
+                    wrap_camera,
+                    update_camera_chunk,
+                    track_camera_velocity,
+                    toggle_map_overlay,
+                    rotate_hillshade_light,
+                    update_chunks,
+                    spawn_built_chunks,
+                    update_chunk_spinner,
+                    cull_chunks,
+                    update_chunk_lod,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                apply_palette
+                    .before(update_chunks)
+                    .run_if(resource_exists::<MapPalette>)
+                    .run_if(resource_exists_and_changed::<GameOptions>),
+            )
+            .add_systems(
+                Update,
+                (controls, clamp_camera)
+                    .chain()
+                    .before(wrap_camera)
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    setup_biome_display,
+                    setup_chunk_spinner,
+                    setup_tile_highlight,
+                    setup_tile_inspector,
+                ),
+            )
+            .add_systems(
+                Update,
+                update_biome_display
+                    .after(wrap_camera)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    close_tile_inspector,
+                    select_tile.run_if(measure_tool_inactive),
+                    update_tile_highlight,
+                    update_tile_inspector,
+                )
+                    .chain()
+                    .after(wrap_camera)
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_world)
+            .add_systems(OnEnter(GameState::Playing), setup_measure_tool)
+            .add_systems(
+                Update,
+                (
+                    toggle_measure_tool,
+                    pick_measure_tiles,
+                    update_measure_readout,
+                    draw_measure_line,
+                )
+                    .chain()
+                    .after(wrap_camera)
+                    .run_if(not(resource_exists::<HeightmapView>))
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_measure_tool)
+            .add_systems(OnEnter(GameState::Playing), setup_world_stats_panel)
+            .add_systems(
+                Update,
+                (toggle_world_stats_panel, update_world_stats_panel)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_world_stats_panel)
+            .add_systems(OnEnter(GameState::Playing), setup_color_grading)
+            .add_systems(
+                Update,
+                (toggle_color_grading, update_color_grading)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_color_grading)
+            .add_systems(
+                Update,
+                (
+                    toggle_ambient_flavor,
+                    spawn_ambient_effects,
+                    update_ambient_effects,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_ambient_effects)
+            .add_systems(OnEnter(GameState::Playing), setup_map_legend)
+            .add_systems(
+                Update,
+                update_map_legend
+                    .after(toggle_map_overlay)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_map_legend)
+            .add_systems(OnEnter(GameState::Playing), setup_world_view)
+            .add_systems(
+                Update,
+                (toggle_world_view, world_view_controls, update_world_view)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_world_view)
+            .add_systems(
+                Update,
+                (
+                    toggle_heightmap_view,
+                    orbit_heightmap_camera.run_if(resource_exists::<HeightmapView>),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_heightmap_view)
+            .add_systems(
+                Update,
+                (toggle_tile_render_mode, update_tile_detail)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (toggle_tile_grid, draw_tile_grid)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    request_map_export,
+                    start_map_export.run_if(resource_exists::<MapExportRequest>),
+                )
+                    .chain()
+                    .after(pause_menu_buttons)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                poll_map_export.run_if(resource_exists::<MapExportTask>),
+            )
+            .add_systems(
+                Update,
+                (
+                    request_map_capture,
+                    start_map_capture.run_if(resource_exists::<MapCaptureRequest>),
+                )
+                    .chain()
+                    .after(pause_menu_buttons)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                poll_map_capture.run_if(resource_exists::<MapCaptureTask>),
+            )
+            .add_systems(OnEnter(GameState::Playing), setup_diagnostics_overlay)
+            .add_systems(
+                Update,
+                (
+                    measure_world.after(update_chunk_lod),
+                    toggle_diagnostics_overlay,
+                    update_diagnostics_overlay,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(OnExit(GameState::Playing), cleanup_diagnostics_overlay);
+    }
+}
@@ -3,6 +3,7 @@ use bevy::state::state::States;
 #[derive(States, Debug, Clone, Eq, PartialEq, Hash, Default)]
 pub enum GameState {
     #[default]
+    Loading,
     MainMenu,
     WorldGenSetup,
     WorldGenerating,
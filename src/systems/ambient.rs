@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::ambient::{AmbientEffect, AmbientKind};
+use crate::components::world::{Biome, DominantBiome};
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::options::{GameOptions, save_options};
+use crate::systems::world::{LoadedChunks, WorldSettings};
+
+// Hard cap on live effects so long observation sessions stay cheap
+const MAX_AMBIENT_EFFECTS: usize = 24;
+const SPAWN_INTERVAL_SECS: f32 = 0.4;
+const BIRDS_PER_FLOCK: usize = 5;
+const AMBIENT_Z: f32 = 1.0;
+
+#[derive(Resource, Default)]
+pub struct AmbientFlavor {
+    pub spawn_cooldown: f32,
+}
+
+pub fn toggle_ambient_flavor(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut options: ResMut<GameOptions>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleAmbient) {
+        options.ambient_flavor = !options.ambient_flavor;
+        save_options(&options);
+    }
+}
+
+// Turning the flavor off, by key or from the Options page, clears what is already flying
+pub fn spawn_ambient_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    (options, mut flavor): (Res<GameOptions>, ResMut<AmbientFlavor>),
+    settings: Res<WorldSettings>,
+    loaded: Res<LoadedChunks>,
+    chunk_query: Query<&DominantBiome>,
+    effects: Query<Entity, With<AmbientEffect>>,
+) {
+    if !options.ambient_flavor {
+        for entity in &effects {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    flavor.spawn_cooldown -= time.delta_secs();
+    if flavor.spawn_cooldown > 0.0 || effects.iter().count() >= MAX_AMBIENT_EFFECTS {
+        return;
+    }
+    flavor.spawn_cooldown = SPAWN_INTERVAL_SECS;
+
+    let mut rng = rand::rng();
+    let chunks: Vec<(&(i32, i32), &Entity)> = loaded.chunks.iter().collect();
+    if chunks.is_empty() {
+        return;
+    }
+
+    let (&(chunk_x, chunk_y), &entity) = chunks[rng.random_range(0..chunks.len())];
+    let Ok(dominant) = chunk_query.get(entity) else {
+        return;
+    };
+    let Some(kind) = ambient_kind(dominant.0) else {
+        return;
+    };
+
+    let chunk_size = settings.chunk_size as f32;
+    let position = Vec2::new(
+        (chunk_x as f32 + rng.random_range(0.0..1.0)) * chunk_size,
+        (chunk_y as f32 + rng.random_range(0.0..1.0)) * chunk_size,
+    );
+
+    match kind {
+        AmbientKind::BirdFlock => {
+            let heading = Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU));
+            let birds: Vec<Vec2> = (0..BIRDS_PER_FLOCK)
+                .map(|_| Vec2::new(rng.random_range(-1.5..1.5), rng.random_range(-1.5..1.5)))
+                .collect();
+
+            commands.spawn((
+                AmbientEffect {
+                    kind,
+                    age: 0.0,
+                    lifetime: 8.0,
+                    velocity: heading * 6.0,
+                },
+                Transform::from_translation(position.extend(AMBIENT_Z)),
+                Visibility::default(),
+                Children::spawn(SpawnIter(birds.into_iter().map(|offset| {
+                    (
+                        Sprite::from_color(Color::srgba(0.1, 0.1, 0.1, 0.0), Vec2::splat(0.4)),
+                        Transform::from_translation(offset.extend(0.0)),
+                    )
+                }))),
+            ));
+        }
+        AmbientKind::WhaleSpout => {
+            commands.spawn((
+                AmbientEffect {
+                    kind,
+                    age: 0.0,
+                    lifetime: 2.5,
+                    velocity: Vec2::ZERO,
+                },
+                Sprite::from_color(Color::srgba(0.9, 0.95, 1.0, 0.0), Vec2::splat(0.6)),
+                Transform::from_translation(position.extend(AMBIENT_Z)),
+            ));
+        }
+        AmbientKind::HeatShimmer => {
+            commands.spawn((
+                AmbientEffect {
+                    kind,
+                    age: 0.0,
+                    lifetime: 5.0,
+                    velocity: Vec2::new(0.0, 0.5),
+                },
+                Sprite::from_color(Color::srgba(1.0, 0.95, 0.8, 0.0), Vec2::new(6.0, 1.5)),
+                Transform::from_translation(position.extend(AMBIENT_Z)),
+            ));
+        }
+    }
+}
+
+pub fn update_ambient_effects(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut effects: Query<(Entity, &mut AmbientEffect, &mut Transform, Option<&Children>)>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    for (entity, mut effect, mut transform, children) in &mut effects {
+        effect.age += time.delta_secs();
+        if effect.age >= effect.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += (effect.velocity * time.delta_secs()).extend(0.0);
+
+        let t = effect.age / effect.lifetime;
+        // Fade in over the first fifth, out over the last
+        let fade = (t * 5.0).min((1.0 - t) * 5.0).min(1.0);
+
+        let alpha = match effect.kind {
+            AmbientKind::BirdFlock => 0.8 * fade,
+            AmbientKind::WhaleSpout => {
+                transform.scale = Vec3::splat(1.0 + t * 2.0);
+                0.7 * fade
+            }
+            AmbientKind::HeatShimmer => {
+                let wobble = (effect.age * 6.0).sin() * 0.15;
+                transform.scale = Vec3::new(1.0 + wobble, 1.0 - wobble, 1.0);
+                0.15 * fade
+            }
+        };
+
+        if let Ok(mut sprite) = sprites.get_mut(entity) {
+            sprite.color.set_alpha(alpha);
+        }
+
+        for child in children.into_iter().flatten() {
+            if let Ok(mut sprite) = sprites.get_mut(*child) {
+                sprite.color.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+pub fn cleanup_ambient_effects(mut commands: Commands, query: Query<Entity, With<AmbientEffect>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn ambient_kind(biome: Biome) -> Option<AmbientKind> {
+    match biome {
+        Biome::Forest
+        | Biome::TemperateForest
+        | Biome::TemperateRainforest
+        | Biome::SubtropicalForest
+        | Biome::TropicalRainforest
+        | Biome::BorealForest
+        | Biome::Taiga => Some(AmbientKind::BirdFlock),
+        Biome::Ocean => Some(AmbientKind::WhaleSpout),
+        Biome::Desert | Biome::HotDesert => Some(AmbientKind::HeatShimmer),
+        _ => None,
+    }
+}
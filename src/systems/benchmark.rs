@@ -0,0 +1,106 @@
+use bevy::{platform::time::Instant, prelude::*};
+
+use crate::components::world_gen::WorldData;
+use crate::states::game_state::GameState;
+use crate::systems::world_gen::biome_table::Biomes;
+
+const BENCHMARK_SEED: u32 = 1337;
+const BENCHMARK_DURATION_SECS: f32 = 30.0;
+const BENCHMARK_PATH_RADIUS: f32 = 2000.0;
+// Simulation ticks to time; the run outlasts the camera loop until it has this many
+const BENCHMARK_TICKS: usize = 1000;
+
+#[derive(Resource, Default)]
+pub struct BenchmarkRun {
+    pub elapsed: f32,
+    pub frame_times: Vec<f32>,
+    pub tick_times: Vec<f32>,
+    tick_started: Option<Instant>,
+}
+
+pub fn benchmark_requested() -> bool {
+    std::env::args().any(|arg| arg == "--benchmark")
+}
+
+pub fn start_benchmark(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    biomes: Res<Biomes>,
+) {
+    println!("Starting benchmark with seed {0}", BENCHMARK_SEED);
+
+    commands.spawn(WorldData {
+        seed: BENCHMARK_SEED,
+        biomes: biomes.table.clone(),
+        ..default()
+    });
+
+    next_state.set(GameState::WorldGenerating);
+}
+
+// Bracket the whole fixed-step schedule, so each tick's time covers every
+// simulation system that ran in it
+pub fn start_benchmark_tick(mut benchmark: ResMut<BenchmarkRun>) {
+    benchmark.tick_started = Some(Instant::now());
+}
+
+pub fn finish_benchmark_tick(mut benchmark: ResMut<BenchmarkRun>) {
+    if let Some(started) = benchmark.tick_started.take() {
+        let tick_time = started.elapsed().as_secs_f32() * 1000.0;
+        benchmark.tick_times.push(tick_time);
+    }
+}
+
+pub fn run_benchmark(
+    time: Res<Time>,
+    mut benchmark: ResMut<BenchmarkRun>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    benchmark.elapsed += time.delta_secs();
+    benchmark.frame_times.push(time.delta_secs() * 1000.0);
+
+    // Scripted camera path: one full loop around a circle, so every run streams
+    // the same chunks in the same order
+    let t = (benchmark.elapsed / BENCHMARK_DURATION_SECS).min(1.0) * std::f32::consts::TAU;
+    camera.translation.x = t.cos() * BENCHMARK_PATH_RADIUS;
+    camera.translation.y = t.sin() * BENCHMARK_PATH_RADIUS;
+
+    if benchmark.elapsed < BENCHMARK_DURATION_SECS || benchmark.tick_times.len() < BENCHMARK_TICKS {
+        return;
+    }
+
+    let mut frame_times = benchmark.frame_times.clone();
+    frame_times.sort_by(|a, b| a.total_cmp(b));
+    let mut tick_times = benchmark.tick_times.clone();
+    tick_times.sort_by(|a, b| a.total_cmp(b));
+
+    println!("Benchmark finished");
+    println!("Frames: {0}", frame_times.len());
+    println!("Frame_p50 {0:.2}ms", percentile(&frame_times, 0.50));
+    println!("Frame_p90 {0:.2}ms", percentile(&frame_times, 0.90));
+    println!("Frame_p99 {0:.2}ms", percentile(&frame_times, 0.99));
+    println!(
+        "Frame_max {0:.2}ms",
+        frame_times.last().copied().unwrap_or(0.0)
+    );
+    println!("Ticks: {0}", tick_times.len());
+    println!("Tick_p50 {0:.2}ms", percentile(&tick_times, 0.50));
+    println!("Tick_p90 {0:.2}ms", percentile(&tick_times, 0.90));
+    println!("Tick_p99 {0:.2}ms", percentile(&tick_times, 0.99));
+    println!(
+        "Tick_max {0:.2}ms",
+        tick_times.last().copied().unwrap_or(0.0)
+    );
+
+    exit.write(AppExit::Success);
+}
+
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let index = ((sorted.len() - 1) as f32 * fraction).round() as usize;
+    sorted[index]
+}
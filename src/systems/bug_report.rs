@@ -0,0 +1,184 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{Cursor, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::log::{
+    BoxedLayer,
+    tracing::{
+        Event, Subscriber,
+        field::{Field, Visit},
+    },
+    tracing_subscriber::{Layer, layer::Context},
+};
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use image::ImageFormat;
+use zip::{ZipWriter, result::ZipResult, write::SimpleFileOptions};
+
+use crate::components::world_gen::WorldData;
+use crate::systems::{season::Season, startup::user_data_dir, world::WorldSettings};
+
+// Log lines kept for the next bug report
+const RECENT_LOG_LINES: usize = 200;
+
+// Ring buffer of recent log lines, filled by the logging layer from any thread
+#[derive(Resource, Clone, Default)]
+pub struct RecentLogs(Arc<Mutex<VecDeque<String>>>);
+
+impl RecentLogs {
+    fn push(&self, line: String) {
+        if let Ok(mut lines) = self.0.lock() {
+            if lines.len() == RECENT_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+// Set by the pause menu; the report is written on the next frame
+#[derive(Resource)]
+pub struct BugReportRequest {
+    pub screenshot: bool,
+}
+
+// `LogPlugin::custom_layer` hook: mirrors every log event into `RecentLogs`
+pub fn capture_recent_logs(app: &mut App) -> Option<BoxedLayer> {
+    let logs = RecentLogs::default();
+    app.insert_resource(logs.clone());
+
+    Some(Box::new(RecentLogLayer(logs)))
+}
+
+struct RecentLogLayer(RecentLogs);
+
+impl<S: Subscriber> Layer<S> for RecentLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = String::new();
+        event.record(&mut MessageVisitor(&mut message));
+
+        let metadata = event.metadata();
+        self.0
+            .push(format!("{} {}: {}", metadata.level(), metadata.target(), message));
+    }
+}
+
+struct MessageVisitor<'a>(&'a mut String);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+// Writes the seed, generation parameters, settings and recent logs (plus an
+// optional screenshot) into one zip under the user's home directory, so a
+// generation bug can be reproduced from the report alone.
+pub fn write_bug_report(
+    mut commands: Commands,
+    request: Res<BugReportRequest>,
+    settings: Res<WorldSettings>,
+    season: Res<Season>,
+    logs: Option<Res<RecentLogs>>,
+    world_data_query: Query<&WorldData>,
+) {
+    commands.remove_resource::<BugReportRequest>();
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let directory = user_data_dir().join("bug_reports");
+    let archive = directory.join(format!("report-{}.zip", timestamp));
+
+    if let Err(err) = fs::create_dir_all(&directory) {
+        error!("Could not create bug report folder {}: {}", directory.display(), err);
+        return;
+    }
+
+    let mut report = format!("Kingdom Sim {}\n\n", env!("CARGO_PKG_VERSION"));
+    match world_data_query.single() {
+        Ok(world_data) => {
+            let _ = writeln!(report, "Seed: {}\n\n{:#?}", world_data.seed, world_data);
+        }
+        Err(err) => {
+            let _ = writeln!(report, "No world data: {:?}", err);
+        }
+    }
+    let _ = writeln!(
+        report,
+        "\n{:#?}\n\nYear {}, {:.0}% through the year",
+        *settings,
+        season.year,
+        season.year_fraction * 100.0
+    );
+
+    let log = logs.map(|logs| logs.lines().join("\n")).unwrap_or_default();
+    let files = vec![
+        ("report.txt", report.into_bytes()),
+        ("log.txt", log.into_bytes()),
+    ];
+
+    if !request.screenshot {
+        save_report(&archive, &files);
+        return;
+    }
+
+    // The screenshot arrives a few frames later, so the archive waits for it
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(move |captured: On<ScreenshotCaptured>| {
+            let mut files = files.clone();
+            match encode_screenshot(&captured.image) {
+                Ok(png) => files.push(("screenshot.png", png)),
+                Err(err) => error!("Could not encode bug report screenshot: {}", err),
+            }
+            save_report(&archive, &files);
+        });
+}
+
+// Drops the alpha channel, which holds brightness rather than transparency under HDR
+fn encode_screenshot(image: &Image) -> Result<Vec<u8>, String> {
+    let image = image
+        .clone()
+        .try_into_dynamic()
+        .map_err(|err| err.to_string())?
+        .to_rgb8();
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, ImageFormat::Png)
+        .map_err(|err| err.to_string())?;
+    Ok(png.into_inner())
+}
+
+fn save_report(path: &Path, files: &[(&str, Vec<u8>)]) {
+    match write_archive(path, files) {
+        Ok(()) => info!("Bug report saved to {}", path.display()),
+        Err(err) => error!("Could not write bug report to {}: {}", path.display(), err),
+    }
+}
+
+fn write_archive(path: &Path, files: &[(&str, Vec<u8>)]) -> ZipResult<()> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    for (name, contents) in files {
+        zip.start_file(*name, SimpleFileOptions::default())?;
+        zip.write_all(contents)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
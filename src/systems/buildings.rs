@@ -0,0 +1,424 @@
+use bevy::prelude::*;
+
+use crate::components::{
+    buildings::{BuildButton, BuildToolbar, Building, BuildingKind},
+    kingdoms::{OwnedBy, Technology},
+    localization::Localized,
+    settlements::{Farm, FarmOf, FishingGrounds, FoodCapacity, Good, ProductionRates, Settlement},
+    world::{Biome, ResourceKind, Square, WorldMap},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    farming::{farm_yield, spawn_farm},
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    notifications::Notifications,
+    pause_menu::Pause,
+    world_gen::biome_table::BiomeTable,
+    world::{
+        LoadedChunks, WorldSettings, cursor_tile, known_square, nearest_copy, set_tile,
+        toroidal_distance,
+    },
+    world_record::WorldRecord,
+};
+
+// A building works for the nearest settlement this many tiles away or closer
+const BUILD_REACH: f32 = 12.0;
+// Tiles around the cursor checked and outlined while placing
+const HIGHLIGHT_RADIUS: i32 = 10;
+// Yearly output a building adds to the settlement it works for
+const MINE_ORE: f32 = 20.0;
+const MINE_STONE: f32 = 10.0;
+const LUMBER_CAMP_TIMBER: f32 = 15.0;
+// Fish a harbour's boats land a year on top of the settlement's own, while the
+// shoals are fully stocked
+const HARBOUR_FISH: f32 = 150.0;
+const BUILDING_SIZE: f32 = 0.7;
+// Over farms and territory, under settlement markers
+const BUILDING_Z: f32 = 0.3;
+const VALID_COLOR: Color = Color::srgba(0.3, 0.95, 0.3, 0.6);
+const INVALID_COLOR: Color = Color::srgba(0.95, 0.25, 0.25, 0.8);
+const BUTTON_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const SELECTED_BUTTON_COLOR: Color = Color::srgb(0.3, 0.45, 0.3);
+
+// The building the player is placing, if any
+#[derive(Resource, Default)]
+pub struct BuildMode(pub Option<BuildingKind>);
+
+// Farms want soil worth the work, mines high ground or a deposit, lumber camps
+// woodland, harbours the shore, and forts any dry land
+pub fn can_build(kind: BuildingKind, square: &Square, biomes: &BiomeTable) -> bool {
+    let water = matches!(square.biome, Biome::Ocean | Biome::ShallowOcean);
+    let forest = matches!(
+        square.biome,
+        Biome::Forest
+            | Biome::TemperateForest
+            | Biome::SubtropicalForest
+            | Biome::BorealForest
+            | Biome::Taiga
+            | Biome::TemperateRainforest
+            | Biome::TropicalRainforest
+    );
+
+    match kind {
+        BuildingKind::Farm => !water && farm_yield(square, biomes) > 0.0,
+        BuildingKind::Mine => {
+            matches!(square.biome, Biome::Hill | Biome::Mountain | Biome::Alpine)
+                || matches!(
+                    square.resources,
+                    ResourceKind::Iron | ResourceKind::Stone | ResourceKind::Gold
+                )
+        }
+        BuildingKind::LumberCamp => !water && (forest || square.resources == ResourceKind::Timber),
+        BuildingKind::Fort => !water,
+        BuildingKind::Harbour => square.biome == Biome::Coast,
+    }
+}
+
+// What building does to the land under it: fields replace whatever grew there,
+// and the rest thin out the vegetation
+fn apply_building(kind: BuildingKind, square: &mut Square) {
+    match kind {
+        BuildingKind::Farm => {
+            square.biome = Biome::Farmland;
+            square.vegetation = square.vegetation.min(0.3);
+        }
+        BuildingKind::LumberCamp => square.vegetation *= 0.5,
+        BuildingKind::Mine | BuildingKind::Fort | BuildingKind::Harbour => {
+            square.vegetation = 0.0
+        }
+    }
+}
+
+pub fn setup_build_toolbar(mut commands: Commands) {
+    let buttons = BuildingKind::ALL.map(|kind| {
+        (
+            Button,
+            BuildButton(kind),
+            Node {
+                padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            children![(
+                Text::new(kind.name()),
+                Localized::new(format!("building.{:?}", kind)),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            )],
+        )
+    });
+
+    commands.spawn((
+        BuildToolbar,
+        // Blocks clicks so a button press doesn't also place a building
+        Interaction::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Percent(50.0),
+            margin: UiRect::left(Val::Px(-215.0)),
+            column_gap: Val::Px(6.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Children::spawn(SpawnIter(buttons.into_iter())),
+    ));
+}
+
+// A toolbar button picks its building, or puts it down again if already picked
+pub fn pick_building(
+    mut mode: ResMut<BuildMode>,
+    mut button_query: Query<(&Interaction, &BuildButton, &mut BackgroundColor)>,
+) {
+    for (interaction, button, _) in &button_query {
+        if *interaction == Interaction::Pressed {
+            mode.0 = if mode.0 == Some(button.0) {
+                None
+            } else {
+                Some(button.0)
+            };
+        }
+    }
+    if !mode.is_changed() {
+        return;
+    }
+
+    for (_, button, mut color) in &mut button_query {
+        color.0 = if mode.0 == Some(button.0) {
+            SELECTED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+    }
+}
+
+// The camera and window a cursor tile is read through
+type CursorView<'w, 's> = (
+    Single<'w, 's, (&'static Camera, &'static GlobalTransform), With<Camera2d>>,
+    Single<'w, 's, &'static Window>,
+);
+
+// Buildings and farms, which each take up their tile
+type Occupancy<'w, 's> = (
+    Query<'w, 's, &'static Building>,
+    Query<'w, 's, &'static Farm>,
+);
+
+// The world a building is placed into, and the map it changes
+type BuildSite<'w, 's> = (
+    Res<'w, WorldSettings>,
+    ResMut<'w, LoadedChunks>,
+    Query<'w, 's, &'static mut WorldMap>,
+    Query<'w, 's, &'static WorldData>,
+);
+
+// A settlement a building can work for, with what the building adds to
+type Workplace = (
+    Entity,
+    &'static Settlement,
+    &'static mut ProductionRates,
+    &'static mut FoodCapacity,
+    &'static mut FishingGrounds,
+    Option<&'static OwnedBy>,
+);
+
+type BuildingOrToolbar = Or<(With<Building>, With<BuildToolbar>)>;
+
+// While placing, left click builds on a valid tile and right click stops. Both
+// clicks, and any click on the toolbar, are used up here so units and the
+// inspector don't see them.
+pub fn place_building(
+    mut commands: Commands,
+    (camera_query, window_query): CursorView,
+    (mut mouse, pause, mut mode): (
+        ResMut<ButtonInput<MouseButton>>,
+        Res<Pause>,
+        ResMut<BuildMode>,
+    ),
+    (settings, mut loaded, mut world_query, world_data_query): BuildSite,
+    (toolbar_query, (building_query, farm_query)): (
+        Query<&Interaction, With<BuildToolbar>>,
+        Occupancy,
+    ),
+    (mut notifications, locale, mut record): (
+        ResMut<Notifications>,
+        Res<Locale>,
+        ResMut<WorldRecord>,
+    ),
+    (tech_query, mut territory, mut settlement_query): (
+        Query<&Technology>,
+        Option<ResMut<TerritoryMap>>,
+        Query<Workplace>,
+    ),
+) {
+    if pause.menu_open {
+        return;
+    }
+    let over_toolbar = toolbar_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    if over_toolbar {
+        mouse.clear_just_pressed(MouseButton::Left);
+        return;
+    }
+    let Some(kind) = mode.0 else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Right) {
+        mouse.clear_just_pressed(MouseButton::Right);
+        mode.0 = None;
+        return;
+    }
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    mouse.clear_just_pressed(MouseButton::Left);
+
+    let (camera, camera_transform) = *camera_query;
+    let Some(tile) = cursor_tile(camera, camera_transform, &window_query) else {
+        return;
+    };
+    let tile = tile.rem_euclid(IVec2::splat(settings.world_size));
+    if occupied(tile, &building_query, &farm_query) {
+        return;
+    }
+    let Some(square) = known_square(tile, &settings, &loaded, world_query.single().ok()) else {
+        return;
+    };
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    if !can_build(kind, &square, &world_data.biomes) {
+        return;
+    }
+
+    let worked_for = settlement_query
+        .iter()
+        .map(|(entity, settlement, ..)| {
+            (entity, toroidal_distance(tile, settlement.tile, &settings))
+        })
+        .filter(|(_, distance)| *distance <= BUILD_REACH)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity);
+
+    // Only a kingdom that has reached its era knows how to build it
+    let era = worked_for
+        .and_then(|settlement| settlement_query.get(settlement).ok())
+        .and_then(|(.., owner)| owner)
+        .and_then(|owner| tech_query.get(owner.0).ok())
+        .map(|technology| technology.era)
+        .unwrap_or_default();
+    if era < kind.era() {
+        let era = kind.era();
+        notifications.push(locale.format(
+            "notice.needs_era",
+            &[
+                &locale.name("building", kind, kind.name()),
+                &locale.name("era", era, era.name()),
+            ],
+        ));
+        return;
+    }
+
+    if let Some(settlement) = worked_for
+        && let Ok((_, _, mut production, mut capacity, mut fishing, owner)) =
+            settlement_query.get_mut(settlement)
+    {
+        match kind {
+            BuildingKind::Farm => {
+                let yield_per_year = farm_yield(&square, &world_data.biomes) * era.farm_yield();
+                production.0[Good::Grain as usize] += yield_per_year;
+                capacity.0 += yield_per_year;
+                let farm = spawn_farm(&mut commands, tile, yield_per_year);
+                commands.entity(farm).insert(FarmOf(settlement));
+            }
+            BuildingKind::Mine => {
+                let (good, amount) = match square.resources {
+                    ResourceKind::Iron | ResourceKind::Gold => (Good::Ore, MINE_ORE),
+                    _ => (Good::Stone, MINE_STONE),
+                };
+                production.0[good as usize] += amount;
+            }
+            BuildingKind::LumberCamp => {
+                production.0[Good::Timber as usize] += LUMBER_CAMP_TIMBER;
+            }
+            BuildingKind::Harbour => {
+                fishing.full_yield += HARBOUR_FISH;
+                production.0[Good::Fish as usize] += HARBOUR_FISH * fishing.shoals;
+                capacity.0 += HARBOUR_FISH;
+            }
+            // A fort on the frontier pushes the border out by its own tile
+            BuildingKind::Fort => {
+                if let (Some(territory), Some(owner)) = (territory.as_deref_mut(), owner)
+                    && let Err(reason) = territory.claim_tile(tile, owner.0)
+                {
+                    info!("The fort at {}, {} claims no land: {}", tile.x, tile.y, reason);
+                }
+            }
+        }
+    }
+
+    let mut built = square.clone();
+    apply_building(kind, &mut built);
+    let mut world_map = world_query.single_mut().ok();
+    set_tile(tile, &built, &settings, &mut loaded, world_map.as_deref_mut());
+    record.tiles_terraformed += 1;
+
+    info!("Built a {} at {}, {}", kind.name(), tile.x, tile.y);
+    spawn_building(&mut commands, kind, tile);
+}
+
+pub fn spawn_building(commands: &mut Commands, kind: BuildingKind, tile: IVec2) {
+    commands.spawn((
+        Sprite::from_color(building_color(kind), Vec2::splat(BUILDING_SIZE)),
+        Transform::from_translation((tile.as_vec2() + Vec2::splat(0.5)).extend(BUILDING_Z)),
+        Building { kind, tile },
+    ));
+}
+
+// Outlines the tiles around the cursor the picked building could go on, and
+// marks the tile under the cursor when it can't
+pub fn draw_build_targets(
+    mut gizmos: Gizmos,
+    (camera_query, window_query): CursorView,
+    (mode, settings, loaded): (Res<BuildMode>, Res<WorldSettings>, Res<LoadedChunks>),
+    (world_query, world_data): (Query<&WorldMap>, Single<&WorldData>),
+    (building_query, farm_query): Occupancy,
+) {
+    let Some(kind) = mode.0 else {
+        return;
+    };
+    let (camera, camera_transform) = *camera_query;
+    let Some(cursor) = cursor_tile(camera, camera_transform, &window_query) else {
+        return;
+    };
+    let world_map = world_query.single().ok();
+    let size = IVec2::splat(settings.world_size);
+
+    for dy in -HIGHLIGHT_RADIUS..=HIGHLIGHT_RADIUS {
+        for dx in -HIGHLIGHT_RADIUS..=HIGHLIGHT_RADIUS {
+            let tile = cursor + IVec2::new(dx, dy);
+            let base = tile.rem_euclid(size);
+            let valid = !occupied(base, &building_query, &farm_query)
+                && known_square(base, &settings, &loaded, world_map)
+                    .is_some_and(|square| can_build(kind, &square, &world_data.biomes));
+
+            let center = tile.as_vec2() + Vec2::splat(0.5);
+            if valid {
+                gizmos.rect_2d(center, Vec2::splat(0.8), VALID_COLOR);
+            } else if tile == cursor {
+                gizmos.rect_2d(center, Vec2::splat(0.8), INVALID_COLOR);
+            }
+        }
+    }
+}
+
+// Buildings are drawn on the copy of their tile nearest the camera
+pub fn update_building_sprites(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut building_query: Query<(&Building, &mut Transform), Without<Camera2d>>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for (building, mut transform) in &mut building_query {
+        let nearest = nearest_copy(camera, building.tile, &settings);
+        let position = (nearest.as_vec2() + Vec2::splat(0.5)).extend(BUILDING_Z);
+        if transform.translation != position {
+            transform.translation = position;
+        }
+    }
+}
+
+pub fn cleanup_buildings(
+    mut commands: Commands,
+    mut mode: ResMut<BuildMode>,
+    query: Query<Entity, BuildingOrToolbar>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    mode.0 = None;
+}
+
+// One building to a tile, and none on fields a settlement has already cleared
+fn occupied(tile: IVec2, building_query: &Query<&Building>, farm_query: &Query<&Farm>) -> bool {
+    building_query.iter().any(|building| building.tile == tile)
+        || farm_query.iter().any(|farm| farm.tile == tile)
+}
+
+fn building_color(kind: BuildingKind) -> Color {
+    match kind {
+        BuildingKind::Farm => Color::srgb(0.9, 0.8, 0.35),
+        BuildingKind::Mine => Color::srgb(0.45, 0.45, 0.5),
+        BuildingKind::LumberCamp => Color::srgb(0.5, 0.32, 0.15),
+        BuildingKind::Fort => Color::srgb(0.75, 0.75, 0.8),
+        BuildingKind::Harbour => Color::srgb(0.3, 0.5, 0.75),
+    }
+}
@@ -0,0 +1,489 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::{
+    input::mouse::AccumulatedMouseScroll,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+use image::ExtendedColorType;
+
+use crate::components::{
+    chronicle::{
+        ChronicleEntry, ChronicleKind, HistoryExportButton, HistoryFilterButton, HistoryList,
+        HistoryPanel, HistoryText,
+    },
+    kingdoms::{Kingdom, KingdomSettlements, Road, Technology},
+    localization::Localized,
+    pause_menu::PausesSim,
+    settlements::Settlement,
+    world::WorldMap,
+    world_gen::WorldData,
+};
+use crate::systems::{
+    export::{map_colors, streamed_map_colors, write_png},
+    keybindings::{InputAction, InputMap},
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    map_capture::{CaptureOverlays, capture_overlays, compose_capture},
+    notifications::Notifications,
+    palette::MapPalette,
+    pause_menu::file_action_display,
+    startup::user_data_dir,
+    world::WorldSettings,
+    world_gen::statistics::{WorldStatistics, world_statistics},
+};
+
+// Pixels the history list moves for each notch of the mouse wheel
+const SCROLL_STEP: f32 = 40.0;
+// Width of the map snapshot in the exported chronicle; smaller worlds keep one
+// pixel per tile
+const SNAPSHOT_SIDE: f32 = 1024.0;
+const BUTTON_COLOR: Color = Color::srgb(0.25, 0.25, 0.3);
+
+// Everything of note that has happened in the world, oldest first
+#[derive(Resource, Default)]
+pub struct Chronicle {
+    entries: Vec<ChronicleEntry>,
+    // Kind of entry the history panel shows, or every kind
+    filter: Option<ChronicleKind>,
+    dirty: bool,
+}
+
+// Set by the history panel's export button; the export starts on the next frame
+#[derive(Resource)]
+pub struct ChronicleExportRequest;
+
+// Paints the map snapshot and writes the document off the main thread, handing
+// back the document's path
+#[derive(Resource)]
+pub struct ChronicleExportTask(Task<Result<PathBuf, String>>);
+
+// Kingdoms as the almanac lists them, and the map snapshot colours them
+type AlmanacKingdoms<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Kingdom,
+        &'static KingdomSettlements,
+        &'static Technology,
+    ),
+>;
+
+impl Chronicle {
+    // Writes an entry under today's date, and to the log. Entries keep the
+    // language they were written in.
+    pub fn record(&mut self, date: String, kind: ChronicleKind, text: impl Into<String>) {
+        let text = text.into();
+        info!("{}", text);
+        self.entries.push(ChronicleEntry { date, kind, text });
+        self.dirty = true;
+    }
+
+    // A chronicle carried over from a save
+    pub fn restore(entries: Vec<ChronicleEntry>) -> Self {
+        Chronicle {
+            entries,
+            filter: None,
+            dirty: true,
+        }
+    }
+
+    pub fn entries(&self) -> &[ChronicleEntry] {
+        &self.entries
+    }
+
+    // Entries of one kind, or of every kind when None, oldest first
+    pub fn of_kind(
+        &self,
+        kind: Option<ChronicleKind>,
+    ) -> impl DoubleEndedIterator<Item = &ChronicleEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| kind.is_none_or(|kind| entry.kind == kind))
+    }
+}
+
+pub fn setup_history_panel(mut commands: Commands) {
+    let button = |label: &'static str, id: &'static str, display: Display| {
+        (
+            Button,
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                display,
+                ..default()
+            },
+            BackgroundColor(BUTTON_COLOR),
+            children![(
+                Text::new(label),
+                Localized::new(id),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            )],
+        )
+    };
+
+    commands.spawn((
+        HistoryPanel,
+        PausesSim,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(60.0),
+            left: Val::Px(10.0),
+            width: Val::Px(460.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        Visibility::Hidden,
+        children![
+            (
+                Node {
+                    column_gap: Val::Px(6.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                children![
+                    (
+                        Text::new("History"),
+                        Localized::new("history.title"),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        Node {
+                            flex_grow: 1.0,
+                            ..default()
+                        },
+                    ),
+                    (
+                        HistoryFilterButton,
+                        button("All", "history.all", Display::Flex)
+                    ),
+                    (
+                        HistoryExportButton,
+                        button("Export", "history.export", file_action_display()),
+                    ),
+                ],
+            ),
+            (
+                HistoryList,
+                // Tracks hovering, so the wheel scrolls the list under the cursor
+                Interaction::default(),
+                Node {
+                    max_height: Val::Px(360.0),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+                ScrollPosition::default(),
+                children![(
+                    HistoryText,
+                    Text::new(""),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )],
+            ),
+        ],
+    ));
+}
+
+pub fn toggle_history_panel(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut chronicle: ResMut<Chronicle>,
+    mut panel_query: Single<&mut Visibility, With<HistoryPanel>>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleHistory) {
+        let shown = **panel_query == Visibility::Hidden;
+        **panel_query = if shown {
+            chronicle.dirty = true;
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+type FilterPressed = (With<HistoryFilterButton>, Changed<Interaction>);
+
+// The filter button steps through every kind of entry and back to all of them;
+// the export button writes the whole chronicle out as a document
+pub fn history_panel_buttons(
+    mut commands: Commands,
+    mut chronicle: ResMut<Chronicle>,
+    filter_query: Query<(&Interaction, &Children), FilterPressed>,
+    export_query: Query<&Interaction, (With<HistoryExportButton>, Changed<Interaction>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in &filter_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let next = match chronicle.filter {
+            None => Some(0),
+            Some(kind) => ChronicleKind::ALL
+                .iter()
+                .position(|other| *other == kind)
+                .map(|index| index + 1)
+                .filter(|index| *index < ChronicleKind::ALL.len()),
+        };
+        chronicle.filter = next.map(|index| ChronicleKind::ALL[index]);
+        chronicle.dirty = true;
+
+        let label = match chronicle.filter {
+            Some(kind) => format!("chronicle_kind.{:?}", kind),
+            None => "history.all".to_string(),
+        };
+        for child in children {
+            if let Ok(mut localized) = label_query.get_mut(*child) {
+                localized.0 = label.clone().into();
+            }
+        }
+    }
+
+    if export_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        commands.insert_resource(ChronicleExportRequest);
+    }
+}
+
+// Writes the chronicle as a Markdown document, with an almanac of the world and
+// a snapshot of the map showing borders, roads and settlement names. The
+// document and the snapshot go in a folder of their own so they can be shared
+// together.
+pub fn start_chronicle_export(
+    mut commands: Commands,
+    (chronicle, locale): (Res<Chronicle>, Res<Locale>),
+    (settings, map_palette): (Res<WorldSettings>, Res<MapPalette>),
+    running: Option<Res<ChronicleExportTask>>,
+    (world_data_query, world_map_query): (Query<&WorldData>, Query<&WorldMap>),
+    territory: Option<Res<TerritoryMap>>,
+    (kingdom_query, road_query, settlement_query): (
+        AlmanacKingdoms,
+        Query<&Road>,
+        Query<&Settlement>,
+    ),
+) {
+    commands.remove_resource::<ChronicleExportRequest>();
+    if running.is_some() {
+        warn!("A chronicle export is already running");
+        return;
+    }
+
+    let world_data = match world_data_query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let settings = *settings;
+    let world_map = world_map_query.single().ok();
+
+    let stats = world_statistics(world_map, &world_data, &settings);
+    let mut kingdoms: Vec<String> = kingdom_query
+        .iter()
+        .map(|(_, kingdom, settlements, technology)| {
+            let era = technology.era;
+            locale.format(
+                "diplomacy.kingdom",
+                &[
+                    &kingdom.name,
+                    &settlements.len(),
+                    &locale.name("era", era, era.name()),
+                ],
+            )
+        })
+        .collect();
+    kingdoms.sort();
+    let seed = locale.seed(world_data.seed, world_data.seed_text.as_deref());
+    let document = chronicle_markdown(&chronicle, &locale, &seed, &stats, &kingdoms);
+
+    let tiles_per_pixel = (settings.world_size as f32 / SNAPSHOT_SIDE).max(1.0);
+    let side = (settings.world_size as f32 / tiles_per_pixel).round() as usize;
+    let overlays = capture_overlays(
+        CaptureOverlays::All,
+        territory.as_deref(),
+        kingdom_query
+            .iter()
+            .map(|(entity, kingdom, ..)| (entity, kingdom)),
+        road_query.iter(),
+        settlement_query.iter(),
+    );
+
+    let palette = map_palette.palette.clone();
+    let painted = world_map.map(|world_map| map_colors(world_map, &world_data, &palette));
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let directory = user_data_dir()
+        .join("chronicles")
+        .join(format!("world-{}-{}", world_data.seed, timestamp));
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let terrain =
+            painted.unwrap_or_else(|| streamed_map_colors(&world_data, &settings, &palette));
+        let pixels = compose_capture(&terrain, &settings, tiles_per_pixel, side, &overlays);
+
+        fs::create_dir_all(&directory).map_err(|err| err.to_string())?;
+        write_png(
+            &directory.join("map.png"),
+            side as u32,
+            &pixels,
+            ExtendedColorType::Rgba8,
+        )?;
+        let path = directory.join("chronicle.md");
+        fs::write(&path, document).map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(path)
+    });
+
+    info!("Exporting the chronicle...");
+    commands.insert_resource(ChronicleExportTask(task));
+}
+
+pub fn poll_chronicle_export(
+    mut commands: Commands,
+    mut task: ResMut<ChronicleExportTask>,
+    locale: Res<Locale>,
+    mut notifications: ResMut<Notifications>,
+) {
+    let Some(result) = check_ready(&mut task.0) else {
+        return;
+    };
+    commands.remove_resource::<ChronicleExportTask>();
+
+    match result {
+        Ok(path) => notifications.push(locale.format("history.exported", &[&path.display()])),
+        Err(err) => error!("Chronicle export failed: {}", err),
+    }
+}
+
+// Newest entries first, so the latest news is at the top of the list
+pub fn update_history_panel(
+    mut chronicle: ResMut<Chronicle>,
+    locale: Res<Locale>,
+    panel_query: Single<&Visibility, With<HistoryPanel>>,
+    mut text_query: Single<&mut Text, With<HistoryText>>,
+) {
+    if **panel_query == Visibility::Hidden || !(chronicle.dirty || locale.is_changed()) {
+        return;
+    }
+    chronicle.dirty = false;
+
+    let lines: Vec<String> = chronicle
+        .of_kind(chronicle.filter)
+        .rev()
+        .map(|entry| format!("{}  {}", entry.date, entry.text))
+        .collect();
+    text_query.0 = if lines.is_empty() {
+        locale.text("history.empty").to_string()
+    } else {
+        lines.join("\n")
+    };
+}
+
+pub fn scroll_history_panel(
+    scroll: Res<AccumulatedMouseScroll>,
+    mut list_query: Single<(&Interaction, &mut ScrollPosition), With<HistoryList>>,
+) {
+    let (interaction, ref mut position) = *list_query;
+    if *interaction != Interaction::None && scroll.delta.y != 0.0 {
+        position.y = (position.y - scroll.delta.y * SCROLL_STEP).max(0.0);
+    }
+}
+
+pub fn cleanup_chronicle(
+    mut commands: Commands,
+    mut chronicle: ResMut<Chronicle>,
+    query: Query<Entity, With<HistoryPanel>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    *chronicle = Chronicle::default();
+}
+
+// A contents list linking to the almanac, the map and a section for each kind
+// of entry that has any, oldest entries first within each
+fn chronicle_markdown(
+    chronicle: &Chronicle,
+    locale: &Locale,
+    seed: &str,
+    stats: &WorldStatistics,
+    kingdoms: &[String],
+) -> String {
+    let kinds: Vec<ChronicleKind> = ChronicleKind::ALL
+        .into_iter()
+        .filter(|kind| chronicle.of_kind(Some(*kind)).next().is_some())
+        .collect();
+    let kind_name = |kind: ChronicleKind| locale.name("chronicle_kind", kind, kind.name());
+    let anchor = |kind: ChronicleKind| format!("{:?}", kind).to_lowercase();
+    // Anchors are written out, as headings in other languages link unreliably
+    let section = |id: &str, title: &str| format!("\n<a id=\"{}\"></a>\n\n## {}\n", id, title);
+
+    let mut lines = vec![
+        format!("# {}\n", locale.text("history.document_title")),
+        format!("{}\n", seed),
+        format!("## {}\n", locale.text("history.contents")),
+        format!("- [{}](#almanac)", locale.text("history.almanac")),
+        format!("- [{}](#map)", locale.text("history.map")),
+    ];
+    for &kind in &kinds {
+        lines.push(format!("- [{}](#{})", kind_name(kind), anchor(kind)));
+    }
+
+    lines.push(section("almanac", locale.text("history.almanac")));
+    let land = locale.format(
+        "stats.land",
+        &[
+            &format!("{:.1}", stats.land_share * 100.0),
+            &format!("{:.1}", (1.0 - stats.land_share) * 100.0),
+        ],
+    );
+    let landmasses = locale.format(
+        "stats.landmasses",
+        &[
+            &(stats.continents + stats.islands),
+            &stats.continents,
+            &stats.islands,
+        ],
+    );
+    lines.push(format!("- {}\n- {}", land, landmasses));
+    if !kingdoms.is_empty() {
+        lines.push(format!("\n### {}\n", locale.text("history.kingdoms")));
+        lines.extend(kingdoms.iter().map(|kingdom| format!("- {}", kingdom)));
+    }
+    lines.push(format!("\n### {}\n", locale.text("stats.biomes")));
+    for &(biome, share) in &stats.biome_shares {
+        lines.push(format!("- {} {:.1}%", locale.biome(biome), share * 100.0));
+    }
+
+    let map = locale.text("history.map");
+    lines.push(section("map", map));
+    lines.push(format!("![{}](map.png)", map));
+
+    if kinds.is_empty() {
+        lines.push(format!("\n{}", locale.text("history.empty")));
+    }
+    for kind in kinds {
+        lines.push(section(&anchor(kind), kind_name(kind)));
+        for entry in chronicle.of_kind(Some(kind)) {
+            lines.push(format!("- **{}** {}", entry.date, entry.text));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
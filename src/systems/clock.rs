@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::components::pause_menu::ClockIndicator;
+use crate::systems::{
+    keybindings::{InputAction, InputMap},
+    localization::Locale,
+    pause_menu::Pause,
+    season::Season,
+};
+
+// Multipliers the speed controls step through
+const SPEEDS: [u32; 3] = [1, 2, 4];
+
+// Pace of the simulation and the simulated time the current fixed tick covers.
+// Sim systems advance by `delta_secs` rather than frame or fixed time, so a
+// speed change or a pause reaches all of them at once. The date itself is kept
+// by `Season`, which is saved with the world.
+#[derive(Resource, Default)]
+pub struct GameClock {
+    speed: usize,
+    pub delta_secs: f32,
+}
+
+impl GameClock {
+    pub fn speed(&self) -> u32 {
+        SPEEDS[self.speed]
+    }
+}
+
+// First in each fixed tick, ahead of every system that reads the clock
+pub fn advance_clock(time: Res<Time<Fixed>>, pause: Res<Pause>, mut clock: ResMut<GameClock>) {
+    clock.delta_secs = if pause.is_paused() {
+        0.0
+    } else {
+        time.delta_secs() * clock.speed() as f32
+    };
+}
+
+pub fn change_speed(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut clock: ResMut<GameClock>,
+) {
+    if input_map.just_pressed(&input, InputAction::SpeedUp) {
+        clock.speed = (clock.speed + 1).min(SPEEDS.len() - 1);
+    }
+    if input_map.just_pressed(&input, InputAction::SlowDown) {
+        clock.speed = clock.speed.saturating_sub(1);
+    }
+}
+
+pub fn setup_clock_indicator(mut commands: Commands) {
+    commands.spawn((
+        ClockIndicator,
+        Text::default(),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        TextLayout::new_with_justify(Justify::Center),
+    ));
+}
+
+// Date and speed, or PAUSED in place of the speed while the sim is held
+pub fn update_clock_indicator(
+    clock: Res<GameClock>,
+    pause: Res<Pause>,
+    season: Res<Season>,
+    locale: Res<Locale>,
+    mut indicator_query: Query<&mut Text, With<ClockIndicator>>,
+) {
+    let pace = if pause.is_paused() {
+        locale.text("clock.paused").to_string()
+    } else {
+        format!("{}x", clock.speed())
+    };
+    let text = format!("{}   {}", locale.date(&season), pace);
+    for mut indicator in &mut indicator_query {
+        if indicator.0 != text {
+            indicator.0 = text.clone();
+        }
+    }
+}
+
+pub fn cleanup_clock(
+    mut commands: Commands,
+    mut clock: ResMut<GameClock>,
+    query: Query<Entity, With<ClockIndicator>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    *clock = GameClock::default();
+}
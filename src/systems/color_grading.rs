@@ -0,0 +1,128 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::components::color_grading::ColorGradingOverlay;
+use crate::components::weather::Storm;
+use crate::components::world::{Biome, ChunkCoord, DominantBiome};
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::options::{GameOptions, save_options};
+use crate::systems::season::{Season, tile_latitude};
+use crate::systems::world::{WorldSettings, nearest_copy};
+
+const GRADING_BLEND_SPEED: f32 = 1.5;
+// Seasonal offset (°C) at which the winter tint is fully applied
+const FULL_WINTER_OFFSET: f32 = 12.0;
+
+pub fn setup_color_grading(mut commands: Commands) {
+    commands.spawn((
+        ColorGradingOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::NONE),
+        GlobalZIndex(-1),
+    ));
+}
+
+pub fn toggle_color_grading(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut options: ResMut<GameOptions>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleColorGrading) {
+        options.color_grading = !options.color_grading;
+        save_options(&options);
+    }
+}
+
+// Graded by the chunks and storms in view, so the tint follows what is on screen
+pub fn update_color_grading(
+    options: Res<GameOptions>,
+    season: Res<Season>,
+    settings: Res<WorldSettings>,
+    time: Res<Time>,
+    camera_query: Single<(&Transform, &Projection), With<Camera2d>>,
+    (chunk_query, storm_query): (Query<(&ChunkCoord, &DominantBiome)>, Query<&Storm>),
+    mut overlay_query: Query<&mut BackgroundColor, With<ColorGradingOverlay>>,
+) {
+    let (camera, projection) = *camera_query;
+    let Projection::Orthographic(projection2d) = projection else {
+        return;
+    };
+    let center = camera.translation.truncate();
+    let view = Rect::from_corners(
+        center + projection2d.area.min,
+        center + projection2d.area.max,
+    );
+    let chunk_size = settings.chunk_size as f32;
+
+    let mut biome_counts: HashMap<Biome, u32> = HashMap::new();
+    for (coord, dominant) in &chunk_query {
+        let min = coord.0.as_vec2() * chunk_size;
+        let chunk = Rect::from_corners(min, min + Vec2::splat(chunk_size));
+        if !view.intersect(chunk).is_empty() {
+            *biome_counts.entry(dominant.0).or_insert(0) += 1;
+        }
+    }
+
+    // The strongest storm whose eye is in view darkens the grade
+    let storminess = storm_query
+        .iter()
+        .filter(|storm| {
+            let eye = nearest_copy(center.as_ivec2(), storm.position.as_ivec2(), &settings);
+            view.contains(eye.as_vec2())
+        })
+        .map(|storm| storm.intensity)
+        .fold(0.0, f32::max);
+
+    let target = match biome_counts.into_iter().max_by_key(|&(_, count)| count) {
+        Some((biome, _)) if options.color_grading => {
+            let latitude = tile_latitude(camera.translation.y as i32, settings.world_size);
+            let winter =
+                (-season.temperature_offset(latitude) / FULL_WINTER_OFFSET).clamp(0.0, 1.0);
+
+            biome_tint(biome)
+                .to_linear()
+                .mix(&WINTER_TINT.to_linear(), winter * 0.5)
+                .mix(&STORM_TINT.to_linear(), storminess * 0.7)
+                .into()
+        }
+        _ => Color::NONE,
+    };
+
+    let blend = (GRADING_BLEND_SPEED * time.delta_secs()).min(1.0);
+
+    for mut background in &mut overlay_query {
+        let current = background.0.to_linear();
+        background.0 = current.mix(&target.to_linear(), blend).into();
+    }
+}
+
+pub fn cleanup_color_grading(
+    mut commands: Commands,
+    query: Query<Entity, With<ColorGradingOverlay>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+const WINTER_TINT: Color = Color::srgba(0.7, 0.8, 1.0, 0.1);
+const STORM_TINT: Color = Color::srgba(0.35, 0.4, 0.5, 0.18);
+
+fn biome_tint(biome: Biome) -> Color {
+    match biome {
+        Biome::Ice | Biome::Snow | Biome::Tundra | Biome::Alpine => {
+            Color::srgba(0.55, 0.7, 1.0, 0.12)
+        }
+        Biome::BorealForest | Biome::Taiga | Biome::ColdDesert => Color::srgba(0.6, 0.7, 0.9, 0.08),
+        Biome::Desert | Biome::HotDesert => Color::srgba(1.0, 0.75, 0.4, 0.12),
+        Biome::Savanna => Color::srgba(1.0, 0.85, 0.5, 0.08),
+        Biome::TropicalRainforest | Biome::SubtropicalForest => Color::srgba(0.6, 1.0, 0.6, 0.06),
+        Biome::Ocean | Biome::ShallowOcean => Color::srgba(0.3, 0.5, 0.9, 0.05),
+        _ => Color::NONE,
+    }
+}
@@ -0,0 +1,422 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{
+    buildings::{Building, BuildingKind},
+    chronicle::ChronicleKind,
+    diplomacy::Treaty,
+    kingdoms::{AiControlled, Kingdom, KingdomSettlements, OwnedBy},
+    settlements::{Population, Settlement},
+    units::{Army, MoveOrder, PathSearch, Siege, Unit, UnitKind},
+    world::Biome,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    diplomacy::Diplomacy,
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    pathfinding::PathGrid,
+    season::Season,
+    settlements::MIN_POPULATION,
+    units::spawn_unit,
+    world::{WorldSettings, toroidal_distance},
+};
+
+// War councils meet a few times a year
+const WAR_TICK_SECS: f32 = 20.0;
+// A settlement needs this many people before soldiers are levied from it
+const LEVY_MIN_POPULATION: f32 = 200.0;
+const LEVY_SHARE: f32 = 0.15;
+// Armies this close meet in battle, so two marching past each other still clash
+const ENGAGE_RADIUS: f32 = 1.0;
+// Share of its strength the winner loses for each unit of strength it beat
+const CASUALTY_RATE: f32 = 0.5;
+// Spread of fortune in a battle, either side of the even odds
+const BATTLE_LUCK: f32 = 0.2;
+// Tiles from a settlement an army can lay siege from
+const SIEGE_RADIUS: f32 = 2.5;
+// Share of a settlement's people who man its walls
+pub const GARRISON_SHARE: f32 = 0.1;
+// Siege progress per simulated second for an army as strong as the garrison
+const SIEGE_RATE: f32 = 0.02;
+// Strength lost each simulated second to the garrison's sallies, per unit of
+// garrison strength
+const SIEGE_ATTRITION: f32 = 0.01;
+// Garrisons hold out this much better with a fort close by
+const FORT_BONUS: f32 = 2.0;
+const FORT_REACH: f32 = 6.0;
+// Share of a settlement's people lost when it falls
+const SACK_LOSS: f32 = 0.2;
+
+#[derive(Resource, Default)]
+pub struct WarCouncil {
+    pub cooldown: f32,
+}
+
+type AiKingdom = (With<Kingdom>, With<AiControlled>);
+
+// A settlement that can be levied from, besieged or taken, with its kingdom
+type HeldSettlement = (
+    Entity,
+    &'static Settlement,
+    &'static mut Population,
+    &'static OwnedBy,
+);
+
+// An army and whether it is marching, finding its way or besieging
+type FieldArmy = (
+    Entity,
+    &'static Army,
+    &'static Unit,
+    Has<MoveOrder>,
+    Has<PathSearch>,
+    Has<Siege>,
+);
+
+// An army standing still, and the siege it is laying if any
+type Besieger = (
+    Entity,
+    &'static Unit,
+    &'static mut Army,
+    Option<&'static mut Siege>,
+);
+
+// Defenders hold rough ground far better than open fields
+pub fn defence_bonus(biome: Biome) -> f32 {
+    match biome {
+        Biome::Mountain | Biome::Hill | Biome::Alpine => 1.5,
+        Biome::Forest
+        | Biome::TemperateForest
+        | Biome::SubtropicalForest
+        | Biome::BorealForest
+        | Biome::Taiga
+        | Biome::TemperateRainforest
+        | Biome::TropicalRainforest => 1.3,
+        _ => 1.0,
+    }
+}
+
+// Defenders a settlement turns out against raiders, who are gone again before
+// a fort's walls come into it
+pub fn raid_garrison(population: f32, tile: IVec2, grid: Option<&PathGrid>) -> f32 {
+    let terrain = grid
+        .map(|grid| defence_bonus(grid.biome(tile)))
+        .unwrap_or(1.0);
+    population * GARRISON_SHARE * terrain
+}
+
+// Whether attackers outfight their defenders, with a battle's luck either way
+pub fn prevails(attackers: f32, defenders: f32) -> bool {
+    attackers * rand::rng().random_range(1.0 - BATTLE_LUCK..1.0 + BATTLE_LUCK) > defenders
+}
+
+// Each AI kingdom at war levies an army from its largest settlement if it has
+// none in the field, and sends idle armies against the nearest enemy
+// settlement. Armies with no war left to fight go home and disband.
+pub fn raise_armies(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    (settings, diplomacy): (Res<WorldSettings>, Res<Diplomacy>),
+    mut council: ResMut<WarCouncil>,
+    kingdom_query: Query<(Entity, &KingdomSettlements), AiKingdom>,
+    mut settlement_query: Query<HeldSettlement>,
+    army_query: Query<FieldArmy>,
+) {
+    council.cooldown -= clock.delta_secs;
+    if council.cooldown > 0.0 {
+        return;
+    }
+    council.cooldown = WAR_TICK_SECS;
+
+    for (kingdom, settlements) in &kingdom_query {
+        let enemy_tiles: Vec<IVec2> = settlement_query
+            .iter()
+            .filter(|(.., owner)| diplomacy.treaty(kingdom, owner.0) == Treaty::War)
+            .map(|(_, settlement, ..)| settlement.tile)
+            .collect();
+
+        let mut fielded = false;
+        for (entity, army, unit, moving, searching, besieging) in &army_query {
+            if army.kingdom != kingdom {
+                continue;
+            }
+            fielded = true;
+            if moving || searching || besieging {
+                continue;
+            }
+
+            let target = enemy_tiles
+                .iter()
+                .min_by(|a, b| {
+                    toroidal_distance(unit.tile, **a, &settings)
+                        .total_cmp(&toroidal_distance(unit.tile, **b, &settings))
+                })
+                .copied();
+            match target {
+                Some(target) => {
+                    commands.entity(entity).insert(MoveOrder::new(target));
+                }
+                None => {
+                    // The soldiers go back to the nearest of their own settlements
+                    let home = settlements
+                        .iter()
+                        .filter_map(|settlement| settlement_query.get(settlement).ok())
+                        .map(|(home, settlement, ..)| {
+                            (
+                                home,
+                                toroidal_distance(unit.tile, settlement.tile, &settings),
+                            )
+                        })
+                        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                        .map(|(home, _)| home);
+                    if let Some(home) = home
+                        && let Ok((.., mut population, _)) = settlement_query.get_mut(home)
+                    {
+                        population.0 += army.strength;
+                    }
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+        if fielded || enemy_tiles.is_empty() {
+            continue;
+        }
+
+        let home = settlements
+            .iter()
+            .filter_map(|entity| settlement_query.get(entity).ok())
+            .max_by(|(.., a, _), (.., b, _)| a.0.total_cmp(&b.0))
+            .map(|(entity, settlement, ..)| (entity, settlement.tile));
+        let Some((home, home_tile)) = home else {
+            continue;
+        };
+        let Ok((.., mut population, _)) = settlement_query.get_mut(home) else {
+            continue;
+        };
+        if population.0 < LEVY_MIN_POPULATION {
+            continue;
+        }
+        let levy = population.0 * LEVY_SHARE;
+        population.0 -= levy;
+
+        let target = enemy_tiles
+            .iter()
+            .min_by(|a, b| {
+                toroidal_distance(home_tile, **a, &settings)
+                    .total_cmp(&toroidal_distance(home_tile, **b, &settings))
+            })
+            .copied()
+            .unwrap_or(home_tile);
+        let army = spawn_unit(&mut commands, UnitKind::Army, home_tile);
+        commands.entity(army).insert((
+            MoveOrder::new(target),
+            Army {
+                kingdom,
+                strength: levy,
+            },
+        ));
+    }
+}
+
+// Hostile armies that meet fight it out. An army standing its ground defends
+// with the terrain under it; the loser is destroyed and the winner bloodied in
+// proportion to the fight it was given.
+pub fn resolve_battles(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    diplomacy: Res<Diplomacy>,
+    grid: Option<Res<PathGrid>>,
+    kingdom_query: Query<&Kingdom>,
+    mut army_query: Query<(Entity, &Unit, &mut Army, Has<MoveOrder>)>,
+) {
+    let mut rng = rand::rng();
+    let armies: Vec<(Entity, IVec2, Entity, bool)> = army_query
+        .iter()
+        .map(|(entity, unit, army, moving)| (entity, unit.tile, army.kingdom, moving))
+        .collect();
+    let mut fallen = Vec::new();
+
+    for (index, &(a, a_tile, a_kingdom, a_moving)) in armies.iter().enumerate() {
+        for &(b, b_tile, b_kingdom, b_moving) in &armies[index + 1..] {
+            if fallen.contains(&a) || fallen.contains(&b) {
+                continue;
+            }
+            if diplomacy.treaty(a_kingdom, b_kingdom) != Treaty::War
+                || toroidal_distance(a_tile, b_tile, &settings) > ENGAGE_RADIUS
+            {
+                continue;
+            }
+            let Ok([(_, _, a_army, _), (_, _, b_army, _)]) = army_query.get_many([a, b]) else {
+                continue;
+            };
+
+            let terrain = |tile: IVec2, moving: bool| {
+                if moving {
+                    return 1.0;
+                }
+                grid.as_deref()
+                    .map(|grid| defence_bonus(grid.biome(tile)))
+                    .unwrap_or(1.0)
+            };
+            let a_power = a_army.strength
+                * terrain(a_tile, a_moving)
+                * rng.random_range(1.0 - BATTLE_LUCK..1.0 + BATTLE_LUCK);
+            let b_power = b_army.strength
+                * terrain(b_tile, b_moving)
+                * rng.random_range(1.0 - BATTLE_LUCK..1.0 + BATTLE_LUCK);
+
+            let (winner, loser, winner_power, loser_power) = if a_power >= b_power {
+                (a, b, a_power, b_power)
+            } else {
+                (b, a, b_power, a_power)
+            };
+            let kingdom_name = |entity: Entity| {
+                let kingdom = if entity == a { a_kingdom } else { b_kingdom };
+                kingdom_query
+                    .get(kingdom)
+                    .map(|kingdom| kingdom.name.clone())
+                    .unwrap_or_default()
+            };
+            info!(
+                "Battle at {}, {}: {} defeats {}",
+                a_tile.x,
+                a_tile.y,
+                kingdom_name(winner),
+                kingdom_name(loser)
+            );
+
+            if let Ok((.., mut army, _)) = army_query.get_mut(winner) {
+                army.strength *= 1.0 - CASUALTY_RATE * loser_power / winner_power;
+            }
+            commands.entity(loser).despawn();
+            fallen.push(loser);
+        }
+    }
+}
+
+// Armies that stop beside an enemy settlement besiege it. The garrison, stronger
+// on rough ground or with a fort nearby, wears the besiegers down until the
+// settlement falls to their kingdom or the army breaks.
+pub fn besiege_settlements(
+    mut commands: Commands,
+    (clock, settings, diplomacy): (Res<GameClock>, Res<WorldSettings>, Res<Diplomacy>),
+    (season, locale): (Res<Season>, Res<Locale>),
+    (mut chronicle, grid, mut territory): (
+        ResMut<Chronicle>,
+        Option<Res<PathGrid>>,
+        Option<ResMut<TerritoryMap>>,
+    ),
+    (kingdom_query, building_query): (Query<&Kingdom>, Query<&Building>),
+    mut settlement_query: Query<HeldSettlement>,
+    mut army_query: Query<Besieger, Without<MoveOrder>>,
+) {
+    for (entity, unit, mut army, siege) in &mut army_query {
+        let Some(mut siege) = siege else {
+            let target = settlement_query.iter().find(|(_, settlement, _, owner)| {
+                diplomacy.treaty(army.kingdom, owner.0) == Treaty::War
+                    && toroidal_distance(unit.tile, settlement.tile, &settings) <= SIEGE_RADIUS
+            });
+            if let Some((settlement, ..)) = target {
+                commands.entity(entity).insert(Siege {
+                    settlement,
+                    progress: 0.0,
+                });
+            }
+            continue;
+        };
+
+        let Ok((_, settlement, mut population, owner)) = settlement_query.get_mut(siege.settlement)
+        else {
+            commands.entity(entity).remove::<Siege>();
+            continue;
+        };
+        // Peace lifts the siege, as does marching off
+        if diplomacy.treaty(army.kingdom, owner.0) != Treaty::War
+            || toroidal_distance(unit.tile, settlement.tile, &settings) > SIEGE_RADIUS
+        {
+            commands.entity(entity).remove::<Siege>();
+            continue;
+        }
+
+        let fortified = building_query.iter().any(|building| {
+            building.kind == BuildingKind::Fort
+                && toroidal_distance(building.tile, settlement.tile, &settings) <= FORT_REACH
+        });
+        let walls = grid
+            .as_deref()
+            .map(|grid| defence_bonus(grid.biome(settlement.tile)))
+            .unwrap_or(1.0)
+            * if fortified { FORT_BONUS } else { 1.0 };
+        let garrison = (population.0 * GARRISON_SHARE * walls).max(1.0);
+        army.strength -= garrison * SIEGE_ATTRITION * clock.delta_secs;
+        if army.strength <= 0.0 {
+            info!("The siege of {} is broken", settlement.name);
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        siege.progress += SIEGE_RATE * clock.delta_secs * army.strength / garrison;
+        if siege.progress < 1.0 {
+            continue;
+        }
+
+        let conqueror = kingdom_query
+            .get(army.kingdom)
+            .map(|kingdom| kingdom.name.as_str())
+            .unwrap_or_default();
+        chronicle.record(
+            locale.date(&season),
+            ChronicleKind::Conquest,
+            locale.format("chronicle.falls", &[&settlement.name, &conqueror]),
+        );
+        population.0 = (population.0 * (1.0 - SACK_LOSS)).max(MIN_POPULATION);
+
+        // Forts nearby change hands with the settlement. Their tiles go to the
+        // conqueror where they border its land and are left unclaimed elsewhere.
+        if let Some(territory) = territory.as_deref_mut() {
+            for fort in building_query.iter().filter(|building| {
+                building.kind == BuildingKind::Fort
+                    && toroidal_distance(building.tile, settlement.tile, &settings) <= FORT_REACH
+            }) {
+                if territory.release_tile(fort.tile, owner.0).is_ok() {
+                    let _ = territory.claim_tile(fort.tile, army.kingdom);
+                }
+            }
+        }
+        commands
+            .entity(siege.settlement)
+            .insert(OwnedBy(army.kingdom));
+        commands.entity(entity).remove::<Siege>();
+    }
+}
+
+// A kingdom that has lost its last settlement is gone, along with its armies
+pub fn dissolve_fallen_kingdoms(
+    mut commands: Commands,
+    season: Res<Season>,
+    locale: Res<Locale>,
+    mut chronicle: ResMut<Chronicle>,
+    kingdom_query: Query<(Entity, &Kingdom, Option<&KingdomSettlements>)>,
+    army_query: Query<(Entity, &Army)>,
+) {
+    for (entity, kingdom, settlements) in &kingdom_query {
+        if settlements.is_some_and(|settlements| settlements.len() > 0) {
+            continue;
+        }
+
+        chronicle.record(
+            locale.date(&season),
+            ChronicleKind::Conquest,
+            locale.format("chronicle.fallen", &[&kingdom.name]),
+        );
+        for (army, _) in army_query.iter().filter(|(_, army)| army.kingdom == entity) {
+            commands.entity(army).despawn();
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn cleanup_combat(mut council: ResMut<WarCouncil>) {
+    *council = WarCouncil::default();
+}
@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+
+use crate::components::diplomacy::Treaty;
+use crate::components::events::DisasterKind;
+use crate::components::kingdoms::{AiControlled, FoundingParty, KingdomSettlements};
+use crate::components::settlements::{Population, Settlement, Stockpile};
+use crate::components::units::{Army, MoveOrder, Siege, Unit};
+use crate::systems::chronicle::Chronicle;
+use crate::systems::combat::GARRISON_SHARE;
+use crate::systems::diplomacy::Diplomacy;
+use crate::systems::events::{strike, take_toll};
+use crate::systems::kingdom_ai::{KingdomAi, MIN_SITE_UTILITY};
+use crate::systems::localization::Locale;
+use crate::systems::notifications::Notifications;
+use crate::systems::season::Season;
+use crate::systems::world::{
+    LoadedChunks, WorldSettings, clear_loaded_chunks, nearest_copy, toroidal_delta,
+    toroidal_distance,
+};
+
+const MONTHS_PER_YEAR: f32 = 12.0;
+// Added to every good in every stockpile by the grant key
+const GRANT_AMOUNT: f32 = 100.0;
+// Eruptions are left out, as they belong to volcanoes rather than towns
+const FORCED_DISASTERS: [DisasterKind; 3] = [
+    DisasterKind::Drought,
+    DisasterKind::Flood,
+    DisasterKind::HarshWinter,
+];
+// AI overlay colours: a ring round each kingdom's seat shows what it is set on
+const WAR_COLOR: Color = Color::srgba(0.9, 0.2, 0.15, 0.9);
+const EXPANSION_COLOR: Color = Color::srgba(0.3, 0.85, 0.35, 0.9);
+const GROWTH_COLOR: Color = Color::srgba(0.4, 0.6, 0.95, 0.9);
+const THREAT_COLOR: Color = Color::srgba(1.0, 0.6, 0.1, 0.9);
+const REJECTED_SITE_COLOR: Color = Color::srgba(0.6, 0.6, 0.6, 0.5);
+const SEAT_RING_RADIUS: f32 = 4.0;
+const SITE_RADIUS: f32 = 1.5;
+// Hostile armies this close to a settlement count as a threat to it
+const THREAT_RADIUS: f32 = 40.0;
+
+// Whether the kingdom AI overlay is drawn
+#[derive(Resource, Default)]
+pub struct AiOverlay(pub bool);
+
+// What striking a settlement with a disaster touches
+type ForcedDisaster<'w, 's> = (
+    Commands<'w, 's>,
+    Res<'w, WorldSettings>,
+    Res<'w, Locale>,
+    ResMut<'w, Notifications>,
+    ResMut<'w, Chronicle>,
+    Query<'w, 's, (&'static Settlement, &'static mut Population)>,
+);
+
+// Settlers and soldiers out in the world, with where they are headed
+type FieldUnits<'w, 's> = (
+    Query<
+        'w,
+        's,
+        (
+            &'static Unit,
+            &'static FoundingParty,
+            Option<&'static MoveOrder>,
+        ),
+    >,
+    Query<
+        'w,
+        's,
+        (
+            &'static Unit,
+            &'static Army,
+            Option<&'static MoveOrder>,
+            Has<Siege>,
+        ),
+    >,
+);
+
+// Page Up skips ahead a month, Page Down ten years. Home grants every settlement a pile
+// of each good, End strikes a random settlement with a disaster and Insert toggles the
+// kingdom AI overlay. Map reveal gets its key once fog of war exists.
+pub fn dev_hotkeys(
+    input: Res<ButtonInput<KeyCode>>,
+    mut season: ResMut<Season>,
+    mut overlay: ResMut<AiOverlay>,
+    mut loaded: ResMut<LoadedChunks>,
+    mut stockpile_query: Query<&mut Stockpile>,
+    forced: ForcedDisaster,
+) {
+    if input.just_pressed(KeyCode::Home) {
+        for mut stockpile in &mut stockpile_query {
+            for amount in &mut stockpile.0 {
+                *amount += GRANT_AMOUNT;
+            }
+        }
+        info!("Dev: granted {} of each good to every settlement", GRANT_AMOUNT);
+        return;
+    }
+    if input.just_pressed(KeyCode::End) {
+        force_disaster(&season, forced);
+        return;
+    }
+    if input.just_pressed(KeyCode::Insert) {
+        overlay.0 = !overlay.0;
+        return;
+    }
+
+    if input.just_pressed(KeyCode::PageUp) {
+        let elapsed = season.year_fraction + 1.0 / MONTHS_PER_YEAR;
+        season.year += elapsed as u32;
+        season.year_fraction = elapsed.fract();
+    } else if input.just_pressed(KeyCode::PageDown) {
+        season.year += 10;
+    } else {
+        return;
+    }
+
+    info!("Dev: advanced to year {0}, month {1}", season.year, season.month() + 1);
+    clear_loaded_chunks(&mut loaded);
+}
+
+fn force_disaster(season: &Season, forced: ForcedDisaster) {
+    let (mut commands, settings, locale, mut notifications, mut chronicle, mut settlement_query) =
+        forced;
+    let mut rng = rand::rng();
+    let targets: Vec<(IVec2, String)> = settlement_query
+        .iter()
+        .map(|(settlement, _)| (settlement.tile, settlement.name.clone()))
+        .collect();
+    let (Some((tile, place)), Some(&kind)) =
+        (targets.choose(&mut rng), FORCED_DISASTERS.choose(&mut rng))
+    else {
+        return;
+    };
+
+    let populations = settlement_query
+        .iter_mut()
+        .map(|(settlement, population)| (settlement.tile, population));
+    let lost = take_toll(kind, *tile, &settings, populations);
+    strike(
+        &mut commands,
+        (season, &locale, &mut notifications, &mut chronicle),
+        (kind, *tile, place.clone()),
+        lost,
+    );
+}
+
+// What each AI kingdom is about, drawn on the copies nearest the camera: a ring
+// round its largest settlement coloured by its goal, arrows to where its
+// settlers and armies are bound, every site it last weighed (grey if not worth
+// settling), and lines from hostile armies to the settlements they threaten,
+// brighter the more they outmatch the garrison
+pub fn draw_ai_overlay(
+    mut gizmos: Gizmos,
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    (overlay, ai, diplomacy): (Res<AiOverlay>, Res<KingdomAi>, Res<Diplomacy>),
+    kingdom_query: Query<(Entity, &KingdomSettlements), With<AiControlled>>,
+    settlement_query: Query<(&Settlement, &Population)>,
+    (party_query, army_query): FieldUnits,
+) {
+    if !overlay.0 {
+        return;
+    }
+
+    let camera = camera_query.translation.truncate().as_ivec2();
+    let at = |tile: IVec2| nearest_copy(camera, tile, &settings).as_vec2() + Vec2::splat(0.5);
+    // From the copy of one tile nearest the camera to the nearest copy of another
+    let span = |from: IVec2, to: IVec2| {
+        let start = at(from);
+        (start, start + toroidal_delta(from, to, &settings).as_vec2())
+    };
+
+    for site in &ai.surveyed {
+        let color = if site.utility >= MIN_SITE_UTILITY {
+            EXPANSION_COLOR
+        } else {
+            REJECTED_SITE_COLOR
+        };
+        gizmos.circle_2d(at(site.tile), SITE_RADIUS, color);
+    }
+    for (unit, party, order) in &party_query {
+        if let Some(order) = order
+            && kingdom_query.contains(party.kingdom)
+        {
+            let (start, end) = span(unit.tile, order.goal);
+            gizmos.arrow_2d(start, end, EXPANSION_COLOR);
+        }
+    }
+    for (unit, army, order, besieging) in &army_query {
+        if !kingdom_query.contains(army.kingdom) {
+            continue;
+        }
+        if let Some(order) = order {
+            let (start, end) = span(unit.tile, order.goal);
+            gizmos.arrow_2d(start, end, WAR_COLOR);
+        }
+        if besieging {
+            gizmos.cross_2d(at(unit.tile), SITE_RADIUS, WAR_COLOR);
+        }
+    }
+
+    for (kingdom, settlements) in &kingdom_query {
+        let at_war = diplomacy.relations().any(|(a, b, relation)| {
+            (a == kingdom || b == kingdom) && relation.treaty == Treaty::War
+        });
+        let expanding = party_query
+            .iter()
+            .any(|(_, party, _)| party.kingdom == kingdom);
+        let goal = if at_war {
+            WAR_COLOR
+        } else if expanding {
+            EXPANSION_COLOR
+        } else {
+            GROWTH_COLOR
+        };
+        let held: Vec<_> = settlements
+            .iter()
+            .filter_map(|entity| settlement_query.get(entity).ok())
+            .collect();
+        if let Some((seat, _)) = held.iter().max_by(|(_, a), (_, b)| a.0.total_cmp(&b.0)) {
+            gizmos.circle_2d(at(seat.tile), SEAT_RING_RADIUS, goal);
+        }
+
+        for (settlement, population) in &held {
+            let garrison = population.0 * GARRISON_SHARE;
+            for (unit, army, ..) in &army_query {
+                if diplomacy.treaty(kingdom, army.kingdom) != Treaty::War
+                    || toroidal_distance(unit.tile, settlement.tile, &settings) > THREAT_RADIUS
+                {
+                    continue;
+                }
+                let odds = (army.strength / garrison.max(1.0)).min(1.0);
+                let (start, end) = span(settlement.tile, unit.tile);
+                gizmos.line_2d(start, end, THREAT_COLOR.with_alpha(0.2 + 0.8 * odds));
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use kingdom_worldgen::{HALO, generate_chunk_data, sampler::WorldSampler};
+
+use crate::components::{
+    settlements::{Farm, Farms, Population, Settlement},
+    world::{Biome, Square, WorldMap},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    clock::GameClock,
+    world::{LoadedChunks, WorldSettings, known_square, set_tile},
+    world_record::WorldRecord,
+};
+
+// Settlements spread over the land around them every few days
+const DEVELOPMENT_TICK_SECS: f32 = 8.0;
+// Tiles from a settlement its fields and houses reach
+const DEVELOPMENT_RADIUS: i32 = 8;
+// People each tile of fields, village and town houses
+const PEOPLE_PER_FARMLAND_TILE: f32 = 20.0;
+const PEOPLE_PER_VILLAGE_TILE: f32 = 80.0;
+const PEOPLE_PER_URBAN_TILE: f32 = 150.0;
+// Only a large town builds up a dense core
+const URBAN_POPULATION: f32 = 1000.0;
+// Tiles a settlement changes each tick, so growth shows year by year rather
+// than all at once
+const TILES_PER_TICK: usize = 2;
+
+#[derive(Resource, Default)]
+pub struct LandDevelopment {
+    pub cooldown: f32,
+    // Streamed chunks around settlements as sampled from the seed, kept since
+    // untouched tiles never change on their own
+    sampled: HashMap<IVec2, Vec<Square>>,
+}
+
+// How built up a tile is: wild land, fields, village, then town
+fn development(biome: Biome) -> u8 {
+    match biome {
+        Biome::Farmland => 1,
+        Biome::Village => 2,
+        Biome::Urban => 3,
+        _ => 0,
+    }
+}
+
+// Nobody builds on water, bare peaks or ice
+fn developable(biome: Biome) -> bool {
+    !matches!(
+        biome,
+        Biome::Ocean
+            | Biome::ShallowOcean
+            | Biome::Mountain
+            | Biome::Alpine
+            | Biome::Ice
+            | Biome::Snow
+    )
+}
+
+fn develop(square: &mut Square, biome: Biome) {
+    square.biome = biome;
+    square.vegetation = match biome {
+        Biome::Farmland => square.vegetation.min(0.3),
+        Biome::Village => square.vegetation.min(0.15),
+        _ => 0.0,
+    };
+}
+
+// As a settlement grows its fields spread out from it, villages grow up nearest
+// it and large towns build a dense core. Its farms become fields as well. Land
+// stays built up when a settlement shrinks, so fallen cities leave their mark.
+pub fn develop_land(
+    clock: Res<GameClock>,
+    settings: Res<WorldSettings>,
+    (mut land, mut record): (ResMut<LandDevelopment>, ResMut<WorldRecord>),
+    mut loaded: ResMut<LoadedChunks>,
+    (mut world_query, world_data): (Query<&mut WorldMap>, Single<&WorldData>),
+    settlement_query: Query<(&Settlement, &Population, Option<&Farms>)>,
+    farm_query: Query<&Farm>,
+) {
+    land.cooldown -= clock.delta_secs;
+    if land.cooldown > 0.0 {
+        return;
+    }
+    land.cooldown = DEVELOPMENT_TICK_SECS;
+    let mut world_map = world_query.single_mut().ok();
+
+    // Nearest tiles first, so land is built up from the centre outward
+    let mut offsets: Vec<IVec2> = (-DEVELOPMENT_RADIUS..=DEVELOPMENT_RADIUS)
+        .flat_map(|dy| (-DEVELOPMENT_RADIUS..=DEVELOPMENT_RADIUS).map(move |dx| IVec2::new(dx, dy)))
+        .filter(|offset| offset.length_squared() <= DEVELOPMENT_RADIUS * DEVELOPMENT_RADIUS)
+        .collect();
+    offsets.sort_by_key(|offset| offset.length_squared());
+
+    for (settlement, population, farms) in &settlement_query {
+        let urban = ((population.0 - URBAN_POPULATION).max(0.0) / PEOPLE_PER_URBAN_TILE) as usize;
+        let village = (population.0 / PEOPLE_PER_VILLAGE_TILE) as usize;
+        let farmland = (population.0 / PEOPLE_PER_FARMLAND_TILE) as usize;
+        let mut biome_at = |tile: IVec2| {
+            let square =
+                land.square_at(tile, &settings, &mut loaded, &world_data, world_map.as_deref());
+            square.biome
+        };
+
+        let mut growth = Vec::new();
+        let mut footprint = 0;
+        for offset in &offsets {
+            if footprint >= farmland || growth.len() >= TILES_PER_TICK {
+                break;
+            }
+            let tile = settlement.tile + *offset;
+            let current = biome_at(tile);
+            if !developable(current) {
+                continue;
+            }
+            let biome = if footprint < urban {
+                Biome::Urban
+            } else if footprint < village {
+                Biome::Village
+            } else {
+                Biome::Farmland
+            };
+            footprint += 1;
+            if development(current) < development(biome) {
+                growth.push((tile, biome));
+            }
+        }
+
+        let farm_tiles = farms
+            .iter()
+            .flat_map(|farms| farms.iter())
+            .filter_map(|farm| farm_query.get(farm).ok())
+            .map(|farm| farm.tile);
+        for tile in farm_tiles {
+            if growth.len() >= TILES_PER_TICK {
+                break;
+            }
+            if development(biome_at(tile)) < development(Biome::Farmland) {
+                growth.push((tile, Biome::Farmland));
+            }
+        }
+
+        for (tile, biome) in growth {
+            let mut square =
+                land.square_at(tile, &settings, &mut loaded, &world_data, world_map.as_deref());
+            develop(&mut square, biome);
+            set_tile(tile, &square, &settings, &mut loaded, world_map.as_deref_mut());
+            record.tiles_terraformed += 1;
+        }
+    }
+}
+
+impl LandDevelopment {
+    // A tile as it now stands. Streamed tiles neither changed nor in view come from
+    // their whole chunk, sampled once, as erosion reaches too far to sample one
+    // tile on its own.
+    fn square_at(
+        &mut self,
+        tile: IVec2,
+        settings: &WorldSettings,
+        loaded: &mut LoadedChunks,
+        world_data: &WorldData,
+        world_map: Option<&WorldMap>,
+    ) -> Square {
+        if let Some(square) = known_square(tile, settings, loaded, world_map) {
+            return square;
+        }
+
+        let chunk_size = settings.chunk_size;
+        let base = tile.rem_euclid(IVec2::splat(settings.world_size));
+        let chunk = base.div_euclid(IVec2::splat(chunk_size));
+        let squares = self.sampled.entry(chunk).or_insert_with(|| {
+            let sampler = loaded.sampler.get_or_insert_with(|| {
+                Arc::new(WorldSampler::new(world_data, settings.world_size))
+            });
+            generate_chunk_data(chunk.x, chunk.y, chunk_size, sampler)
+        });
+        let local = base - chunk * chunk_size + IVec2::splat(HALO);
+        squares[(local.y * (chunk_size + 2 * HALO) + local.x) as usize].clone()
+    }
+}
+
+pub fn cleanup_development(mut land: ResMut<LandDevelopment>) {
+    *land = LandDevelopment::default();
+}
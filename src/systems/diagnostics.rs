@@ -0,0 +1,174 @@
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+        RegisterDiagnostic,
+    },
+    prelude::*,
+};
+
+use crate::components::{
+    diagnostics::{DiagnosticsOverlay, DiagnosticsText},
+    world::{ChunkCoord, Square, WorldMap},
+};
+use crate::systems::{
+    keybindings::{InputAction, InputMap},
+    world::LoadedChunks,
+    world_gen::progress::{WorldGenProgress, WorldGenStage},
+};
+
+// Milliseconds each generation stage took, in `WorldGenStage::ALL` order
+pub const WORLDGEN_STAGE_TIMES: [DiagnosticPath; 3] = [
+    DiagnosticPath::const_new("worldgen/elevation_ms"),
+    DiagnosticPath::const_new("worldgen/climate_ms"),
+    DiagnosticPath::const_new("worldgen/biomes_ms"),
+];
+pub const CHUNK_MESHES: DiagnosticPath = DiagnosticPath::const_new("world/chunk_meshes");
+pub const VISIBLE_CHUNKS: DiagnosticPath = DiagnosticPath::const_new("world/visible_chunks");
+pub const TILE_DATA_BYTES: DiagnosticPath = DiagnosticPath::const_new("world/tile_data_bytes");
+
+// Generation happens once per world, so each stage keeps only its last time
+pub fn register_worldgen_diagnostics(app: &mut App) {
+    for path in WORLDGEN_STAGE_TIMES {
+        app.register_diagnostic(
+            Diagnostic::new(path)
+                .with_suffix(" ms")
+                .with_max_history_length(1),
+        );
+    }
+}
+
+pub fn register_world_diagnostics(app: &mut App) {
+    for path in [CHUNK_MESHES, VISIBLE_CHUNKS, TILE_DATA_BYTES] {
+        app.register_diagnostic(Diagnostic::new(path).with_max_history_length(1));
+    }
+}
+
+pub fn record_worldgen_times(diagnostics: &mut Diagnostics, progress: &WorldGenProgress) {
+    for (path, secs) in WORLDGEN_STAGE_TIMES.iter().zip(progress.stage_secs()) {
+        diagnostics.add_measurement(path, || secs as f64 * 1000.0);
+    }
+}
+
+// Chunk meshes are counted whether or not culling hid them. Tile data covers
+// the pregenerated map and the streamed chunks kept in memory.
+pub fn measure_world(
+    mut diagnostics: Diagnostics,
+    loaded: Res<LoadedChunks>,
+    world_query: Query<&WorldMap>,
+    chunk_query: Query<&Visibility, With<ChunkCoord>>,
+) {
+    let visible = chunk_query
+        .iter()
+        .filter(|visibility| **visibility != Visibility::Hidden)
+        .count();
+    let squares = world_query
+        .iter()
+        .map(WorldMap::tile_count)
+        .chain(loaded.chunk_data.values().map(Vec::len))
+        .sum::<usize>();
+
+    diagnostics.add_measurement(&CHUNK_MESHES, || chunk_query.iter().len() as f64);
+    diagnostics.add_measurement(&VISIBLE_CHUNKS, || visible as f64);
+    diagnostics.add_measurement(&TILE_DATA_BYTES, || (squares * size_of::<Square>()) as f64);
+}
+
+pub fn setup_diagnostics_overlay(mut commands: Commands) {
+    commands.spawn((
+        DiagnosticsOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(100.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        GlobalZIndex(10),
+        Visibility::Hidden,
+        children![(
+            DiagnosticsText,
+            Text::new(""),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    ));
+}
+
+pub fn toggle_diagnostics_overlay(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut overlay_query: Single<&mut Visibility, With<DiagnosticsOverlay>>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleDiagnostics) {
+        let shown = **overlay_query == Visibility::Hidden;
+        **overlay_query = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn update_diagnostics_overlay(
+    store: Res<DiagnosticsStore>,
+    overlay_query: Single<&Visibility, With<DiagnosticsOverlay>>,
+    mut text_query: Single<&mut Text, With<DiagnosticsText>>,
+) {
+    if **overlay_query == Visibility::Hidden {
+        return;
+    }
+
+    let smoothed = |path: &DiagnosticPath| store.get(path).and_then(Diagnostic::smoothed);
+    let latest = |path: &DiagnosticPath| store.get(path).and_then(Diagnostic::value);
+
+    let mut lines = vec![format!(
+        "FPS {:.0} ({:.1} ms)",
+        smoothed(&FrameTimeDiagnosticsPlugin::FPS).unwrap_or_default(),
+        smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME).unwrap_or_default()
+    )];
+    lines.push(format!(
+        "Chunk meshes {:.0}, {:.0} visible",
+        latest(&CHUNK_MESHES).unwrap_or_default(),
+        latest(&VISIBLE_CHUNKS).unwrap_or_default()
+    ));
+    lines.push(format!(
+        "Tile data {:.1} MB",
+        latest(&TILE_DATA_BYTES).unwrap_or_default() / (1024.0 * 1024.0)
+    ));
+
+    // Streamed worlds generate chunk by chunk and never run the stages
+    let stage_times: Vec<(WorldGenStage, f64)> = WorldGenStage::ALL
+        .iter()
+        .zip(&WORLDGEN_STAGE_TIMES)
+        .filter_map(|(stage, path)| Some((*stage, latest(path)?)))
+        .collect();
+    if stage_times.is_empty() {
+        lines.push("World generation: streamed".to_string());
+    } else {
+        lines.push("World generation".to_string());
+        for (stage, millis) in stage_times {
+            lines.push(format!("  {} {:.0} ms", stage.label(), millis));
+        }
+    }
+
+    text_query.0 = lines.join("\n");
+}
+
+// The next world may be streamed and never report stage times of its own
+pub fn cleanup_diagnostics_overlay(
+    mut commands: Commands,
+    mut store: ResMut<DiagnosticsStore>,
+    query: Query<Entity, With<DiagnosticsOverlay>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    for path in &WORLDGEN_STAGE_TIMES {
+        if let Some(diagnostic) = store.get_mut(path) {
+            diagnostic.clear_history();
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    diplomacy::{DiplomacyPanel, DiplomacyPanelText, Relation, Treaty},
+    kingdoms::{AiControlled, Kingdom, KingdomSettlements, OwnedBy, Technology},
+    pause_menu::PausesSim,
+    trade::TradeRoute,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    keybindings::{InputAction, InputMap},
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    season::Season,
+    world_record::WorldRecord,
+};
+
+// Courts weigh up their neighbours a few times a year
+const DIPLOMACY_TICK_SECS: f32 = 20.0;
+// Score gained per tick for the square root of the goods traded between two
+// kingdoms, and lost for every cell edge of border they share
+const TRADE_WEIGHT: f32 = 0.4;
+const FRICTION_WEIGHT: f32 = 0.3;
+// Wars wear both sides down until peace looks better
+const WAR_WEARINESS: f32 = 3.0;
+// Share of the score kept each tick, so old grudges and friendships fade
+const SCORE_RETENTION: f32 = 0.95;
+const WAR_THRESHOLD: f32 = -50.0;
+const PEACE_THRESHOLD: f32 = -10.0;
+const ALLIANCE_THRESHOLD: f32 = 60.0;
+const ALLIANCE_BREAK_THRESHOLD: f32 = 20.0;
+// Chance a court acts on a tick once a threshold is crossed, so treaties don't
+// all change on the same day
+const DECISION_CHANCE: f64 = 0.5;
+
+// Relations between every pair of kingdoms, keyed with the lower entity first.
+// Pairs with no entry are at peace with a neutral score.
+#[derive(Resource, Default)]
+pub struct Diplomacy {
+    relations: HashMap<(Entity, Entity), Relation>,
+    cooldown: f32,
+}
+
+impl Diplomacy {
+    pub fn relation(&self, a: Entity, b: Entity) -> Relation {
+        self.relations.get(&pair(a, b)).copied().unwrap_or_default()
+    }
+
+    pub fn treaty(&self, a: Entity, b: Entity) -> Treaty {
+        self.relation(a, b).treaty
+    }
+
+    pub fn relations(&self) -> impl Iterator<Item = (Entity, Entity, Relation)> + '_ {
+        self.relations
+            .iter()
+            .map(|(&(a, b), &relation)| (a, b, relation))
+    }
+
+    // Picks the ledger back up from a save
+    pub fn restore(relations: impl IntoIterator<Item = (Entity, Entity, Relation)>) -> Self {
+        Diplomacy {
+            relations: relations
+                .into_iter()
+                .map(|(a, b, relation)| (pair(a, b), relation))
+                .collect(),
+            cooldown: 0.0,
+        }
+    }
+}
+
+// Scores drift with trade and border friction; AI courts then declare war, make
+// peace, or join and leave alliances as the scores cross their thresholds
+pub fn update_diplomacy(
+    clock: Res<GameClock>,
+    (season, locale): (Res<Season>, Res<Locale>),
+    mut diplomacy: ResMut<Diplomacy>,
+    (mut chronicle, mut record): (ResMut<Chronicle>, ResMut<WorldRecord>),
+    territory: Option<Res<TerritoryMap>>,
+    kingdom_query: Query<(Entity, &Kingdom, Has<AiControlled>)>,
+    (owner_query, route_query): (Query<&OwnedBy>, Query<&TradeRoute>),
+) {
+    diplomacy.cooldown -= clock.delta_secs;
+    if diplomacy.cooldown > 0.0 {
+        return;
+    }
+    diplomacy.cooldown = DIPLOMACY_TICK_SECS;
+
+    let borders = territory
+        .map(|territory| territory.borders())
+        .unwrap_or_default();
+    let mut trade: HashMap<(Entity, Entity), f32> = HashMap::new();
+    for route in &route_query {
+        if let (Ok(from), Ok(to)) = (owner_query.get(route.from), owner_query.get(route.to))
+            && from.0 != to.0
+        {
+            *trade.entry(pair(from.0, to.0)).or_default() += route.volume;
+        }
+    }
+
+    // Kingdoms that have fallen drop out of the ledger
+    diplomacy
+        .relations
+        .retain(|(a, b), _| kingdom_query.contains(*a) && kingdom_query.contains(*b));
+
+    let mut rng = rand::rng();
+    let kingdoms: Vec<_> = kingdom_query.iter().collect();
+    for (index, &(a, a_kingdom, a_ai)) in kingdoms.iter().enumerate() {
+        for &(b, b_kingdom, b_ai) in &kingdoms[index + 1..] {
+            let key = pair(a, b);
+            let mut relation = diplomacy.relation(a, b);
+
+            let border = borders.get(&key).copied().unwrap_or(0) as f32;
+            let traded = trade.get(&key).copied().unwrap_or(0.0);
+            relation.score += TRADE_WEIGHT * traded.sqrt() - FRICTION_WEIGHT * border;
+            if relation.treaty == Treaty::War {
+                relation.score += WAR_WEARINESS;
+            }
+            relation.score = (relation.score * SCORE_RETENTION).clamp(-100.0, 100.0);
+
+            if a_ai && b_ai && rng.random_bool(DECISION_CHANCE) {
+                let treaty = next_treaty(relation);
+                if treaty != relation.treaty {
+                    let (kind, id) = match (relation.treaty, treaty) {
+                        (_, Treaty::War) => (ChronicleKind::War, "chronicle.war"),
+                        (Treaty::War, _) => (ChronicleKind::Peace, "chronicle.peace"),
+                        (_, Treaty::Alliance) => (ChronicleKind::Peace, "chronicle.alliance"),
+                        _ => (ChronicleKind::Peace, "chronicle.alliance_ends"),
+                    };
+                    let text = locale.format(id, &[&a_kingdom.name, &b_kingdom.name]);
+                    chronicle.record(locale.date(&season), kind, text);
+                    if treaty == Treaty::War {
+                        record.wars_fought += 1;
+                    }
+                    relation.treaty = treaty;
+                }
+            }
+
+            diplomacy.relations.insert(key, relation);
+        }
+    }
+}
+
+pub fn setup_diplomacy_panel(mut commands: Commands) {
+    commands.spawn((
+        DiplomacyPanel,
+        PausesSim,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(180.0),
+            left: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        Visibility::Hidden,
+        children![(
+            DiplomacyPanelText,
+            Text::new(""),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    ));
+}
+
+pub fn toggle_diplomacy_panel(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut panel_query: Single<&mut Visibility, With<DiplomacyPanel>>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleDiplomacy) {
+        let shown = **panel_query == Visibility::Hidden;
+        **panel_query = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// Each kingdom and its era, with its allies, enemies and everyone else and the
+// score between them
+pub fn update_diplomacy_panel(
+    diplomacy: Res<Diplomacy>,
+    locale: Res<Locale>,
+    panel_query: Single<&Visibility, With<DiplomacyPanel>>,
+    mut text_query: Single<&mut Text, With<DiplomacyPanelText>>,
+    kingdom_query: Query<(Entity, &Kingdom, &KingdomSettlements, &Technology)>,
+) {
+    if **panel_query == Visibility::Hidden {
+        return;
+    }
+
+    let mut kingdoms: Vec<_> = kingdom_query.iter().collect();
+    kingdoms.sort_by(|(_, a, ..), (_, b, ..)| a.name.cmp(&b.name));
+
+    let mut lines = vec![locale.text("diplomacy.title").to_string()];
+    for &(entity, kingdom, settlements, technology) in &kingdoms {
+        let era = technology.era;
+        lines.push(locale.format(
+            "diplomacy.kingdom",
+            &[
+                &kingdom.name,
+                &settlements.len(),
+                &locale.name("era", era, era.name()),
+            ],
+        ));
+        for treaty in [Treaty::Alliance, Treaty::War, Treaty::Peace] {
+            let others: Vec<String> = kingdoms
+                .iter()
+                .filter(|(other, ..)| *other != entity)
+                .filter_map(|&(other, other_kingdom, ..)| {
+                    let relation = diplomacy.relation(entity, other);
+                    (relation.treaty == treaty)
+                        .then(|| format!("{} {:+.0}", other_kingdom.name, relation.score))
+                })
+                .collect();
+            if !others.is_empty() {
+                let treaty = locale.name("treaty", treaty, treaty.name());
+                lines.push(format!("  {}: {}", treaty, others.join(", ")));
+            }
+        }
+    }
+
+    let text = lines.join("\n");
+    if text_query.0 != text {
+        text_query.0 = text;
+    }
+}
+
+pub fn cleanup_diplomacy(
+    mut commands: Commands,
+    mut diplomacy: ResMut<Diplomacy>,
+    query: Query<Entity, With<DiplomacyPanel>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    *diplomacy = Diplomacy::default();
+}
+
+fn pair(a: Entity, b: Entity) -> (Entity, Entity) {
+    (a.min(b), a.max(b))
+}
+
+// Thresholds sit apart so a score hovering near one doesn't flip the treaty
+// back and forth
+fn next_treaty(relation: Relation) -> Treaty {
+    match relation.treaty {
+        Treaty::Peace if relation.score < WAR_THRESHOLD => Treaty::War,
+        Treaty::Peace if relation.score > ALLIANCE_THRESHOLD => Treaty::Alliance,
+        Treaty::Alliance if relation.score < ALLIANCE_BREAK_THRESHOLD => Treaty::Peace,
+        Treaty::War if relation.score > PEACE_THRESHOLD => Treaty::Peace,
+        treaty => treaty,
+    }
+}
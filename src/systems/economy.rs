@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::components::settlements::{
+    FoodCapacity, Good, Population, ProductionModifier, ProductionRates, Stockpile,
+};
+use crate::systems::{clock::GameClock, growth::Growth, season::YEAR_LENGTH_SECS};
+
+// Food one person eats in a year
+const FOOD_PER_PERSON: f32 = 1.0;
+// A worker grows or gathers enough to feed this many people, so settlements
+// below their capacity put food by
+const FOOD_PER_WORKER: f32 = 1.25;
+// Stores hold this many years of full output of each good
+const STORAGE_YEARS: f32 = 3.0;
+// Founders arrive with half a year of food
+const FOUNDING_FOOD_YEARS: f32 = 0.5;
+
+// Share of the land's full output the settlement has the hands to work
+pub fn labour_share(population: f32, capacity: f32) -> f32 {
+    if capacity <= 0.0 {
+        return 0.0;
+    }
+
+    (population * FOOD_PER_WORKER / capacity).min(1.0)
+}
+
+pub fn founding_stockpile(population: f32) -> Stockpile {
+    let mut stockpile = Stockpile::default();
+    stockpile.0[Good::Grain as usize] = population * FOOD_PER_PERSON * FOUNDING_FOOD_YEARS;
+    stockpile
+}
+
+// Each tick a settlement adds what it worked from the land to its stores, then
+// eats. Game and fish are eaten before grain since they keep worse. Whatever
+// hunger is left over costs population, as much as the growth model says.
+pub fn update_stockpiles(
+    clock: Res<GameClock>,
+    growth: Res<Growth>,
+    mut query: Query<(
+        &mut Stockpile,
+        &ProductionRates,
+        &mut Population,
+        &FoodCapacity,
+        Option<&ProductionModifier>,
+    )>,
+) {
+    let years = clock.delta_secs / YEAR_LENGTH_SECS;
+
+    for (mut stockpile, production, mut population, capacity, modifier) in &mut query {
+        let labour = labour_share(population.0, capacity.0);
+        for good in Good::ALL {
+            let limit = production.get(good) * STORAGE_YEARS;
+            let share = modifier.map(|modifier| modifier.0[good as usize]).unwrap_or(1.0);
+            let stored = &mut stockpile.0[good as usize];
+            if *stored < limit {
+                *stored = (*stored + production.get(good) * labour * share * years).min(limit);
+            }
+        }
+
+        let needed = population.0 * FOOD_PER_PERSON * years;
+        let mut hunger = needed;
+        for good in [Good::Game, Good::Fish, Good::Grain] {
+            let stored = &mut stockpile.0[good as usize];
+            let eaten = hunger.min(*stored);
+            *stored -= eaten;
+            hunger -= eaten;
+        }
+
+        if hunger > 0.0 && needed > 0.0 {
+            population.0 *= 1.0 - hunger / needed * growth.model.starvation_loss * years;
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use bevy::prelude::*;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    era_end::{EraOutcome, EraSummaryPanel, EraSummaryText},
+    kingdoms::{AiControlled, Kingdom, KingdomSettlements},
+    pause_menu::PausesSim,
+    settlements::{Population, Settlement},
+};
+use crate::systems::{
+    chronicle::Chronicle, clock::GameClock, localization::Locale, season::Season,
+    world_record::WorldRecord,
+};
+
+// The state of the world is weighed a few times a year
+const WATCH_TICK_SECS: f32 = 20.0;
+// Share of all settlements one kingdom must hold to have unified the world, and
+// the fewest that counts
+const UNIFICATION_SHARE: f32 = 0.8;
+const MIN_UNIFIED_SETTLEMENTS: usize = 5;
+// Share of the peak population below which the world has collapsed outright,
+// or, if it stays there this many years, sunk into a dark age
+const COLLAPSE_SHARE: f32 = 0.1;
+const DARK_AGE_SHARE: f32 = 0.5;
+const DARK_AGE_YEARS: u32 = 10;
+// Chronicle entries the summary recalls, and the kinds worth recalling
+const HIGHLIGHTS: usize = 8;
+const HIGHLIGHT_KINDS: [ChronicleKind; 3] = [
+    ChronicleKind::Conquest,
+    ChronicleKind::Discovery,
+    ChronicleKind::Disaster,
+];
+
+// What the watch has seen of the world since play began
+#[derive(Resource, Default)]
+pub struct EraWatch {
+    cooldown: f32,
+    first_year: Option<u32>,
+    most_kingdoms: usize,
+    // Year the world's people fell below the dark-age share of their peak,
+    // while they stay there
+    decline_since: Option<u32>,
+    pub ended: Option<EraOutcome>,
+}
+
+// The world as the watch weighs it on one tick
+struct WorldCensus {
+    year: u32,
+    population: f32,
+    peak_population: f32,
+    kingdoms: usize,
+    settlements: usize,
+    // Settlements held by the largest kingdom
+    largest: usize,
+}
+
+impl EraWatch {
+    // Takes in the census and returns the way the era has ended, if it has
+    fn weigh(&mut self, census: &WorldCensus) -> Option<EraOutcome> {
+        self.first_year.get_or_insert(census.year);
+        self.most_kingdoms = self.most_kingdoms.max(census.kingdoms);
+        if census.peak_population <= 0.0 {
+            return None;
+        }
+
+        if census.kingdoms == 0 || census.population < census.peak_population * COLLAPSE_SHARE {
+            return Some(EraOutcome::Collapse);
+        }
+        if self.most_kingdoms > 1
+            && census.largest >= MIN_UNIFIED_SETTLEMENTS
+            && census.largest as f32 >= census.settlements as f32 * UNIFICATION_SHARE
+        {
+            return Some(EraOutcome::Unification);
+        }
+        if census.population >= census.peak_population * DARK_AGE_SHARE {
+            self.decline_since = None;
+            return None;
+        }
+        let since = *self.decline_since.get_or_insert(census.year);
+        (census.year >= since + DARK_AGE_YEARS).then_some(EraOutcome::DarkAge)
+    }
+}
+
+// The era is only watched for an ending while every kingdom is run by the
+// simulation
+pub fn observing(player_query: Query<(), (With<Kingdom>, Without<AiControlled>)>) -> bool {
+    player_query.is_empty()
+}
+
+pub fn setup_era_summary(mut commands: Commands) {
+    commands.spawn((
+        EraSummaryPanel,
+        PausesSim,
+        Button,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(100.0),
+            left: Val::Percent(50.0),
+            width: Val::Px(480.0),
+            margin: UiRect::left(Val::Px(-240.0)),
+            padding: UiRect::all(Val::Px(14.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        Visibility::Hidden,
+        children![(
+            EraSummaryText,
+            Text::new(""),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        )],
+    ));
+}
+
+type SummaryPanel<'w, 's> = (
+    Single<'w, 's, &'static mut Visibility, With<EraSummaryPanel>>,
+    Single<'w, 's, &'static mut Text, With<EraSummaryText>>,
+);
+
+// Ends the era when one kingdom has unified the world, every kingdom has
+// fallen or the people have dwindled into a dark age, and opens the summary:
+// how long it lasted, what is left standing and the chronicle's highlights
+pub fn watch_for_era_end(
+    (clock, season, locale): (Res<GameClock>, Res<Season>, Res<Locale>),
+    (mut watch, mut chronicle, record): (ResMut<EraWatch>, ResMut<Chronicle>, Res<WorldRecord>),
+    kingdom_query: Query<(&Kingdom, Option<&KingdomSettlements>)>,
+    settlement_query: Query<&Population, With<Settlement>>,
+    (mut panel_query, mut text_query): SummaryPanel,
+) {
+    if watch.ended.is_some() {
+        return;
+    }
+    watch.cooldown -= clock.delta_secs;
+    if watch.cooldown > 0.0 {
+        return;
+    }
+    watch.cooldown = WATCH_TICK_SECS;
+
+    let largest = kingdom_query
+        .iter()
+        .map(|(kingdom, settlements)| (kingdom, settlements.map_or(0, KingdomSettlements::len)))
+        .max_by_key(|(_, settlements)| *settlements);
+    let census = WorldCensus {
+        year: season.year,
+        population: settlement_query.iter().map(|population| population.0).sum(),
+        peak_population: record.peak_population,
+        kingdoms: kingdom_query.iter().count(),
+        settlements: settlement_query.iter().count(),
+        largest: largest.map_or(0, |(_, settlements)| settlements),
+    };
+    let Some(outcome) = watch.weigh(&census) else {
+        return;
+    };
+    watch.ended = Some(outcome);
+
+    let ruler = largest.map_or("", |(kingdom, _)| kingdom.name.as_str());
+    let (kind, title) = match outcome {
+        EraOutcome::Unification => (
+            ChronicleKind::Conquest,
+            locale.format("era.unification", &[&ruler]),
+        ),
+        EraOutcome::Collapse => (
+            ChronicleKind::Disaster,
+            locale.text("era.collapse").to_string(),
+        ),
+        EraOutcome::DarkAge => (
+            ChronicleKind::Disaster,
+            locale.text("era.dark_age").to_string(),
+        ),
+    };
+    chronicle.record(locale.date(&season), kind, title.clone());
+
+    let mut lines = vec![
+        title,
+        locale.format(
+            "era.span",
+            &[&watch.first_year.unwrap_or(season.year), &season.year],
+        ),
+        locale.format("era.kingdoms", &[&census.kingdoms, &census.settlements]),
+        locale.format("era.population", &[&format!("{:.0}", census.population)]),
+    ];
+    lines.extend(record.lines(season.year, &locale));
+    let mut highlights: Vec<String> = chronicle
+        .entries()
+        .iter()
+        .rev()
+        // Past the entry for the era's end just written
+        .skip(1)
+        .filter(|entry| HIGHLIGHT_KINDS.contains(&entry.kind))
+        .take(HIGHLIGHTS)
+        .map(|entry| format!("{}  {}", entry.date, entry.text))
+        .collect();
+    if !highlights.is_empty() {
+        highlights.reverse();
+        lines.push(String::new());
+        lines.push(locale.text("era.highlights").to_string());
+        lines.extend(highlights);
+    }
+    lines.push(String::new());
+    lines.push(locale.text("era.keep_watching").to_string());
+
+    text_query.0 = lines.join("\n");
+    **panel_query = Visibility::Inherited;
+}
+
+pub fn close_era_summary(
+    mut panel_query: Query<(&Interaction, &mut Visibility), With<EraSummaryPanel>>,
+) {
+    for (interaction, mut visibility) in &mut panel_query {
+        if *interaction == Interaction::Pressed {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+    }
+}
+
+pub fn cleanup_era_watch(
+    mut commands: Commands,
+    mut watch: ResMut<EraWatch>,
+    query: Query<Entity, With<EraSummaryPanel>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    *watch = EraWatch::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn census(year: u32, population: f32, kingdoms: usize, largest: usize) -> WorldCensus {
+        WorldCensus {
+            year,
+            population,
+            peak_population: 1000.0,
+            kingdoms,
+            settlements: 10,
+            largest,
+        }
+    }
+
+    #[test]
+    fn one_kingdom_holding_most_settlements_unifies_the_world() {
+        let mut watch = EraWatch::default();
+        assert_eq!(watch.weigh(&census(1, 1000.0, 3, 4)), None);
+        assert_eq!(
+            watch.weigh(&census(2, 1000.0, 2, 8)),
+            Some(EraOutcome::Unification)
+        );
+    }
+
+    #[test]
+    fn a_world_that_started_with_one_kingdom_is_not_unified() {
+        let mut watch = EraWatch::default();
+        assert_eq!(watch.weigh(&census(1, 1000.0, 1, 10)), None);
+    }
+
+    #[test]
+    fn a_lasting_decline_is_a_dark_age_and_a_crash_a_collapse() {
+        let mut watch = EraWatch::default();
+        watch.weigh(&census(1, 1000.0, 3, 4));
+        assert_eq!(watch.weigh(&census(2, 400.0, 3, 4)), None);
+        assert_eq!(watch.weigh(&census(5, 600.0, 3, 4)), None);
+        assert_eq!(watch.weigh(&census(6, 400.0, 3, 4)), None);
+        assert_eq!(
+            watch.weigh(&census(16, 400.0, 3, 4)),
+            Some(EraOutcome::DarkAge)
+        );
+
+        let mut watch = EraWatch::default();
+        watch.weigh(&census(1, 1000.0, 3, 4));
+        assert_eq!(
+            watch.weigh(&census(2, 50.0, 3, 4)),
+            Some(EraOutcome::Collapse)
+        );
+        let empty = WorldCensus {
+            peak_population: 0.0,
+            ..census(1, 0.0, 0, 0)
+        };
+        assert_eq!(EraWatch::default().weigh(&empty), None);
+    }
+}
@@ -0,0 +1,590 @@
+// Events written in assets/events.ron rather than in code. Each has a condition
+// in a small language that every settlement is checked against once a season:
+//
+//     settlement.population > 5000 and biome == HotDesert
+//
+// Comparisons (`<`, `<=`, `>`, `>=`, `==`, `!=`) join with `and`, `or` and
+// `not` and group with brackets. Numbers compare with numbers; names such as
+// biomes, seasons, tiers and eras compare with `==` and `!=` only, and must be
+// spelt as the game spells them.
+
+use std::io;
+
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, io::Reader};
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    events::LocalClimate,
+    kingdoms::{Era, OwnedBy, Technology},
+    settlements::{Good, Population, Settlement, SettlementTier, Stockpile},
+    world::Biome,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    localization::Locale,
+    notifications::Notifications,
+    season::{SEASON_NAMES, Season, tile_latitude},
+    settlements::MIN_POPULATION,
+    world::WorldSettings,
+};
+
+// Under the asset folder, which the asset server watches for edits
+const EVENT_SCRIPTS_PATH: &str = "events.ron";
+
+// What a variable holds, so a condition comparing the wrong things is turned
+// away when the file loads rather than quietly never firing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueType {
+    Number,
+    Name,
+}
+
+const VARIABLES: [(&str, ValueType); 8] = [
+    ("settlement.population", ValueType::Number),
+    ("settlement.tier", ValueType::Name),
+    ("settlement.era", ValueType::Name),
+    ("biome", ValueType::Name),
+    ("moisture", ValueType::Number),
+    // Signed in [-1, 1], positive north of the equator
+    ("latitude", ValueType::Number),
+    ("season", ValueType::Name),
+    ("year", ValueType::Number),
+];
+// Followed by a good, as in `stock.Grain`
+const STOCK_PREFIX: &str = "stock.";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f32),
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Variable(String),
+    Value(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare(Operand, Comparison, Operand),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f32),
+    Compare(Comparison),
+    Open,
+    Close,
+}
+
+// One event as written in the file
+#[derive(Deserialize)]
+struct EventDefinition {
+    // String ID of the text told when it happens, with `{}` for the settlement
+    text: String,
+    when: String,
+    // Chance each season that a settlement meeting the condition is struck
+    chance: f32,
+    // Where it goes in the chronicle; events without one are only announced
+    #[serde(default)]
+    kind: Option<ChronicleKind>,
+    // Share of the people gained, or lost when negative
+    #[serde(default)]
+    population: f32,
+    // Goods added to the stockpile, or taken from it when negative
+    #[serde(default)]
+    goods: Vec<(Good, f32)>,
+}
+
+pub struct ScriptedEvent {
+    text: String,
+    condition: Condition,
+    chance: f32,
+    kind: Option<ChronicleKind>,
+    population: f32,
+    goods: Vec<(Good, f32)>,
+}
+
+// The events as the asset server loads them from assets/events.ron
+#[derive(Asset, TypePath)]
+pub struct EventScriptAsset(pub Vec<ScriptedEvent>);
+
+#[derive(Default, TypePath)]
+pub struct EventScriptLoader;
+
+impl AssetLoader for EventScriptLoader {
+    type Asset = EventScriptAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<EventScriptAsset, io::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        let events =
+            parse_events(&text).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(EventScriptAsset(events))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+// The loaded events, and the season they were last checked in. A file that
+// doesn't parse leaves the last good events in place, as the asset server
+// keeps them.
+#[derive(Resource)]
+pub struct EventScripts {
+    pub handle: Handle<EventScriptAsset>,
+    last_season: Option<u32>,
+}
+
+pub fn load_event_scripts(asset_server: &AssetServer) -> EventScripts {
+    EventScripts {
+        handle: asset_server.load(EVENT_SCRIPTS_PATH),
+        last_season: None,
+    }
+}
+
+// Everything a condition can ask about one settlement
+struct SettlementFacts<'a> {
+    population: f32,
+    tier: SettlementTier,
+    era: Era,
+    climate: &'a LocalClimate,
+    latitude: f32,
+    season: &'static str,
+    year: u32,
+    stockpile: &'a Stockpile,
+}
+
+impl SettlementFacts<'_> {
+    fn value(&self, variable: &str) -> Value {
+        if let Some(good) = variable.strip_prefix(STOCK_PREFIX).and_then(good_named) {
+            return Value::Number(self.stockpile.get(good));
+        }
+        match variable {
+            "settlement.population" => Value::Number(self.population),
+            "settlement.tier" => Value::Name(format!("{:?}", self.tier)),
+            "settlement.era" => Value::Name(format!("{:?}", self.era)),
+            "biome" => Value::Name(format!("{:?}", self.climate.biome)),
+            "moisture" => Value::Number(self.climate.moisture),
+            "latitude" => Value::Number(self.latitude),
+            "season" => Value::Name(self.season.to_string()),
+            "year" => Value::Number(self.year as f32),
+            _ => Value::Number(0.0),
+        }
+    }
+}
+
+impl Condition {
+    fn holds(&self, facts: &SettlementFacts) -> bool {
+        match self {
+            Condition::Compare(left, comparison, right) => {
+                let value = |operand: &Operand| match operand {
+                    Operand::Variable(variable) => facts.value(variable),
+                    Operand::Value(value) => value.clone(),
+                };
+                compare(&value(left), *comparison, &value(right))
+            }
+            Condition::And(left, right) => left.holds(facts) && right.holds(facts),
+            Condition::Or(left, right) => left.holds(facts) || right.holds(facts),
+            Condition::Not(condition) => !condition.holds(facts),
+        }
+    }
+}
+
+fn compare(left: &Value, comparison: Comparison, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => match comparison {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+        },
+        (Value::Name(left), Value::Name(right)) => match comparison {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn good_named(name: &str) -> Option<Good> {
+    Good::ALL
+        .into_iter()
+        .find(|good| format!("{:?}", good) == name)
+}
+
+fn variable_type(name: &str) -> Option<ValueType> {
+    if name
+        .strip_prefix(STOCK_PREFIX)
+        .is_some_and(|good| good_named(good).is_some())
+    {
+        return Some(ValueType::Number);
+    }
+    VARIABLES
+        .iter()
+        .find(|(variable, _)| *variable == name)
+        .map(|(_, value_type)| *value_type)
+}
+
+// Names a name variable can hold, so a misspelt one is refused rather than
+// never matching
+fn known_names(variable: &str) -> Vec<String> {
+    match variable {
+        "settlement.tier" => SettlementTier::ALL
+            .iter()
+            .map(|tier| format!("{:?}", tier))
+            .collect(),
+        "settlement.era" => Era::ALL.iter().map(|era| format!("{:?}", era)).collect(),
+        "biome" => Biome::ALL
+            .iter()
+            .map(|biome| format!("{:?}", biome))
+            .collect(),
+        "season" => SEASON_NAMES
+            .iter()
+            .map(|season| season.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_events(text: &str) -> Result<Vec<ScriptedEvent>, String> {
+    let definitions: Vec<EventDefinition> = ron::from_str(text).map_err(|err| err.to_string())?;
+
+    definitions
+        .into_iter()
+        .map(|definition| {
+            let condition = parse_condition(&definition.when)
+                .map_err(|err| format!("{}: {}", definition.text, err))?;
+            Ok(ScriptedEvent {
+                text: definition.text,
+                condition,
+                chance: definition.chance,
+                kind: definition.kind,
+                population: definition.population,
+                goods: definition.goods,
+            })
+        })
+        .collect()
+}
+
+fn parse_condition(text: &str) -> Result<Condition, String> {
+    let tokens = tokenize(text)?;
+    let mut parser = Parser { tokens, next: 0 };
+    let condition = parser.or()?;
+    match parser.tokens.get(parser.next) {
+        None => Ok(condition),
+        Some(token) => Err(format!("unexpected {:?} after the condition", token)),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+        } else if "<>=!".contains(c) {
+            chars.next();
+            let equals = chars.next_if_eq(&'=').is_some();
+            tokens.push(Token::Compare(match (c, equals) {
+                ('<', false) => Comparison::Less,
+                ('<', true) => Comparison::LessOrEqual,
+                ('>', false) => Comparison::Greater,
+                ('>', true) => Comparison::GreaterOrEqual,
+                ('=', true) => Comparison::Equal,
+                ('!', true) => Comparison::NotEqual,
+                _ => return Err(format!("`{}` on its own is not a comparison", c)),
+            }));
+        } else if c.is_ascii_digit() || c == '-' {
+            let mut number: String = chars.next_if_eq(&'-').into_iter().collect();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                number.push(c);
+            }
+            let value = number
+                .parse()
+                .map_err(|_| format!("`{}` is not a number", number))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.') {
+                word.push(c);
+            }
+            tokens.push(Token::Word(word));
+        } else {
+            return Err(format!("unexpected `{}`", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+// Recursive descent, `or` binding loosest and `not` tightest
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.tokens.get(self.next), Some(Token::Word(next)) if next == word)
+    }
+
+    fn take(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.next).cloned();
+        self.next += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.and()?;
+        while self.peek_word("or") {
+            self.next += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.not()?;
+        while self.peek_word("and") {
+            self.next += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, String> {
+        if self.peek_word("not") {
+            self.next += 1;
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+        if self.tokens.get(self.next) == Some(&Token::Open) {
+            self.next += 1;
+            let condition = self.or()?;
+            return match self.take() {
+                Some(Token::Close) => Ok(condition),
+                _ => Err("a bracket is left open".to_string()),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Condition, String> {
+        let (left, left_type) = self.operand()?;
+        let Some(Token::Compare(comparison)) = self.take() else {
+            return Err(format!("expected a comparison after {:?}", left));
+        };
+        let (right, right_type) = self.operand()?;
+
+        if !matches!(left, Operand::Variable(_)) && !matches!(right, Operand::Variable(_)) {
+            return Err(format!("neither {:?} nor {:?} is a variable", left, right));
+        }
+        if left_type != right_type {
+            return Err(format!("{:?} and {:?} can't be compared", left, right));
+        }
+        let ordered = !matches!(comparison, Comparison::Equal | Comparison::NotEqual);
+        if left_type == ValueType::Name && ordered {
+            return Err(format!(
+                "{:?} and {:?} can only be compared with == or !=",
+                left, right
+            ));
+        }
+
+        if let (Operand::Variable(variable), Operand::Value(Value::Name(name)))
+        | (Operand::Value(Value::Name(name)), Operand::Variable(variable)) = (&left, &right)
+            && !known_names(variable).contains(name)
+        {
+            return Err(format!("{} is never {}", variable, name));
+        }
+
+        Ok(Condition::Compare(left, comparison, right))
+    }
+
+    // A word that isn't a variable is a name, like `HotDesert` or `Winter`
+    fn operand(&mut self) -> Result<(Operand, ValueType), String> {
+        match self.take() {
+            Some(Token::Number(value)) => {
+                Ok((Operand::Value(Value::Number(value)), ValueType::Number))
+            }
+            Some(Token::Word(word)) if ["and", "or", "not"].contains(&word.as_str()) => {
+                Err(format!("expected a value before `{}`", word))
+            }
+            Some(Token::Word(word)) => Ok(match variable_type(&word) {
+                Some(value_type) => (Operand::Variable(word), value_type),
+                None => (Operand::Value(Value::Name(word)), ValueType::Name),
+            }),
+            Some(token) => Err(format!("expected a value, found {:?}", token)),
+            None => Err("the condition ends too soon".to_string()),
+        }
+    }
+}
+
+type ScriptedSettlements<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Settlement,
+        &'static LocalClimate,
+        &'static SettlementTier,
+        Option<&'static OwnedBy>,
+        &'static mut Population,
+        &'static mut Stockpile,
+    ),
+>;
+
+// Once a season, every settlement is checked against every event, and each
+// whose condition holds rolls for it
+pub fn run_scripted_events(
+    (season, locale): (Res<Season>, Res<Locale>),
+    settings: Res<WorldSettings>,
+    mut scripts: ResMut<EventScripts>,
+    assets: Res<Assets<EventScriptAsset>>,
+    (mut notifications, mut chronicle): (ResMut<Notifications>, ResMut<Chronicle>),
+    mut settlement_query: ScriptedSettlements,
+    tech_query: Query<&Technology>,
+) {
+    let current = season.season_number();
+    // A game starts partway into a season, so the first one is let pass
+    let last = scripts.last_season.replace(current);
+    if last.is_none_or(|last| last == current) {
+        return;
+    }
+    let Some(EventScriptAsset(events)) = assets.get(&scripts.handle) else {
+        return;
+    };
+
+    let mut rng = rand::rng();
+    for (settlement, climate, tier, owner, mut population, mut stockpile) in &mut settlement_query {
+        let latitude = tile_latitude(settlement.tile.y, settings.world_size);
+        let era = owner
+            .and_then(|owner| tech_query.get(owner.0).ok())
+            .map(|technology| technology.era)
+            .unwrap_or_default();
+
+        for event in events {
+            let facts = SettlementFacts {
+                population: population.0,
+                tier: *tier,
+                era,
+                climate,
+                latitude,
+                season: season.name(latitude),
+                year: season.year,
+                stockpile: &stockpile,
+            };
+            if !event.condition.holds(&facts)
+                || !rng.random_bool(event.chance.clamp(0.0, 1.0).into())
+            {
+                continue;
+            }
+
+            population.0 = (population.0 * (1.0 + event.population)).max(MIN_POPULATION);
+            for &(good, amount) in &event.goods {
+                let stock = &mut stockpile.0[good as usize];
+                *stock = (*stock + amount).max(0.0);
+            }
+
+            let text = locale.format(&event.text, &[&settlement.name]);
+            if let Some(kind) = event.kind {
+                chronicle.record(locale.date(&season), kind, text.clone());
+            }
+            notifications.push(text);
+        }
+    }
+}
+
+pub fn cleanup_event_scripts(mut scripts: ResMut<EventScripts>) {
+    scripts.last_season = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions_join_with_and_binding_tighter_than_or() {
+        let condition = parse_condition("biome == Taiga or season == Winter and year > 5");
+        let compare = |variable: &str, comparison, value| {
+            Condition::Compare(
+                Operand::Variable(variable.to_string()),
+                comparison,
+                Operand::Value(value),
+            )
+        };
+
+        assert_eq!(
+            condition,
+            Ok(Condition::Or(
+                Box::new(compare(
+                    "biome",
+                    Comparison::Equal,
+                    Value::Name("Taiga".into())
+                )),
+                Box::new(Condition::And(
+                    Box::new(compare(
+                        "season",
+                        Comparison::Equal,
+                        Value::Name("Winter".into())
+                    )),
+                    Box::new(compare("year", Comparison::Greater, Value::Number(5.0))),
+                )),
+            ))
+        );
+    }
+
+    #[test]
+    fn mismatched_and_unfinished_conditions_are_refused() {
+        for text in [
+            "biome > HotDesert",
+            "settlement.population == Town",
+            "Taiga == Tundra",
+            "(year > 5",
+            "year >",
+            "stock.Gold > 10",
+            "biome == HotDessert",
+            "season != Monsoon",
+        ] {
+            assert!(parse_condition(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn shipped_events_parse() {
+        let events = parse_events(include_str!("../../assets/events.ron")).unwrap();
+        assert!(!events.is_empty());
+    }
+}
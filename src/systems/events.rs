@@ -0,0 +1,288 @@
+use bevy::prelude::*;
+use kingdom_worldgen::MAX_ELEVATION;
+use rand::Rng;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    events::{Disaster, DisasterKind, LocalClimate},
+    settlements::{Good, Population, ProductionModifier, Settlement},
+    world::{Landmark, LandmarkKind},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    localization::Locale,
+    notifications::Notifications,
+    season::{Season, YEAR_LENGTH_SECS, tile_latitude},
+    world::{WorldSettings, nearest_copy, toroidal_distance},
+    world_gen::settlements::survey_tile,
+};
+
+// Fate is tempted a few times a month
+const EVENT_TICK_SECS: f32 = 10.0;
+// Yearly chance a settlement where the land allows it is struck. Harsh winters
+// are only rolled for in winter, so strike about as often as the rest.
+const DROUGHT_CHANCE: f32 = 0.15;
+const FLOOD_CHANCE: f32 = 0.15;
+const HARSH_WINTER_CHANCE: f32 = 0.6;
+// Yearly chance each volcano erupts
+const ERUPTION_CHANCE: f32 = 0.03;
+const DRY_MOISTURE: f32 = 0.3;
+const WET_MOISTURE: f32 = 0.55;
+// Wet land this close above the sea, as a share of the highest peak, floods
+const FLOODPLAIN_HEIGHT: f64 = 0.03;
+// Distance from the equator, as a share of the way to the pole, past which
+// winters can turn deadly
+const HIGH_LATITUDE: f32 = 0.55;
+
+// Where news of a disaster goes: the calendar and language it is written in,
+// the notifications and the chronicle
+type StrikeNews<'a> = (
+    &'a Season,
+    &'a Locale,
+    &'a mut Notifications,
+    &'a mut Chronicle,
+);
+
+#[derive(Resource, Default)]
+pub struct WorldEvents {
+    pub cooldown: f32,
+}
+
+// Settlements note what land they stand on, and how dry and low it lies, once,
+// when they are founded
+pub fn survey_local_climate(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    world_data_query: Query<&WorldData>,
+    settlement_query: Query<(Entity, &Settlement), Without<LocalClimate>>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+
+    for (entity, settlement) in &settlement_query {
+        let square = survey_tile(world_data, &settings, settlement.tile);
+        commands.entity(entity).insert(LocalClimate {
+            biome: square.biome,
+            moisture: square.moisture,
+            height: square.elevation - world_data.sea_level() as f32,
+        });
+    }
+}
+
+// Rolls for the disasters each settlement's land is prone to, and for each
+// volcano to erupt. A settlement already caught in a disaster of a kind isn't
+// struck by another until it passes.
+pub fn roll_disasters(
+    mut commands: Commands,
+    (clock, settings): (Res<GameClock>, Res<WorldSettings>),
+    (season, locale): (Res<Season>, Res<Locale>),
+    mut events: ResMut<WorldEvents>,
+    (mut notifications, mut chronicle): (ResMut<Notifications>, ResMut<Chronicle>),
+    mut settlement_query: Query<(&Settlement, &LocalClimate, &mut Population)>,
+    (disaster_query, landmark_query): (Query<&Disaster>, Query<&Landmark>),
+) {
+    events.cooldown -= clock.delta_secs;
+    if events.cooldown > 0.0 {
+        return;
+    }
+    events.cooldown = EVENT_TICK_SECS;
+
+    let mut rng = rand::rng();
+    let mut roll = |yearly_chance: f32| {
+        rng.random_bool((yearly_chance * EVENT_TICK_SECS / YEAR_LENGTH_SECS) as f64)
+    };
+    let struck = |kind: DisasterKind, tile: IVec2| {
+        disaster_query.iter().any(|disaster| {
+            disaster.kind == kind
+                && toroidal_distance(disaster.tile, tile, &settings) <= kind.radius()
+        })
+    };
+
+    let mut strikes: Vec<(DisasterKind, IVec2, String)> = Vec::new();
+    for (settlement, climate, _) in &settlement_query {
+        let latitude = tile_latitude(settlement.tile.y, settings.world_size);
+        let floodplain = (climate.height as f64) < MAX_ELEVATION * FLOODPLAIN_HEIGHT;
+        let prone = [
+            (
+                DisasterKind::Drought,
+                climate.moisture < DRY_MOISTURE,
+                DROUGHT_CHANCE,
+            ),
+            (
+                DisasterKind::Flood,
+                floodplain && climate.moisture > WET_MOISTURE,
+                FLOOD_CHANCE,
+            ),
+            (
+                DisasterKind::HarshWinter,
+                latitude.abs() > HIGH_LATITUDE && season.name(latitude) == "Winter",
+                HARSH_WINTER_CHANCE,
+            ),
+        ];
+
+        for (kind, exposed, chance) in prone {
+            if exposed && !struck(kind, settlement.tile) && roll(chance) {
+                strikes.push((kind, settlement.tile, settlement.name.clone()));
+            }
+        }
+    }
+
+    for landmark in &landmark_query {
+        let tile = IVec2::new(landmark.x, landmark.y);
+        if landmark.kind != LandmarkKind::Volcano
+            || struck(DisasterKind::Eruption, tile)
+            || !roll(ERUPTION_CHANCE)
+        {
+            continue;
+        }
+        // Named for the nearest town in reach of the ash, if there is one
+        let place = settlement_query
+            .iter()
+            .map(|(settlement, ..)| {
+                (
+                    settlement,
+                    toroidal_distance(tile, settlement.tile, &settings),
+                )
+            })
+            .filter(|(_, distance)| *distance <= DisasterKind::Eruption.radius())
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(settlement, _)| settlement.name.clone())
+            .unwrap_or_else(|| format!("{}, {}", tile.x, tile.y));
+        strikes.push((DisasterKind::Eruption, tile, place));
+    }
+
+    for (kind, tile, place) in strikes {
+        let populations = settlement_query
+            .iter_mut()
+            .map(|(settlement, _, population)| (settlement.tile, population));
+        let lost = take_toll(kind, tile, &settings, populations);
+        strike(
+            &mut commands,
+            (&season, &locale, &mut notifications, &mut chronicle),
+            (kind, tile, place),
+            lost,
+        );
+    }
+}
+
+// Thins out the settlements within reach of a disaster, returning how many died
+pub fn take_toll<'a>(
+    kind: DisasterKind,
+    tile: IVec2,
+    settings: &WorldSettings,
+    populations: impl Iterator<Item = (IVec2, Mut<'a, Population>)>,
+) -> f32 {
+    let mut lost = 0.0;
+    for (settlement_tile, mut population) in populations {
+        if toroidal_distance(tile, settlement_tile, settings) <= kind.radius() {
+            lost += population.0 * kind.toll();
+            population.0 *= 1.0 - kind.toll();
+        }
+    }
+    lost
+}
+
+// Announces a disaster, writes it into the chronicle and sets it running
+pub fn strike(
+    commands: &mut Commands,
+    (season, locale, notifications, chronicle): StrikeNews,
+    (kind, tile, place): (DisasterKind, IVec2, String),
+    lost: f32,
+) {
+    let name = locale.name("disaster", kind, kind.name());
+    let text = if lost >= 1.0 {
+        locale.format("chronicle.disaster_toll", &[&name, &place, &lost.round()])
+    } else {
+        locale.format("chronicle.disaster", &[&name, &place])
+    };
+    chronicle.record(locale.date(season), ChronicleKind::Disaster, text.clone());
+    notifications.push(text);
+    commands.spawn(Disaster {
+        kind,
+        tile,
+        place,
+        remaining: kind.duration() * YEAR_LENGTH_SECS,
+    });
+}
+
+// Disasters run their course, and every settlement caught in one works only a
+// share of its usual output until they pass
+pub fn update_disasters(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    settings: Res<WorldSettings>,
+    locale: Res<Locale>,
+    mut notifications: ResMut<Notifications>,
+    mut disaster_query: Query<(Entity, &mut Disaster)>,
+    settlement_query: Query<(Entity, &Settlement, Option<&ProductionModifier>)>,
+) {
+    for (entity, mut disaster) in &mut disaster_query {
+        disaster.remaining -= clock.delta_secs;
+        if disaster.remaining <= 0.0 {
+            let kind = disaster.kind;
+            let name = locale.name("disaster", kind, kind.name());
+            notifications.push(locale.format("notice.disaster_passed", &[&name, &disaster.place]));
+            commands.entity(entity).despawn();
+        }
+    }
+
+    for (entity, settlement, modifier) in &settlement_query {
+        let mut share = [1.0; Good::ALL.len()];
+        for (_, disaster) in &disaster_query {
+            let reach = toroidal_distance(disaster.tile, settlement.tile, &settings);
+            if disaster.remaining > 0.0 && reach <= disaster.kind.radius() {
+                for (share, factor) in share.iter_mut().zip(disaster.kind.output_share()) {
+                    *share *= factor;
+                }
+            }
+        }
+
+        let share = ProductionModifier(share);
+        if share.0 == [1.0; Good::ALL.len()] {
+            if modifier.is_some() {
+                commands.entity(entity).remove::<ProductionModifier>();
+            }
+        } else if modifier != Some(&share) {
+            commands.entity(entity).insert(share);
+        }
+    }
+}
+
+// Each disaster is ringed on the copy of its tile nearest the camera
+pub fn draw_disasters(
+    mut gizmos: Gizmos,
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    disaster_query: Query<&Disaster>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for disaster in &disaster_query {
+        let nearest = nearest_copy(camera, disaster.tile, &settings);
+        let color = match disaster.kind {
+            DisasterKind::Drought => Color::srgba(0.85, 0.6, 0.2, 0.8),
+            DisasterKind::Flood => Color::srgba(0.2, 0.45, 0.95, 0.8),
+            DisasterKind::Eruption => Color::srgba(0.95, 0.25, 0.1, 0.8),
+            DisasterKind::HarshWinter => Color::srgba(0.8, 0.9, 1.0, 0.8),
+        };
+        gizmos.circle_2d(
+            nearest.as_vec2() + Vec2::splat(0.5),
+            disaster.kind.radius(),
+            color,
+        );
+    }
+}
+
+pub fn cleanup_events(
+    mut commands: Commands,
+    mut events: ResMut<WorldEvents>,
+    query: Query<Entity, With<Disaster>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    *events = WorldEvents::default();
+}
@@ -0,0 +1,314 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+use image::ExtendedColorType;
+use kingdom_worldgen::{
+    HALO, export::write_heightmap_png, generate_chunk_data, sampler::WorldSampler,
+};
+
+use crate::{
+    components::{
+        world::{Square, WorldMap},
+        world_gen::WorldData,
+    },
+    systems::{
+        keybindings::{InputAction, InputMap},
+        options::GameOptions,
+        palette::Palette,
+        parallel::*,
+        startup::user_data_dir,
+        world::{MapOverlay, WorldSettings, tile_color},
+    },
+};
+
+// Grayscale layers written beside the biome map when the option is on
+const CHANNELS: [(MapOverlay, &str); 3] = [
+    (MapOverlay::Elevation, "elevation"),
+    (MapOverlay::Temperature, "temperature"),
+    (MapOverlay::Moisture, "moisture"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    // Biome colours, plus the grayscale layers when the option is on
+    Map,
+    // Elevation as a 16-bit grayscale PNG and raw little-endian f32s, for terrain
+    // tools like Blender or World Machine
+    Heightmap,
+}
+
+// Set by a hotkey or the pause menu; the export starts on the next frame
+#[derive(Resource)]
+pub struct MapExportRequest(pub ExportKind);
+
+// Writes the PNGs off the main thread and hands back the folder they went to
+#[derive(Resource)]
+pub struct MapExportTask(Task<Result<PathBuf, String>>);
+
+// One pixel per tile, rows from the north edge down so the image reads like the map
+struct MapLayers {
+    size: usize,
+    kind: ExportKind,
+    biome: Vec<u8>,
+    // Same order as CHANNELS; empty when only the biome map is exported
+    channels: Vec<Vec<u8>>,
+    heights: Vec<f32>,
+}
+
+impl MapLayers {
+    fn new(size: usize, kind: ExportKind, with_channels: bool) -> Self {
+        let pixels = size * size;
+        let mut layers = MapLayers {
+            size,
+            kind,
+            biome: Vec::new(),
+            channels: Vec::new(),
+            heights: Vec::new(),
+        };
+        match kind {
+            ExportKind::Map => {
+                layers.biome = vec![0; pixels * 4];
+                if with_channels {
+                    layers.channels = vec![vec![0; pixels]; CHANNELS.len()];
+                }
+            }
+            ExportKind::Heightmap => layers.heights = vec![0.0; pixels],
+        }
+
+        layers
+    }
+
+    fn paint(
+        &mut self,
+        x: usize,
+        y: usize,
+        square: &Square,
+        world_data: &WorldData,
+        palette: &Palette,
+    ) {
+        let pixel = (self.size - 1 - y) * self.size + x;
+        if self.kind == ExportKind::Heightmap {
+            self.heights[pixel] = square.elevation;
+            return;
+        }
+
+        let sea_level = world_data.sea_level() as f32;
+        let color = tile_color(
+            square,
+            square.biome,
+            MapOverlay::Biome,
+            &world_data.biomes,
+            palette,
+            sea_level,
+        );
+        let color = color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0) as u8);
+        self.biome[pixel * 4..pixel * 4 + 4].copy_from_slice(&color);
+
+        for (layer, (overlay, _)) in self.channels.iter_mut().zip(CHANNELS) {
+            if let Some(scale) = overlay.heatmap_scale(sea_level) {
+                let value = overlay.heatmap_value(square, sea_level);
+                let t = (value - scale.min) / (scale.max - scale.min);
+                layer[pixel] = (t.clamp(0.0, 1.0) * 255.0) as u8;
+            }
+        }
+    }
+}
+
+pub fn request_map_export(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+) {
+    // The web build has no files to export to
+    if cfg!(target_arch = "wasm32") {
+        return;
+    }
+    if input_map.just_pressed(&input, InputAction::ExportMap) {
+        commands.insert_resource(MapExportRequest(ExportKind::Map));
+    } else if input_map.just_pressed(&input, InputAction::ExportHeightmap) {
+        commands.insert_resource(MapExportRequest(ExportKind::Heightmap));
+    }
+}
+
+// A pregenerated map is painted here, which is quick; a streamed world has to be
+// generated chunk by chunk first, so that happens on the task as well
+pub fn start_map_export(
+    mut commands: Commands,
+    request: Res<MapExportRequest>,
+    options: Res<GameOptions>,
+    settings: Res<WorldSettings>,
+    running: Option<Res<MapExportTask>>,
+    world_data_query: Query<&WorldData>,
+    world_map_query: Query<&WorldMap>,
+) {
+    commands.remove_resource::<MapExportRequest>();
+    if running.is_some() {
+        warn!("A map export is already running");
+        return;
+    }
+
+    let world_data = match world_data_query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let settings = *settings;
+    let kind = request.0;
+    let with_channels = options.export_layers;
+    let directory = export_directory(world_data.seed);
+
+    // Exports feed other tools, so they keep the default colours whatever palette
+    // the map is shown in
+    let palette = Palette::default();
+
+    let painted = world_map_query
+        .single()
+        .ok()
+        .map(|world_map| layers_from_map(world_map, &world_data, &palette, kind, with_channels));
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let layers = painted.unwrap_or_else(|| {
+            layers_from_chunks(&world_data, &settings, &palette, kind, with_channels)
+        });
+        write_layers(layers, &directory).map(|_| directory)
+    });
+
+    info!("Exporting the map...");
+    commands.insert_resource(MapExportTask(task));
+}
+
+pub fn poll_map_export(mut commands: Commands, mut task: ResMut<MapExportTask>) {
+    let Some(result) = check_ready(&mut task.0) else {
+        return;
+    };
+    commands.remove_resource::<MapExportTask>();
+
+    match result {
+        Ok(directory) => info!("Map exported to {}", directory.display()),
+        Err(err) => error!("Map export failed: {}", err),
+    }
+}
+
+pub fn export_directory(seed: u32) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    user_data_dir()
+        .join("exports")
+        .join(format!("world-{}-{}", seed, timestamp))
+}
+
+// North-up biome colours, four bytes and one pixel per tile
+pub fn map_colors(world_map: &WorldMap, world_data: &WorldData, palette: &Palette) -> Vec<u8> {
+    layers_from_map(world_map, world_data, palette, ExportKind::Map, false).biome
+}
+
+// `map_colors` for a streamed world, generated chunk by chunk
+pub fn streamed_map_colors(
+    world_data: &WorldData,
+    settings: &WorldSettings,
+    palette: &Palette,
+) -> Vec<u8> {
+    layers_from_chunks(world_data, settings, palette, ExportKind::Map, false).biome
+}
+
+fn layers_from_map(
+    world_map: &WorldMap,
+    world_data: &WorldData,
+    palette: &Palette,
+    kind: ExportKind,
+    with_channels: bool,
+) -> MapLayers {
+    let size = world_map.width as usize;
+    let mut layers = MapLayers::new(size, kind, with_channels);
+    for (index, square) in world_map.squares().enumerate() {
+        layers.paint(index % size, index / size, &square, world_data, palette);
+    }
+
+    layers
+}
+
+// Generates one row of chunks at a time in parallel, so only a row's worth of
+// squares is held at once even for the largest worlds
+fn layers_from_chunks(
+    world_data: &WorldData,
+    settings: &WorldSettings,
+    palette: &Palette,
+    kind: ExportKind,
+    with_channels: bool,
+) -> MapLayers {
+    let chunk_size = settings.chunk_size;
+    let halo_side = chunk_size + 2 * HALO;
+    let mut layers = MapLayers::new(settings.world_size as usize, kind, with_channels);
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+
+    for chunk_y in 0..settings.chunks_per_side() {
+        let row: Vec<Vec<Square>> = (0..settings.chunks_per_side())
+            .into_par_iter()
+            .map(|chunk_x| generate_chunk_data(chunk_x, chunk_y, chunk_size, &sampler))
+            .collect();
+
+        for (chunk_x, data) in row.iter().enumerate() {
+            for local_y in 0..chunk_size {
+                for local_x in 0..chunk_size {
+                    let square = &data[((local_y + HALO) * halo_side + local_x + HALO) as usize];
+                    let x = chunk_x * chunk_size as usize + local_x as usize;
+                    let y = (chunk_y * chunk_size + local_y) as usize;
+                    layers.paint(x, y, square, world_data, palette);
+                }
+            }
+        }
+    }
+
+    layers
+}
+
+fn write_layers(layers: MapLayers, directory: &Path) -> Result<(), String> {
+    fs::create_dir_all(directory).map_err(|err| err.to_string())?;
+    let size = layers.size as u32;
+
+    if layers.kind == ExportKind::Heightmap {
+        // The PNG spans sea floor to peak over the full 16-bit range; the raw file
+        // keeps the exact elevations
+        write_heightmap_png(&directory.join("heightmap.png"), size, &layers.heights)?;
+
+        let raw: Vec<u8> = layers
+            .heights
+            .iter()
+            .flat_map(|height| height.to_le_bytes())
+            .collect();
+        let path = directory.join(format!("heightmap_{}x{}.r32", size, size));
+        return fs::write(&path, raw).map_err(|err| format!("{}: {}", path.display(), err));
+    }
+
+    write_png(
+        &directory.join("biome.png"),
+        size,
+        &layers.biome,
+        ExtendedColorType::Rgba8,
+    )?;
+    for (layer, (_, name)) in layers.channels.iter().zip(CHANNELS) {
+        let path = directory.join(format!("{}.png", name));
+        write_png(&path, size, layer, ExtendedColorType::L8)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_png(
+    path: &Path,
+    size: u32,
+    data: &[u8],
+    color: ExtendedColorType,
+) -> Result<(), String> {
+    image::save_buffer(path, data, size, size, color)
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
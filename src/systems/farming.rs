@@ -0,0 +1,171 @@
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::components::{
+    kingdoms::{OwnedBy, Technology},
+    settlements::{
+        Farm, FarmOf, FarmlandSurvey, Farms, FoodCapacity, Good, Population, ProductionRates,
+        Settlement,
+    },
+    world::Square,
+    world_gen::WorldData,
+};
+use crate::systems::{
+    clock::GameClock,
+    world::{WorldSettings, nearest_copy},
+    world_gen::{biome_table::BiomeTable, settlements::survey_farmland},
+};
+
+// Tiles from a settlement its farmers will walk out to
+const FARM_RADIUS: i32 = 5;
+// People a settlement needs for each farm it works
+const PEOPLE_PER_FARM: f32 = 40.0;
+// Grain a year from a farm on the best soil in the best weather
+const BASE_FARM_YIELD: f32 = 30.0;
+// Fields poorer than this aren't worth clearing
+const MIN_FARM_YIELD: f32 = 3.0;
+// Each settlement clears at most one field this often
+const CLEARING_SECS: f32 = 6.0;
+const FARM_COLOR: Color = Color::srgba(0.85, 0.72, 0.3, 0.55);
+// Over the chunk meshes, under the territory tint and markers
+const FARM_Z: f32 = 0.1;
+
+#[derive(Resource, Default)]
+pub struct FarmClearing {
+    pub cooldown: f32,
+}
+
+// Fertile soil in a mild, well watered climate yields most. Crops fail in
+// frost and scorch past 35°C, and want neither desert nor swamp.
+pub fn farm_yield(square: &Square, biomes: &BiomeTable) -> f32 {
+    let warmth = if square.temperature < 15.0 {
+        (square.temperature + 5.0) / 20.0
+    } else if square.temperature > 25.0 {
+        (35.0 - square.temperature) / 10.0
+    } else {
+        1.0
+    };
+    let water = if square.moisture < 0.4 {
+        square.moisture / 0.4
+    } else if square.moisture > 0.8 {
+        (1.0 - square.moisture) / 0.2 * 0.5 + 0.5
+    } else {
+        1.0
+    };
+
+    biomes.fertility(square.biome)
+        * warmth.clamp(0.0, 1.0)
+        * water.clamp(0.0, 1.0)
+        * BASE_FARM_YIELD
+}
+
+// Settlements look over the land around them once, when they are founded
+pub fn survey_farm_plots(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    world_data_query: Query<&WorldData>,
+    settlement_query: Query<(Entity, &Settlement), Without<FarmlandSurvey>>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+
+    for (entity, settlement) in &settlement_query {
+        let mut plots: Vec<(IVec2, f32)> =
+            survey_farmland(world_data, &settings, settlement.tile, FARM_RADIUS)
+                .into_iter()
+                .map(|(tile, square)| (tile, farm_yield(&square, &world_data.biomes)))
+                .filter(|(_, yield_per_year)| *yield_per_year >= MIN_FARM_YIELD)
+                .collect();
+        plots.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        commands.entity(entity).insert(FarmlandSurvey(plots));
+    }
+}
+
+// A settlement's hands and surveyed plots, and what its farms add to
+type Farmland = (
+    Entity,
+    &'static Population,
+    &'static FarmlandSurvey,
+    Option<&'static Farms>,
+    &'static mut ProductionRates,
+    &'static mut FoodCapacity,
+    Option<&'static OwnedBy>,
+);
+
+// A settlement with hands to spare clears its best free plot into a farm, which
+// feeds it from then on. Farmers of a kingdom in a later era get more from it.
+pub fn clear_farmland(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut clearing: ResMut<FarmClearing>,
+    mut settlement_query: Query<Farmland>,
+    farm_query: Query<&Farm>,
+    tech_query: Query<&Technology>,
+) {
+    clearing.cooldown -= clock.delta_secs;
+    if clearing.cooldown > 0.0 {
+        return;
+    }
+    clearing.cooldown = CLEARING_SECS;
+
+    let mut farmed: HashSet<IVec2> = farm_query.iter().map(|farm| farm.tile).collect();
+    for (entity, population, survey, farms, mut production, mut capacity, owner) in
+        &mut settlement_query
+    {
+        let worked = farms.map(|farms| farms.len()).unwrap_or(0);
+        if worked as f32 >= (population.0 / PEOPLE_PER_FARM).floor() {
+            continue;
+        }
+        let Some(&(tile, yield_per_year)) =
+            survey.0.iter().find(|(tile, _)| !farmed.contains(tile))
+        else {
+            continue;
+        };
+
+        farmed.insert(tile);
+        let era = owner
+            .and_then(|owner| tech_query.get(owner.0).ok())
+            .map(|technology| technology.era)
+            .unwrap_or_default();
+        let yield_per_year = yield_per_year * era.farm_yield();
+        production.0[Good::Grain as usize] += yield_per_year;
+        capacity.0 += yield_per_year;
+        let farm = spawn_farm(&mut commands, tile, yield_per_year);
+        commands.entity(farm).insert(FarmOf(entity));
+    }
+}
+
+pub fn spawn_farm(commands: &mut Commands, tile: IVec2, yield_per_year: f32) -> Entity {
+    commands
+        .spawn((
+            Sprite::from_color(FARM_COLOR, Vec2::ONE),
+            Transform::from_translation((tile.as_vec2() + Vec2::splat(0.5)).extend(FARM_Z)),
+            Farm {
+                tile,
+                yield_per_year,
+            },
+        ))
+        .id()
+}
+
+// Farms are drawn on the copy of their tile nearest the camera
+pub fn update_farm_sprites(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut farm_query: Query<(&Farm, &mut Transform), Without<Camera2d>>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for (farm, mut transform) in &mut farm_query {
+        let nearest = nearest_copy(camera, farm.tile, &settings);
+        let position = (nearest.as_vec2() + Vec2::splat(0.5)).extend(FARM_Z);
+        if transform.translation != position {
+            transform.translation = position;
+        }
+    }
+}
+
+pub fn cleanup_farming(mut clearing: ResMut<FarmClearing>) {
+    *clearing = FarmClearing::default();
+}
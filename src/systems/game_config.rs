@@ -1,10 +1,70 @@
 use bevy::input::keyboard::Key;
-use bevy::ui::Node;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use bevy::ui::{Node, RelativeCursorPosition, UiGlobalTransform};
 use bevy::{input::keyboard::KeyboardInput, prelude::*};
+use kingdom_worldgen::survey::WorldSurvey;
+use rand::RngCore;
 
-use crate::{components::game_config::*, states::game_state::GameState};
+use crate::{
+    components::{
+        game_config::*,
+        localization::Localized,
+        world_gen::{GenerationMode, NoiseKind, WorldData, parse_seed},
+    },
+    states::game_state::GameState,
+    systems::{
+        growth::{Growth, GrowthModelsAsset},
+        localization::Locale,
+        world::{DEFAULT_CHUNK_SIZE, DEFAULT_WORLD_SIZE, WorldSettings},
+        world_gen::{
+            biome_table::Biomes,
+            comparison::compare_worlds,
+            presets::{
+                PRESETS, load_saved_preset, sanitize_preset_name, save_preset, saved_preset_names,
+            },
+        },
+    },
+};
+
+// Random seeds generated and ranked per comparison run
+const COMPARISON_SEEDS: usize = 6;
+
+// Bounds for the typed fields that have one; read_world_settings clamps to the
+// same sizes
+pub const MIN_WORLD_SIZE: i32 = 256;
+pub const MAX_WORLD_SIZE: i32 = 16384;
+pub const MIN_CHUNK_SIZE: i32 = 16;
+pub const MAX_CHUNK_SIZE: i32 = 256;
+const MAX_EROSION_ITERATIONS: u32 = 200;
+const MAX_PLATES: u32 = 64;
+
+// Options of the cycling buttons, in the order a click steps through them
+const GENERATION_MODES: [GenerationMode; 2] = [GenerationMode::Noise, GenerationMode::Tectonic];
+const NOISE_KINDS: [NoiseKind; 4] = [
+    NoiseKind::OpenSimplex,
+    NoiseKind::Perlin,
+    NoiseKind::RidgedMultifractal,
+    NoiseKind::Worley,
+];
 
-pub fn setup_game_config(mut commands: Commands) {
+// Set by the Compare Seeds button; the current inputs are read into a template
+// world before the survey starts
+#[derive(Resource)]
+pub struct SeedComparisonRequest;
+
+#[derive(Resource)]
+pub struct SeedComparison(Task<Vec<WorldSurvey>>);
+
+// Set by the Save Preset button; like a comparison, the inputs are read into a
+// WorldData first and the preset is written from that
+#[derive(Resource)]
+pub struct SavePresetRequest;
+
+pub fn setup_game_config(
+    mut commands: Commands,
+    (growth, growth_assets): (Res<Growth>, Res<Assets<GrowthModelsAsset>>),
+    locale: Res<Locale>,
+) {
     commands.spawn((
         Node {
             width: Val::Percent(100.0),
@@ -18,14 +78,30 @@ pub fn setup_game_config(mut commands: Commands) {
         BackgroundColor(Color::BLACK),
         GameConfigUI,
         children![
-            seed_field(),
+            preset_selector(),
+            seed_field(&locale),
             terrain_scale_field(),
             continental_scale_field(),
             octave_field(),
             sea_threshold_field(),
+            mountain_threshold_field(),
             temperature_scale_field(),
             moisture_scale_field(),
             scaling_factor_field(),
+            erosion_iterations_field(),
+            erosion_strength_field(),
+            warp_strength_field(),
+            generation_mode_field(),
+            plate_count_field(),
+            noise_kind_field(),
+            world_size_field(),
+            chunk_size_field(),
+            streaming_field(),
+            gpu_generation_field(),
+            migration_start_field(),
+            fantasy_field(),
+            difficulty_field(&growth, &growth_assets),
+            heightmap_field(&locale),
             (
                 Button,
                 Node {
@@ -36,6 +112,7 @@ pub fn setup_game_config(mut commands: Commands) {
                 GameConfigAction::Generate,
                 children![(
                     Text::new("Generate"),
+                    Localized::new("config.generate"),
                     TextFont {
                         font_size: 32.0,
                         ..default()
@@ -53,6 +130,7 @@ pub fn setup_game_config(mut commands: Commands) {
                 GameConfigAction::Back,
                 children![(
                     Text::new("Back to Menu"),
+                    Localized::new("config.back_to_menu"),
                     TextFont {
                         font_size: 32.0,
                         ..default()
@@ -62,76 +140,76 @@ pub fn setup_game_config(mut commands: Commands) {
             ),
         ],
     ));
-}
 
-fn seed_field() -> impl Bundle {
-    return (
+    commands.spawn((
         Node {
-            width: Val::Percent(100.0),
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            flex_direction: FlexDirection::Row,
-            column_gap: Val::Px(16.0),
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(10.0)),
             ..default()
         },
+        BackgroundColor(Color::srgb(0.08, 0.08, 0.08)),
+        GameConfigUI,
         children![
-            (
-                Text::new("Seed:"),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE)
-            ),
             (
                 Button,
                 Node {
-                    padding: UiRect::all(Val::Px(20.0)),
+                    padding: UiRect::all(Val::Px(10.0)),
                     ..default()
                 },
                 BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new()
-                },
-                SeedField,
+                GameConfigAction::CompareSeeds,
                 children![(
-                    Text::new(""),
-                    SeedField,
+                    Text::new(locale.format("config.compare_seeds", &[&COMPARISON_SEEDS])),
                     TextFont {
                         font_size: 20.0,
                         ..default()
                     },
                     TextColor(Color::WHITE)
                 )]
-            )
+            ),
+            (
+                Text::new("Surveys random seeds with the current settings"),
+                Localized::new("config.compare_hint"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                SeedComparisonStatus,
+            ),
+            (
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                SeedComparisonList,
+            ),
         ],
-    );
-}
+    ));
 
-fn terrain_scale_field() -> impl Bundle {
-    return (
+    commands.spawn((
         Node {
-            width: Val::Percent(100.0),
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            flex_direction: FlexDirection::Row,
-            column_gap: Val::Px(16.0),
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            top: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(10.0)),
             ..default()
         },
+        BackgroundColor(Color::srgb(0.08, 0.08, 0.08)),
+        GameConfigUI,
         children![
-            (
-                Text::new("Terrain scale:"),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE)
-            ),
             (
                 Button,
                 Node {
-                    padding: UiRect::all(Val::Px(20.0)),
+                    min_width: Val::Px(200.0),
+                    padding: UiRect::all(Val::Px(10.0)),
                     ..default()
                 },
                 BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
@@ -139,68 +217,81 @@ fn terrain_scale_field() -> impl Bundle {
                 InputValue {
                     text: String::new()
                 },
-                TerrainScaleField,
+                PresetNameField,
+                Placeholder(locale.text("config.preset_name").to_string()),
                 children![(
                     Text::new(""),
-                    TerrainScaleField,
                     TextFont {
                         font_size: 20.0,
                         ..default()
                     },
                     TextColor(Color::WHITE)
                 )]
-            )
-        ],
-    );
-}
-
-fn continental_scale_field() -> impl Bundle {
-    return (
-        Node {
-            width: Val::Percent(100.0),
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            flex_direction: FlexDirection::Row,
-            column_gap: Val::Px(16.0),
-            ..default()
-        },
-        children![
-            (
-                Text::new("Continental scale:"),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE)
             ),
             (
                 Button,
                 Node {
-                    padding: UiRect::all(Val::Px(20.0)),
+                    padding: UiRect::all(Val::Px(10.0)),
                     ..default()
                 },
                 BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new()
-                },
-                ContinentalScaleField,
+                GameConfigAction::SavePreset,
                 children![(
-                    Text::new(""),
-                    ContinentalScaleField,
+                    Text::new("Save Preset"),
+                    Localized::new("config.save_preset"),
                     TextFont {
                         font_size: 20.0,
                         ..default()
                     },
                     TextColor(Color::WHITE)
                 )]
-            )
+            ),
+            (
+                Text::new("Saved presets; click one to load it"),
+                Localized::new("config.saved_presets_hint"),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                SavedPresetStatus,
+            ),
+            (
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                SavedPresetList,
+                Children::spawn(SpawnIter(saved_preset_names().into_iter().map(saved_preset_row))),
+            ),
         ],
-    );
+    ));
 }
 
-fn octave_field() -> impl Bundle {
-    return (
+fn saved_preset_row(name: String) -> impl Bundle {
+    (
+        Button,
+        Node {
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+        children![(
+            Text::new(name.clone()),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::WHITE)
+        )],
+        SavedPresetButton(name),
+    )
+}
+
+// A setup row: the field's label, then whatever sets its value
+fn field_row(id: &'static str, label: &'static str, input: impl Bundle) -> impl Bundle {
+    (
         Node {
             width: Val::Percent(100.0),
             justify_content: JustifyContent::Center,
@@ -211,86 +302,139 @@ fn octave_field() -> impl Bundle {
         },
         children![
             (
-                Text::new("Number of octaves:"),
+                Text::new(label),
+                Localized::new(id),
                 TextFont {
                     font_size: 20.0,
                     ..default()
                 },
-                TextColor(Color::WHITE)
+                TextColor(Color::WHITE),
             ),
-            (
-                Button,
-                Node {
-                    padding: UiRect::all(Val::Px(20.0)),
-                    ..default()
-                },
-                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new()
-                },
-                OctaveField,
-                children![(
-                    Text::new(""),
-                    OctaveField,
-                    TextFont {
-                        font_size: 20.0,
-                        ..default()
-                    },
-                    TextColor(Color::WHITE)
-                )]
-            )
+            input,
         ],
-    );
+    )
 }
 
-fn sea_threshold_field() -> impl Bundle {
-    return (
+// Typed input held to `rule`; left empty, it generates with its placeholder
+fn text_input(rule: FieldRule, placeholder: String, marker: impl Component) -> impl Bundle {
+    (
+        Button,
         Node {
-            width: Val::Percent(100.0),
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            flex_direction: FlexDirection::Row,
-            column_gap: Val::Px(16.0),
+            padding: UiRect::all(Val::Px(20.0)),
             ..default()
         },
+        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+        TextInput,
+        InputValue {
+            text: String::new(),
+        },
+        marker,
+        rule,
+        Placeholder(placeholder),
+        children![(
+            Text::new(""),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE)
+        )],
+    )
+}
+
+// Button that steps through its options each time it is clicked; `show_choices`
+// writes the chosen one's name
+fn choice_button(choice: Choice, marker: impl Bundle) -> impl Bundle {
+    (
+        Button,
+        Node {
+            padding: UiRect::all(Val::Px(20.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+        choice,
+        marker,
+        children![(
+            Text::new(""),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE)
+        )],
+    )
+}
+
+// Label, drag track and numeric readout for a bounded parameter
+fn slider_row(
+    id: &'static str,
+    label: &'static str,
+    slider: Slider,
+    value: f64,
+    marker: impl Component,
+) -> impl Bundle {
+    field_row(id, label, slider_track(slider, value, marker))
+}
+
+// Drag track with its handle and readout; `drag_sliders`, `update_slider_handles`
+// and `update_text_display` keep it in step with its value
+pub fn slider_track(slider: Slider, value: f64, marker: impl Component) -> impl Bundle {
+    let fraction = slider.fraction(value);
+    let text = format_slider_value(&slider, value);
+
+    (
+        Button,
+        Node {
+            width: Val::Px(240.0),
+            height: Val::Px(24.0),
+            margin: UiRect::right(Val::Px(64.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+        RelativeCursorPosition::default(),
+        InputValue { text: text.clone() },
+        slider,
+        marker,
         children![
             (
-                Text::new("Sea threshold:"),
-                TextFont {
-                    font_size: 20.0,
+                SliderHandle,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(fraction * 100.0),
+                    width: Val::Px(10.0),
+                    height: Val::Percent(100.0),
+                    margin: UiRect::left(Val::Px(-5.0)),
                     ..default()
                 },
-                TextColor(Color::WHITE),
+                BackgroundColor(Color::srgb(0.8, 0.8, 0.8)),
             ),
+            // Readout just right of the track
             (
-                Button,
+                Text::new(text),
                 Node {
-                    padding: UiRect::all(Val::Px(20.0)),
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(100.0),
+                    margin: UiRect::left(Val::Px(12.0)),
                     ..default()
                 },
-                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new(),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
                 },
-                SeaThresholdField,
-                children![(
-                    Text::new(""),
-                    SeaThresholdField,
-                    TextFont {
-                        font_size: 20.0,
-                        ..default()
-                    },
-                    TextColor(Color::WHITE)
-                )],
-            )
+                TextColor(Color::WHITE),
+            ),
         ],
-    );
+    )
 }
 
-fn temperature_scale_field() -> impl Bundle {
-    return (
+// Whole steps print as integers so fields like the octave count still parse
+fn format_slider_value(slider: &Slider, value: f64) -> String {
+    let decimals = (-slider.step.log10()).ceil().max(0.0) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+fn preset_selector() -> impl Bundle {
+    (
         Node {
             width: Val::Percent(100.0),
             justify_content: JustifyContent::Center,
@@ -299,88 +443,384 @@ fn temperature_scale_field() -> impl Bundle {
             column_gap: Val::Px(16.0),
             ..default()
         },
-        children![
-            (
-                Text::new("Temperature scale:"),
+        Children::spawn((
+            Spawn((
+                Text::new("Preset:"),
+                Localized::new("config.preset"),
                 TextFont {
                     font_size: 20.0,
                     ..default()
                 },
                 TextColor(Color::WHITE),
-            ),
-            (
-                Button,
-                Node {
-                    padding: UiRect::all(Val::Px(20.0)),
-                    ..default()
-                },
-                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new(),
-                },
-                TemperatureScaleField,
-                children![(
-                    Text::new(""),
-                    TemperatureScaleField,
-                    TextFont {
-                        font_size: 20.0,
+            )),
+            SpawnIter(PRESETS.iter().enumerate().map(|(index, preset)| {
+                (
+                    Button,
+                    Node {
+                        padding: UiRect::all(Val::Px(10.0)),
                         ..default()
                     },
-                    TextColor(Color::WHITE)
-                )],
-            )
-        ],
-    );
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    PresetButton(index),
+                    children![(
+                        Text::new(preset.name),
+                        Localized::new(format!("preset.{}", preset.name)),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE)
+                    )],
+                )
+            })),
+        )),
+    )
+}
+
+fn seed_field(locale: &Locale) -> impl Bundle {
+    field_row(
+        "config.seed",
+        "Seed:",
+        text_input(
+            FieldRule::Seed,
+            locale.text("config.random_seed").to_string(),
+            SeedField,
+        ),
+    )
+}
+
+fn terrain_scale_field() -> impl Bundle {
+    field_row(
+        "config.terrain_scale",
+        "Terrain scale:",
+        text_input(
+            FieldRule::Positive,
+            WorldData::default().terrain_scale.to_string(),
+            TerrainScaleField,
+        ),
+    )
+}
+
+fn continental_scale_field() -> impl Bundle {
+    field_row(
+        "config.continental_scale",
+        "Continental scale:",
+        text_input(
+            FieldRule::Positive,
+            WorldData::default().continental_scale.to_string(),
+            ContinentalScaleField,
+        ),
+    )
+}
+
+fn octave_field() -> impl Bundle {
+    slider_row(
+        "config.octaves",
+        "Number of octaves:",
+        Slider {
+            min: 1.0,
+            max: 8.0,
+            step: 1.0,
+        },
+        WorldData::default().num_of_octaves as f64,
+        OctaveField,
+    )
+}
+
+fn sea_threshold_field() -> impl Bundle {
+    slider_row(
+        "config.sea_threshold",
+        "Sea threshold:",
+        Slider {
+            min: 0.3,
+            max: 0.7,
+            step: 0.01,
+        },
+        WorldData::default().sea_threshold,
+        SeaThresholdField,
+    )
+}
+
+fn mountain_threshold_field() -> impl Bundle {
+    slider_row(
+        "config.mountain_threshold",
+        "Mountain threshold:",
+        Slider {
+            min: 0.4,
+            max: 0.95,
+            step: 0.01,
+        },
+        WorldData::default().mountain_threshold,
+        MountainThresholdField,
+    )
+}
+
+fn temperature_scale_field() -> impl Bundle {
+    field_row(
+        "config.temperature_scale",
+        "Temperature scale:",
+        text_input(
+            FieldRule::Positive,
+            WorldData::default().temperature_scale.to_string(),
+            TemperatureScaleField,
+        ),
+    )
 }
 
 fn moisture_scale_field() -> impl Bundle {
-    return (
+    field_row(
+        "config.moisture_scale",
+        "Moisture scale:",
+        text_input(
+            FieldRule::Positive,
+            WorldData::default().moisture_scale.to_string(),
+            MoistureScaleField,
+        ),
+    )
+}
+
+fn scaling_factor_field() -> impl Bundle {
+    field_row(
+        "config.scaling_factor",
+        "Scaling factor:",
+        text_input(
+            FieldRule::Positive,
+            WorldData::default().scaling_factor.to_string(),
+            ScalingFactorField,
+        ),
+    )
+}
+
+fn erosion_iterations_field() -> impl Bundle {
+    field_row(
+        "config.erosion_iterations",
+        "Erosion iterations:",
+        text_input(
+            FieldRule::Count {
+                min: 0,
+                max: MAX_EROSION_ITERATIONS,
+            },
+            WorldData::default().erosion_iterations.to_string(),
+            ErosionIterationsField,
+        ),
+    )
+}
+
+fn erosion_strength_field() -> impl Bundle {
+    slider_row(
+        "config.erosion_strength",
+        "Erosion strength:",
+        Slider {
+            min: 0.0,
+            max: 1.0,
+            step: 0.05,
+        },
+        WorldData::default().erosion_strength,
+        ErosionStrengthField,
+    )
+}
+
+fn warp_strength_field() -> impl Bundle {
+    slider_row(
+        "config.domain_warp",
+        "Domain warp:",
+        Slider {
+            min: 0.0,
+            max: 1.0,
+            step: 0.05,
+        },
+        WorldData::default().warp_strength,
+        WarpStrengthField,
+    )
+}
+
+fn generation_mode_field() -> impl Bundle {
+    field_row(
+        "config.generation_mode",
+        "Generation mode:",
+        choice_button(
+            generation_mode_choice(GenerationMode::Noise),
+            GenerationModeField,
+        ),
+    )
+}
+
+fn plate_count_field() -> impl Bundle {
+    field_row(
+        "config.plate_count",
+        "Plate count:",
+        text_input(
+            FieldRule::Count {
+                min: 2,
+                max: MAX_PLATES,
+            },
+            WorldData::default().plate_count.to_string(),
+            PlateCountField,
+        ),
+    )
+}
+
+fn world_size_field() -> impl Bundle {
+    field_row(
+        "config.world_size",
+        "World size (power of two):",
+        text_input(
+            FieldRule::PowerOfTwo {
+                min: MIN_WORLD_SIZE,
+                max: MAX_WORLD_SIZE,
+            },
+            DEFAULT_WORLD_SIZE.to_string(),
+            WorldSizeField,
+        ),
+    )
+}
+
+fn chunk_size_field() -> impl Bundle {
+    field_row(
+        "config.chunk_size",
+        "Chunk size (power of two):",
+        text_input(
+            FieldRule::PowerOfTwo {
+                min: MIN_CHUNK_SIZE,
+                max: MAX_CHUNK_SIZE,
+            },
+            DEFAULT_CHUNK_SIZE.to_string(),
+            ChunkSizeField,
+        ),
+    )
+}
+
+fn streaming_field() -> impl Bundle {
+    field_row(
+        "config.generation",
+        "Generation:",
+        choice_button(
+            Choice::toggle(
+                true,
+                ("config.pregenerated", "Pregenerated"),
+                ("config.streamed", "Streamed"),
+            ),
+            StreamingField,
+        ),
+    )
+}
+
+// Only offered in builds with the `gpu-worldgen` feature
+fn gpu_generation_field() -> impl Bundle {
+    (
         Node {
             width: Val::Percent(100.0),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             flex_direction: FlexDirection::Row,
             column_gap: Val::Px(16.0),
+            display: if cfg!(feature = "gpu-worldgen") {
+                Display::Flex
+            } else {
+                Display::None
+            },
             ..default()
         },
         children![
             (
-                Text::new("Moisture scale:"),
+                Text::new("Use GPU generation:"),
+                Localized::new("config.gpu_generation"),
                 TextFont {
                     font_size: 20.0,
                     ..default()
                 },
                 TextColor(Color::WHITE),
             ),
-            (
-                Button,
-                Node {
-                    padding: UiRect::all(Val::Px(20.0)),
+            choice_button(
+                Choice::toggle(false, ("config.off", "Off"), ("config.on", "On")),
+                GpuGenerationField,
+            ),
+        ],
+    )
+}
+
+fn migration_start_field() -> impl Bundle {
+    field_row(
+        "config.migration_start",
+        "Peoples begin as:",
+        choice_button(
+            Choice::toggle(
+                false,
+                ("config.kingdoms", "Kingdoms"),
+                ("config.migrating_bands", "Migrating bands"),
+            ),
+            MigrationStartField,
+        ),
+    )
+}
+
+fn fantasy_field() -> impl Bundle {
+    field_row(
+        "config.fantasy",
+        "Monster lairs:",
+        choice_button(
+            Choice::toggle(false, ("config.off", "Off"), ("config.on", "On")),
+            FantasyField,
+        ),
+    )
+}
+
+// Names the growth model the game runs by, from assets/growth_models.ron, in
+// the order the file lists them
+fn difficulty_field(growth: &Growth, assets: &Assets<GrowthModelsAsset>) -> impl Bundle {
+    let difficulties = growth.difficulties(assets);
+    let choice = Choice {
+        index: difficulties
+            .iter()
+            .position(|difficulty| *difficulty == growth.difficulty)
+            .unwrap_or(0),
+        options: difficulties
+            .iter()
+            .map(|difficulty| (format!("difficulty.{}", difficulty), difficulty.clone()))
+            .collect(),
+    };
+
+    field_row(
+        "config.difficulty",
+        "Difficulty:",
+        choice_button(choice, DifficultyField(difficulties)),
+    )
+}
+
+fn heightmap_field(locale: &Locale) -> impl Bundle {
+    field_row(
+        "config.heightmap",
+        "Heightmap image:",
+        // Wider than the other inputs, as paths run long
+        (
+            Button,
+            Node {
+                min_width: Val::Px(240.0),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            TextInput,
+            InputValue {
+                text: String::new(),
+            },
+            HeightmapField,
+            FieldRule::ExistingFile,
+            Placeholder(locale.text("config.no_heightmap").to_string()),
+            children![(
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
                     ..default()
                 },
-                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new(),
-                },
-                MoistureScaleField,
-                children![(
-                    Text::new(""),
-                    MoistureScaleField,
-                    TextFont {
-                        font_size: 20.0,
-                        ..default()
-                    },
-                    TextColor(Color::WHITE)
-                )],
-            )
-        ],
-    );
+                TextColor(Color::WHITE)
+            )],
+        ),
+    )
 }
 
-fn scaling_factor_field() -> impl Bundle {
-    return (
+fn noise_kind_field() -> impl Bundle {
+    (
         Node {
             width: Val::Percent(100.0),
             justify_content: JustifyContent::Center,
@@ -390,68 +830,306 @@ fn scaling_factor_field() -> impl Bundle {
             ..default()
         },
         children![
-            (
-                Text::new("Scaling factor:"),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-            ),
-            (
-                Button,
-                Node {
-                    padding: UiRect::all(Val::Px(20.0)),
-                    ..default()
-                },
-                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
-                TextInput,
-                InputValue {
-                    text: String::new(),
-                },
-                ScalingFactorField,
-                children![(
-                    Text::new(""),
-                    ScalingFactorField,
-                    TextFont {
-                        font_size: 20.0,
-                        ..default()
-                    },
-                    TextColor(Color::WHITE)
-                )],
-            )
+            noise_kind_label_text("config.terrain_noise", "Terrain noise:"),
+            noise_kind_button(TerrainNoiseField),
+            noise_kind_label_text("config.continental_noise", "Continental:"),
+            noise_kind_button(ContinentalNoiseField),
+            noise_kind_label_text("config.temperature_noise", "Temperature:"),
+            noise_kind_button(TemperatureNoiseField),
+            noise_kind_label_text("config.moisture_noise", "Moisture:"),
+            noise_kind_button(MoistureNoiseField),
         ],
-    );
+    )
+}
+
+fn noise_kind_label_text(id: &'static str, label: &'static str) -> impl Bundle {
+    (
+        Text::new(label),
+        Localized::new(id),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+    )
 }
 
+fn noise_kind_button(layer: impl Component) -> impl Bundle {
+    choice_button(
+        noise_kind_choice(NoiseKind::OpenSimplex),
+        (NoiseKindField, layer),
+    )
+}
+
+type FieldPressed = (With<TextInput>, Changed<Interaction>);
+
+// Click a field to focus it; Tab, Shift+Tab and Enter step through the fields in
+// the order they appear on screen
 pub fn focus_text_inputs(
     mut commands: Commands,
-    interactions: Query<(Entity, &Interaction), (With<TextInput>, Changed<Interaction>)>,
+    keys: Res<ButtonInput<KeyCode>>,
+    interactions: Query<(Entity, &Interaction), FieldPressed>,
+    inputs: Query<(Entity, &UiGlobalTransform), With<TextInput>>,
     focused: Query<Entity, With<Focused>>,
 ) {
-    for (entity, interaction) in &interactions {
+    let mut target = interactions
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Pressed)
+        .map(|(entity, _)| entity);
+
+    let step = if keys.just_pressed(KeyCode::Tab) {
+        match keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            true => -1,
+            false => 1,
+        }
+    } else if keys.just_pressed(KeyCode::Enter) {
+        1
+    } else {
+        0
+    };
+
+    if target.is_none() && step != 0 {
+        // Top to bottom, then left to right
+        let mut order: Vec<(Entity, Vec2)> = inputs
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation))
+            .collect();
+        order.sort_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)));
+
+        if !order.is_empty() {
+            let count = order.len() as i32;
+            let next = match focused
+                .iter()
+                .next()
+                .and_then(|current| order.iter().position(|(entity, _)| *entity == current))
+            {
+                Some(index) => (index as i32 + step).rem_euclid(count),
+                None if step > 0 => 0,
+                None => count - 1,
+            };
+            target = Some(order[next as usize].0);
+        }
+    }
+
+    if let Some(target) = target {
+        for entity in &focused {
+            commands.entity(entity).remove::<Focused>();
+        }
+
+        commands.entity(target).insert(Focused);
+    }
+}
+
+pub fn highlight_focused_input(mut query: Query<(&mut Outline, Has<Focused>), With<TextInput>>) {
+    for (mut outline, focused) in &mut query {
+        let color = if focused {
+            Color::srgb(0.9, 0.75, 0.3)
+        } else {
+            Color::NONE
+        };
+        if outline.color != color {
+            outline.color = color;
+        }
+    }
+}
+
+// Which setup field an input is, one flag per field a preset fills in
+type PresetFields = (
+    Has<SeedField>,
+    Has<TerrainScaleField>,
+    Has<ContinentalScaleField>,
+    Has<OctaveField>,
+    Has<SeaThresholdField>,
+    Has<MountainThresholdField>,
+    Has<TemperatureScaleField>,
+    Has<MoistureScaleField>,
+    Has<ScalingFactorField>,
+    Has<ErosionIterationsField>,
+    Has<ErosionStrengthField>,
+    Has<WarpStrengthField>,
+    Has<PlateCountField>,
+    Has<HeightmapField>,
+);
+
+// The same for the fields picked with a `Choice`
+type PresetChoices = (
+    Has<GenerationModeField>,
+    Has<TerrainNoiseField>,
+    Has<ContinentalNoiseField>,
+    Has<TemperatureNoiseField>,
+    Has<MoistureNoiseField>,
+);
+
+pub fn apply_world_preset(
+    preset_buttons: Query<(&Interaction, &PresetButton), Changed<Interaction>>,
+    mut inputs: Query<(&mut InputValue, PresetFields)>,
+    mut choices: Query<(&mut Choice, Has<GenerationModeField>)>,
+) {
+    for (interaction, preset_button) in &preset_buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let preset = &PRESETS[preset_button.0];
+
+        for (mut input, fields) in &mut inputs {
+            // Presets leave the seed and heightmap alone
+            let (
+                _,
+                terrain_scale,
+                continental_scale,
+                octaves,
+                sea_threshold,
+                mountain_threshold,
+                temperature_scale,
+                moisture_scale,
+                scaling_factor,
+                erosion_iterations,
+                erosion_strength,
+                warp_strength,
+                plate_count,
+                _,
+            ) = fields;
+
+            input.text = if terrain_scale {
+                preset.terrain_scale.to_string()
+            } else if continental_scale {
+                preset.continental_scale.to_string()
+            } else if octaves {
+                preset.num_of_octaves.to_string()
+            } else if sea_threshold {
+                preset.sea_threshold.to_string()
+            } else if mountain_threshold {
+                preset.mountain_threshold.to_string()
+            } else if temperature_scale {
+                preset.temperature_scale.to_string()
+            } else if moisture_scale {
+                preset.moisture_scale.to_string()
+            } else if scaling_factor {
+                preset.scaling_factor.to_string()
+            } else if erosion_iterations {
+                preset.erosion_iterations.to_string()
+            } else if erosion_strength {
+                preset.erosion_strength.to_string()
+            } else if warp_strength {
+                preset.warp_strength.to_string()
+            } else if plate_count {
+                preset.plate_count.to_string()
+            } else {
+                continue;
+            };
+        }
+
+        for (mut choice, generation_mode) in &mut choices {
+            if generation_mode {
+                choice.index = option_index(&GENERATION_MODES, preset.generation_mode);
+            }
+        }
+    }
+}
+
+fn option_index<T: PartialEq>(options: &[T], value: T) -> usize {
+    options.iter().position(|option| *option == value).unwrap_or(0)
+}
+
+fn generation_mode_choice(mode: GenerationMode) -> Choice {
+    Choice {
+        index: option_index(&GENERATION_MODES, mode),
+        options: GENERATION_MODES
+            .map(|option| {
+                let english = match option {
+                    GenerationMode::Noise => "Noise",
+                    GenerationMode::Tectonic => "Tectonic plates",
+                };
+                (format!("generation_mode.{:?}", option), english.to_string())
+            })
+            .to_vec(),
+    }
+}
+
+fn noise_kind_choice(kind: NoiseKind) -> Choice {
+    Choice {
+        index: option_index(&NOISE_KINDS, kind),
+        options: NOISE_KINDS
+            .map(|option| {
+                let english = match option {
+                    NoiseKind::OpenSimplex => "OpenSimplex",
+                    NoiseKind::Perlin => "Perlin",
+                    NoiseKind::RidgedMultifractal => "Ridged",
+                    NoiseKind::Worley => "Worley",
+                };
+                (format!("noise.{:?}", option), english.to_string())
+            })
+            .to_vec(),
+    }
+}
+
+pub fn cycle_choices(mut query: Query<(&Interaction, &mut Choice), Changed<Interaction>>) {
+    for (interaction, mut choice) in &mut query {
         if *interaction == Interaction::Pressed {
-            for e in &focused {
-                commands.entity(e).remove::<Focused>();
+            choice.index = (choice.index + 1) % choice.options.len();
+        }
+    }
+}
+
+// Writes each choice's current option on its button, in the player's language
+pub fn show_choices(
+    locale: Res<Locale>,
+    query: Query<(Ref<Choice>, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (choice, children) in &query {
+        if !locale.is_changed() && !choice.is_changed() {
+            continue;
+        }
+
+        let (id, english) = &choice.options[choice.index];
+        let shown = locale.get(id).unwrap_or(english);
+        for &child in children {
+            if let Ok(mut text) = text_query.get_mut(child) {
+                text.0 = shown.to_string();
             }
+        }
+    }
+}
 
-            commands.entity(entity).insert(Focused);
+pub fn read_difficulty(query: Query<(&Choice, &DifficultyField)>, mut growth: ResMut<Growth>) {
+    for (choice, field) in &query {
+        let difficulty = &field.0[choice.index];
+        if growth.difficulty != *difficulty {
+            growth.difficulty = difficulty.clone();
         }
     }
 }
 
+// Falls back to `default` unless the text is a power of two within `min..=max`
+pub fn parse_power_of_two(text: &str, min: i32, max: i32, default: i32) -> i32 {
+    match text.parse::<i32>() {
+        Ok(value) if value.count_ones() == 1 && (min..=max).contains(&value) => value,
+        _ => default,
+    }
+}
+
+type ButtonChanged = (Changed<Interaction>, With<Button>);
+
 pub fn game_config_buttons(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
-    mut button_query: Query<
-        (&Interaction, &GameConfigAction),
-        (Changed<Interaction>, With<Button>),
-    >,
+    mut button_query: Query<(&Interaction, &GameConfigAction), ButtonChanged>,
+    invalid_query: Query<(), With<InvalidInput>>,
 ) {
     for (interaction, action) in &mut button_query {
         if *interaction == Interaction::Pressed {
             match action {
+                GameConfigAction::Generate if !invalid_query.is_empty() => {}
                 GameConfigAction::Generate => {
-                    next_state.set(GameState::Playing);
+                    next_state.set(GameState::WorldGenerating);
+                }
+                GameConfigAction::CompareSeeds => {
+                    commands.insert_resource(SeedComparisonRequest);
+                }
+                GameConfigAction::SavePreset => {
+                    commands.insert_resource(SavePresetRequest);
                 }
                 GameConfigAction::Back => {
                     next_state.set(GameState::MainMenu);
@@ -496,22 +1174,627 @@ fn is_printable_char(chr: char) -> bool {
     !is_in_private_use_area && !chr.is_ascii_control()
 }
 
+// While a track is held, the value follows the cursor, snapped to the slider's step
+pub fn drag_sliders(
+    mut query: Query<(&Interaction, &RelativeCursorPosition, &Slider, &mut InputValue)>,
+) {
+    for (interaction, cursor, slider, mut input) in &mut query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(normalized) = cursor.normalized else {
+            continue;
+        };
+
+        let fraction = (normalized.x as f64 + 0.5).clamp(0.0, 1.0);
+        let raw = slider.min + fraction * (slider.max - slider.min);
+        let value = (slider.min + ((raw - slider.min) / slider.step).round() * slider.step)
+            .clamp(slider.min, slider.max);
+        let text = format_slider_value(slider, value);
+        if input.text != text {
+            input.text = text;
+        }
+    }
+}
+
+// Moves handles to match their value, whether it came from a drag or a preset
+pub fn update_slider_handles(
+    slider_query: Query<(&Slider, &InputValue, &Children), Changed<InputValue>>,
+    mut handle_query: Query<&mut Node, With<SliderHandle>>,
+) {
+    for (slider, input, children) in &slider_query {
+        let Ok(value) = input.text.parse::<f64>() else {
+            continue;
+        };
+        for &child in children {
+            if let Ok(mut node) = handle_query.get_mut(child) {
+                node.left = Val::Percent(slider.fraction(value) * 100.0);
+            }
+        }
+    }
+}
+
 pub fn update_text_display(
-    query: Query<(&InputValue, &Children), Changed<InputValue>>,
-    mut text_query: Query<&mut Text>,
+    query: Query<(&InputValue, Option<&Placeholder>, &Children), Changed<InputValue>>,
+    mut text_query: Query<(&mut Text, &mut TextColor), Without<FieldError>>,
 ) {
-    for (input, children) in &query {
+    for (input, placeholder, children) in &query {
+        let (shown, color) = match placeholder {
+            Some(placeholder) if input.text.is_empty() => {
+                (placeholder.0.as_str(), Color::srgb(0.5, 0.5, 0.5))
+            }
+            _ => (input.text.as_str(), Color::WHITE),
+        };
         for &child in children {
-            if let Ok(mut text) = text_query.get_mut(child) {
+            if let Ok((mut text, mut text_color)) = text_query.get_mut(child) {
                 text.clear();
-                text.push_str(&input.text);
+                text.push_str(shown);
+                text_color.0 = color;
             }
         }
     }
 }
 
+type RuledField = (
+    Entity,
+    &'static InputValue,
+    &'static FieldRule,
+    &'static mut BackgroundColor,
+    Option<&'static Children>,
+);
+
+// Checks each ruled field as it is edited: an invalid one turns red and gains a
+// message underneath, and the Generate button dims until every field passes
+pub fn validate_inputs(
+    mut commands: Commands,
+    mut field_query: Query<RuledField, Changed<InputValue>>,
+    mut error_query: Query<&mut Text, With<FieldError>>,
+    invalid_query: Query<(), With<InvalidInput>>,
+    mut generate_query: Query<(&GameConfigAction, &mut BackgroundColor), Without<FieldRule>>,
+    locale: Res<Locale>,
+) {
+    let mut invalid_count = invalid_query.iter().count();
+    let mut changed = false;
+
+    for (entity, input, rule, mut background, children) in &mut field_query {
+        changed = true;
+        let error = rule.check(&input.text, &locale);
+        let invalid = error.is_some();
+        let was_invalid = invalid_query.contains(entity);
+        let error_text = children
+            .into_iter()
+            .flatten()
+            .find(|&&child| error_query.contains(child));
+
+        match (error, error_text) {
+            (Some(message), Some(&child)) => {
+                if let Ok(mut text) = error_query.get_mut(child) {
+                    text.0 = message;
+                }
+            }
+            (Some(message), None) => {
+                commands.entity(entity).with_child((
+                    FieldError,
+                    Text::new(message),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(1.0, 0.35, 0.35)),
+                    Node {
+                        position_type: PositionType::Absolute,
+                        top: Val::Percent(100.0),
+                        left: Val::Px(0.0),
+                        ..default()
+                    },
+                ));
+            }
+            (None, Some(&child)) => commands.entity(child).despawn(),
+            (None, None) => {}
+        }
+
+        if invalid {
+            background.0 = Color::srgb(0.45, 0.1, 0.1);
+            if !was_invalid {
+                commands.entity(entity).insert(InvalidInput);
+                invalid_count += 1;
+            }
+        } else {
+            background.0 = Color::srgb(0.15, 0.15, 0.15);
+            if was_invalid {
+                commands.entity(entity).remove::<InvalidInput>();
+                invalid_count -= 1;
+            }
+        }
+    }
+
+    if !changed {
+        return;
+    }
+    for (action, mut background) in &mut generate_query {
+        if matches!(action, GameConfigAction::Generate) {
+            background.0 = if invalid_count > 0 {
+                Color::srgb(0.08, 0.08, 0.08)
+            } else {
+                Color::srgb(0.15, 0.15, 0.15)
+            };
+        }
+    }
+}
+
+// Runs after the inputs have been read into a WorldData; that template is taken
+// back out of the world and generated under fresh seeds off the main thread
+pub fn start_seed_comparison(
+    mut commands: Commands,
+    world_data_query: Query<(Entity, &WorldData)>,
+    mut status_query: Query<&mut Text, With<SeedComparisonStatus>>,
+    list_query: Query<Entity, With<SeedComparisonList>>,
+    locale: Res<Locale>,
+) {
+    commands.remove_resource::<SeedComparisonRequest>();
+
+    let template = match world_data_query.single() {
+        Ok((entity, data)) => {
+            commands.entity(entity).despawn();
+            data.clone()
+        }
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+
+    let mut rng = rand::rng();
+    let seeds: Vec<u32> = (0..COMPARISON_SEEDS).map(|_| rng.next_u32()).collect();
+    let task = AsyncComputeTaskPool::get().spawn(async move { compare_worlds(&template, &seeds) });
+    commands.insert_resource(SeedComparison(task));
+
+    for mut text in &mut status_query {
+        text.0 = locale.format("config.comparing", &[&COMPARISON_SEEDS]);
+    }
+    for entity in &list_query {
+        commands.entity(entity).despawn_related::<Children>();
+    }
+}
+
+pub fn poll_seed_comparison(
+    mut commands: Commands,
+    mut comparison: ResMut<SeedComparison>,
+    mut status_query: Query<&mut Text, With<SeedComparisonStatus>>,
+    list_query: Query<Entity, With<SeedComparisonList>>,
+    locale: Res<Locale>,
+) {
+    let Some(ranked) = check_ready(&mut comparison.0) else {
+        return;
+    };
+    commands.remove_resource::<SeedComparison>();
+
+    for mut text in &mut status_query {
+        text.0 = locale.text("config.compared").to_string();
+    }
+
+    for entity in &list_query {
+        commands.entity(entity).with_children(|list| {
+            for (rank, stats) in ranked.iter().enumerate() {
+                list.spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    SeedCandidate(stats.seed),
+                    children![(
+                        Text::new(locale.format(
+                            "config.candidate",
+                            &[
+                                &(rank + 1),
+                                &stats.seed,
+                                &format!("{:.0}", stats.land_share * 100.0),
+                                &stats.biome_count,
+                                &stats.continents,
+                            ],
+                        )),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE)
+                    )],
+                ));
+            }
+        });
+    }
+}
+
+pub fn pick_seed_candidate(
+    candidate_query: Query<(&Interaction, &SeedCandidate), Changed<Interaction>>,
+    mut seed_query: Query<&mut InputValue, With<SeedField>>,
+) {
+    for (interaction, candidate) in &candidate_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        for mut input in &mut seed_query {
+            input.text = candidate.0.to_string();
+        }
+    }
+}
+
+// Runs after the inputs have been read into a WorldData, which is taken back out
+// of the world and written under the typed name
+pub fn save_world_preset(
+    mut commands: Commands,
+    world_data_query: Query<(Entity, &WorldData)>,
+    name_query: Query<&InputValue, With<PresetNameField>>,
+    mut status_query: Query<&mut Text, With<SavedPresetStatus>>,
+    list_query: Query<Entity, With<SavedPresetList>>,
+    locale: Res<Locale>,
+) {
+    commands.remove_resource::<SavePresetRequest>();
+
+    let data = match world_data_query.single() {
+        Ok((entity, data)) => {
+            commands.entity(entity).despawn();
+            data.clone()
+        }
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+
+    let name = name_query
+        .iter()
+        .map(|input| sanitize_preset_name(&input.text))
+        .next()
+        .unwrap_or_default();
+    let status = if name.is_empty() {
+        locale.text("config.preset_needs_name").to_string()
+    } else {
+        save_preset(&name, &data);
+        for entity in &list_query {
+            commands
+                .entity(entity)
+                .despawn_related::<Children>()
+                .with_children(|list| {
+                    for saved in saved_preset_names() {
+                        list.spawn(saved_preset_row(saved));
+                    }
+                });
+        }
+        locale.format("config.preset_saved", &[&name])
+    };
+
+    for mut text in &mut status_query {
+        text.0 = status.clone();
+    }
+}
+
+pub fn load_world_preset(
+    preset_query: Query<(&Interaction, &SavedPresetButton), Changed<Interaction>>,
+    mut status_query: Query<&mut Text, With<SavedPresetStatus>>,
+    mut inputs: Query<(&mut InputValue, PresetFields)>,
+    mut choices: Query<(&mut Choice, PresetChoices)>,
+    locale: Res<Locale>,
+) {
+    for (interaction, button) in &preset_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let Some(data) = load_saved_preset(&button.0) else {
+            for mut text in &mut status_query {
+                text.0 = locale.format("config.preset_unreadable", &[&button.0]);
+            }
+            continue;
+        };
+
+        for (mut input, fields) in &mut inputs {
+            let (
+                seed,
+                terrain_scale,
+                continental_scale,
+                octaves,
+                sea_threshold,
+                mountain_threshold,
+                temperature_scale,
+                moisture_scale,
+                scaling_factor,
+                erosion_iterations,
+                erosion_strength,
+                warp_strength,
+                plate_count,
+                heightmap,
+            ) = fields;
+
+            input.text = if seed {
+                data.seed_text.clone().unwrap_or_else(|| data.seed.to_string())
+            } else if terrain_scale {
+                data.terrain_scale.to_string()
+            } else if continental_scale {
+                data.continental_scale.to_string()
+            } else if octaves {
+                data.num_of_octaves.to_string()
+            } else if sea_threshold {
+                data.sea_threshold.to_string()
+            } else if mountain_threshold {
+                data.mountain_threshold.to_string()
+            } else if temperature_scale {
+                data.temperature_scale.to_string()
+            } else if moisture_scale {
+                data.moisture_scale.to_string()
+            } else if scaling_factor {
+                data.scaling_factor.to_string()
+            } else if erosion_iterations {
+                data.erosion_iterations.to_string()
+            } else if erosion_strength {
+                data.erosion_strength.to_string()
+            } else if warp_strength {
+                data.warp_strength.to_string()
+            } else if plate_count {
+                data.plate_count.to_string()
+            } else if heightmap {
+                data.heightmap.clone().unwrap_or_default()
+            } else {
+                continue;
+            };
+        }
+
+        for (mut choice, choices) in &mut choices {
+            let (
+                generation_mode,
+                terrain_noise,
+                continental_noise,
+                temperature_noise,
+                moisture_noise,
+            ) = choices;
+            choice.index = if generation_mode {
+                option_index(&GENERATION_MODES, data.generation_mode)
+            } else if terrain_noise {
+                option_index(&NOISE_KINDS, data.terrain_noise)
+            } else if continental_noise {
+                option_index(&NOISE_KINDS, data.continental_noise)
+            } else if temperature_noise {
+                option_index(&NOISE_KINDS, data.temperature_noise)
+            } else if moisture_noise {
+                option_index(&NOISE_KINDS, data.moisture_noise)
+            } else {
+                continue;
+            };
+        }
+
+        for mut text in &mut status_query {
+            text.0 = locale.format("config.preset_loaded", &[&button.0]);
+        }
+    }
+}
+
 pub fn cleanup_game_config(mut commands: Commands, query: Query<Entity, With<GameConfigUI>>) {
     for entity in &query {
         commands.entity(entity).despawn();
     }
+
+    // Dropping an unfinished survey cancels it
+    commands.remove_resource::<SeedComparison>();
+    commands.remove_resource::<SeedComparisonRequest>();
+    commands.remove_resource::<SavePresetRequest>();
+}
+
+type SettingToggles = (
+    &'static Choice,
+    Has<StreamingField>,
+    Has<GpuGenerationField>,
+    Has<MigrationStartField>,
+    Has<FantasyField>,
+);
+
+pub fn read_world_settings(
+    mut commands: Commands,
+    world_size_query: Query<&InputValue, With<WorldSizeField>>,
+    chunk_size_query: Query<&InputValue, With<ChunkSizeField>>,
+    toggles: Query<SettingToggles>,
+) {
+    let mut world_size = DEFAULT_WORLD_SIZE;
+    let mut chunk_size = DEFAULT_CHUNK_SIZE;
+    let mut streaming = true;
+    let mut gpu_generation = false;
+    let mut migration_start = false;
+    let mut fantasy = false;
+
+    for (choice, is_streaming, is_gpu_generation, is_migration_start, is_fantasy) in &toggles {
+        if is_streaming {
+            streaming = choice.is_on();
+        } else if is_gpu_generation {
+            gpu_generation = choice.is_on();
+        } else if is_migration_start {
+            migration_start = choice.is_on();
+        } else if is_fantasy {
+            fantasy = choice.is_on();
+        }
+    }
+
+    for input in &world_size_query {
+        world_size =
+            parse_power_of_two(&input.text, MIN_WORLD_SIZE, MAX_WORLD_SIZE, DEFAULT_WORLD_SIZE);
+    }
+
+    for input in &chunk_size_query {
+        chunk_size =
+            parse_power_of_two(&input.text, MIN_CHUNK_SIZE, MAX_CHUNK_SIZE, DEFAULT_CHUNK_SIZE);
+    }
+
+    // Both are powers of two, so this also keeps the world a whole number of chunks
+    chunk_size = chunk_size.min(world_size);
+
+    debug!(
+        "World size {}, chunk size {}, streaming {}, GPU generation {}",
+        world_size, chunk_size, streaming, gpu_generation
+    );
+
+    commands.insert_resource(WorldSettings {
+        world_size,
+        chunk_size,
+        streaming,
+        gpu_generation,
+        migration_start,
+        fantasy,
+    });
+}
+
+pub fn read_worldgen_inputs(
+    mut commands: Commands,
+    biomes: Res<Biomes>,
+    inputs: Query<(&InputValue, PresetFields)>,
+    choices: Query<(&Choice, PresetChoices)>,
+) {
+    // Unreadable text falls back to the same default the field's placeholder shows
+    let defaults = WorldData::default();
+    let mut rng = rand::rng();
+    let mut seed = rng.next_u32();
+    let mut seed_text = None;
+    let mut terrain_scale = defaults.terrain_scale;
+    let mut continental_scale = defaults.continental_scale;
+    let mut num_of_octaves = defaults.num_of_octaves;
+    let mut sea_threshold = defaults.sea_threshold;
+    let mut mountain_threshold = defaults.mountain_threshold;
+    let mut temperature_scale = defaults.temperature_scale;
+    let mut moisture_scale = defaults.moisture_scale;
+    let mut scaling_factor = defaults.scaling_factor;
+    let mut erosion_iterations = defaults.erosion_iterations;
+    let mut erosion_strength = defaults.erosion_strength;
+    let mut warp_strength = defaults.warp_strength;
+    let mut generation_mode = defaults.generation_mode;
+    let mut plate_count = defaults.plate_count;
+    let mut terrain_noise = defaults.terrain_noise;
+    let mut continental_noise = defaults.continental_noise;
+    let mut temperature_noise = defaults.temperature_noise;
+    let mut moisture_noise = defaults.moisture_noise;
+    let mut heightmap = defaults.heightmap;
+
+    for (input, fields) in &inputs {
+        let (
+            is_seed,
+            is_terrain_scale,
+            is_continental_scale,
+            is_octaves,
+            is_sea_threshold,
+            is_mountain_threshold,
+            is_temperature_scale,
+            is_moisture_scale,
+            is_scaling_factor,
+            is_erosion_iterations,
+            is_erosion_strength,
+            is_warp_strength,
+            is_plate_count,
+            is_heightmap,
+        ) = fields;
+        let text = &input.text;
+
+        if is_seed {
+            if !text.is_empty() {
+                (seed, seed_text) = parse_seed(text);
+            }
+        } else if is_heightmap {
+            heightmap = Some(text.clone()).filter(|path| !path.is_empty());
+        } else if is_terrain_scale {
+            terrain_scale = text.parse::<f64>().unwrap_or(terrain_scale);
+        } else if is_continental_scale {
+            continental_scale = text.parse::<f64>().unwrap_or(continental_scale);
+        } else if is_octaves {
+            num_of_octaves = text.parse::<u32>().unwrap_or(num_of_octaves);
+        } else if is_sea_threshold {
+            sea_threshold = text.parse::<f64>().unwrap_or(sea_threshold);
+        } else if is_mountain_threshold {
+            mountain_threshold = text.parse::<f64>().unwrap_or(mountain_threshold);
+        } else if is_temperature_scale {
+            temperature_scale = text.parse::<f64>().unwrap_or(temperature_scale);
+        } else if is_moisture_scale {
+            moisture_scale = text.parse::<f64>().unwrap_or(moisture_scale);
+        } else if is_scaling_factor {
+            scaling_factor = text.parse::<f64>().unwrap_or(scaling_factor);
+        } else if is_erosion_iterations {
+            erosion_iterations = text.parse::<u32>().unwrap_or(erosion_iterations);
+        } else if is_erosion_strength {
+            erosion_strength = text.parse::<f64>().unwrap_or(erosion_strength).clamp(0.0, 1.0);
+        } else if is_warp_strength {
+            warp_strength = text.parse::<f64>().unwrap_or(warp_strength).clamp(0.0, 1.0);
+        } else if is_plate_count {
+            plate_count = text.parse::<u32>().unwrap_or(plate_count);
+        }
+    }
+
+    for (choice, choices) in &choices {
+        let (
+            is_generation_mode,
+            is_terrain_noise,
+            is_continental_noise,
+            is_temperature_noise,
+            is_moisture_noise,
+        ) = choices;
+        if is_generation_mode {
+            generation_mode = GENERATION_MODES[choice.index];
+        } else if is_terrain_noise {
+            terrain_noise = NOISE_KINDS[choice.index];
+        } else if is_continental_noise {
+            continental_noise = NOISE_KINDS[choice.index];
+        } else if is_temperature_noise {
+            temperature_noise = NOISE_KINDS[choice.index];
+        } else if is_moisture_noise {
+            moisture_noise = NOISE_KINDS[choice.index];
+        }
+    }
+
+    debug!(
+        "World data: seed {} ({:?}), terrain scale {}, continental scale {}, \
+         temperature scale {}, moisture scale {}, {} octaves, sea {}, mountains {}, \
+         scaling {}, erosion {} x {}, warp {}, {:?} mode, {} plates, noise {:?}/{:?}/{:?}/{:?}",
+        seed,
+        seed_text,
+        terrain_scale,
+        continental_scale,
+        temperature_scale,
+        moisture_scale,
+        num_of_octaves,
+        sea_threshold,
+        mountain_threshold,
+        scaling_factor,
+        erosion_iterations,
+        erosion_strength,
+        warp_strength,
+        generation_mode,
+        plate_count,
+        terrain_noise,
+        continental_noise,
+        temperature_noise,
+        moisture_noise
+    );
+
+    commands.spawn(WorldData {
+        seed,
+        seed_text,
+        terrain_scale,
+        continental_scale,
+        num_of_octaves,
+        sea_threshold,
+        mountain_threshold,
+        temperature_scale,
+        moisture_scale,
+        scaling_factor,
+        erosion_iterations,
+        erosion_strength,
+        warp_strength,
+        generation_mode,
+        plate_count,
+        wind_strength: defaults.wind_strength,
+        trade_wind_limit: defaults.trade_wind_limit,
+        westerly_limit: defaults.westerly_limit,
+        terrain_noise,
+        continental_noise,
+        temperature_noise,
+        moisture_noise,
+        heightmap,
+        biomes: biomes.table.clone(),
+    });
 }
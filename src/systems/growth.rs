@@ -0,0 +1,225 @@
+// How quickly settlements grow, and how hard famine, plague and hunger hit them,
+// as named models in assets/growth_models.ron. The difficulty picked on the
+// world setup screen names the model a game runs by.
+
+use std::io;
+
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::components::settlements::Specialization;
+
+// Under the asset folder, which the asset server watches for edits
+const GROWTH_MODELS_PATH: &str = "growth_models.ron";
+// Played when a game names no model the file has, as saves from before
+// difficulties do
+pub const DEFAULT_DIFFICULTY: &str = "standard";
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct GrowthModel {
+    // Yearly growth rate while food is plentiful; it tapers off approaching capacity
+    pub growth_rate: f32,
+    // Yearly odds of each event for a single settlement, and the share of its
+    // people it gains or loses
+    pub good_harvest_chance: f64,
+    pub good_harvest_gain: f32,
+    pub famine_chance: f64,
+    pub famine_loss: f32,
+    pub plague_chance: f64,
+    pub plague_loss: f32,
+    // Yearly share of the hungry who die or leave when the stores run dry
+    pub starvation_loss: f32,
+}
+
+// The standard model, which games run by until the file has loaded
+impl Default for GrowthModel {
+    fn default() -> Self {
+        GrowthModel {
+            growth_rate: 0.08,
+            good_harvest_chance: 0.15,
+            good_harvest_gain: 0.1,
+            famine_chance: 0.05,
+            famine_loss: 0.2,
+            plague_chance: 0.02,
+            plague_loss: 0.3,
+            starvation_loss: 0.5,
+        }
+    }
+}
+
+// The models as the asset server loads them, in the order the difficulty
+// button steps through them
+#[derive(Asset, TypePath)]
+pub struct GrowthModelsAsset(pub Vec<(String, GrowthModel)>);
+
+#[derive(Default, TypePath)]
+pub struct GrowthModelsLoader;
+
+impl AssetLoader for GrowthModelsLoader {
+    type Asset = GrowthModelsAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<GrowthModelsAsset, io::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        let models = parse_growth_models(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(GrowthModelsAsset(models))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+// The model settlements grow by. It starts as the built-in standard model and
+// takes the file's model for the difficulty once that has loaded.
+#[derive(Resource)]
+pub struct Growth {
+    pub difficulty: String,
+    pub model: GrowthModel,
+    // The model as each specialization lives it, indexed like `Specialization::ALL`
+    specialized: [GrowthModel; Specialization::ALL.len()],
+    pub handle: Handle<GrowthModelsAsset>,
+}
+
+impl Growth {
+    pub fn model_for(&self, specialization: Specialization) -> &GrowthModel {
+        &self.specialized[specialization as usize]
+    }
+
+    // Every difficulty the file offers, or just the default before it has loaded
+    pub fn difficulties(&self, assets: &Assets<GrowthModelsAsset>) -> Vec<String> {
+        assets
+            .get(&self.handle)
+            .map(|asset| asset.0.iter().map(|(name, _)| name.clone()).collect())
+            .unwrap_or_else(|| vec![DEFAULT_DIFFICULTY.to_string()])
+    }
+}
+
+pub fn load_growth_models(asset_server: &AssetServer) -> Growth {
+    Growth {
+        difficulty: DEFAULT_DIFFICULTY.to_string(),
+        model: GrowthModel::default(),
+        specialized: specialize(&GrowthModel::default()),
+        handle: asset_server.load(GROWTH_MODELS_PATH),
+    }
+}
+
+// Takes up the difficulty's model whenever the file is loaded or saved, or
+// another difficulty is picked. A difficulty the file lacks keeps the model in
+// place, as does a file that doesn't parse.
+pub fn apply_growth_model(
+    mut events: MessageReader<AssetEvent<GrowthModelsAsset>>,
+    assets: Res<Assets<GrowthModelsAsset>>,
+    mut growth: ResMut<Growth>,
+) {
+    let reloaded = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == growth.handle.id()
+        }
+        _ => false,
+    });
+    if !reloaded && !growth.is_changed() {
+        return;
+    }
+    let Some(asset) = assets.get(&growth.handle) else {
+        return;
+    };
+
+    match asset.0.iter().find(|(name, _)| *name == growth.difficulty) {
+        Some((_, model)) if *model != growth.model => {
+            growth.model = *model;
+            growth.specialized = specialize(model);
+        }
+        Some(_) => {}
+        None => warn!(
+            "No growth model named {} in {}",
+            growth.difficulty, GROWTH_MODELS_PATH
+        ),
+    }
+}
+
+// Fishing villages ride out bad harvests and rarely see bumper ones, as the sea
+// feeds them either way. Mining towns grow slowly from thin soil and crowd
+// into camps where sickness spreads.
+fn specialize(model: &GrowthModel) -> [GrowthModel; Specialization::ALL.len()] {
+    Specialization::ALL.map(|specialization| match specialization {
+        Specialization::Farming => *model,
+        Specialization::Fishing => GrowthModel {
+            good_harvest_chance: model.good_harvest_chance * 0.5,
+            famine_chance: model.famine_chance * 0.5,
+            ..*model
+        },
+        Specialization::Mining => GrowthModel {
+            growth_rate: model.growth_rate * 0.75,
+            plague_chance: (model.plague_chance * 1.5).min(1.0),
+            ..*model
+        },
+    })
+}
+
+fn parse_growth_models(text: &str) -> Result<Vec<(String, GrowthModel)>, String> {
+    let models: Vec<(String, GrowthModel)> = ron::from_str(text).map_err(|err| err.to_string())?;
+    if models.is_empty() {
+        return Err("the file has no growth models".to_string());
+    }
+
+    // Odds and shares outside 0..=1 would break the dice or leave people owed
+    for (name, model) in &models {
+        let chances = [
+            model.good_harvest_chance,
+            model.famine_chance,
+            model.plague_chance,
+        ];
+        let losses = [model.famine_loss, model.plague_loss, model.starvation_loss];
+        if chances.iter().any(|chance| !(0.0..=1.0).contains(chance))
+            || losses.iter().any(|loss| !(0.0..=1.0).contains(loss))
+        {
+            return Err(format!("{}: chances and losses must be from 0 to 1", name));
+        }
+    }
+
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_standard_model_is_the_built_in_one() {
+        let models = parse_growth_models(include_str!("../../assets/growth_models.ron")).unwrap();
+        let standard = models
+            .iter()
+            .find(|(name, _)| name == DEFAULT_DIFFICULTY)
+            .map(|(_, model)| *model);
+        assert_eq!(standard, Some(GrowthModel::default()));
+    }
+
+    #[test]
+    fn chances_above_one_are_refused() {
+        let text = "[(\"cursed\", (growth_rate: 0.1, good_harvest_chance: 0.1, \
+                    good_harvest_gain: 0.1, famine_chance: 2.0, famine_loss: 0.1, \
+                    plague_chance: 0.1, plague_loss: 0.1, starvation_loss: 0.1))]";
+        assert!(parse_growth_models(text).is_err());
+    }
+
+    #[test]
+    fn fishing_villages_weather_famine_better_than_farms() {
+        let growth = specialize(&GrowthModel::default());
+        let farming = growth[Specialization::Farming as usize];
+        let fishing = growth[Specialization::Fishing as usize];
+        assert_eq!(farming, GrowthModel::default());
+        assert!(fishing.famine_chance < farming.famine_chance);
+        assert_eq!(fishing.growth_rate, farming.growth_rate);
+    }
+}
@@ -0,0 +1,291 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+    render::render_resource::PrimitiveTopology::TriangleList,
+};
+use bevy_mesh::Indices;
+use kingdom_worldgen::{HALO, generate_chunk_data, sampler::WorldSampler};
+
+use crate::components::{
+    heightmap_view::HeightmapViewEntity,
+    world::{Square, WorldMap},
+    world_gen::WorldData,
+};
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::palette::{MapPalette, Palette};
+use crate::systems::world::{Hillshade, LoadedChunks, MapOverlay, WorldSettings, tile_color};
+
+// Tiles per side of the terrain patch around the 2D camera, and the tile step
+// between vertices
+const PATCH_TILES: i32 = 256;
+const PATCH_STEP: i32 = 2;
+// World units of height per elevation unit; tiles are one unit wide
+const HEIGHT_SCALE: f32 = 0.5;
+const ORBIT_SPEED: f32 = 0.005;
+const ZOOM_SPEED: f32 = 20.0;
+const MIN_PITCH: f32 = 0.1;
+const MAX_PITCH: f32 = 1.5;
+const MIN_DISTANCE: f32 = 40.0;
+const MAX_DISTANCE: f32 = 600.0;
+
+// 3D heightmap of the area under the 2D camera (H), for judging relief. While it
+// is open the 2D camera is switched off and the 3D camera also carries the UI.
+#[derive(Resource)]
+pub struct HeightmapView {
+    // Radians clockwise from looking north
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+pub fn toggle_heightmap_view(
+    mut commands: Commands,
+    (input, input_map): (Res<ButtonInput<KeyCode>>, Res<InputMap>),
+    (view, view_entities): (
+        Option<Res<HeightmapView>>,
+        Query<Entity, With<HeightmapViewEntity>>,
+    ),
+    (settings, loaded, world_map_query, world_data_query): (
+        Res<WorldSettings>,
+        Res<LoadedChunks>,
+        Query<&WorldMap>,
+        Query<&WorldData>,
+    ),
+    (hillshade, map_palette): (Res<Hillshade>, Res<MapPalette>),
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<StandardMaterial>>),
+    camera_2d: Single<(&mut Camera, &Transform), With<Camera2d>>,
+) {
+    if !input_map.just_pressed(&input, InputAction::ToggleHeightmap) {
+        return;
+    }
+    let (mut camera, camera_transform) = camera_2d.into_inner();
+
+    if view.is_some() {
+        for entity in &view_entities {
+            commands.entity(entity).despawn();
+        }
+        commands.remove_resource::<HeightmapView>();
+        camera.is_active = true;
+        return;
+    }
+
+    let world_data = match world_data_query.single() {
+        Ok(data) => data,
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+
+    let center = camera_transform.translation.truncate().floor().as_ivec2();
+    let origin = center - IVec2::splat(PATCH_TILES / 2);
+    let world_map = world_map_query.single().ok();
+    let squares = patch_squares(origin, &settings, &loaded, world_map, world_data);
+    let mesh = heightmap_mesh(&squares, world_data, &map_palette.palette);
+
+    let view = HeightmapView {
+        yaw: 0.0,
+        pitch: 0.8,
+        distance: 300.0,
+    };
+
+    // Light from the same direction as the 2D relief shading
+    let azimuth = hillshade.azimuth.to_radians();
+    let altitude = hillshade.altitude.to_radians();
+    let light_direction = Vec3::new(
+        azimuth.sin() * altitude.cos(),
+        altitude.sin(),
+        -azimuth.cos() * altitude.cos(),
+    );
+
+    commands.spawn((
+        HeightmapViewEntity,
+        Camera3d::default(),
+        Camera {
+            order: 1,
+            ..default()
+        },
+        orbit_transform(&view),
+    ));
+    commands.spawn((
+        HeightmapViewEntity,
+        DirectionalLight {
+            illuminance: 8000.0,
+            ..default()
+        },
+        Transform::from_translation(light_direction).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        HeightmapViewEntity,
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            perceptual_roughness: 0.9,
+            ..default()
+        })),
+    ));
+
+    commands.insert_resource(view);
+    camera.is_active = false;
+}
+
+// Left drag orbits around the patch centre, the wheel zooms
+pub fn orbit_heightmap_camera(
+    mut view: ResMut<HeightmapView>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut camera_query: Query<&mut Transform, (With<Camera3d>, With<HeightmapViewEntity>)>,
+) {
+    if mouse.pressed(MouseButton::Left) {
+        view.yaw += motion.delta.x * ORBIT_SPEED;
+        view.pitch = (view.pitch + motion.delta.y * ORBIT_SPEED).clamp(MIN_PITCH, MAX_PITCH);
+    }
+    view.distance =
+        (view.distance - scroll.delta.y * ZOOM_SPEED).clamp(MIN_DISTANCE, MAX_DISTANCE);
+
+    for mut transform in &mut camera_query {
+        *transform = orbit_transform(&view);
+    }
+}
+
+pub fn cleanup_heightmap_view(
+    mut commands: Commands,
+    mut camera_2d: Single<&mut Camera, With<Camera2d>>,
+    view_entities: Query<Entity, With<HeightmapViewEntity>>,
+) {
+    for entity in &view_entities {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<HeightmapView>();
+    camera_2d.is_active = true;
+}
+
+fn orbit_transform(view: &HeightmapView) -> Transform {
+    let offset = Vec3::new(
+        -view.yaw.sin() * view.pitch.cos(),
+        view.pitch.sin(),
+        view.yaw.cos() * view.pitch.cos(),
+    ) * view.distance;
+
+    Transform::from_translation(offset).looking_at(Vec3::ZERO, Vec3::Y)
+}
+
+// One square per vertex, row by row from the patch's south-west corner. Reads the
+// pregenerated map when there is one, otherwise the streamed chunk cache, and
+// generates any chunk the cache doesn't hold.
+fn patch_squares(
+    origin: IVec2,
+    settings: &WorldSettings,
+    loaded: &LoadedChunks,
+    world_map: Option<&WorldMap>,
+    world_data: &WorldData,
+) -> Vec<Square> {
+    let side = PATCH_TILES / PATCH_STEP + 1;
+    let chunk_size = settings.chunk_size;
+    let size = settings.world_size;
+    let mut generated: Vec<((i32, i32), Vec<Square>)> = Vec::new();
+    let mut fallback_sampler = None;
+    let mut squares = Vec::with_capacity((side * side) as usize);
+
+    for row in 0..side {
+        for column in 0..side {
+            let x = origin.x + column * PATCH_STEP;
+            let y = origin.y + row * PATCH_STEP;
+
+            let square = match world_map {
+                Some(world_map) => {
+                    world_map.square((y.rem_euclid(size) * size + x.rem_euclid(size)) as usize)
+                }
+                None => {
+                    let chunk = (x.div_euclid(chunk_size), y.div_euclid(chunk_size));
+                    if !loaded.chunk_data.contains_key(&chunk)
+                        && !generated.iter().any(|(key, _)| *key == chunk)
+                    {
+                        let sampler = match loaded.sampler.as_deref() {
+                            Some(sampler) => sampler,
+                            None => &*fallback_sampler
+                                .get_or_insert_with(|| WorldSampler::new(world_data, size)),
+                        };
+                        let data =
+                            generate_chunk_data(chunk.0, chunk.1, settings.chunk_size, sampler);
+                        generated.push((chunk, data));
+                    }
+                    let data = match loaded.chunk_data.get(&chunk) {
+                        Some(data) => data,
+                        None => &generated.iter().find(|(key, _)| *key == chunk).unwrap().1,
+                    };
+
+                    // Chunk data carries a HALO ring around the chunk's own tiles
+                    let halo_side = chunk_size + 2 * HALO;
+                    let local_x = x.rem_euclid(chunk_size) + HALO;
+                    let local_y = y.rem_euclid(chunk_size) + HALO;
+                    data[(local_y * halo_side + local_x) as usize].clone()
+                }
+            };
+            squares.push(square);
+        }
+    }
+
+    squares
+}
+
+// Grid mesh centred on the origin with Y up and north toward -Z. The sea is
+// flattened to sea level so coasts read as shorelines rather than slopes.
+fn heightmap_mesh(squares: &[Square], world_data: &WorldData, palette: &Palette) -> Mesh {
+    let side = PATCH_TILES / PATCH_STEP + 1;
+    let sea_level = world_data.sea_level() as f32;
+    let half = PATCH_TILES as f32 / 2.0;
+    let height = |column: i32, row: i32| {
+        let column = column.clamp(0, side - 1);
+        let row = row.clamp(0, side - 1);
+        squares[(row * side + column) as usize].elevation.max(sea_level) * HEIGHT_SCALE
+    };
+
+    let mut positions = Vec::with_capacity(squares.len());
+    let mut normals = Vec::with_capacity(squares.len());
+    let mut colors = Vec::with_capacity(squares.len());
+    for row in 0..side {
+        for column in 0..side {
+            let square = &squares[(row * side + column) as usize];
+            positions.push([
+                (column * PATCH_STEP) as f32 - half,
+                height(column, row),
+                half - (row * PATCH_STEP) as f32,
+            ]);
+
+            // Central differences across the neighbouring vertices
+            let span = (2 * PATCH_STEP) as f32;
+            let dx = (height(column + 1, row) - height(column - 1, row)) / span;
+            let dz = (height(column, row - 1) - height(column, row + 1)) / span;
+            normals.push(Vec3::new(-dx, 1.0, -dz).normalize().to_array());
+
+            colors.push(tile_color(
+                square,
+                square.biome,
+                MapOverlay::Biome,
+                &world_data.biomes,
+                palette,
+                sea_level,
+            ));
+        }
+    }
+
+    let mut indices = Vec::with_capacity(((side - 1) * (side - 1) * 6) as usize);
+    for row in 0..side - 1 {
+        for column in 0..side - 1 {
+            let index = (row * side + column) as u32;
+            let above = index + side as u32;
+            indices.extend_from_slice(&[index, index + 1, above + 1, above + 1, above, index]);
+        }
+    }
+
+    let mut mesh = Mesh::new(TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_indices(Indices::U32(indices));
+
+    mesh
+}
@@ -0,0 +1,513 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{
+    keybindings::{KeybindingsAction, KeybindingsUI, RebindButton, RebindLabel},
+    localization::Localized,
+    pause_menu::PauseMenuUI,
+};
+use crate::systems::{
+    localization::Locale,
+    pause_menu::{Pause, open_pause_menu},
+    startup::{read_user_config, write_user_config},
+};
+
+const KEYBINDINGS_FILE: &str = "keybindings.ron";
+
+// Everything the player can trigger from the keyboard. Escape is left out on
+// purpose: it always opens the pause menu, so the controls page stays reachable
+// whatever gets rebound.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InputAction {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    ZoomIn,
+    ZoomOut,
+    OverlayBiome,
+    OverlayResources,
+    OverlayTemperature,
+    OverlayMoisture,
+    OverlayElevation,
+    OverlayPolitical,
+    OverlayTrade,
+    OverlayWildlife,
+    RotateLightLeft,
+    RotateLightRight,
+    ToggleGrid,
+    ToggleTileRender,
+    ToggleColorGrading,
+    ToggleAmbient,
+    ToggleHeightmap,
+    ToggleWorldView,
+    ToggleDiplomacy,
+    ToggleHistory,
+    ToggleDiagnostics,
+    WorldViewProjection,
+    WorldViewMeridianWest,
+    WorldViewMeridianEast,
+    ToggleTimeline,
+    TimelineEarlier,
+    TimelineLater,
+    ExportMap,
+    ExportHeightmap,
+    CaptureMap,
+    MeasureDistance,
+    ToggleWorldStats,
+    RegenerateWorld,
+    Pause,
+    SpeedUp,
+    SlowDown,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 40] = [
+        InputAction::PanUp,
+        InputAction::PanDown,
+        InputAction::PanLeft,
+        InputAction::PanRight,
+        InputAction::ZoomIn,
+        InputAction::ZoomOut,
+        InputAction::OverlayBiome,
+        InputAction::OverlayResources,
+        InputAction::OverlayTemperature,
+        InputAction::OverlayMoisture,
+        InputAction::OverlayElevation,
+        InputAction::OverlayPolitical,
+        InputAction::OverlayTrade,
+        InputAction::OverlayWildlife,
+        InputAction::RotateLightLeft,
+        InputAction::RotateLightRight,
+        InputAction::ToggleGrid,
+        InputAction::ToggleTileRender,
+        InputAction::ToggleColorGrading,
+        InputAction::ToggleAmbient,
+        InputAction::ToggleHeightmap,
+        InputAction::ToggleWorldView,
+        InputAction::ToggleDiplomacy,
+        InputAction::ToggleHistory,
+        InputAction::ToggleDiagnostics,
+        InputAction::WorldViewProjection,
+        InputAction::WorldViewMeridianWest,
+        InputAction::WorldViewMeridianEast,
+        InputAction::ToggleTimeline,
+        InputAction::TimelineEarlier,
+        InputAction::TimelineLater,
+        InputAction::ExportMap,
+        InputAction::ExportHeightmap,
+        InputAction::CaptureMap,
+        InputAction::MeasureDistance,
+        InputAction::ToggleWorldStats,
+        InputAction::RegenerateWorld,
+        InputAction::Pause,
+        InputAction::SpeedUp,
+        InputAction::SlowDown,
+    ];
+
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            InputAction::PanUp => KeyCode::KeyW,
+            InputAction::PanDown => KeyCode::KeyS,
+            InputAction::PanLeft => KeyCode::KeyA,
+            InputAction::PanRight => KeyCode::KeyD,
+            InputAction::ZoomIn => KeyCode::Period,
+            InputAction::ZoomOut => KeyCode::Comma,
+            InputAction::OverlayBiome => KeyCode::F1,
+            InputAction::OverlayResources => KeyCode::F2,
+            InputAction::OverlayTemperature => KeyCode::F9,
+            InputAction::OverlayMoisture => KeyCode::F4,
+            InputAction::OverlayElevation => KeyCode::F5,
+            InputAction::OverlayPolitical => KeyCode::F6,
+            InputAction::OverlayTrade => KeyCode::F7,
+            InputAction::OverlayWildlife => KeyCode::F11,
+            InputAction::RotateLightLeft => KeyCode::BracketLeft,
+            InputAction::RotateLightRight => KeyCode::BracketRight,
+            InputAction::ToggleGrid => KeyCode::KeyL,
+            InputAction::ToggleTileRender => KeyCode::KeyT,
+            InputAction::ToggleColorGrading => KeyCode::KeyG,
+            InputAction::ToggleAmbient => KeyCode::KeyF,
+            InputAction::ToggleHeightmap => KeyCode::KeyH,
+            InputAction::ToggleWorldView => KeyCode::KeyO,
+            InputAction::ToggleDiplomacy => KeyCode::KeyK,
+            InputAction::ToggleHistory => KeyCode::KeyJ,
+            InputAction::ToggleDiagnostics => KeyCode::F3,
+            InputAction::WorldViewProjection => KeyCode::KeyN,
+            InputAction::WorldViewMeridianWest => KeyCode::ArrowLeft,
+            InputAction::WorldViewMeridianEast => KeyCode::ArrowRight,
+            InputAction::ToggleTimeline => KeyCode::KeyY,
+            InputAction::TimelineEarlier => KeyCode::ArrowDown,
+            InputAction::TimelineLater => KeyCode::ArrowUp,
+            InputAction::ExportMap => KeyCode::F12,
+            InputAction::ExportHeightmap => KeyCode::F8,
+            InputAction::CaptureMap => KeyCode::F10,
+            InputAction::MeasureDistance => KeyCode::KeyM,
+            InputAction::ToggleWorldStats => KeyCode::KeyI,
+            InputAction::RegenerateWorld => KeyCode::KeyR,
+            InputAction::Pause => KeyCode::Space,
+            InputAction::SpeedUp => KeyCode::Equal,
+            InputAction::SlowDown => KeyCode::Minus,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InputAction::PanUp => "Pan up",
+            InputAction::PanDown => "Pan down",
+            InputAction::PanLeft => "Pan left",
+            InputAction::PanRight => "Pan right",
+            InputAction::ZoomIn => "Zoom in",
+            InputAction::ZoomOut => "Zoom out",
+            InputAction::OverlayBiome => "Biome map",
+            InputAction::OverlayResources => "Resource map",
+            InputAction::OverlayTemperature => "Temperature map",
+            InputAction::OverlayMoisture => "Moisture map",
+            InputAction::OverlayElevation => "Elevation map",
+            InputAction::OverlayPolitical => "Political map",
+            InputAction::OverlayTrade => "Trade map",
+            InputAction::OverlayWildlife => "Wildlife map",
+            InputAction::RotateLightLeft => "Turn light left",
+            InputAction::RotateLightRight => "Turn light right",
+            InputAction::ToggleGrid => "Tile grid",
+            InputAction::ToggleTileRender => "Tile textures",
+            InputAction::ToggleColorGrading => "Color grading",
+            InputAction::ToggleAmbient => "Ambient effects",
+            InputAction::ToggleHeightmap => "3D view",
+            InputAction::ToggleWorldView => "World view",
+            InputAction::ToggleDiplomacy => "Diplomacy panel",
+            InputAction::ToggleHistory => "History panel",
+            InputAction::ToggleDiagnostics => "Diagnostics",
+            InputAction::WorldViewProjection => "World view projection",
+            InputAction::WorldViewMeridianWest => "World view west",
+            InputAction::WorldViewMeridianEast => "World view east",
+            InputAction::ToggleTimeline => "Territory timeline",
+            InputAction::TimelineEarlier => "Timeline earlier",
+            InputAction::TimelineLater => "Timeline later",
+            InputAction::ExportMap => "Export map",
+            InputAction::ExportHeightmap => "Export heightmap",
+            InputAction::CaptureMap => "Capture whole map",
+            InputAction::MeasureDistance => "Measure distance",
+            InputAction::ToggleWorldStats => "World statistics",
+            InputAction::RegenerateWorld => "Regenerate world (Shift: same seed)",
+            InputAction::Pause => "Pause",
+            InputAction::SpeedUp => "Speed up",
+            InputAction::SlowDown => "Slow down",
+        }
+    }
+}
+
+// Key for each action, saved to keybindings.ron in the user data folder
+#[derive(Resource, Serialize, Deserialize, Clone)]
+pub struct InputMap {
+    bindings: BTreeMap<InputAction, KeyCode>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        InputMap {
+            bindings: InputAction::ALL
+                .iter()
+                .map(|&action| (action, action.default_key()))
+                .collect(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn key(&self, action: InputAction) -> KeyCode {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or(action.default_key())
+    }
+
+    pub fn pressed(&self, input: &ButtonInput<KeyCode>, action: InputAction) -> bool {
+        input.pressed(self.key(action))
+    }
+
+    pub fn just_pressed(&self, input: &ButtonInput<KeyCode>, action: InputAction) -> bool {
+        input.just_pressed(self.key(action))
+    }
+
+    // An action already on the key takes this action's old key, so no key ever
+    // triggers two actions
+    fn rebind(&mut self, action: InputAction, key: KeyCode) {
+        let previous = self.key(action);
+        for bound in self.bindings.values_mut() {
+            if *bound == key {
+                *bound = previous;
+            }
+        }
+        self.bindings.insert(action, key);
+    }
+}
+
+// Set while the controls page waits for the key to bind to this action
+#[derive(Resource)]
+pub struct PendingRebind(pub InputAction);
+
+// Missing actions fall back to their defaults, so files written by older builds
+// keep working. An action added since takes its default key even when the file
+// gives that key to another action, which moves to its own default.
+pub fn load_input_map() -> InputMap {
+    match read_user_config::<InputMap>(KEYBINDINGS_FILE) {
+        Some(saved) => merge_saved_bindings(saved),
+        None => InputMap::default(),
+    }
+}
+
+fn merge_saved_bindings(saved: InputMap) -> InputMap {
+    let mut input_map = InputMap::default();
+    let added: Vec<InputAction> = InputAction::ALL
+        .into_iter()
+        .filter(|action| !saved.bindings.contains_key(action))
+        .collect();
+    input_map.bindings.extend(saved.bindings);
+    for action in added {
+        let key = action.default_key();
+        for other in InputAction::ALL {
+            if other != action && input_map.key(other) == key {
+                input_map.bindings.insert(other, other.default_key());
+            }
+        }
+    }
+
+    input_map
+}
+
+// Runs right after input is collected, so the captured key is swallowed before
+// any gameplay system sees it. Escape cancels.
+pub fn capture_rebind(
+    mut commands: Commands,
+    pending: Res<PendingRebind>,
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Some(&key) = input.get_just_pressed().next() else {
+        return;
+    };
+    input.reset(key);
+    commands.remove_resource::<PendingRebind>();
+
+    if key != KeyCode::Escape {
+        input_map.rebind(pending.0, key);
+        write_user_config(KEYBINDINGS_FILE, &*input_map);
+    }
+}
+
+// Swaps the pause menu for the controls page; the sim stays paused behind it
+pub fn open_keybindings_page(
+    commands: &mut Commands,
+    input_map: &InputMap,
+    menu_query: &Query<Entity, With<PauseMenuUI>>,
+) {
+    for entity in menu_query {
+        commands.entity(entity).despawn();
+    }
+
+    let rows = InputAction::ALL.map(|action| {
+        (
+            Node {
+                width: Val::Px(420.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![
+                (
+                    Text::new(action.label()),
+                    Localized::new(format!("action.{:?}", action)),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ),
+                (
+                    Button,
+                    Node {
+                        width: Val::Px(160.0),
+                        padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    RebindButton(action),
+                    children![(
+                        RebindLabel(action),
+                        Text::new(key_name(input_map.key(action))),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    )],
+                ),
+            ],
+        )
+    });
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            PauseMenuUI,
+            KeybindingsUI,
+            Children::spawn(SpawnIter(rows.into_iter())),
+        ))
+        .with_child((
+            Node {
+                margin: UiRect::top(Val::Px(12.0)),
+                column_gap: Val::Px(16.0),
+                ..default()
+            },
+            children![
+                (
+                    Button,
+                    Node {
+                        padding: UiRect::all(Val::Px(12.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    KeybindingsAction::ResetDefaults,
+                    children![(
+                        Text::new("Reset Defaults"),
+                        Localized::new("controls.reset_defaults"),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE)
+                    )]
+                ),
+                (
+                    Button,
+                    Node {
+                        padding: UiRect::all(Val::Px(12.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    KeybindingsAction::Back,
+                    children![(
+                        Text::new("Back"),
+                        Localized::new("controls.back"),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE)
+                    )]
+                ),
+            ],
+        ));
+}
+
+pub fn keybindings_buttons(
+    mut commands: Commands,
+    mut input_map: ResMut<InputMap>,
+    mut pause: ResMut<Pause>,
+    rebind_query: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+    action_query: Query<(&Interaction, &KeybindingsAction), Changed<Interaction>>,
+    page_query: Query<Entity, With<KeybindingsUI>>,
+) {
+    for (interaction, button) in &rebind_query {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(PendingRebind(button.0));
+        }
+    }
+
+    for (interaction, action) in &action_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            KeybindingsAction::ResetDefaults => {
+                *input_map = InputMap::default();
+                write_user_config(KEYBINDINGS_FILE, &*input_map);
+            }
+            KeybindingsAction::Back => {
+                commands.remove_resource::<PendingRebind>();
+                for entity in &page_query {
+                    commands.entity(entity).despawn();
+                }
+                open_pause_menu(&mut commands, &mut pause);
+            }
+        }
+    }
+}
+
+pub fn update_rebind_labels(
+    input_map: Res<InputMap>,
+    pending: Option<Res<PendingRebind>>,
+    locale: Res<Locale>,
+    mut label_query: Query<(&RebindLabel, &mut Text)>,
+) {
+    for (label, mut text) in &mut label_query {
+        let shown = match &pending {
+            Some(pending) if pending.0 == label.0 => locale.text("controls.press_key").to_string(),
+            _ => key_name(input_map.key(label.0)),
+        };
+        if text.0 != shown {
+            text.0 = shown;
+        }
+    }
+}
+
+// "KeyW" reads as "W", "Digit1" as "1"
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")) {
+        Some(short) => short.to_string(),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(input_map: &InputMap) -> InputMap {
+        let text = ron::to_string(input_map).unwrap();
+        merge_saved_bindings(ron::from_str(&text).unwrap())
+    }
+
+    #[test]
+    fn rebound_keys_survive_a_save() {
+        let mut input_map = InputMap::default();
+        input_map.rebind(InputAction::PanUp, KeyCode::ArrowUp);
+        // Taking another action's key hands it PanDown's old one
+        input_map.rebind(InputAction::PanDown, KeyCode::KeyA);
+
+        let loaded = round_trip(&input_map);
+        for action in InputAction::ALL {
+            assert_eq!(loaded.key(action), input_map.key(action), "{:?}", action);
+        }
+        assert_eq!(loaded.key(InputAction::PanLeft), KeyCode::KeyS);
+    }
+
+    #[test]
+    fn actions_missing_from_a_save_take_their_default_key() {
+        let mut saved = InputMap::default();
+        saved.bindings.remove(&InputAction::SlowDown);
+        // Another action holding that key gives it up
+        let key = InputAction::SlowDown.default_key();
+        saved.bindings.insert(InputAction::ZoomIn, key);
+
+        let loaded = round_trip(&saved);
+        assert_eq!(loaded.key(InputAction::SlowDown), key);
+        assert_eq!(
+            loaded.key(InputAction::ZoomIn),
+            InputAction::ZoomIn.default_key()
+        );
+    }
+}
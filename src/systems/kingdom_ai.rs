@@ -0,0 +1,263 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    kingdoms::{AiControlled, Culture, FoundingParty, Kingdom, KingdomSettlements, OwnedBy, Road},
+    settlements::{Good, Population, Settlement, Stockpile},
+    units::{MoveOrder, Unit, UnitKind},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    pathfinding::PathGrid,
+    season::Season,
+    settlements::spawn_settlement,
+    units::spawn_unit,
+    world::{WorldSettings, nearest_copy, toroidal_delta, toroidal_distance},
+    world_gen::{
+        names::place_name,
+        settlements::{MIN_SETTLEMENT_SPACING, SettlementSite, local_landform, survey_site},
+    },
+};
+
+// Kingdoms take stock a few times a year; surveying sites is too slow to do
+// every tick
+const AI_TICK_SECS: f32 = 30.0;
+// A settlement needs this many people and a year's food to spare before it
+// sends settlers out
+const EXPEDITION_MIN_POPULATION: f32 = 300.0;
+const EXPEDITION_SIZE: f32 = 60.0;
+// Candidate sites tried per decision, in a ring around the sending settlement
+const CANDIDATE_SITES: usize = 8;
+const EXPEDITION_MIN_DISTANCE: f32 = 70.0;
+const EXPEDITION_MAX_DISTANCE: f32 = 120.0;
+// Utility weights: food counts in full, other goods for less, and every tile
+// of the journey takes a little off
+const OTHER_GOODS_WEIGHT: f32 = 0.5;
+const DISTANCE_WEIGHT: f32 = 2.0;
+pub const MIN_SITE_UTILITY: f32 = 300.0;
+const ROAD_COLOR: Color = Color::srgba(0.55, 0.4, 0.25, 0.9);
+
+#[derive(Resource, Default)]
+pub struct KingdomAi {
+    pub cooldown: f32,
+    // Every site weighed at the last decision, kept for the dev tools overlay
+    pub surveyed: Vec<SurveyedSite>,
+}
+
+// A place settlers could be sent and what it was judged worth
+pub struct SurveyedSite {
+    pub tile: IVec2,
+    pub utility: f32,
+}
+
+// Each AI kingdom with no settlers already on the road weighs a ring of sites
+// around its largest settlement and sends settlers to the best one worth it
+pub fn plan_expansion(
+    mut commands: Commands,
+    (clock, settings): (Res<GameClock>, Res<WorldSettings>),
+    mut ai: ResMut<KingdomAi>,
+    (territory, world_data_query): (Option<Res<TerritoryMap>>, Query<&WorldData>),
+    kingdom_query: Query<(Entity, &KingdomSettlements), With<AiControlled>>,
+    mut settlement_query: Query<(&Settlement, &mut Population, &Stockpile)>,
+    party_query: Query<&FoundingParty>,
+) {
+    ai.cooldown -= clock.delta_secs;
+    if ai.cooldown > 0.0 {
+        return;
+    }
+    ai.cooldown = AI_TICK_SECS;
+    ai.surveyed.clear();
+
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    let mut rng = rand::rng();
+    let occupied: Vec<IVec2> = settlement_query
+        .iter()
+        .map(|(settlement, ..)| settlement.tile)
+        .collect();
+
+    for (kingdom, settlements) in &kingdom_query {
+        if party_query.iter().any(|party| party.kingdom == kingdom) {
+            continue;
+        }
+
+        let home = settlements
+            .iter()
+            .filter_map(|entity| {
+                settlement_query
+                    .get(entity)
+                    .ok()
+                    .map(|(s, p, _)| (entity, s, p))
+            })
+            .max_by(|(_, _, a), (_, _, b)| a.0.total_cmp(&b.0))
+            .map(|(entity, settlement, _)| (entity, settlement.tile));
+        let Some((home, home_tile)) = home else {
+            continue;
+        };
+        let Ok((_, population, stockpile)) = settlement_query.get(home) else {
+            continue;
+        };
+        let food = [Good::Grain, Good::Fish, Good::Game]
+            .iter()
+            .map(|good| stockpile.get(*good))
+            .sum::<f32>();
+        if population.0 < EXPEDITION_MIN_POPULATION || food < population.0 {
+            continue;
+        }
+
+        let start = ai.surveyed.len();
+        let candidates: Vec<SurveyedSite> = (0..CANDIDATE_SITES)
+            .filter_map(|index| {
+                let angle = (index as f32 + rng.random::<f32>()) / CANDIDATE_SITES as f32 * TAU;
+                let distance = rng.random_range(EXPEDITION_MIN_DISTANCE..EXPEDITION_MAX_DISTANCE);
+                let offset = Vec2::from_angle(angle) * distance;
+                let tile =
+                    (home_tile + offset.as_ivec2()).rem_euclid(IVec2::splat(settings.world_size));
+
+                let crowded = occupied.iter().any(|other| {
+                    toroidal_distance(tile, *other, &settings) < MIN_SETTLEMENT_SPACING
+                });
+                let foreign = territory
+                    .as_deref()
+                    .and_then(|territory| territory.owner_at(tile.x, tile.y))
+                    .is_some_and(|owner| owner != kingdom);
+                if crowded || foreign {
+                    return None;
+                }
+
+                let site = survey_site(world_data, &settings, tile, String::new())?;
+                Some(SurveyedSite {
+                    tile,
+                    utility: site_utility(&site) - DISTANCE_WEIGHT * distance,
+                })
+            })
+            .collect();
+        ai.surveyed.extend(candidates);
+
+        let best = ai.surveyed[start..]
+            .iter()
+            .max_by(|a, b| a.utility.total_cmp(&b.utility));
+        let Some(&SurveyedSite { tile, utility }) = best else {
+            continue;
+        };
+        if utility < MIN_SITE_UTILITY {
+            continue;
+        }
+
+        if let Ok((_, mut population, _)) = settlement_query.get_mut(home) {
+            population.0 -= EXPEDITION_SIZE;
+        }
+        let settlers = spawn_unit(&mut commands, UnitKind::Settler, home_tile);
+        commands.entity(settlers).insert((
+            MoveOrder::new(tile),
+            FoundingParty {
+                kingdom,
+                population: EXPEDITION_SIZE,
+            },
+        ));
+    }
+}
+
+// Settlers found their settlement wherever their journey ends, and the way they
+// came becomes a road back home that later travellers move faster along
+pub fn found_settlements(
+    mut commands: Commands,
+    (settings, world_data_query): (Res<WorldSettings>, Query<&WorldData>),
+    mut grid: Option<ResMut<PathGrid>>,
+    (season, locale): (Res<Season>, Res<Locale>),
+    mut chronicle: ResMut<Chronicle>,
+    party_query: Query<(Entity, &Unit, &FoundingParty, Option<&MoveOrder>)>,
+    kingdom_query: Query<(&Kingdom, &Culture)>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+
+    for (entity, unit, party, order) in &party_query {
+        let Some(order) = order else {
+            // The search found no way there
+            info!("Settlers gave up on founding a settlement");
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if order.path.is_empty() || order.next < order.path.len() {
+            continue;
+        }
+
+        // Named in the tongue of the kingdom that sent them
+        let kingdom = kingdom_query.get(party.kingdom).ok();
+        let culture = kingdom.map(|(_, culture)| *culture).unwrap_or_default();
+        let landform = local_landform(world_data, &settings, unit.tile);
+        let name = place_name(world_data.seed, culture, landform, unit.tile);
+        let site =
+            survey_site(world_data, &settings, unit.tile, name.clone()).unwrap_or(SettlementSite {
+                name,
+                tile: unit.tile,
+                food_capacity: 0.0,
+                production: default(),
+                fishing: default(),
+            });
+        let founder = kingdom.map(|(kingdom, _)| kingdom.name.as_str()).unwrap_or_default();
+        chronicle.record(
+            locale.date(&season),
+            ChronicleKind::Founding,
+            locale.format("chronicle.founds", &[&founder, &site.name]),
+        );
+
+        let settlement = spawn_settlement(&mut commands, site, party.population);
+        commands.entity(settlement).insert(OwnedBy(party.kingdom));
+        if let Some(grid) = grid.as_deref_mut() {
+            grid.add_road(&order.path);
+        }
+        commands.spawn(Road {
+            tiles: order.path.clone(),
+        });
+        commands.entity(entity).despawn();
+    }
+}
+
+// Each road is drawn from the copy of its first tile nearest the camera, then
+// step by step, so it runs on unbroken across the world edge
+pub fn draw_roads(
+    mut gizmos: Gizmos,
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    road_query: Query<&Road>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for road in &road_query {
+        let Some(&first) = road.tiles.first() else {
+            continue;
+        };
+        let mut tile = nearest_copy(camera, first, &settings);
+        let mut points = vec![tile.as_vec2() + Vec2::splat(0.5)];
+        for pair in road.tiles.windows(2) {
+            tile += toroidal_delta(pair[0], pair[1], &settings);
+            points.push(tile.as_vec2() + Vec2::splat(0.5));
+        }
+
+        gizmos.linestrip_2d(points, ROAD_COLOR);
+    }
+}
+
+pub fn cleanup_kingdom_ai(mut ai: ResMut<KingdomAi>) {
+    *ai = KingdomAi::default();
+}
+
+fn site_utility(site: &SettlementSite) -> f32 {
+    let other_goods: f32 = [Good::Timber, Good::Stone, Good::Ore]
+        .iter()
+        .map(|good| site.production.get(*good))
+        .sum();
+
+    site.food_capacity + OTHER_GOODS_WEIGHT * other_goods
+}
@@ -0,0 +1,650 @@
+use std::collections::BinaryHeap;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+use kingdom_worldgen::{MAX_ELEVATION, sampler::WorldSampler};
+use rand::{Rng, SeedableRng, rngs::SmallRng, seq::SliceRandom};
+
+use crate::components::{
+    kingdoms::{
+        AiControlled, Culture, Fame, Kingdom, OwnedBy, Road, Technology, TerritoryLayer, Treasury,
+    },
+    settlements::{Population, Settlement},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    clock::GameClock,
+    parallel::*,
+    timeline::TerritoryHistory,
+    world::{MapOverlay, WorldSettings, toroidal_distance},
+    world_gen::{names::NameGenerator, settlements::local_landform},
+};
+
+// Territory is claimed on a coarse grid, like regions, so the flood fill stays
+// cheap on the largest worlds
+const TERRITORY_CELL: i32 = 16;
+const SETTLEMENTS_PER_KINGDOM: usize = 4;
+// Cells above this are impassable to claims, as is the sea
+const MOUNTAIN_ELEVATION: f64 = 0.65 * MAX_ELEVATION;
+// Claim budget spent per cell crossed, plus more for every unit of climb
+const STEP_COST: u32 = 10;
+const CLIMB_COST: f32 = 4.0;
+// A settlement's reach: a few cells for a hamlet, growing with its population
+const BASE_CLAIM: u32 = 3 * STEP_COST;
+const CLAIM_PER_SQRT_POPULATION: f32 = 2.5;
+const TERRITORY_REFRESH_SECS: f32 = 5.0;
+const TERRITORY_ALPHA: f32 = 0.45;
+// Over the chunk meshes, under the tile highlight
+const TERRITORY_Z: f32 = 0.15;
+
+// Which kingdom holds each coarse cell, with single tiles claimed or released
+// on top. The terrain the claims spread over is sampled once per world, off the
+// main thread.
+#[derive(Resource)]
+pub struct TerritoryMap {
+    cells_per_side: i32,
+    // Elevation per cell, or None where the sea or mountains block claims
+    terrain: Option<Vec<Option<f32>>>,
+    task: Option<Task<Vec<Option<f32>>>>,
+    owner: Vec<Option<Entity>>,
+    // Tiles whose owner differs from their cell's, in base world coordinates.
+    // None marks a tile released from the kingdom around it.
+    tile_claims: HashMap<IVec2, Option<Entity>>,
+    image: Handle<Image>,
+    refresh_cooldown: f32,
+    dirty: bool,
+    // Frontier lines in base world coordinates, each with the kingdom it's
+    // drawn for; rebuilt when the owners change
+    border_lines: Vec<(Vec2, Vec2, Entity)>,
+    borders_dirty: bool,
+}
+
+// Who holds each tile, copied out of the territory map for work off the main thread
+pub struct TerritorySnapshot {
+    cells_per_side: i32,
+    owner: Vec<Option<Entity>>,
+    tile_claims: HashMap<IVec2, Option<Entity>>,
+}
+
+impl TerritorySnapshot {
+    pub fn owner_at(&self, tile: IVec2) -> Option<Entity> {
+        let tile = tile.rem_euclid(IVec2::splat(self.cells_per_side * TERRITORY_CELL));
+        if let Some(claim) = self.tile_claims.get(&tile) {
+            return *claim;
+        }
+
+        let cell = tile.div_euclid(IVec2::splat(TERRITORY_CELL));
+        self.owner[cell_index(cell.x, cell.y, self.cells_per_side)]
+    }
+}
+
+impl TerritoryMap {
+    pub fn snapshot(&self) -> TerritorySnapshot {
+        TerritorySnapshot {
+            cells_per_side: self.cells_per_side,
+            owner: self.owner.clone(),
+            tile_claims: self.tile_claims.clone(),
+        }
+    }
+
+    // Owner of every coarse cell, row by row, without the single tile claims
+    pub fn cell_owners(&self) -> &[Option<Entity>] {
+        &self.owner
+    }
+
+    pub fn owner_at(&self, x: i32, y: i32) -> Option<Entity> {
+        let tile = IVec2::new(x, y).rem_euclid(IVec2::splat(self.world_size()));
+        if let Some(claim) = self.tile_claims.get(&tile) {
+            return *claim;
+        }
+
+        self.cell_owner(tile)
+    }
+
+    // Takes a single tile for a kingdom. It must be unheld, passable and share an
+    // edge with land the kingdom already holds.
+    pub fn claim_tile(&mut self, tile: IVec2, kingdom: Entity) -> Result<(), String> {
+        let tile = tile.rem_euclid(IVec2::splat(self.world_size()));
+        match self.owner_at(tile.x, tile.y) {
+            Some(owner) if owner == kingdom => return Err("already held".to_string()),
+            Some(_) => return Err("held by another kingdom".to_string()),
+            None => {}
+        }
+
+        let cell = tile.div_euclid(IVec2::splat(TERRITORY_CELL));
+        let blocked = self.terrain.as_ref().is_some_and(|terrain| {
+            terrain[cell_index(cell.x, cell.y, self.cells_per_side)].is_none()
+        });
+        if blocked {
+            return Err("sea or mountains".to_string());
+        }
+
+        let adjacent = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+            .iter()
+            .any(|offset| {
+                let neighbour = tile + *offset;
+                self.owner_at(neighbour.x, neighbour.y) == Some(kingdom)
+            });
+        if !adjacent {
+            return Err("not next to the kingdom's land".to_string());
+        }
+
+        self.set_tile_owner(tile, Some(kingdom));
+        Ok(())
+    }
+
+    // Tiles held apart from the cell around them, or released from it, for saving.
+    // The cells themselves follow from the settlements.
+    pub fn tile_claims(&self) -> impl Iterator<Item = (IVec2, Option<Entity>)> + '_ {
+        self.tile_claims.iter().map(|(tile, owner)| (*tile, *owner))
+    }
+
+    // Puts saved tile claims back as they were. The cells may not be claimed yet,
+    // so they are taken as given rather than checked against them.
+    pub fn restore_tile_claims(
+        &mut self,
+        claims: impl IntoIterator<Item = (IVec2, Option<Entity>)>,
+    ) {
+        self.tile_claims.extend(claims);
+        self.borders_dirty = true;
+    }
+
+    // Gives up a tile a kingdom holds, leaving it unclaimed
+    pub fn release_tile(&mut self, tile: IVec2, kingdom: Entity) -> Result<(), String> {
+        let tile = tile.rem_euclid(IVec2::splat(self.world_size()));
+        if self.owner_at(tile.x, tile.y) != Some(kingdom) {
+            return Err("not held by the kingdom".to_string());
+        }
+
+        self.set_tile_owner(tile, None);
+        Ok(())
+    }
+
+    // Length of the border each pair of kingdoms shares, in cell edges. Pairs
+    // are keyed with the lower entity first.
+    pub fn borders(&self) -> HashMap<(Entity, Entity), u32> {
+        let cells = self.cells_per_side;
+        let mut borders = HashMap::new();
+
+        for y in 0..cells {
+            for x in 0..cells {
+                let Some(owner) = self.owner[cell_index(x, y, cells)] else {
+                    continue;
+                };
+                for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                    if let Some(neighbour) = self.owner[cell_index(nx, ny, cells)]
+                        && neighbour != owner
+                    {
+                        *borders
+                            .entry((owner.min(neighbour), owner.max(neighbour)))
+                            .or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        borders
+    }
+
+    fn world_size(&self) -> i32 {
+        self.cells_per_side * TERRITORY_CELL
+    }
+
+    fn cell_owner(&self, tile: IVec2) -> Option<Entity> {
+        let cell = tile.div_euclid(IVec2::splat(TERRITORY_CELL));
+        self.owner[cell_index(cell.x, cell.y, self.cells_per_side)]
+    }
+
+    // Tile claims matching the cell around them are dropped, so the overrides
+    // only ever hold real differences
+    fn set_tile_owner(&mut self, tile: IVec2, owner: Option<Entity>) {
+        if self.cell_owner(tile) == owner {
+            self.tile_claims.remove(&tile);
+        } else {
+            self.tile_claims.insert(tile, owner);
+        }
+        self.borders_dirty = true;
+    }
+
+    // Frontiers run along cell edges, except in cells holding tile claims, which
+    // are traced tile by tile. Each edge is visited once: cells and tiles look
+    // right and up, and tiles in traced cells also look left and down into
+    // cells that aren't traced.
+    fn trace_borders(&self) -> Vec<(Vec2, Vec2, Entity)> {
+        let cells = self.cells_per_side;
+        let traced: HashSet<IVec2> = self
+            .tile_claims
+            .keys()
+            .map(|tile| tile.div_euclid(IVec2::splat(TERRITORY_CELL)))
+            .collect();
+        let is_traced = |cell: IVec2| traced.contains(&cell.rem_euclid(IVec2::splat(cells)));
+        let mut lines = Vec::new();
+        let mut edge = |a: Option<Entity>, b: Option<Entity>, start: Vec2, end: Vec2| {
+            if a != b
+                && let Some(kingdom) = a.or(b)
+            {
+                lines.push((start, end, kingdom));
+            }
+        };
+
+        let size = TERRITORY_CELL as f32;
+        for y in 0..cells {
+            for x in 0..cells {
+                let cell = IVec2::new(x, y);
+                if is_traced(cell) {
+                    continue;
+                }
+                let corner = cell.as_vec2() * size;
+                let owner = self.owner[cell_index(x, y, cells)];
+                if !is_traced(cell + IVec2::X) {
+                    let right = self.owner[cell_index(x + 1, y, cells)];
+                    edge(
+                        owner,
+                        right,
+                        corner + Vec2::X * size,
+                        corner + Vec2::splat(size),
+                    );
+                }
+                if !is_traced(cell + IVec2::Y) {
+                    let up = self.owner[cell_index(x, y + 1, cells)];
+                    edge(
+                        owner,
+                        up,
+                        corner + Vec2::Y * size,
+                        corner + Vec2::splat(size),
+                    );
+                }
+            }
+        }
+
+        for cell in &traced {
+            let origin = *cell * TERRITORY_CELL;
+            for dy in 0..TERRITORY_CELL {
+                for dx in 0..TERRITORY_CELL {
+                    let tile = origin + IVec2::new(dx, dy);
+                    let corner = tile.as_vec2();
+                    let owner = self.owner_at(tile.x, tile.y);
+                    let neighbour =
+                        |offset: IVec2| self.owner_at(tile.x + offset.x, tile.y + offset.y);
+
+                    edge(
+                        owner,
+                        neighbour(IVec2::X),
+                        corner + Vec2::X,
+                        corner + Vec2::ONE,
+                    );
+                    edge(
+                        owner,
+                        neighbour(IVec2::Y),
+                        corner + Vec2::Y,
+                        corner + Vec2::ONE,
+                    );
+                    if dx == 0 && !is_traced(*cell + IVec2::NEG_X) {
+                        edge(owner, neighbour(IVec2::NEG_X), corner, corner + Vec2::Y);
+                    }
+                    if dy == 0 && !is_traced(*cell + IVec2::NEG_Y) {
+                        edge(owner, neighbour(IVec2::NEG_Y), corner, corner + Vec2::X);
+                    }
+                }
+            }
+        }
+
+        lines
+    }
+}
+
+// Groups the settlements into kingdoms around a few capitals, each speaking its
+// own tongue, and renames every settlement in its kingdom's. Deterministic for
+// a seed, like settlement placement.
+pub fn found_kingdoms(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    world_data_query: Query<&WorldData>,
+    mut settlement_query: Query<(Entity, &mut Settlement)>,
+) {
+    let world_data = match world_data_query.single() {
+        Ok(data) => data,
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+
+    let mut settlements: Vec<(Entity, IVec2)> = settlement_query
+        .iter()
+        .map(|(entity, settlement)| (entity, settlement.tile))
+        .collect();
+    settlements.sort_by_key(|(_, tile)| (tile.x, tile.y));
+
+    let mut rng = SmallRng::seed_from_u64(world_data.seed.wrapping_add(14) as u64);
+    let mut capitals = settlements.clone();
+    capitals.shuffle(&mut rng);
+    capitals.truncate(settlements.len().div_ceil(SETTLEMENTS_PER_KINGDOM));
+
+    // Tongues are dealt round in turn, so neighbouring indices never share one
+    let first_culture = rng.random_range(0..Culture::ALL.len());
+    let mut namer = NameGenerator::new(world_data.seed.wrapping_add(15) as u64);
+    let kingdoms: Vec<(Entity, IVec2, Culture)> = capitals
+        .iter()
+        .enumerate()
+        .map(|(index, &(_, tile))| {
+            // Golden-angle hues keep neighbouring indices far apart on the wheel
+            let hue = (index as f32 * 137.5).rem_euclid(360.0);
+            let culture = Culture::ALL[(first_culture + index) % Culture::ALL.len()];
+            let kingdom = commands
+                .spawn((
+                    Kingdom {
+                        name: namer.name(culture),
+                        color: Color::hsl(hue, 0.7, 0.5),
+                    },
+                    culture,
+                    Treasury::default(),
+                    Technology::default(),
+                    Fame::default(),
+                    AiControlled,
+                ))
+                .id();
+            (kingdom, tile, culture)
+        })
+        .collect();
+
+    for (settlement, tile) in settlements {
+        let nearest = kingdoms.iter().min_by(|(_, a, _), (_, b, _)| {
+            toroidal_distance(tile, *a, &settings)
+                .total_cmp(&toroidal_distance(tile, *b, &settings))
+        });
+        if let Some(&(kingdom, _, culture)) = nearest {
+            commands.entity(settlement).insert(OwnedBy(kingdom));
+            if let Ok((_, mut settlement)) = settlement_query.get_mut(settlement) {
+                let landform = local_landform(world_data, &settings, settlement.tile);
+                settlement.name = namer.settlement_name(culture, landform);
+            }
+        }
+    }
+}
+
+pub fn setup_territory(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    settings: Res<WorldSettings>,
+    query: Query<&WorldData>,
+) {
+    let world_data = match query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let cells = (settings.world_size / TERRITORY_CELL).max(1);
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: cells as u32,
+            height: cells as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    let image = images.add(image);
+
+    // Enough copies around the camera to cover the view across the world edges
+    for _ in 0..9 {
+        commands.spawn((
+            TerritoryLayer,
+            Sprite {
+                image: image.clone(),
+                custom_size: Some(Vec2::splat(settings.world_size as f32)),
+                ..default()
+            },
+            Transform::default(),
+            Visibility::Hidden,
+        ));
+    }
+
+    let world_size = settings.world_size;
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { survey_terrain(&world_data, world_size, cells) });
+    commands.insert_resource(TerritoryMap {
+        cells_per_side: cells,
+        terrain: None,
+        task: Some(task),
+        owner: vec![None; (cells * cells) as usize],
+        tile_claims: HashMap::new(),
+        image,
+        refresh_cooldown: TERRITORY_REFRESH_SECS,
+        dirty: false,
+        border_lines: Vec::new(),
+        borders_dirty: false,
+    });
+}
+
+// The first claims are made as soon as the terrain is known, even while paused
+pub fn poll_territory_survey(
+    mut territory: ResMut<TerritoryMap>,
+    settlement_query: Query<(&Settlement, &Population, &OwnedBy)>,
+) {
+    let Some(task) = territory.task.as_mut() else {
+        return;
+    };
+    let Some(terrain) = check_ready(task) else {
+        return;
+    };
+    territory.task = None;
+    territory.terrain = Some(terrain);
+
+    claim_cells(&mut territory, &settlement_query);
+}
+
+// Borders follow the settlements as they grow and shrink
+pub fn claim_territory(
+    clock: Res<GameClock>,
+    mut territory: ResMut<TerritoryMap>,
+    settlement_query: Query<(&Settlement, &Population, &OwnedBy)>,
+) {
+    territory.refresh_cooldown -= clock.delta_secs;
+    if territory.refresh_cooldown > 0.0 {
+        return;
+    }
+    territory.refresh_cooldown = TERRITORY_REFRESH_SECS;
+
+    claim_cells(&mut territory, &settlement_query);
+}
+
+type TerritoryLayerOnly = (With<TerritoryLayer>, Without<Camera2d>);
+
+// Shows the territory tint on the political map, laid out around the copy of
+// the world the camera is over. A year picked on the timeline is shown as it
+// was instead.
+pub fn update_territory_layer(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    overlay: Res<MapOverlay>,
+    settings: Res<WorldSettings>,
+    (mut territory, history): (ResMut<TerritoryMap>, Res<TerritoryHistory>),
+    mut images: ResMut<Assets<Image>>,
+    kingdom_query: Query<&Kingdom>,
+    mut layer_query: Query<(&mut Transform, &mut Visibility), TerritoryLayerOnly>,
+) {
+    if history.is_changed() {
+        territory.dirty = true;
+    }
+    let shown = *overlay == MapOverlay::Political;
+    for (_, mut visibility) in &mut layer_query {
+        visibility.set_if_neq(if shown {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !shown {
+        return;
+    }
+
+    let world_size = settings.world_size as f32;
+    let camera = camera_query.translation.truncate();
+    let origin = (camera / world_size).floor() * world_size + Vec2::splat(world_size / 2.0);
+    let offsets = (-1..=1).flat_map(|x| (-1..=1).map(move |y| Vec2::new(x as f32, y as f32)));
+    for ((mut transform, _), offset) in layer_query.iter_mut().zip(offsets) {
+        transform.translation = (origin + offset * world_size).extend(TERRITORY_Z);
+    }
+
+    if !territory.dirty {
+        return;
+    }
+    territory.dirty = false;
+
+    let cells = territory.cells_per_side;
+    let owners = history.viewed_owners().unwrap_or(&territory.owner);
+    let mut pixels = Vec::with_capacity((cells * cells * 4) as usize);
+    // Image rows run top down, world rows bottom up
+    for row in (0..cells).rev() {
+        for column in 0..cells {
+            let owner = owners[cell_index(column, row, cells)];
+            let color = owner
+                .and_then(|kingdom| {
+                    kingdom_query
+                        .get(kingdom)
+                        .map(|kingdom| kingdom.color)
+                        .ok()
+                        .or_else(|| history.color(kingdom))
+                })
+                .map(|color| color.with_alpha(TERRITORY_ALPHA).to_srgba().to_u8_array())
+                .unwrap_or([0, 0, 0, 0]);
+            pixels.extend_from_slice(&color);
+        }
+    }
+    if let Some(image) = images.get_mut(&territory.image) {
+        image.data = Some(pixels);
+    }
+}
+
+// Outlines each kingdom's frontier in its colour on the political map, on the
+// copies of the world around the camera. Only today's frontiers are traced, so
+// none are drawn over a past year.
+pub fn draw_borders(
+    mut gizmos: Gizmos,
+    camera_query: Single<&Transform, With<Camera2d>>,
+    overlay: Res<MapOverlay>,
+    settings: Res<WorldSettings>,
+    (mut territory, history): (ResMut<TerritoryMap>, Res<TerritoryHistory>),
+    kingdom_query: Query<&Kingdom>,
+) {
+    if territory.borders_dirty {
+        territory.borders_dirty = false;
+        territory.border_lines = territory.trace_borders();
+    }
+    if *overlay != MapOverlay::Political || history.viewing_past() {
+        return;
+    }
+
+    let world_size = settings.world_size as f32;
+    let camera = camera_query.translation.truncate();
+    let origin = (camera / world_size).floor() * world_size;
+    for &(start, end, kingdom) in &territory.border_lines {
+        let Ok(kingdom) = kingdom_query.get(kingdom) else {
+            continue;
+        };
+        let color = kingdom.color.darker(0.15);
+        for x in -1..=1 {
+            for y in -1..=1 {
+                let offset = origin + Vec2::new(x as f32, y as f32) * world_size;
+                gizmos.line_2d(start + offset, end + offset, color);
+            }
+        }
+    }
+}
+
+type KingdomEntities = (With<Kingdom>, With<TerritoryLayer>, With<Road>);
+
+pub fn cleanup_kingdoms(
+    mut commands: Commands,
+    query: Query<Entity, Or<KingdomEntities>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<TerritoryMap>();
+}
+
+fn survey_terrain(world_data: &WorldData, world_size: i32, cells: i32) -> Vec<Option<f32>> {
+    let sampler = WorldSampler::new(world_data, world_size);
+
+    (0..cells * cells)
+        .into_par_iter()
+        .map(|index| {
+            let elevation = sampler.elevation_at(
+                index % cells * TERRITORY_CELL + TERRITORY_CELL / 2,
+                index / cells * TERRITORY_CELL + TERRITORY_CELL / 2,
+            );
+            let passable = (world_data.sea_level()..=MOUNTAIN_ELEVATION).contains(&elevation);
+            passable.then_some(elevation as f32)
+        })
+        .collect()
+}
+
+// Weighted flood fill from every settlement at once. Each spreads with a budget
+// that crossing cells and climbing use up; a contested cell goes to whichever
+// claim reaches it with the most budget to spare.
+fn claim_cells(
+    territory: &mut TerritoryMap,
+    settlement_query: &Query<(&Settlement, &Population, &OwnedBy)>,
+) {
+    let Some(terrain) = territory.terrain.as_ref() else {
+        return;
+    };
+    let cells = territory.cells_per_side;
+    let mut owner = vec![None; (cells * cells) as usize];
+
+    let mut frontier = BinaryHeap::new();
+    for (settlement, population, owned_by) in settlement_query {
+        let cell = settlement.tile.div_euclid(IVec2::splat(TERRITORY_CELL));
+        let budget = BASE_CLAIM + (population.0.sqrt() * CLAIM_PER_SQRT_POPULATION) as u32;
+        frontier.push((budget, cell_index(cell.x, cell.y, cells), owned_by.0));
+    }
+
+    while let Some((budget, index, kingdom)) = frontier.pop() {
+        let cell = IVec2::new(index as i32 % cells, index as i32 / cells);
+        if owner[index].is_some() {
+            continue;
+        }
+        owner[index] = Some(kingdom);
+
+        for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = cell + offset;
+            let next_index = cell_index(next.x, next.y, cells);
+            let Some(elevation) = terrain[next_index] else {
+                continue;
+            };
+            if owner[next_index].is_some() {
+                continue;
+            }
+
+            let climb = (elevation - terrain[index].unwrap_or(elevation)).max(0.0);
+            let cost = STEP_COST + (climb * CLIMB_COST) as u32;
+            if budget >= cost {
+                frontier.push((budget - cost, next_index, kingdom));
+            }
+        }
+    }
+
+    if territory.owner != owner {
+        territory.owner = owner;
+        territory.dirty = true;
+        territory.borders_dirty = true;
+    }
+}
+
+fn cell_index(cx: i32, cy: i32, cells: i32) -> usize {
+    (cy.rem_euclid(cells) * cells + cx.rem_euclid(cells)) as usize
+}
@@ -0,0 +1,365 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use kingdom_worldgen::{MAX_ELEVATION, climate_square, sampler::WorldSampler};
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    kingdoms::{AiControlled, Fame, Kingdom, KingdomSettlements, OwnedBy},
+    lairs::{HuntingParty, Lair, LairKind},
+    settlements::{FishingGrounds, Good, Population, Settlement, Stockpile},
+    units::{MoveOrder, PathSearch, Unit, UnitKind},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    combat::{prevails, raid_garrison},
+    localization::Locale,
+    notifications::Notifications,
+    pathfinding::PathGrid,
+    season::{Season, YEAR_LENGTH_SECS},
+    settlements::MIN_POPULATION,
+    units::spawn_unit,
+    world::{WorldSettings, nearest_copy, toroidal_distance},
+    world_gen::regions::MOUNTAIN_ELEVATION,
+};
+
+const LAIR_SIZE: f32 = 1.2;
+// Above buildings and settlement markers, below units
+const LAIR_Z: f32 = 0.5;
+const KINGDOMS_PER_LAIR: usize = 2;
+const MAX_LAIRS: usize = 6;
+const LAIR_ATTEMPTS: u32 = 2000;
+// Lairs lie close enough to a settlement to threaten it, but never on its doorstep
+const LAIR_MIN_DISTANCE: f32 = 15.0;
+const LAIR_MAX_DISTANCE: f32 = 35.0;
+const LAIR_SPACING: f32 = 40.0;
+// Share of the sea's depth below which the ocean counts as deep
+const DEEP_SEA_SHARE: f64 = 0.6;
+// Share of a raided settlement's people lost
+const LAIR_RAID_LOSS: f32 = 0.05;
+// Share of a monster's strength lost when a garrison drives it off
+const REPELLED_WOUNDS: f32 = 0.2;
+// A raided kingdom sends hunters from settlements this large, levied like an army
+const HUNT_MIN_POPULATION: f32 = 200.0;
+const HUNT_SHARE: f32 = 0.15;
+// Tiles from the lair hunters must reach to bring the monster to battle
+const HUNT_REACH: f32 = 2.0;
+// Hunters lost for each unit of strength the monster had
+const HUNT_CASUALTIES: f32 = 0.5;
+
+// How dangerous a kind of monster is and what it is after
+struct Monster {
+    strength: f32,
+    // Tiles from the lair it hunts over
+    reach: f32,
+    // Food stores it carries off, and the share of them
+    plunder: &'static [Good],
+    plunder_share: f32,
+    fame: u32,
+    color: Color,
+}
+
+const DRAGON: Monster = Monster {
+    strength: 80.0,
+    reach: 40.0,
+    plunder: &[Good::Grain, Good::Game],
+    plunder_share: 0.3,
+    fame: 3,
+    color: Color::srgb(0.9, 0.35, 0.1),
+};
+
+const SEA_SERPENT: Monster = Monster {
+    strength: 50.0,
+    reach: 25.0,
+    plunder: &[Good::Fish],
+    plunder_share: 0.5,
+    fame: 2,
+    color: Color::srgb(0.1, 0.6, 0.55),
+};
+
+fn monster(kind: LairKind) -> &'static Monster {
+    match kind {
+        LairKind::Dragon => &DRAGON,
+        LairKind::SeaSerpent => &SEA_SERPENT,
+    }
+}
+
+pub fn fantasy_world(settings: Res<WorldSettings>) -> bool {
+    settings.fantasy
+}
+
+pub fn spawn_lair(
+    commands: &mut Commands,
+    kind: LairKind,
+    tile: IVec2,
+    strength: f32,
+    raid_cooldown: f32,
+) -> Entity {
+    commands
+        .spawn((
+            Sprite::from_color(monster(kind).color, Vec2::splat(LAIR_SIZE)),
+            Transform::from_translation((tile.as_vec2() + Vec2::splat(0.5)).extend(LAIR_Z)),
+            Lair {
+                kind,
+                tile,
+                strength,
+                raid_cooldown,
+            },
+        ))
+        .id()
+}
+
+// Dragons on peaks and sea serpents in deep water within reach of the
+// settlements, one lair for every couple of kingdoms
+pub fn spawn_lairs(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    world_data_query: Query<&WorldData>,
+    kingdom_query: Query<(), With<Kingdom>>,
+    settlement_query: Query<&Settlement>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    let homes: Vec<IVec2> = settlement_query
+        .iter()
+        .map(|settlement| settlement.tile)
+        .collect();
+    if homes.is_empty() {
+        return;
+    }
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let mut rng = SmallRng::seed_from_u64(world_data.seed.wrapping_add(19) as u64);
+    let count = (kingdom_query.iter().count() / KINGDOMS_PER_LAIR).clamp(1, MAX_LAIRS);
+    let size = IVec2::splat(settings.world_size);
+    let deep_sea = MAX_ELEVATION * world_data.sea_threshold * DEEP_SEA_SHARE;
+
+    let mut lairs: Vec<IVec2> = Vec::new();
+    for attempt in 0..LAIR_ATTEMPTS {
+        if lairs.len() >= count {
+            break;
+        }
+        let kind = if attempt % 2 == 0 {
+            LairKind::Dragon
+        } else {
+            LairKind::SeaSerpent
+        };
+        let home = homes[rng.random_range(0..homes.len())];
+        let offset = Vec2::from_angle(rng.random::<f32>() * TAU)
+            * rng.random_range(LAIR_MIN_DISTANCE..LAIR_MAX_DISTANCE);
+        let tile = (home + offset.as_ivec2()).rem_euclid(size);
+        let crowded = homes
+            .iter()
+            .any(|other| toroidal_distance(tile, *other, &settings) < LAIR_MIN_DISTANCE)
+            || lairs
+                .iter()
+                .any(|other| toroidal_distance(tile, *other, &settings) < LAIR_SPACING);
+        if crowded {
+            continue;
+        }
+
+        let elevation = climate_square(&sampler, world_data, tile.x, tile.y).elevation as f64;
+        let fits = match kind {
+            LairKind::Dragon => elevation > MOUNTAIN_ELEVATION,
+            LairKind::SeaSerpent => elevation < deep_sea,
+        };
+        if !fits {
+            continue;
+        }
+
+        // Staggered so the monsters don't all strike on the same day
+        let cooldown = rng.random_range(0.0..YEAR_LENGTH_SECS);
+        spawn_lair(&mut commands, kind, tile, monster(kind).strength, cooldown);
+        lairs.push(tile);
+    }
+}
+
+// A settlement a monster can fall on, with its kingdom if it has one
+type Prey = (
+    Entity,
+    &'static Settlement,
+    &'static mut Population,
+    &'static mut Stockpile,
+    &'static FishingGrounds,
+    Option<&'static OwnedBy>,
+);
+
+// Once a year each monster falls on the nearest settlement in its reach; sea
+// serpents only trouble those that fish. The garrison may drive it off
+// wounded, but either way an AI kingdom with people to spare sends hunters
+// after it.
+pub fn raid_from_lairs(
+    mut commands: Commands,
+    (clock, season, locale): (Res<GameClock>, Res<Season>, Res<Locale>),
+    (settings, grid): (Res<WorldSettings>, Option<Res<PathGrid>>),
+    (mut chronicle, mut notifications): (ResMut<Chronicle>, ResMut<Notifications>),
+    mut lair_query: Query<(Entity, &mut Lair)>,
+    mut settlement_query: Query<Prey>,
+    (ai_query, hunt_query): (Query<(), With<AiControlled>>, Query<&HuntingParty>),
+) {
+    for (entity, mut lair) in &mut lair_query {
+        lair.raid_cooldown -= clock.delta_secs;
+        if lair.raid_cooldown > 0.0 {
+            continue;
+        }
+        let monster = monster(lair.kind);
+        let target = settlement_query
+            .iter()
+            .filter(|(.., fishing, _)| lair.kind != LairKind::SeaSerpent || fishing.water_tiles > 0)
+            .map(|(target, settlement, ..)| {
+                (
+                    target,
+                    toroidal_distance(lair.tile, settlement.tile, &settings),
+                )
+            })
+            .filter(|(_, distance)| *distance <= monster.reach)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((target, _)) = target else {
+            continue;
+        };
+        let Ok((_, settlement, mut population, mut stockpile, _, owner)) =
+            settlement_query.get_mut(target)
+        else {
+            continue;
+        };
+        lair.raid_cooldown = YEAR_LENGTH_SECS;
+
+        let garrison = raid_garrison(population.0, settlement.tile, grid.as_deref());
+        if prevails(lair.strength, garrison) {
+            population.0 = (population.0 * (1.0 - LAIR_RAID_LOSS)).max(MIN_POPULATION);
+            for good in monster.plunder {
+                stockpile.0[*good as usize] *= 1.0 - monster.plunder_share;
+            }
+            let id = format!("chronicle.{}_raids", lair.kind.key());
+            let text = locale.format(&id, &[&settlement.name]);
+            chronicle.record(locale.date(&season), ChronicleKind::Disaster, text.clone());
+            notifications.push(text);
+        } else {
+            lair.strength *= 1.0 - REPELLED_WOUNDS;
+            let id = format!("chronicle.{}_repelled", lair.kind.key());
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::Disaster,
+                locale.format(&id, &[&settlement.name]),
+            );
+        }
+
+        let Some(&OwnedBy(kingdom)) = owner else {
+            continue;
+        };
+        let hunting = hunt_query
+            .iter()
+            .any(|party| party.kingdom == kingdom && party.lair == entity);
+        if hunting || !ai_query.contains(kingdom) || population.0 < HUNT_MIN_POPULATION {
+            continue;
+        }
+        let levy = population.0 * HUNT_SHARE;
+        population.0 -= levy;
+        let hunters = spawn_unit(&mut commands, UnitKind::Hunters, settlement.tile);
+        commands.entity(hunters).insert((
+            MoveOrder::new(lair.tile),
+            HuntingParty {
+                kingdom,
+                lair: entity,
+                strength: levy,
+            },
+        ));
+    }
+}
+
+// Hunters neither on the move nor waiting on a path
+type Resting = (Without<MoveOrder>, Without<PathSearch>);
+
+// Hunters that reach the lair bring the monster to battle. Slaying it wins
+// their kingdom fame; failing, they are lost. Survivors, and parties whose
+// monster is already dead or out of reach, go back to the nearest of their
+// kingdom's settlements.
+pub fn hunt_monsters(
+    mut commands: Commands,
+    (season, locale, settings): (Res<Season>, Res<Locale>, Res<WorldSettings>),
+    mut chronicle: ResMut<Chronicle>,
+    party_query: Query<(Entity, &Unit, &HuntingParty), Resting>,
+    mut lair_query: Query<&mut Lair>,
+    mut kingdom_query: Query<(&Kingdom, &mut Fame, Option<&KingdomSettlements>)>,
+    mut settlement_query: Query<(&Settlement, &mut Population)>,
+) {
+    // Lairs despawn once commands apply, so a second party reaching one this
+    // tick must not slay it again
+    let mut slain = Vec::new();
+    for (entity, unit, party) in &party_query {
+        commands.entity(entity).despawn();
+        let Ok((kingdom, mut fame, settlements)) = kingdom_query.get_mut(party.kingdom) else {
+            continue;
+        };
+
+        let mut survivors = party.strength;
+        if !slain.contains(&party.lair)
+            && let Ok(mut lair) = lair_query.get_mut(party.lair)
+            && toroidal_distance(unit.tile, lair.tile, &settings) <= HUNT_REACH
+        {
+            let key = lair.kind.key();
+            if !prevails(party.strength, lair.strength) {
+                lair.strength *= 1.0 - REPELLED_WOUNDS;
+                chronicle.record(
+                    locale.date(&season),
+                    ChronicleKind::War,
+                    locale.format(&format!("chronicle.{}_hunt_fails", key), &[&kingdom.name]),
+                );
+                continue;
+            }
+
+            fame.0 += monster(lair.kind).fame;
+            survivors = (survivors - lair.strength * HUNT_CASUALTIES).max(0.0);
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::War,
+                locale.format(&format!("chronicle.{}_slain", key), &[&kingdom.name]),
+            );
+            commands.entity(party.lair).despawn();
+            slain.push(party.lair);
+        }
+
+        let home = settlements
+            .into_iter()
+            .flat_map(KingdomSettlements::iter)
+            .filter_map(|home| {
+                let (settlement, _) = settlement_query.get(home).ok()?;
+                Some((
+                    home,
+                    toroidal_distance(unit.tile, settlement.tile, &settings),
+                ))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(home, _)| home);
+        if let Some(home) = home
+            && let Ok((_, mut population)) = settlement_query.get_mut(home)
+        {
+            population.0 += survivors;
+        }
+    }
+}
+
+pub fn update_lair_sprites(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut lair_query: Query<(&Lair, &mut Transform), Without<Camera2d>>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for (lair, mut transform) in &mut lair_query {
+        let nearest = nearest_copy(camera, lair.tile, &settings);
+        let position = (nearest.as_vec2() + Vec2::splat(0.5)).extend(LAIR_Z);
+        if transform.translation != position {
+            transform.translation = position;
+        }
+    }
+}
+
+pub fn cleanup_lairs(mut commands: Commands, query: Query<Entity, With<Lair>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+use bevy::ui::Node;
+
+use crate::{
+    components::{
+        loading_screen::{LoadingBarFill, LoadingCancelButton, LoadingScreenUI, LoadingStageText},
+        localization::Localized,
+        world_gen::WorldData,
+    },
+    systems::{localization::Locale, world_gen::progress::WorldGenProgress},
+};
+
+pub fn setup_loading_screen(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    world_data_query: Query<&WorldData>,
+) {
+    let mut screen = commands.spawn(loading_screen(locale.text("loading.title")));
+    for world_data in &world_data_query {
+        screen.with_child((
+            Text::new(locale.seed(world_data.seed, world_data.seed_text.as_deref())),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+        ));
+    }
+    screen.with_child((
+            Button,
+            Node {
+                margin: UiRect::top(Val::Px(16.0)),
+                padding: UiRect::all(Val::Px(12.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            LoadingCancelButton,
+            children![(
+                Text::new("Cancel"),
+                Localized::new("loading.cancel"),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE)
+            )],
+        ));
+}
+
+pub fn setup_splash_screen(mut commands: Commands) {
+    commands.spawn(loading_screen("Kingdom Sim"));
+}
+
+fn loading_screen(title: &str) -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(16.0),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK),
+        LoadingScreenUI,
+        children![
+            (
+                Text::new(title),
+                TextFont {
+                    font_size: 32.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ),
+            (
+                Node {
+                    width: Val::Px(480.0),
+                    height: Val::Px(24.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                children![(
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.3, 0.6, 0.3)),
+                    LoadingBarFill,
+                )],
+            ),
+            (
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                LoadingStageText,
+            )
+        ],
+    )
+}
+
+pub fn update_loading_screen(
+    progress: Option<Res<WorldGenProgress>>,
+    locale: Res<Locale>,
+    mut bar_query: Query<&mut Node, With<LoadingBarFill>>,
+    mut text_query: Query<&mut Text, With<LoadingStageText>>,
+) {
+    let Some(progress) = progress else {
+        return;
+    };
+    let current = progress.current();
+    let stage = current.stage;
+
+    show_progress(
+        current.overall(),
+        &locale.format(
+            "loading.stage",
+            &[
+                &locale.name("stage", stage, stage.label()),
+                &(current.fraction.clamp(0.0, 1.0) * 100.0).round(),
+            ],
+        ),
+        &mut bar_query,
+        &mut text_query,
+    );
+}
+
+pub fn show_progress(
+    fraction: f32,
+    label: &str,
+    bar_query: &mut Query<&mut Node, With<LoadingBarFill>>,
+    text_query: &mut Query<&mut Text, With<LoadingStageText>>,
+) {
+    for mut node in bar_query.iter_mut() {
+        node.width = Val::Percent(fraction.clamp(0.0, 1.0) * 100.0);
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.0 = label.to_string();
+    }
+}
+
+pub fn cleanup_loading_screen(mut commands: Commands, query: Query<Entity, With<LoadingScreenUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
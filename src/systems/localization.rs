@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Display};
+use std::fs;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::components::localization::Localized;
+use crate::components::world::Biome;
+use crate::systems::{options::GameOptions, season::Season};
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+const LOCALES_DIR: &str = "assets/locales";
+// Compiled in so every string ID has text even without the assets folder
+const BUILT_IN_ENGLISH: &str = include_str!("../../assets/locales/en.ron");
+
+#[derive(Deserialize)]
+struct LocaleFile {
+    name: String,
+    strings: HashMap<String, String>,
+}
+
+// UI text for the chosen language, by string ID. IDs the language file leaves
+// out keep their English text.
+#[derive(Resource)]
+pub struct Locale {
+    pub code: String,
+    // The language's own name for itself, as shown in the options
+    pub name: String,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn get(&self, id: &str) -> Option<&str> {
+        self.strings.get(id).map(String::as_str)
+    }
+
+    // An unknown ID shows as itself, so a typo is visible rather than blank
+    pub fn text<'a>(&'a self, id: &'a str) -> &'a str {
+        self.get(id).unwrap_or(id)
+    }
+
+    // Fills each `{}` in the text, in order
+    pub fn format(&self, id: &str, args: &[&dyn Display]) -> String {
+        args.iter().fold(self.text(id).to_string(), |text, arg| {
+            text.replacen("{}", &arg.to_string(), 1)
+        })
+    }
+
+    // The name of an enum value, under `group.Variant`, or its English name
+    pub fn name<'a>(&'a self, group: &str, value: impl Debug, english: &'a str) -> &'a str {
+        self.get(&format!("{}.{:?}", group, value)).unwrap_or(english)
+    }
+
+    pub fn biome(&self, biome: Biome) -> &str {
+        self.name("biome", biome, biome.name())
+    }
+
+    pub fn season<'a>(&'a self, season: &'a str) -> &'a str {
+        self.get(&format!("season.{}", season)).unwrap_or(season)
+    }
+
+    pub fn date(&self, season: &Season) -> String {
+        let month = season.month_name();
+        let month = self.get(&format!("month.{}", month)).unwrap_or(month);
+        self.format("calendar.date", &[&season.day(), &month, &season.year])
+    }
+
+    // A seed typed as a phrase shows the phrase and the number it hashed to
+    pub fn seed(&self, seed: u32, text: Option<&str>) -> String {
+        match text {
+            Some(text) => self.format("seed.phrase", &[&text, &seed]),
+            None => self.format("seed.number", &[&seed]),
+        }
+    }
+}
+
+// Language codes with a file in the locales folder, English first
+pub fn language_codes() -> Vec<String> {
+    let mut codes = vec![DEFAULT_LANGUAGE.to_string()];
+
+    let Ok(entries) = fs::read_dir(LOCALES_DIR) else {
+        return codes;
+    };
+    let mut found: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .filter(|code| !codes.contains(code))
+        .collect();
+    found.sort();
+    codes.extend(found);
+
+    codes
+}
+
+// A language that is missing or unreadable falls back to English
+pub fn load_locale(code: &str) -> Locale {
+    let english: LocaleFile = ron::from_str(BUILT_IN_ENGLISH).unwrap();
+    let mut locale = Locale {
+        code: DEFAULT_LANGUAGE.to_string(),
+        name: english.name,
+        strings: english.strings,
+    };
+    if code == DEFAULT_LANGUAGE {
+        return locale;
+    }
+
+    let path = format!("{}/{}.ron", LOCALES_DIR, code);
+    let file = fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| ron::from_str::<LocaleFile>(&text).map_err(|err| err.to_string()));
+    match file {
+        Ok(file) => {
+            locale.code = code.to_string();
+            locale.name = file.name;
+            locale.strings.extend(file.strings);
+        }
+        Err(err) => warn!("Using English; {}: {}", path, err),
+    }
+
+    locale
+}
+
+pub fn apply_language(options: Res<GameOptions>, mut locale: ResMut<Locale>) {
+    if options.language != locale.code {
+        *locale = load_locale(&options.language);
+    }
+}
+
+// Fills in text that is new or has a new ID, and all of it again when the
+// language changes
+pub fn localize_texts(locale: Res<Locale>, mut query: Query<(Ref<Localized>, &mut Text)>) {
+    for (localized, mut text) in &mut query {
+        if locale.is_changed() || localized.is_changed() {
+            text.0 = locale.text(&localized.0).to_string();
+        }
+    }
+}
@@ -1,9 +1,24 @@
 use bevy::prelude::*;
 use bevy::ui::Node;
 
-use crate::{components::main_menu::{MainMenuAction, MainMenuUI}, states::game_state::GameState};
+use crate::{
+    components::{
+        localization::Localized,
+        main_menu::{LoadGameStatus, MainMenuAction, MainMenuUI, SavedWorldRecord},
+    },
+    states::game_state::GameState,
+    systems::{
+        localization::Locale,
+        options::{GameOptions, open_options_page},
+        save_game::{LoadGameTask, save_exists, saved_record, start_loading_game},
+    },
+};
 
 pub fn setup_main_menu(mut commands: Commands) {
+    spawn_main_menu(&mut commands);
+}
+
+pub fn spawn_main_menu(commands: &mut Commands) {
     commands.spawn((
         Node {
             width: Val::Percent(100.0),
@@ -26,6 +41,55 @@ pub fn setup_main_menu(mut commands: Commands) {
             MainMenuAction::NewGame,
             children![(
                 Text::new("New Game"),
+                Localized::new("menu.new_game"),
+                TextFont {
+                    font_size: 32.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE)
+            )]
+        ),
+        (
+            Button,
+            Node {
+                padding: UiRect::all(Val::Px(20.0)),
+                // Nothing to load until a world has been saved
+                display: if save_exists() { Display::Flex } else { Display::None },
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            MainMenuAction::LoadGame,
+            children![(
+                Text::new("Load Game"),
+                Localized::new("menu.load_game"),
+                TextFont {
+                    font_size: 32.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE)
+            )]
+        ),
+        (
+            Text::new(""),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            TextLayout::new_with_justify(Justify::Center),
+            SavedWorldRecord
+        ),
+        (
+            Button,
+            Node {
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            MainMenuAction::Options,
+            children![(
+                Text::new("Options"),
+                Localized::new("menu.options"),
                 TextFont {
                     font_size: 32.0,
                     ..default()
@@ -37,25 +101,57 @@ pub fn setup_main_menu(mut commands: Commands) {
             Button,
             Node {
                 padding: UiRect::all(Val::Px(20.0)),
+                // A page can't be closed from inside it
+                display: if cfg!(target_arch = "wasm32") { Display::None } else { Display::Flex },
                 ..default()
             },
             BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
             MainMenuAction::Quit,
             children![(
                 Text::new("Quit"),
+                Localized::new("menu.quit"),
                 TextFont {
                     font_size: 32.0,
                     ..default()
                 },
                 TextColor(Color::WHITE)
             )]
+        ),
+        (
+            Text::new(""),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.9, 0.4, 0.4)),
+            LoadGameStatus
         )]
     ));
 }
 
+// Fills in the saved world's record when the menu opens; left empty when
+// there is no readable save
+pub fn show_saved_record(
+    locale: Res<Locale>,
+    mut text_query: Query<&mut Text, Added<SavedWorldRecord>>,
+) {
+    for mut text in &mut text_query {
+        if let Ok((years, record)) = saved_record() {
+            text.0 = record.lines(years, &locale).join("\n");
+        }
+    }
+}
+
+type ButtonChanged = (Changed<Interaction>, With<Button>);
+
 pub fn main_menu_buttons(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
-    mut interaction_query: Query<(&Interaction, &MainMenuAction), (Changed<Interaction>, With<Button>)>,
+    loading: Option<Res<LoadGameTask>>,
+    mut exit: MessageWriter<AppExit>,
+    options: Res<GameOptions>,
+    menu_query: Query<Entity, With<MainMenuUI>>,
+    mut interaction_query: Query<(&Interaction, &MainMenuAction), ButtonChanged>,
 ) {
     for (interaction, action) in &mut interaction_query {
         if *interaction == Interaction::Pressed {
@@ -63,8 +159,18 @@ pub fn main_menu_buttons(
                 MainMenuAction::NewGame => {
                     next_state.set(GameState::WorldGenSetup);
                 },
+                MainMenuAction::LoadGame if loading.is_none() => {
+                    start_loading_game(&mut commands);
+                },
+                MainMenuAction::LoadGame => {},
+                MainMenuAction::Options => {
+                    for entity in &menu_query {
+                        commands.entity(entity).despawn();
+                    }
+                    open_options_page(&mut commands, &options, MainMenuUI);
+                },
                 MainMenuAction::Quit => {
-                    std::process::exit(0);
+                    exit.write(AppExit::Success);
                 }
             }
         }
@@ -0,0 +1,423 @@
+use std::path::PathBuf;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use image::ExtendedColorType;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        kingdoms::{Kingdom, Road},
+        settlements::Settlement,
+        world::WorldMap,
+        world_gen::WorldData,
+    },
+    systems::{
+        export::{export_directory, map_colors, streamed_map_colors, write_png},
+        keybindings::{InputAction, InputMap},
+        kingdoms::{TerritoryMap, TerritorySnapshot},
+        options::GameOptions,
+        palette::MapPalette,
+        world::WorldSettings,
+    },
+};
+
+// Captures wider than this would need gigabytes to hold
+const MAX_CAPTURE_SIDE: usize = 16384;
+const TERRITORY_ALPHA: f32 = 0.45;
+const BORDER_SHADE: f32 = 0.5;
+const ROAD_COLOR: [u8; 3] = [120, 84, 52];
+const LABEL_COLOR: [u8; 3] = [255, 255, 255];
+const LABEL_SHADOW: [u8; 3] = [0, 0, 0];
+
+// Capital letters five pixels wide and seven tall, rows top to bottom with the
+// leftmost column in bit 4
+const GLYPH_WIDTH: i32 = 5;
+const GLYPH_HEIGHT: i32 = 7;
+const GLYPHS: [[u8; 7]; 26] = [
+    [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+    [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+    [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+];
+
+// What gets drawn over the terrain, each level adding to the one before
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CaptureOverlays {
+    None,
+    Borders,
+    #[default]
+    BordersAndRoads,
+    All,
+}
+
+impl CaptureOverlays {
+    pub const ALL: [CaptureOverlays; 4] = [
+        CaptureOverlays::None,
+        CaptureOverlays::Borders,
+        CaptureOverlays::BordersAndRoads,
+        CaptureOverlays::All,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CaptureOverlays::None => "Terrain only",
+            CaptureOverlays::Borders => "Borders",
+            CaptureOverlays::BordersAndRoads => "Borders, roads",
+            CaptureOverlays::All => "Borders, roads, names",
+        }
+    }
+
+    fn borders(self) -> bool {
+        self != CaptureOverlays::None
+    }
+
+    fn roads(self) -> bool {
+        matches!(self, CaptureOverlays::BordersAndRoads | CaptureOverlays::All)
+    }
+
+    fn labels(self) -> bool {
+        self == CaptureOverlays::All
+    }
+}
+
+// Set by a hotkey or the pause menu; the capture starts on the next frame
+#[derive(Resource)]
+pub struct MapCaptureRequest;
+
+#[derive(Resource)]
+pub struct MapCaptureTask(Task<Result<PathBuf, String>>);
+
+// Everything drawn over the terrain, copied out of the world so the image can be
+// put together off the main thread
+pub struct CaptureOverlayData {
+    territory: Option<TerritorySnapshot>,
+    kingdom_colors: HashMap<Entity, [f32; 3]>,
+    roads: Vec<Vec<IVec2>>,
+    // Settlement names and tiles
+    labels: Vec<(String, IVec2)>,
+}
+
+// The whole world as one image, whatever part of it the camera shows
+struct Capture {
+    side: usize,
+    world_size: i32,
+    tiles_per_pixel: f32,
+    pixels: Vec<u8>,
+}
+
+impl Capture {
+    // Tile a column or row of pixels falls on, counted from the west or north edge
+    fn tile_at(&self, pixel: usize) -> i32 {
+        (((pixel as f32 + 0.5) * self.tiles_per_pixel) as i32).min(self.world_size - 1)
+    }
+
+    // Base world tile under a pixel
+    fn tile(&self, x: usize, y: usize) -> IVec2 {
+        IVec2::new(self.tile_at(x), self.world_size - 1 - self.tile_at(y))
+    }
+
+    // Pixels a tile covers, as the first and last column and row
+    fn pixel_span(&self, tile: IVec2) -> (usize, usize, usize, usize) {
+        let span = |from: i32| {
+            let first = (from as f32 / self.tiles_per_pixel) as usize;
+            let last = (((from + 1) as f32 / self.tiles_per_pixel).ceil() as usize).max(first + 1);
+            (first.min(self.side - 1), (last - 1).min(self.side - 1))
+        };
+        let (x0, x1) = span(tile.x);
+        let (y0, y1) = span(self.world_size - 1 - tile.y);
+
+        (x0, x1, y0, y1)
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: [u8; 3]) {
+        if x < 0 || y < 0 || x as usize >= self.side || y as usize >= self.side {
+            return;
+        }
+        let index = (y as usize * self.side + x as usize) * 4;
+        self.pixels[index..index + 3].copy_from_slice(&color);
+    }
+
+    fn blend(&mut self, x: usize, y: usize, color: [f32; 3], alpha: f32) {
+        let index = (y * self.side + x) * 4;
+        for (channel, target) in self.pixels[index..index + 3].iter_mut().zip(color) {
+            let mixed = *channel as f32 / 255.0 * (1.0 - alpha) + target * alpha;
+            *channel = (mixed.clamp(0.0, 1.0) * 255.0) as u8;
+        }
+    }
+
+    // Tints held land with its kingdom's colour and darkens the pixels along
+    // frontiers. Owners are looked up a row at a time to keep memory flat.
+    fn draw_territory(
+        &mut self,
+        territory: &TerritorySnapshot,
+        colors: &HashMap<Entity, [f32; 3]>,
+    ) {
+        let owners_of_row = |capture: &Capture, y: usize| -> Vec<Option<Entity>> {
+            (0..capture.side)
+                .map(|x| territory.owner_at(capture.tile(x, y)))
+                .collect()
+        };
+
+        let side = self.side;
+        let mut above = owners_of_row(self, side - 1);
+        let mut row = owners_of_row(self, 0);
+        for y in 0..side {
+            let below = owners_of_row(self, (y + 1) % side);
+            for x in 0..side {
+                let Some(owner) = row[x] else {
+                    continue;
+                };
+                let Some(&color) = colors.get(&owner) else {
+                    continue;
+                };
+                let (left, right) = (row[(x + side - 1) % side], row[(x + 1) % side]);
+                if [left, right, above[x], below[x]].iter().any(|&other| other != row[x]) {
+                    self.blend(x, y, color.map(|channel| channel * BORDER_SHADE), 1.0);
+                } else {
+                    self.blend(x, y, color, TERRITORY_ALPHA);
+                }
+            }
+            above = std::mem::replace(&mut row, below);
+        }
+    }
+
+    fn draw_road(&mut self, tiles: &[IVec2]) {
+        for tile in tiles {
+            let (x0, x1, y0, y1) = self.pixel_span(*tile);
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    self.set(x as i32, y as i32, ROAD_COLOR);
+                }
+            }
+        }
+    }
+
+    // A dot on the settlement with its name in capitals beside it, shadowed so it
+    // reads over any terrain
+    fn draw_label(&mut self, name: &str, tile: IVec2) {
+        let (x0, x1, y0, y1) = self.pixel_span(tile);
+        let centre = IVec2::new(((x0 + x1) / 2) as i32, ((y0 + y1) / 2) as i32);
+        for (offset, color) in [(IVec2::ONE, LABEL_SHADOW), (IVec2::ZERO, LABEL_COLOR)] {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    self.set(centre.x + dx + offset.x, centre.y + dy + offset.y, color);
+                }
+            }
+
+            let mut left = centre.x + 4 + offset.x;
+            let top = centre.y - GLYPH_HEIGHT / 2 + offset.y;
+            for character in name.chars() {
+                self.draw_glyph(character, left, top, color);
+                left += GLYPH_WIDTH + 1;
+            }
+        }
+    }
+
+    // Letters only; anything else leaves a gap
+    fn draw_glyph(&mut self, character: char, left: i32, top: i32, color: [u8; 3]) {
+        let upper = character.to_ascii_uppercase();
+        if !upper.is_ascii_uppercase() {
+            return;
+        }
+
+        let glyph = GLYPHS[(upper as u8 - b'A') as usize];
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (0x10 >> column) != 0 {
+                    self.set(left + column, top + row as i32, color);
+                }
+            }
+        }
+    }
+}
+
+pub fn request_map_capture(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+) {
+    // Captures are written beside the map exports, which the web build can't make
+    if cfg!(target_arch = "wasm32") {
+        return;
+    }
+    if input_map.just_pressed(&input, InputAction::CaptureMap) {
+        commands.insert_resource(MapCaptureRequest);
+    }
+}
+
+// Terrain from a pregenerated map is painted here like a map export; a streamed
+// world is generated on the task. Overlays are copied out now so the capture
+// shows the world as it stood when asked for.
+pub fn start_map_capture(
+    mut commands: Commands,
+    options: Res<GameOptions>,
+    (settings, map_palette): (Res<WorldSettings>, Res<MapPalette>),
+    running: Option<Res<MapCaptureTask>>,
+    territory: Option<Res<TerritoryMap>>,
+    (world_data_query, world_map_query): (Query<&WorldData>, Query<&WorldMap>),
+    (kingdom_query, road_query, settlement_query): (
+        Query<(Entity, &Kingdom)>,
+        Query<&Road>,
+        Query<&Settlement>,
+    ),
+) {
+    commands.remove_resource::<MapCaptureRequest>();
+    if running.is_some() {
+        warn!("A map capture is already running");
+        return;
+    }
+
+    let world_data = match world_data_query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let settings = *settings;
+    let tiles_per_pixel = options.capture_scale;
+    let side = (settings.world_size as f32 / tiles_per_pixel).round() as usize;
+    if side > MAX_CAPTURE_SIDE {
+        error!(
+            "A {0}x{0} capture is too large; choose fewer pixels per tile in the options",
+            side
+        );
+        return;
+    }
+
+    let overlay_data = capture_overlays(
+        options.capture_overlays,
+        territory.as_deref(),
+        kingdom_query.iter(),
+        road_query.iter(),
+        settlement_query.iter(),
+    );
+
+    let palette = map_palette.palette.clone();
+    let painted = world_map_query
+        .single()
+        .ok()
+        .map(|world_map| map_colors(world_map, &world_data, &palette));
+    let directory = export_directory(world_data.seed);
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let terrain =
+            painted.unwrap_or_else(|| streamed_map_colors(&world_data, &settings, &palette));
+        let pixels = compose_capture(&terrain, &settings, tiles_per_pixel, side, &overlay_data);
+
+        std::fs::create_dir_all(&directory).map_err(|err| err.to_string())?;
+        let path = directory.join("capture.png");
+        write_png(&path, side as u32, &pixels, ExtendedColorType::Rgba8)?;
+        Ok(path)
+    });
+
+    info!("Capturing the map at {0}x{0}...", side);
+    commands.insert_resource(MapCaptureTask(task));
+}
+
+pub fn poll_map_capture(mut commands: Commands, mut task: ResMut<MapCaptureTask>) {
+    let Some(result) = check_ready(&mut task.0) else {
+        return;
+    };
+    commands.remove_resource::<MapCaptureTask>();
+
+    match result {
+        Ok(path) => info!("Map captured to {}", path.display()),
+        Err(err) => error!("Map capture failed: {}", err),
+    }
+}
+
+// Copies out the overlays the chosen level draws
+pub fn capture_overlays<'a>(
+    overlays: CaptureOverlays,
+    territory: Option<&TerritoryMap>,
+    kingdoms: impl Iterator<Item = (Entity, &'a Kingdom)>,
+    roads: impl Iterator<Item = &'a Road>,
+    settlements: impl Iterator<Item = &'a Settlement>,
+) -> CaptureOverlayData {
+    CaptureOverlayData {
+        territory: territory
+            .filter(|_| overlays.borders())
+            .map(|territory| territory.snapshot()),
+        kingdom_colors: kingdoms
+            .map(|(entity, kingdom)| {
+                let color = kingdom.color.to_srgba();
+                (entity, [color.red, color.green, color.blue])
+            })
+            .collect(),
+        roads: if overlays.roads() {
+            roads.map(|road| road.tiles.clone()).collect()
+        } else {
+            Vec::new()
+        },
+        labels: if overlays.labels() {
+            settlements
+                .map(|settlement| (settlement.name.clone(), settlement.tile))
+                .collect()
+        } else {
+            Vec::new()
+        },
+    }
+}
+
+// Scales the one-pixel-per-tile terrain to the capture's size, then draws the
+// overlays on top. Four bytes to a pixel, rows from the north edge down.
+pub fn compose_capture(
+    terrain: &[u8],
+    settings: &WorldSettings,
+    tiles_per_pixel: f32,
+    side: usize,
+    overlays: &CaptureOverlayData,
+) -> Vec<u8> {
+    let world_size = settings.world_size;
+    let mut capture = Capture {
+        side,
+        world_size,
+        tiles_per_pixel,
+        pixels: vec![0; side * side * 4],
+    };
+
+    for y in 0..side {
+        let row = capture.tile_at(y) as usize;
+        for x in 0..side {
+            let source = (row * world_size as usize + capture.tile_at(x) as usize) * 4;
+            let target = (y * side + x) * 4;
+            capture.pixels[target..target + 4].copy_from_slice(&terrain[source..source + 4]);
+        }
+    }
+
+    if let Some(territory) = &overlays.territory {
+        capture.draw_territory(territory, &overlays.kingdom_colors);
+    }
+    for road in &overlays.roads {
+        capture.draw_road(road);
+    }
+    for (name, tile) in &overlays.labels {
+        capture.draw_label(name, *tile);
+    }
+
+    capture.pixels
+}
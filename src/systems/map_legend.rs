@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::components::map_legend::{MapLegendText, MapLegendUI};
+use crate::components::world_gen::WorldData;
+use crate::systems::localization::Locale;
+use crate::systems::world::{MapOverlay, heatmap_color};
+
+// Number of colour swatches the gradient bar is drawn with
+const LEGEND_SEGMENTS: usize = 24;
+const LEGEND_WIDTH: f32 = 240.0;
+
+pub fn setup_map_legend(mut commands: Commands) {
+    let segment_width = LEGEND_WIDTH / LEGEND_SEGMENTS as f32;
+    let swatches = (0..LEGEND_SEGMENTS).map(move |i| {
+        let t = (i as f32 + 0.5) / LEGEND_SEGMENTS as f32;
+        let [r, g, b, a] = heatmap_color(t);
+        (
+            Node {
+                width: Val::Px(segment_width),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(r, g, b, a)),
+        )
+    });
+
+    commands.spawn((
+        MapLegendUI,
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Visibility::Hidden,
+        children![
+            (
+                MapLegendText::Title,
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ),
+            (
+                Node {
+                    width: Val::Px(LEGEND_WIDTH),
+                    height: Val::Px(14.0),
+                    ..default()
+                },
+                Children::spawn(SpawnIter(swatches)),
+            ),
+            (
+                Node {
+                    width: Val::Px(LEGEND_WIDTH),
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                },
+                children![
+                    (
+                        MapLegendText::Min,
+                        Text::new(""),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ),
+                    (
+                        MapLegendText::Max,
+                        Text::new(""),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ),
+                ],
+            ),
+        ],
+    ));
+}
+
+// Shows the legend only while a heatmap overlay is active and relabels it
+// whenever the overlay or the language changes
+pub fn update_map_legend(
+    overlay: Res<MapOverlay>,
+    locale: Res<Locale>,
+    world_data_query: Query<&WorldData>,
+    mut legend_query: Query<&mut Visibility, With<MapLegendUI>>,
+    mut text_query: Query<(&mut Text, &MapLegendText)>,
+) {
+    if !overlay.is_changed() && !locale.is_changed() {
+        return;
+    }
+
+    let (Ok(mut visibility), Ok(world_data)) =
+        (legend_query.single_mut(), world_data_query.single())
+    else {
+        return;
+    };
+
+    let Some(scale) = overlay.heatmap_scale(world_data.sea_level() as f32) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    *visibility = Visibility::Visible;
+
+    for (mut text, line) in &mut text_query {
+        text.0 = match line {
+            MapLegendText::Title => format!("{} ({})", locale.text(scale.label), scale.unit),
+            MapLegendText::Min => format!("{:.0} {}", scale.min, scale.unit),
+            MapLegendText::Max => format!("{:.0} {}", scale.max, scale.unit),
+        };
+    }
+}
+
+pub fn cleanup_map_legend(mut commands: Commands, query: Query<Entity, With<MapLegendUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
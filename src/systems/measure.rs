@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+
+use crate::components::measure::MeasureUI;
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::localization::Locale;
+use crate::systems::pause_menu::Pause;
+use crate::systems::world::{WorldSettings, cursor_tile, toroidal_delta, toroidal_distance, wrap};
+
+const MEASURE_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+
+// While on, left clicks pick the two ends of a measurement instead of selecting
+// tiles. Ends are kept wrapped into the base world.
+#[derive(Resource, Default)]
+pub struct MeasureTool {
+    pub active: bool,
+    from: Option<IVec2>,
+    to: Option<IVec2>,
+}
+
+pub fn measure_tool_inactive(measure: Res<MeasureTool>) -> bool {
+    !measure.active
+}
+
+pub fn setup_measure_tool(mut commands: Commands) {
+    commands.spawn((
+        MeasureUI,
+        Text::new(""),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Visibility::Hidden,
+    ));
+}
+
+pub fn toggle_measure_tool(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut measure: ResMut<MeasureTool>,
+) {
+    if input_map.just_pressed(&input, InputAction::MeasureDistance) {
+        *measure = MeasureTool {
+            active: !measure.active,
+            ..default()
+        };
+    }
+}
+
+// A click after a finished measurement starts the next one; right click clears
+pub fn pick_measure_tiles(
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    window_query: Single<&Window>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pause: Res<Pause>,
+    settings: Res<WorldSettings>,
+    mut measure: ResMut<MeasureTool>,
+) {
+    if !measure.active || pause.menu_open {
+        return;
+    }
+
+    if mouse.just_pressed(MouseButton::Right) {
+        measure.from = None;
+        measure.to = None;
+    }
+
+    let (camera, camera_transform) = *camera_query;
+    if mouse.just_pressed(MouseButton::Left)
+        && let Some(tile) = cursor_tile(camera, camera_transform, &window_query)
+    {
+        let size = settings.world_size;
+        let tile = IVec2::new(wrap(tile.x, size), wrap(tile.y, size));
+        match (measure.from, measure.to) {
+            (Some(_), None) => measure.to = Some(tile),
+            _ => {
+                measure.from = Some(tile);
+                measure.to = None;
+            }
+        }
+    }
+}
+
+pub fn update_measure_readout(
+    measure: Res<MeasureTool>,
+    settings: Res<WorldSettings>,
+    locale: Res<Locale>,
+    mut readout: Single<(&mut Text, &mut Visibility), With<MeasureUI>>,
+) {
+    if !measure.is_changed() && !locale.is_changed() {
+        return;
+    }
+
+    let (text, visibility) = &mut *readout;
+    **visibility = if measure.active {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    text.0 = match (measure.from, measure.to) {
+        (Some(from), Some(to)) => {
+            let delta = toroidal_delta(from, to, &settings);
+            locale.format(
+                "measure.distance",
+                &[
+                    &format!("{:.1}", toroidal_distance(from, to, &settings)),
+                    &delta.x.abs(),
+                    &delta.y.abs(),
+                ],
+            )
+        }
+        (Some(_), None) => locale.text("measure.second_tile").to_string(),
+        _ => locale.text("measure.first_tile").to_string(),
+    };
+}
+
+// The shortest line between the ends, which may leave one edge of the world and
+// come back in at the other, on each copy of the world around the camera
+pub fn draw_measure_line(
+    mut gizmos: Gizmos,
+    camera_query: Single<&Transform, With<Camera2d>>,
+    measure: Res<MeasureTool>,
+    settings: Res<WorldSettings>,
+) {
+    let Some(from) = measure.from.filter(|_| measure.active) else {
+        return;
+    };
+    let start = from.as_vec2() + Vec2::splat(0.5);
+    let end = match measure.to {
+        Some(to) => start + toroidal_delta(from, to, &settings).as_vec2(),
+        None => start,
+    };
+
+    let world_size = settings.world_size as f32;
+    let camera = camera_query.translation.truncate();
+    let origin = (camera / world_size).floor() * world_size;
+    for x in -1..=1 {
+        for y in -1..=1 {
+            let offset = origin + Vec2::new(x as f32, y as f32) * world_size;
+            gizmos.line_2d(start + offset, end + offset, MEASURE_COLOR);
+            gizmos.circle_2d(start + offset, 0.5, MEASURE_COLOR);
+            if measure.to.is_some() {
+                gizmos.circle_2d(end + offset, 0.5, MEASURE_COLOR);
+            }
+        }
+    }
+}
+
+pub fn cleanup_measure_tool(
+    mut commands: Commands,
+    mut measure: ResMut<MeasureTool>,
+    query: Query<Entity, With<MeasureUI>>,
+) {
+    *measure = MeasureTool::default();
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
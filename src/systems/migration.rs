@@ -0,0 +1,360 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    kingdoms::{Culture, FoundingParty, Kingdom, KingdomSettlements, OwnedBy, Road},
+    settlements::{
+        FishingGrounds, FoodCapacity, Good, MigrantGroup, Population, ProductionRates, Settlement,
+        Stockpile,
+    },
+    units::{MoveOrder, Unit, UnitKind},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    pathfinding::straight_path,
+    season::Season,
+    units::spawn_unit,
+    world::{WorldSettings, toroidal_distance},
+    world_gen::{
+        names::place_name,
+        settlements::{MIN_SETTLEMENT_SPACING, local_landform, survey_site},
+    },
+};
+
+// People weigh up leaving a few times a year
+const MIGRATION_TICK_SECS: f32 = 20.0;
+// Share of an overcrowded or hungry settlement that leaves at once
+const MIGRANT_SHARE: f32 = 0.15;
+// Groups smaller than this stay put
+const MIN_MIGRANTS: f32 = 10.0;
+// Stores holding less food than this many years for everyone count as famine
+const FAMINE_STORE_YEARS: f32 = 0.05;
+const MAX_MIGRATION_DISTANCE: f32 = 150.0;
+// Room for newcomers counts for more among their own kingdom, and every tile
+// of the journey takes a little off
+const KINSHIP_WEIGHT: f32 = 2.0;
+const DISTANCE_WEIGHT: f32 = 1.0;
+// Sites tried when nowhere will take them in, in a ring around home
+const CANDIDATE_SITES: usize = 8;
+const FOUNDING_MIN_DISTANCE: f32 = 60.0;
+const FOUNDING_MAX_DISTANCE: f32 = 100.0;
+
+#[derive(Resource, Default)]
+pub struct MigrationTimer {
+    pub cooldown: f32,
+}
+
+// What a settlement's people weigh up before leaving it
+type MigrationSource = (
+    Entity,
+    &'static Settlement,
+    &'static mut Population,
+    &'static FoodCapacity,
+    &'static Stockpile,
+    Option<&'static OwnedBy>,
+);
+
+// A settlement, its tile, the room it has left, its kingdom and whether it is in want
+type Place = (Entity, IVec2, f32, Option<Entity>, bool);
+
+// Each overcrowded or famine-struck settlement with no migrants already on the
+// road sends a share of its people to the settlement with the most room to
+// feed them, preferring their own kingdom and nearer places. When none has
+// room they set out to found a settlement of their own.
+pub fn send_migrants(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    settings: Res<WorldSettings>,
+    (mut timer, territory): (ResMut<MigrationTimer>, Option<Res<TerritoryMap>>),
+    world_data_query: Query<&WorldData>,
+    mut settlement_query: Query<MigrationSource>,
+    migrant_query: Query<&MigrantGroup>,
+) {
+    timer.cooldown -= clock.delta_secs;
+    if timer.cooldown > 0.0 {
+        return;
+    }
+    timer.cooldown = MIGRATION_TICK_SECS;
+
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    let mut rng = rand::rng();
+
+    // Room each settlement has left once the migrants already headed there
+    // arrive, and whether it is in want itself
+    let mut places: Vec<Place> = settlement_query
+        .iter()
+        .map(
+            |(entity, settlement, population, capacity, stockpile, owner)| {
+                let incoming: f32 = migrant_query
+                    .iter()
+                    .filter(|group| group.destination == Some(entity))
+                    .map(|group| group.population)
+                    .sum();
+                let food: f32 = [Good::Grain, Good::Fish, Good::Game]
+                    .iter()
+                    .map(|good| stockpile.get(*good))
+                    .sum();
+                let overcrowded = population.0 > capacity.0;
+                let hungry = food < population.0 * FAMINE_STORE_YEARS;
+                (
+                    entity,
+                    settlement.tile,
+                    capacity.0 - population.0 - incoming,
+                    owner.map(|owner| owner.0),
+                    overcrowded || hungry,
+                )
+            },
+        )
+        .collect();
+
+    for index in 0..places.len() {
+        let (home, tile, _, kingdom, in_want) = places[index];
+        if !in_want || migrant_query.iter().any(|group| group.from == home) {
+            continue;
+        }
+        let Ok((_, _, population, ..)) = settlement_query.get(home) else {
+            continue;
+        };
+        let migrants = population.0 * MIGRANT_SHARE;
+        if migrants < MIN_MIGRANTS {
+            continue;
+        }
+
+        let destination = places
+            .iter()
+            .enumerate()
+            .filter(|(_, (entity, .., room, _, in_want))| {
+                *entity != home && !in_want && *room >= migrants
+            })
+            .map(|(other, &(_, other_tile, room, owner, _))| {
+                let distance = toroidal_distance(tile, other_tile, &settings);
+                let kinship = if owner.is_some() && owner == kingdom {
+                    KINSHIP_WEIGHT
+                } else {
+                    1.0
+                };
+                (
+                    other,
+                    other_tile,
+                    distance,
+                    room * kinship - DISTANCE_WEIGHT * distance,
+                )
+            })
+            .filter(|(_, _, distance, score)| *distance <= MAX_MIGRATION_DISTANCE && *score > 0.0)
+            .max_by(|(.., a), (.., b)| a.total_cmp(b));
+
+        if let Some((other, goal, ..)) = destination {
+            places[other].2 -= migrants;
+            let unit = spawn_unit(&mut commands, UnitKind::Migrants, tile);
+            commands.entity(unit).insert((
+                MoveOrder::new(goal),
+                MigrantGroup {
+                    from: home,
+                    destination: Some(places[other].0),
+                    population: migrants,
+                },
+            ));
+        } else {
+            // A new settlement is claimed for the kingdom they leave; people
+            // from a settlement no kingdom holds have no one to found it for
+            let Some(kingdom) = kingdom else {
+                continue;
+            };
+            let site = (0..CANDIDATE_SITES)
+                .filter_map(|candidate| {
+                    let angle = (candidate as f32 + rng.random::<f32>()) / CANDIDATE_SITES as f32;
+                    let distance = rng.random_range(FOUNDING_MIN_DISTANCE..FOUNDING_MAX_DISTANCE);
+                    let offset = Vec2::from_angle(angle * TAU) * distance;
+                    let site_tile =
+                        (tile + offset.as_ivec2()).rem_euclid(IVec2::splat(settings.world_size));
+
+                    let crowded = places.iter().any(|(_, other, ..)| {
+                        toroidal_distance(site_tile, *other, &settings) < MIN_SETTLEMENT_SPACING
+                    });
+                    let foreign = territory
+                        .as_deref()
+                        .and_then(|territory| territory.owner_at(site_tile.x, site_tile.y))
+                        .is_some_and(|owner| owner != kingdom);
+                    if crowded || foreign {
+                        return None;
+                    }
+
+                    let site = survey_site(world_data, &settings, site_tile, String::new())?;
+                    (site.food_capacity >= migrants)
+                        .then_some((site_tile, site.food_capacity - DISTANCE_WEIGHT * distance))
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            let Some((goal, _)) = site else {
+                continue;
+            };
+
+            let unit = spawn_unit(&mut commands, UnitKind::Migrants, tile);
+            commands.entity(unit).insert((
+                MoveOrder::new(goal),
+                FoundingParty {
+                    kingdom,
+                    population: migrants,
+                },
+                MigrantGroup {
+                    from: home,
+                    destination: None,
+                    population: migrants,
+                },
+            ));
+        }
+
+        if let Ok((_, _, mut population, ..)) = settlement_query.get_mut(home) {
+            population.0 -= migrants;
+        }
+    }
+}
+
+// Migrants join the settlement they were headed for when they reach it. With no
+// way there, or nowhere left to arrive at, they go back home. Groups out to
+// found a settlement are settled by the kingdom AI like any founding party.
+pub fn settle_migrants(
+    mut commands: Commands,
+    mut population_query: Query<&mut Population>,
+    migrant_query: Query<
+        (Entity, &Unit, &MigrantGroup, Option<&MoveOrder>),
+        Without<FoundingParty>,
+    >,
+) {
+    for (entity, unit, group, order) in &migrant_query {
+        let arrived =
+            order.is_some_and(|order| !order.path.is_empty() && order.next >= order.path.len());
+        if order.is_some() && !arrived {
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        let settled = group
+            .destination
+            .filter(|_| arrived)
+            .and_then(|destination| population_query.get_mut(destination).ok());
+        if let Some(mut population) = settled {
+            population.0 += group.population;
+            info!(
+                "{:.0} migrants settled at {}, {}",
+                group.population, unit.tile.x, unit.tile.y
+            );
+        } else if let Ok(mut population) = population_query.get_mut(group.from) {
+            population.0 += group.population;
+        }
+    }
+}
+
+pub fn migration_start(settings: Res<WorldSettings>) -> bool {
+    settings.migration_start
+}
+
+// A settlement and what it takes from the land, all of which a migration start
+// moves to wherever its people end up
+type FoundedSettlement = (
+    &'static mut Settlement,
+    &'static Population,
+    &'static mut Transform,
+    &'static mut FoodCapacity,
+    &'static mut ProductionRates,
+    &'static mut FishingGrounds,
+);
+
+// With a migration start, the journeys are played out before the first frame.
+// Each kingdom gathers where one of its settlements was placed and sets out as
+// bands, one for each settlement and as many strong. Every band makes for the
+// best land in a ring around the gathering that no other band has claimed,
+// falling back on the place its settlement stood, and its settlement is
+// founded there with the way it came as a road and a name fitting the land.
+pub fn simulate_migration(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    (season, locale, mut chronicle): (Res<Season>, Res<Locale>, ResMut<Chronicle>),
+    world_data_query: Query<&WorldData>,
+    kingdom_query: Query<(&Kingdom, &Culture, &KingdomSettlements)>,
+    mut settlement_query: Query<FoundedSettlement>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    let mut rng = SmallRng::seed_from_u64(world_data.seed.wrapping_add(16) as u64);
+    let mut claimed: Vec<IVec2> = Vec::new();
+
+    for (kingdom, &culture, settlements) in &kingdom_query {
+        let bands: Vec<(Entity, IVec2, f32)> = settlements
+            .iter()
+            .filter_map(|entity| {
+                let (settlement, population, ..) = settlement_query.get(entity).ok()?;
+                Some((entity, settlement.tile, population.0))
+            })
+            .collect();
+        let Some(&(_, gathering, _)) = bands.first() else {
+            continue;
+        };
+
+        for (entity, placed, population) in bands {
+            let goal = (0..CANDIDATE_SITES)
+                .filter_map(|candidate| {
+                    let angle = (candidate as f32 + rng.random::<f32>()) / CANDIDATE_SITES as f32;
+                    let distance = rng.random_range(FOUNDING_MIN_DISTANCE..FOUNDING_MAX_DISTANCE);
+                    let offset = Vec2::from_angle(angle * TAU) * distance;
+                    let site_tile = (gathering + offset.as_ivec2())
+                        .rem_euclid(IVec2::splat(settings.world_size));
+
+                    let crowded = claimed.iter().any(|other| {
+                        toroidal_distance(site_tile, *other, &settings) < MIN_SETTLEMENT_SPACING
+                    });
+                    if crowded {
+                        return None;
+                    }
+
+                    let site = survey_site(world_data, &settings, site_tile, String::new())?;
+                    let utility = site.food_capacity - DISTANCE_WEIGHT * distance;
+                    (site.food_capacity >= population).then_some((site, utility))
+                })
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(site, _)| site);
+            let Ok((mut settlement, _, mut transform, mut capacity, mut production, mut fishing)) =
+                settlement_query.get_mut(entity)
+            else {
+                continue;
+            };
+            let Some(site) = goal else {
+                claimed.push(placed);
+                continue;
+            };
+            claimed.push(site.tile);
+
+            let landform = local_landform(world_data, &settings, site.tile);
+            settlement.name = place_name(world_data.seed, culture, landform, site.tile);
+            settlement.tile = site.tile;
+            transform.translation =
+                (site.tile.as_vec2() + Vec2::splat(0.5)).extend(transform.translation.z);
+            capacity.0 = site.food_capacity;
+            *production = site.production;
+            *fishing = site.fishing;
+            if site.tile != gathering {
+                commands.spawn(Road {
+                    tiles: straight_path(gathering, site.tile, &settings),
+                });
+            }
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::Founding,
+                locale.format("chronicle.founds", &[&kingdom.name, &settlement.name]),
+            );
+        }
+    }
+}
+
+pub fn cleanup_migration(mut timer: ResMut<MigrationTimer>) {
+    *timer = MigrationTimer::default();
+}
@@ -1,4 +1,58 @@
 pub mod main_menu;
 pub mod game_config;
 pub mod world_gen;
-pub mod world;
\ No newline at end of file
+pub mod world;
+pub mod color_grading;
+pub mod benchmark;
+pub mod tile_material;
+pub mod season;
+pub mod pause_menu;
+pub mod loading_screen;
+pub mod ambient;
+#[cfg(feature = "dev-tools")]
+pub mod dev_tools;
+pub mod startup;
+pub mod map_legend;
+pub mod world_view;
+pub mod bug_report;
+pub mod weather;
+pub mod water_material;
+pub mod heightmap_view;
+pub mod keybindings;
+pub mod options;
+pub mod save_game;
+pub mod export;
+pub mod settlements;
+pub mod economy;
+pub mod growth;
+pub mod kingdoms;
+pub mod pathfinding;
+pub mod units;
+pub mod clock;
+pub mod kingdom_ai;
+pub mod trade;
+pub mod diplomacy;
+pub mod combat;
+pub mod farming;
+pub mod buildings;
+pub mod events;
+pub mod event_scripts;
+pub mod notifications;
+pub mod wildlife;
+pub mod chronicle;
+pub mod development;
+pub mod migration;
+pub mod technology;
+pub mod diagnostics;
+pub mod parallel;
+pub mod palette;
+pub mod map_capture;
+pub mod localization;
+pub mod sound;
+pub mod measure;
+pub mod world_stats;
+pub mod nomads;
+pub mod lairs;
+pub mod era_end;
+pub mod world_record;
+pub mod timeline;
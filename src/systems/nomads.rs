@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+use kingdom_worldgen::{climate_square, sampler::WorldSampler};
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    kingdoms::{AiControlled, Culture, Kingdom, OwnedBy, Treasury},
+    nomads::{Horde, Tributary},
+    settlements::{Good, Population, Settlement, Stockpile},
+    units::{MoveOrder, PathSearch, Unit, UnitKind},
+    world::Biome,
+    world_gen::WorldData,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    combat::{prevails, raid_garrison},
+    localization::Locale,
+    notifications::Notifications,
+    pathfinding::PathGrid,
+    season::{Season, YEAR_LENGTH_SECS, tile_latitude},
+    settlements::{MIN_POPULATION, spawn_settlement},
+    units::spawn_unit,
+    world::{WorldSettings, toroidal_distance},
+    world_gen::{
+        names::{NameGenerator, place_name},
+        settlements::{MIN_SETTLEMENT_SPACING, local_landform, survey_site},
+    },
+};
+
+// Open country herds can graze the year round
+const PASTURE_BIOMES: [Biome; 4] = [
+    Biome::Grassland,
+    Biome::Savanna,
+    Biome::Tundra,
+    Biome::ColdDesert,
+];
+const KINGDOMS_PER_HORDE: usize = 3;
+const MAX_HORDES: usize = 4;
+const PASTURE_ATTEMPTS: u32 = 2000;
+// How far the winter pasture lies toward the equator from the summer one
+const MIGRATION_MIN_DISTANCE: i32 = 40;
+const MIGRATION_MAX_DISTANCE: i32 = 90;
+const HORDE_STRENGTH: f32 = 150.0;
+// Tiles from a pasture that count as being on it
+const PASTURE_REACH: f32 = 3.0;
+// Settlements this close to a horde are in reach of its riders
+const RAID_RADIUS: f32 = 40.0;
+// Share of the people and food stores a successful raid takes, the riders it
+// draws to the horde, and the riders lost when a raid is driven off
+const RAID_LOSS: f32 = 0.1;
+const RAID_PLUNDER: f32 = 0.5;
+const RAID_GAIN: f32 = 0.1;
+const REPELLED_LOSS: f32 = 0.3;
+// Yearly gold a kingdom pays for each rider to be left alone
+const TRIBUTE_PER_RIDER: f32 = 0.5;
+// Years of tribute after which a horde settles down among its payers, and the
+// people who come off the steppe with each rider
+const SETTLE_AFTER_YEARS: u32 = 5;
+const PEOPLE_PER_RIDER: f32 = 3.0;
+// A horde beaten down below this scatters
+const MIN_HORDE_STRENGTH: f32 = 30.0;
+
+// One horde for every few kingdoms, each with a summer pasture out on the
+// grassland or tundra and a winter one further from the pole, both clear of
+// the settlements
+pub fn spawn_hordes(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    world_data_query: Query<&WorldData>,
+    kingdom_query: Query<&Kingdom>,
+    settlement_query: Query<&Settlement>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let mut rng = SmallRng::seed_from_u64(world_data.seed.wrapping_add(17) as u64);
+    let mut namer = NameGenerator::new(world_data.seed.wrapping_add(18) as u64);
+    let count = (kingdom_query.iter().count() / KINGDOMS_PER_HORDE).clamp(1, MAX_HORDES);
+    let mut taken: Vec<IVec2> = settlement_query
+        .iter()
+        .map(|settlement| settlement.tile)
+        .collect();
+
+    let size = IVec2::splat(settings.world_size);
+    let grazing = |tile: IVec2, taken: &[IVec2]| {
+        let square = climate_square(&sampler, world_data, tile.x, tile.y);
+        PASTURE_BIOMES.contains(&square.biome)
+            && taken.iter().all(|other| {
+                toroidal_distance(tile, *other, &settings) >= MIN_SETTLEMENT_SPACING / 2.0
+            })
+    };
+
+    let mut hordes = 0;
+    for _attempt in 0..PASTURE_ATTEMPTS {
+        if hordes >= count {
+            break;
+        }
+        let summer = IVec2::new(
+            rng.random_range(0..settings.world_size),
+            rng.random_range(0..settings.world_size),
+        );
+        let distance = rng.random_range(MIGRATION_MIN_DISTANCE..=MIGRATION_MAX_DISTANCE);
+        let toward_equator = if tile_latitude(summer.y, settings.world_size) > 0.0 {
+            -1
+        } else {
+            1
+        };
+        let drift = rng.random_range(-distance / 2..=distance / 2);
+        let winter = (summer + IVec2::new(drift, toward_equator * distance)).rem_euclid(size);
+        if !grazing(summer, &taken) || !grazing(winter, &taken) {
+            continue;
+        }
+
+        let culture = Culture::ALL[rng.random_range(0..Culture::ALL.len())];
+        let horde = spawn_unit(&mut commands, UnitKind::Nomads, summer);
+        commands.entity(horde).insert(Horde {
+            name: namer.name(culture),
+            culture,
+            strength: HORDE_STRENGTH,
+            summer_pasture: summer,
+            winter_pasture: winter,
+            bound_for: None,
+            tributaries: Vec::new(),
+            raid_cooldown: YEAR_LENGTH_SECS,
+        });
+        taken.extend([summer, winter]);
+        hordes += 1;
+    }
+}
+
+// Hordes neither on the move nor waiting on a path
+type Resting = (Without<MoveOrder>, Without<PathSearch>);
+
+// Hordes spend spring and summer on their summer pasture and ride for the
+// winter one as autumn comes
+pub fn drive_herds(
+    mut commands: Commands,
+    (season, settings): (Res<Season>, Res<WorldSettings>),
+    mut horde_query: Query<(Entity, &Unit, &mut Horde), Resting>,
+) {
+    for (entity, unit, mut horde) in &mut horde_query {
+        let latitude = tile_latitude(horde.summer_pasture.y, settings.world_size);
+        let pasture = match season.name(latitude) {
+            "Spring" | "Summer" => horde.summer_pasture,
+            _ => horde.winter_pasture,
+        };
+        if horde.bound_for == Some(pasture)
+            || toroidal_distance(unit.tile, pasture, &settings) <= PASTURE_REACH
+        {
+            continue;
+        }
+
+        horde.bound_for = Some(pasture);
+        commands.entity(entity).insert(MoveOrder::new(pasture));
+    }
+}
+
+// A settlement a horde can fall on, with its kingdom
+type RaidTarget = (
+    Entity,
+    &'static Settlement,
+    &'static mut Population,
+    &'static mut Stockpile,
+    &'static OwnedBy,
+);
+
+// Once a year a horde falls on the nearest settlement in reach, unless its
+// kingdom is paying it off. AI kingdoms pay when their treasury can bear it.
+// Otherwise the riders carry off people and food stores, or are driven off by
+// the garrison, which fights better on rough ground.
+pub fn raid_borders(
+    clock: Res<GameClock>,
+    (season, locale): (Res<Season>, Res<Locale>),
+    (settings, grid): (Res<WorldSettings>, Option<Res<PathGrid>>),
+    (mut chronicle, mut notifications): (ResMut<Chronicle>, ResMut<Notifications>),
+    mut horde_query: Query<(&Unit, &mut Horde)>,
+    mut settlement_query: Query<RaidTarget>,
+    mut kingdom_query: Query<(&Kingdom, &mut Treasury, Has<AiControlled>)>,
+) {
+    for (unit, mut horde) in &mut horde_query {
+        horde.raid_cooldown -= clock.delta_secs;
+        if horde.raid_cooldown > 0.0 {
+            continue;
+        }
+        let target = settlement_query
+            .iter()
+            .map(|(entity, settlement, .., owner)| {
+                let distance = toroidal_distance(unit.tile, settlement.tile, &settings);
+                (entity, owner.0, distance)
+            })
+            .filter(|(.., distance)| *distance <= RAID_RADIUS)
+            .min_by(|(.., a), (.., b)| a.total_cmp(b));
+        let Some((target, kingdom, _)) = target else {
+            continue;
+        };
+        horde.raid_cooldown = YEAR_LENGTH_SECS;
+
+        let year = season.year;
+        let spared = horde
+            .tributaries
+            .iter()
+            .any(|tributary| tributary.kingdom == kingdom && tributary.until_year >= year);
+        if spared {
+            continue;
+        }
+
+        let tribute = horde.strength * TRIBUTE_PER_RIDER;
+        if let Ok((payer, mut treasury, true)) = kingdom_query.get_mut(kingdom)
+            && treasury.0 >= tribute
+        {
+            treasury.0 -= tribute;
+            match horde
+                .tributaries
+                .iter_mut()
+                .find(|tributary| tributary.kingdom == kingdom)
+            {
+                Some(tributary) => {
+                    tributary.years += 1;
+                    tributary.until_year = year;
+                }
+                None => horde.tributaries.push(Tributary {
+                    kingdom,
+                    years: 1,
+                    until_year: year,
+                }),
+            }
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::Peace,
+                locale.format("chronicle.tribute", &[&payer.name, &horde.name]),
+            );
+            continue;
+        }
+
+        let Ok((_, settlement, mut population, mut stockpile, _)) =
+            settlement_query.get_mut(target)
+        else {
+            continue;
+        };
+        let garrison = raid_garrison(population.0, settlement.tile, grid.as_deref());
+        if prevails(horde.strength, garrison) {
+            population.0 = (population.0 * (1.0 - RAID_LOSS)).max(MIN_POPULATION);
+            for good in [Good::Grain, Good::Fish, Good::Game] {
+                stockpile.0[good as usize] *= 1.0 - RAID_PLUNDER;
+            }
+            horde.strength *= 1.0 + RAID_GAIN;
+            let text = locale.format("chronicle.raid", &[&horde.name, &settlement.name]);
+            chronicle.record(locale.date(&season), ChronicleKind::War, text.clone());
+            notifications.push(text);
+        } else {
+            horde.strength *= 1.0 - REPELLED_LOSS;
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::War,
+                locale.format("chronicle.raid_repelled", &[&settlement.name, &horde.name]),
+            );
+        }
+    }
+}
+
+// A horde paid off long enough by one kingdom settles where it winters, as a
+// settlement of that kingdom, once it is back there and the land will feed
+// it. One beaten down too far scatters.
+pub fn settle_hordes(
+    mut commands: Commands,
+    (season, locale, settings): (Res<Season>, Res<Locale>, Res<WorldSettings>),
+    mut chronicle: ResMut<Chronicle>,
+    world_data_query: Query<&WorldData>,
+    horde_query: Query<(Entity, &Unit, &Horde), Without<MoveOrder>>,
+    kingdom_query: Query<&Kingdom>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+
+    for (entity, unit, horde) in &horde_query {
+        if horde.strength < MIN_HORDE_STRENGTH {
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::War,
+                locale.format("chronicle.horde_scatters", &[&horde.name]),
+            );
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let patron = horde
+            .tributaries
+            .iter()
+            .filter(|tributary| tributary.years >= SETTLE_AFTER_YEARS)
+            .max_by_key(|tributary| tributary.years);
+        let Some(patron) = patron else {
+            continue;
+        };
+        let Ok(kingdom) = kingdom_query.get(patron.kingdom) else {
+            continue;
+        };
+        if toroidal_distance(unit.tile, horde.winter_pasture, &settings) > PASTURE_REACH {
+            continue;
+        }
+        let landform = local_landform(world_data, &settings, unit.tile);
+        let name = place_name(world_data.seed, horde.culture, landform, unit.tile);
+        let Some(site) = survey_site(world_data, &settings, unit.tile, name) else {
+            continue;
+        };
+
+        chronicle.record(
+            locale.date(&season),
+            ChronicleKind::Founding,
+            locale.format(
+                "chronicle.horde_settles",
+                &[&horde.name, &site.name, &kingdom.name],
+            ),
+        );
+        let settlement = spawn_settlement(&mut commands, site, horde.strength * PEOPLE_PER_RIDER);
+        commands.entity(settlement).insert(OwnedBy(patron.kingdom));
+        commands.entity(entity).despawn();
+    }
+}
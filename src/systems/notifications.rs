@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+use crate::components::events::{Notification, NotificationArea};
+
+// Seconds a notification stays on screen, the last of them spent fading
+const NOTIFICATION_SECS: f32 = 8.0;
+const FADE_SECS: f32 = 2.0;
+// Older notifications are dropped once the column is this long
+const MAX_NOTIFICATIONS: usize = 5;
+
+// Messages waiting to be shown. Anything in the game can push one; they appear
+// under the clock on the next frame.
+#[derive(Resource, Default)]
+pub struct Notifications {
+    pending: Vec<String>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        info!("{}", text);
+        self.pending.push(text);
+    }
+}
+
+pub fn setup_notification_area(mut commands: Commands) {
+    commands.spawn((
+        NotificationArea,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(44.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+    ));
+}
+
+// Shows pending messages, newest at the bottom, and fades out old ones. Runs on
+// real time so notifications clear while the game is paused.
+pub fn update_notifications(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut notifications: ResMut<Notifications>,
+    area_query: Single<(Entity, Option<&Children>), With<NotificationArea>>,
+    mut notification_query: Query<(&mut Notification, &mut TextColor, &mut BackgroundColor)>,
+) {
+    let (area, children) = *area_query;
+    let shown: Vec<Entity> = children
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    let overflow = (shown.len() + notifications.pending.len()).saturating_sub(MAX_NOTIFICATIONS);
+
+    for (index, &entity) in shown.iter().enumerate() {
+        let Ok((mut notification, mut color, mut background)) = notification_query.get_mut(entity)
+        else {
+            continue;
+        };
+        notification.remaining -= time.delta_secs();
+        if index < overflow || notification.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = (notification.remaining / FADE_SECS).min(1.0);
+        color.0.set_alpha(alpha);
+        background.0.set_alpha(alpha * 0.7);
+    }
+
+    for text in notifications.pending.drain(..) {
+        commands.entity(area).with_child((
+            Notification {
+                remaining: NOTIFICATION_SECS,
+            },
+            Text::new(text),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ));
+    }
+}
+
+pub fn cleanup_notifications(
+    mut commands: Commands,
+    mut notifications: ResMut<Notifications>,
+    query: Query<Entity, With<NotificationArea>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    *notifications = Notifications::default();
+}
@@ -0,0 +1,439 @@
+use std::thread::available_parallelism;
+
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PresentMode, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::localization::Localized,
+    components::game_config::{InputValue, Slider},
+    components::options::{OptionsBack, OptionsSetting, OptionsUI, OptionsValue, OptionsVolume},
+    states::game_state::GameState,
+    systems::{
+        game_config::slider_track,
+        main_menu::spawn_main_menu,
+        map_capture::CaptureOverlays,
+        localization::{DEFAULT_LANGUAGE, Locale, language_codes},
+        palette::{DEFAULT_PALETTE, palette_names},
+        pause_menu::{Pause, open_pause_menu},
+        startup::{read_user_config, write_user_config},
+    },
+};
+
+const OPTIONS_FILE: &str = "options.ron";
+const RESOLUTIONS: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+const CAPTURE_SCALES: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+// Volumes are dragged in percent
+const VOLUME_SLIDER: Slider = Slider {
+    min: 0.0,
+    max: 100.0,
+    step: 5.0,
+};
+
+// Display and performance choices, saved to options.ron in the user data folder
+// and applied to the window whenever they change
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct GameOptions {
+    // Logical window size, used while windowed
+    pub resolution: (u32, u32),
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub ui_scale: f32,
+    // Threads for whole-world generation; 0 uses every core
+    pub worker_threads: usize,
+    // Map exports also write elevation, temperature and moisture as grayscale
+    pub export_layers: bool,
+    // Name of the map colour palette, built in or from the user palettes folder
+    pub palette: String,
+    // Tiles per pixel of a full map capture; below 1 each tile spans several pixels
+    pub capture_scale: f32,
+    pub capture_overlays: CaptureOverlays,
+    // Tints the map toward the season and the biome in view
+    pub color_grading: bool,
+    // Bird flocks, whale spouts and heat shimmer over the map
+    pub ambient_flavor: bool,
+    // Code of the UI language, matching a file in assets/locales
+    pub language: String,
+    // Linear volumes from silent at 0 to full at 1
+    pub music_volume: f32,
+    pub ambience_volume: f32,
+    // Button clicks
+    pub sound_volume: f32,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        GameOptions {
+            resolution: (1600, 900),
+            fullscreen: false,
+            vsync: true,
+            ui_scale: 1.0,
+            worker_threads: 0,
+            export_layers: false,
+            palette: DEFAULT_PALETTE.to_string(),
+            capture_scale: 1.0,
+            capture_overlays: CaptureOverlays::default(),
+            color_grading: true,
+            ambient_flavor: true,
+            language: DEFAULT_LANGUAGE.to_string(),
+            music_volume: 0.6,
+            ambience_volume: 0.8,
+            sound_volume: 0.8,
+        }
+    }
+}
+
+impl GameOptions {
+    fn cycle(&mut self, setting: OptionsSetting) {
+        match setting {
+            OptionsSetting::Resolution => {
+                self.resolution = next_choice(&RESOLUTIONS, self.resolution)
+            }
+            OptionsSetting::WindowMode => self.fullscreen = !self.fullscreen,
+            OptionsSetting::Vsync => self.vsync = !self.vsync,
+            OptionsSetting::UiScale => self.ui_scale = next_choice(&UI_SCALES, self.ui_scale),
+            OptionsSetting::WorkerThreads => {
+                self.worker_threads = next_choice(&worker_thread_choices(), self.worker_threads);
+            }
+            OptionsSetting::ExportLayers => self.export_layers = !self.export_layers,
+            OptionsSetting::Palette => {
+                self.palette = next_choice(&palette_names(), self.palette.clone());
+            }
+            OptionsSetting::CaptureScale => {
+                self.capture_scale = next_choice(&CAPTURE_SCALES, self.capture_scale);
+            }
+            OptionsSetting::CaptureOverlays => {
+                self.capture_overlays = next_choice(&CaptureOverlays::ALL, self.capture_overlays);
+            }
+            OptionsSetting::ColorGrading => self.color_grading = !self.color_grading,
+            OptionsSetting::AmbientFlavor => self.ambient_flavor = !self.ambient_flavor,
+            OptionsSetting::Language => {
+                self.language = next_choice(&language_codes(), self.language.clone());
+            }
+        }
+    }
+
+    fn describe(&self, setting: OptionsSetting, locale: &Locale) -> String {
+        let text = |id| locale.text(id).to_string();
+        match setting {
+            OptionsSetting::Resolution => format!("{} x {}", self.resolution.0, self.resolution.1),
+            OptionsSetting::WindowMode if self.fullscreen => text("options.fullscreen"),
+            OptionsSetting::WindowMode => text("options.windowed"),
+            OptionsSetting::Vsync if self.vsync => text("options.on"),
+            OptionsSetting::Vsync => text("options.off"),
+            OptionsSetting::UiScale => format!("{:.0}%", self.ui_scale * 100.0),
+            OptionsSetting::WorkerThreads if self.worker_threads == 0 => text("options.auto"),
+            OptionsSetting::WorkerThreads => self.worker_threads.to_string(),
+            OptionsSetting::ExportLayers if self.export_layers => text("options.all_layers"),
+            OptionsSetting::ExportLayers => text("options.biome_only"),
+            OptionsSetting::Palette => self.palette.clone(),
+            OptionsSetting::CaptureScale if self.capture_scale < 1.0 => {
+                locale.format("options.pixels_per_tile", &[&(1.0 / self.capture_scale).round()])
+            }
+            OptionsSetting::CaptureScale => {
+                locale.format("options.tiles_per_pixel", &[&self.capture_scale])
+            }
+            OptionsSetting::CaptureOverlays => locale
+                .get(&format!("capture.{:?}", self.capture_overlays))
+                .unwrap_or(self.capture_overlays.label())
+                .to_string(),
+            OptionsSetting::ColorGrading if self.color_grading => text("options.on"),
+            OptionsSetting::ColorGrading => text("options.off"),
+            OptionsSetting::AmbientFlavor if self.ambient_flavor => text("options.on"),
+            OptionsSetting::AmbientFlavor => text("options.off"),
+            // Each language is listed under its own name once loaded
+            OptionsSetting::Language if locale.code == self.language => locale.name.clone(),
+            OptionsSetting::Language => self.language.clone(),
+        }
+    }
+
+    fn volume_mut(&mut self, volume: OptionsVolume) -> &mut f32 {
+        match volume {
+            OptionsVolume::Music => &mut self.music_volume,
+            OptionsVolume::Ambience => &mut self.ambience_volume,
+            OptionsVolume::Sound => &mut self.sound_volume,
+        }
+    }
+}
+
+// The choice after `current`, wrapping; a value not in the list starts it over
+fn next_choice<T: Clone + PartialEq>(choices: &[T], current: T) -> T {
+    let index = choices
+        .iter()
+        .position(|choice| *choice == current)
+        .map_or(0, |index| (index + 1) % choices.len());
+
+    choices[index].clone()
+}
+
+// Auto, then powers of two up to the core count
+fn worker_thread_choices() -> Vec<usize> {
+    let cores = available_parallelism().map_or(1, |cores| cores.get());
+    let mut choices = vec![0];
+    choices.extend(
+        (0..)
+            .map(|power| 1 << power)
+            .take_while(|&threads| threads < cores),
+    );
+    choices.push(cores);
+
+    choices
+}
+
+pub fn load_options() -> GameOptions {
+    read_user_config(OPTIONS_FILE).unwrap_or_default()
+}
+
+pub fn save_options(options: &GameOptions) {
+    write_user_config(OPTIONS_FILE, options);
+}
+
+// Runs whole-world generation on a pool of the chosen size, or rayon's global
+// pool for auto
+#[cfg(feature = "parallel")]
+pub fn run_on_workers<R: Send>(threads: usize, work: impl FnOnce() -> R + Send) -> R {
+    if threads > 0 {
+        match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => return pool.install(work),
+            Err(err) => warn!("Could not start {} generation threads: {}", threads, err),
+        }
+    }
+
+    work()
+}
+
+// Without the parallel feature there are no other threads to run on
+#[cfg(not(feature = "parallel"))]
+pub fn run_on_workers<R>(_threads: usize, work: impl FnOnce() -> R) -> R {
+    work()
+}
+
+pub fn apply_options(
+    options: Res<GameOptions>,
+    mut window: Single<&mut Window>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let (width, height) = options.resolution;
+    window.resolution.set(width as f32, height as f32);
+    window.mode = if options.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    window.present_mode = if options.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    ui_scale.0 = options.ui_scale;
+}
+
+// The map camera's viewport was sized for the starting window; keep it covering
+// the window after resolution and fullscreen changes
+pub fn fit_camera_viewport(
+    window: Single<&Window>,
+    mut camera: Single<&mut Camera, With<Camera2d>>,
+) {
+    let size = window.resolution.physical_size();
+    if let Some(viewport) = camera.viewport.as_mut()
+        && viewport.physical_size != size
+    {
+        viewport.physical_size = size;
+    }
+}
+
+// `owner` tags the page with the menu it replaced, so that menu's cleanup and
+// Escape handling also cover it
+pub fn open_options_page(commands: &mut Commands, options: &GameOptions, owner: impl Component) {
+    let settings = [
+        (OptionsSetting::Language, "options.language", "Language"),
+        (OptionsSetting::Resolution, "options.resolution", "Resolution"),
+        (OptionsSetting::WindowMode, "options.display", "Display"),
+        (OptionsSetting::Vsync, "options.vsync", "Vsync"),
+        (OptionsSetting::UiScale, "options.ui_scale", "UI scale"),
+        (OptionsSetting::WorkerThreads, "options.worker_threads", "Generation threads"),
+        (OptionsSetting::ExportLayers, "options.export_layers", "Map export"),
+        (OptionsSetting::Palette, "options.palette", "Map colours"),
+        (OptionsSetting::CaptureScale, "options.capture_scale", "Map capture scale"),
+        (OptionsSetting::CaptureOverlays, "options.capture_overlays", "Map capture shows"),
+        (OptionsSetting::ColorGrading, "options.color_grading", "Color grading"),
+        (OptionsSetting::AmbientFlavor, "options.ambient_flavor", "Wildlife and shimmer"),
+    ];
+    let volumes = [
+        (OptionsVolume::Music, options.music_volume, "options.music_volume", "Music volume"),
+        (
+            OptionsVolume::Ambience,
+            options.ambience_volume,
+            "options.ambience_volume",
+            "Ambience volume",
+        ),
+        (OptionsVolume::Sound, options.sound_volume, "options.sound_volume", "Interface sounds"),
+    ];
+    let rows = settings.map(|(setting, id, label)| {
+        (
+            Node {
+                width: Val::Px(480.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![
+                (
+                    Text::new(label),
+                    Localized::new(id),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ),
+                (
+                    Button,
+                    Node {
+                        width: Val::Px(220.0),
+                        padding: UiRect::all(Val::Px(6.0)),
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    setting,
+                    children![(
+                        OptionsValue(setting),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    )],
+                ),
+            ],
+        )
+    });
+    let volume_rows = volumes.map(|(volume, value, id, label)| {
+        (
+            Node {
+                width: Val::Px(480.0),
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            children![
+                (
+                    Text::new(label),
+                    Localized::new(id),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ),
+                slider_track(VOLUME_SLIDER, (value * 100.0).round() as f64, volume),
+            ],
+        )
+    });
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            OptionsUI,
+            owner,
+            Children::spawn((
+                SpawnIter(rows.into_iter()),
+                SpawnIter(volume_rows.into_iter()),
+            )),
+        ))
+        .with_child((
+            Button,
+            Node {
+                margin: UiRect::top(Val::Px(12.0)),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            OptionsBack,
+            children![(
+                Text::new("Back"),
+                Localized::new("options.back"),
+                TextFont {
+                    font_size: 32.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE)
+            )],
+        ));
+}
+
+pub fn options_buttons(
+    mut commands: Commands,
+    mut options: ResMut<GameOptions>,
+    mut pause: ResMut<Pause>,
+    state: Res<State<GameState>>,
+    setting_query: Query<(&Interaction, &OptionsSetting), Changed<Interaction>>,
+    back_query: Query<&Interaction, (With<OptionsBack>, Changed<Interaction>)>,
+    page_query: Query<Entity, With<OptionsUI>>,
+) {
+    for (interaction, setting) in &setting_query {
+        if *interaction == Interaction::Pressed {
+            options.cycle(*setting);
+            save_options(&options);
+        }
+    }
+
+    for interaction in &back_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        for entity in &page_query {
+            commands.entity(entity).despawn();
+        }
+        if *state.get() == GameState::MainMenu {
+            spawn_main_menu(&mut commands);
+        } else {
+            open_pause_menu(&mut commands, &mut pause);
+        }
+    }
+}
+
+// Dragging a volume track saves the new volume as soon as it moves a step
+pub fn read_volume_sliders(
+    mut options: ResMut<GameOptions>,
+    slider_query: Query<(&OptionsVolume, &InputValue), Changed<InputValue>>,
+) {
+    for (volume, input) in &slider_query {
+        let Ok(percent) = input.text.parse::<f32>() else {
+            continue;
+        };
+        let value = percent / 100.0;
+        if *options.volume_mut(*volume) != value {
+            *options.volume_mut(*volume) = value;
+            save_options(&options);
+        }
+    }
+}
+
+// Values are filled in once the page spawns, and again on any change to the
+// options or the language
+pub fn update_options_labels(
+    options: Res<GameOptions>,
+    locale: Res<Locale>,
+    mut label_query: Query<(Ref<OptionsValue>, &mut Text)>,
+) {
+    let changed = options.is_changed() || locale.is_changed();
+    for (value, mut text) in &mut label_query {
+        if changed || value.is_added() {
+            text.0 = options.describe(value.0, &locale);
+        }
+    }
+}
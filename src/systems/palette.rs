@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::world::Biome;
+use crate::systems::{
+    options::GameOptions,
+    startup::{read_user_config, user_data_dir},
+    tile_material::{BiomeTileMaterial, BiomeTileMaterialHandle, build_biome_atlas},
+    world::{LoadedChunks, clear_loaded_chunks},
+    world_gen::biome_table::{BiomeTable, Biomes},
+    world_view::WorldView,
+};
+
+// The biome table's own colours
+pub const DEFAULT_PALETTE: &str = "Default";
+const BUILT_IN_PALETTES: [(&str, &str); 3] = [
+    ("Parchment", include_str!("../../assets/palettes/parchment.ron")),
+    ("High contrast", include_str!("../../assets/palettes/high_contrast.ron")),
+    ("Colorblind safe", include_str!("../../assets/palettes/colorblind_safe.ron")),
+];
+// Folder under the user data folder holding the player's own palettes, one RON
+// file per palette in the same format as the built-in ones
+const USER_PALETTES_DIR: &str = "palettes";
+
+// Sea colours from the shallow shelf out to the deepest water
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OceanGradient {
+    pub shelf: (f32, f32, f32),
+    pub deep: (f32, f32, f32),
+    pub abyssal: (f32, f32, f32),
+}
+
+impl Default for OceanGradient {
+    fn default() -> Self {
+        OceanGradient {
+            shelf: (0.15, 0.4, 0.75),
+            deep: (0.0, 0.12, 0.45),
+            abyssal: (0.0, 0.03, 0.15),
+        }
+    }
+}
+
+// Map colours laid over the biome table. Biomes a palette leaves out keep the
+// table's colour.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    pub biomes: HashMap<Biome, (f32, f32, f32)>,
+    pub ocean: OceanGradient,
+}
+
+impl Palette {
+    pub fn biome_color(&self, biome: Biome, table: &BiomeTable) -> [f32; 4] {
+        match self.biomes.get(&biome) {
+            Some(&(r, g, b)) => [r, g, b, 1.0],
+            None => table.color(biome),
+        }
+    }
+
+    // Open sea, graded by how far the floor sits below the world's sea level:
+    // shelf water is light, open ocean darker and trenches near black
+    pub fn ocean_color(&self, elevation: f32, sea_level: f32) -> [f32; 4] {
+        let OceanGradient {
+            shelf,
+            deep,
+            abyssal,
+        } = self.ocean;
+
+        let depth = ((sea_level - elevation) / sea_level).clamp(0.0, 1.0);
+
+        let (from, to, t) = if depth < 0.3 {
+            (shelf, deep, depth / 0.3)
+        } else {
+            (deep, abyssal, (depth - 0.3) / 0.7)
+        };
+
+        [
+            from.0 + (to.0 - from.0) * t,
+            from.1 + (to.1 - from.1) * t,
+            from.2 + (to.2 - from.2) * t,
+            1.0,
+        ]
+    }
+}
+
+// The palette the map is drawn with, by the name chosen in the options
+#[derive(Resource)]
+pub struct MapPalette {
+    pub name: String,
+    pub palette: Arc<Palette>,
+}
+
+impl MapPalette {
+    pub fn load(name: &str) -> Self {
+        MapPalette {
+            name: name.to_string(),
+            palette: Arc::new(load_palette(name)),
+        }
+    }
+}
+
+// The default, the built-in palettes, then the player's own alphabetically
+pub fn palette_names() -> Vec<String> {
+    let mut names = vec![DEFAULT_PALETTE.to_string()];
+    names.extend(BUILT_IN_PALETTES.iter().map(|(name, _)| name.to_string()));
+
+    let Ok(entries) = fs::read_dir(user_data_dir().join(USER_PALETTES_DIR)) else {
+        return names;
+    };
+    let mut user_names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .filter(|name| !names.contains(name))
+        .collect();
+    user_names.sort();
+    names.extend(user_names);
+
+    names
+}
+
+// A palette that is missing or unreadable falls back to the default colours
+pub fn load_palette(name: &str) -> Palette {
+    if name == DEFAULT_PALETTE {
+        return Palette::default();
+    }
+    if let Some((_, text)) = BUILT_IN_PALETTES.iter().find(|(built_in, _)| *built_in == name) {
+        return ron::from_str(text).unwrap_or_else(|err| {
+            warn!("Palette {} is unreadable ({}); using the default colours", name, err);
+            Palette::default()
+        });
+    }
+
+    read_user_config(&format!("{}/{}.ron", USER_PALETTES_DIR, name)).unwrap_or_else(|| {
+        warn!("No palette named {}; using the default colours", name);
+        Palette::default()
+    })
+}
+
+// Swaps palettes when the options change, recolouring the loaded chunks through
+// the same rebuild as overlay changes, the world view and the textured atlas
+pub fn apply_palette(
+    options: Res<GameOptions>,
+    biomes: Res<Biomes>,
+    mut map_palette: ResMut<MapPalette>,
+    loaded: Option<ResMut<LoadedChunks>>,
+    world_view: Option<ResMut<WorldView>>,
+    material_handle: Option<Res<BiomeTileMaterialHandle>>,
+    (mut materials, mut images): (ResMut<Assets<BiomeTileMaterial>>, ResMut<Assets<Image>>),
+) {
+    if options.palette == map_palette.name {
+        return;
+    }
+
+    *map_palette = MapPalette::load(&options.palette);
+    if let Some(mut loaded) = loaded {
+        clear_loaded_chunks(&mut loaded);
+    }
+    if let Some(mut world_view) = world_view {
+        world_view.forget_overview();
+    }
+    if let Some(handle) = material_handle
+        && let Some(material) = materials.get_mut(&handle.0)
+    {
+        material.atlas = images.add(build_biome_atlas(&biomes.table, &map_palette.palette));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_palettes_parse() {
+        for (name, text) in BUILT_IN_PALETTES {
+            assert!(ron::from_str::<Palette>(text).is_ok(), "{}", name);
+        }
+    }
+}
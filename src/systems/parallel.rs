@@ -0,0 +1,4 @@
+// The world generation crate's parallel iterators, so the game's own passes run
+// on the same rayon workers, or in order on one thread without the `parallel`
+// feature
+pub use kingdom_worldgen::parallel::*;
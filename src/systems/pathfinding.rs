@@ -0,0 +1,446 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use kingdom_worldgen::{climate_square, sampler::WorldSampler};
+
+use crate::components::{
+    kingdoms::Road,
+    world::{Biome, WorldMap},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    parallel::*,
+    world::{WorldSettings, index_toroidal, toroidal_delta},
+    world_gen::biome_table::BiomeTable,
+};
+
+// Cost of crossing open grassland; every other cost is relative to it
+pub const BASE_COST: u32 = 10;
+// Any road, whatever the ground under it; `BiomeTable` refuses biomes cheaper
+// than this, which keeps the A* heuristic below the true cost
+const ROAD_COST: u32 = BASE_COST / 2;
+// A streamed world's grid has about this many cells along each side, each one
+// sampled at its centre
+const STREAMED_CELLS: i32 = 256;
+
+// What a traveller may cross besides open land. Oceans and mountains are
+// impassable unless allowed here.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Passage {
+    pub water: bool,
+    pub mountains: bool,
+}
+
+impl Passage {
+    pub const LAND: Passage = Passage {
+        water: false,
+        mountains: false,
+    };
+
+    // Cost of stepping onto a tile of this biome, or None where it can't be
+    // entered. Never below a road's.
+    pub fn movement_cost(&self, biome: Biome, biomes: &BiomeTable) -> Option<u32> {
+        let allowed = match biome {
+            Biome::Ocean | Biome::ShallowOcean => self.water,
+            Biome::Mountain => self.mountains,
+            _ => true,
+        };
+        allowed.then(|| {
+            ((biomes.movement_cost(biome) * BASE_COST as f32).round() as u32).max(ROAD_COST)
+        })
+    }
+}
+
+// Biomes of the world and the roads built over it, shared so paths can be
+// searched off the main thread without copying the world for each search. A
+// pregenerated map gives one cell per tile; a streamed world is sampled into
+// coarser cells, as it has no full map to copy.
+#[derive(Resource, Clone)]
+pub struct PathGrid {
+    world_size: i32,
+    // Tiles along each side of a cell
+    cell: i32,
+    // Sized to the cells, for the world's wrapping helpers
+    cells: WorldSettings,
+    biomes: Arc<Vec<Biome>>,
+    roads: Arc<Vec<bool>>,
+    // Movement costs the world was generated with
+    table: Arc<BiomeTable>,
+}
+
+impl PathGrid {
+    pub fn from_world_map(world_map: &WorldMap, table: Arc<BiomeTable>) -> Self {
+        PathGrid {
+            world_size: world_map.width as i32,
+            cell: 1,
+            cells: WorldSettings {
+                world_size: world_map.width as i32,
+                ..default()
+            },
+            biomes: Arc::new(world_map.biome().to_vec()),
+            roads: Arc::new(vec![false; world_map.tile_count()]),
+            table,
+        }
+    }
+
+    // Tiles past the last whole cell fall in with it
+    pub fn from_sampler(sampler: &WorldSampler, table: Arc<BiomeTable>) -> Self {
+        let world_size = sampler.world_size();
+        let cell = (world_size / STREAMED_CELLS).max(1);
+        let side = world_size / cell;
+        let biomes = (0..side * side)
+            .into_par_iter()
+            .map(|index| {
+                let centre = IVec2::new(index % side, index / side) * cell + cell / 2;
+                climate_square(sampler, sampler.world_data(), centre.x, centre.y).biome
+            })
+            .collect();
+
+        PathGrid {
+            world_size,
+            cell,
+            cells: WorldSettings {
+                world_size: side,
+                ..default()
+            },
+            biomes: Arc::new(biomes),
+            roads: Arc::new(vec![false; (side * side) as usize]),
+            table,
+        }
+    }
+
+    pub fn movement_cost(&self, tile: IVec2, passage: Passage) -> Option<u32> {
+        self.step_cost(self.index(tile), passage)
+    }
+
+    pub fn biome(&self, tile: IVec2) -> Biome {
+        self.biomes[self.index(tile)]
+    }
+
+    // Searches already running keep the grid they started with
+    pub fn add_road(&mut self, tiles: &[IVec2]) {
+        let indices: Vec<usize> = tiles.iter().map(|tile| self.index(*tile)).collect();
+        let roads = Arc::make_mut(&mut self.roads);
+        for index in indices {
+            roads[index] = true;
+        }
+    }
+
+    // Roads only ever cross land their builders could walk, so they stay open
+    // to anyone allowed onto the ground beneath
+    fn step_cost(&self, index: usize, passage: Passage) -> Option<u32> {
+        let cost = passage.movement_cost(self.biomes[index], &self.table)?;
+        Some(if self.roads[index] { ROAD_COST } else { cost })
+    }
+
+    fn index(&self, tile: IVec2) -> usize {
+        let tile = tile.rem_euclid(IVec2::splat(self.world_size));
+        let cell = (tile / self.cell).min(IVec2::splat(self.cells.world_size - 1));
+        index_toroidal(cell.x, cell.y, &self.cells)
+    }
+
+    fn cell(&self, index: usize) -> IVec2 {
+        let side = self.cells.world_size;
+        IVec2::new(index as i32 % side, index as i32 / side)
+    }
+
+    fn centre(&self, index: usize) -> IVec2 {
+        self.cell(index) * self.cell + self.cell / 2
+    }
+
+    // Fewest steps between two cells with the world wrapping on both axes
+    fn wrapped_steps(&self, from: usize, to: usize) -> u32 {
+        let delta = toroidal_delta(self.cell(from), self.cell(to), &self.cells);
+        (delta.x.abs() + delta.y.abs()) as u32
+    }
+}
+
+// A* over the four cell neighbours, wrapping across the world edges. Returns the
+// tiles from `start` to `goal` inclusive, in base world coordinates, or None
+// when the goal can't be reached under `passage`. On a coarse grid the route
+// runs through the centres of the cells it crosses. Long searches can stall a
+// frame; `spawn_find_path` runs the same search on the task pool.
+pub fn find_path(
+    grid: &PathGrid,
+    start_tile: IVec2,
+    goal_tile: IVec2,
+    passage: Passage,
+) -> Option<Vec<IVec2>> {
+    let start = grid.index(start_tile);
+    let goal = grid.index(goal_tile);
+    grid.step_cost(goal, passage)?;
+
+    // Every step costs at least ROAD_COST, so this never overestimates
+    let heuristic = |index: usize| grid.wrapped_steps(index, goal) * ROAD_COST;
+
+    let mut open = BinaryHeap::new();
+    let mut cost_so_far: HashMap<usize, u32> = HashMap::new();
+    let mut came_from: HashMap<usize, usize> = HashMap::new();
+    open.push(Reverse((heuristic(start), start)));
+    cost_so_far.insert(start, 0);
+
+    while let Some(Reverse((estimate, current))) = open.pop() {
+        if current == goal {
+            let mut cells = vec![current];
+            while let Some(&previous) = came_from.get(cells.last().unwrap()) {
+                cells.push(previous);
+            }
+            let world = WorldSettings {
+                world_size: grid.world_size,
+                ..default()
+            };
+            let mut path = vec![start_tile.rem_euclid(IVec2::splat(grid.world_size))];
+            for &cell in cells.iter().rev().skip(1) {
+                walk_to(&mut path, grid.centre(cell), &world);
+            }
+            walk_to(&mut path, goal_tile, &world);
+            return Some(path);
+        }
+
+        let cost = cost_so_far[&current];
+        // A cheaper route here was already expanded
+        if estimate > cost + heuristic(current) {
+            continue;
+        }
+
+        let cell = grid.cell(current);
+        for offset in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            let next = index_toroidal(cell.x + offset.x, cell.y + offset.y, &grid.cells);
+            let Some(step_cost) = grid.step_cost(next, passage) else {
+                continue;
+            };
+
+            let next_cost = cost + step_cost;
+            if cost_so_far
+                .get(&next)
+                .is_none_or(|&known| next_cost < known)
+            {
+                cost_so_far.insert(next, next_cost);
+                came_from.insert(next, current);
+                open.push(Reverse((next_cost + heuristic(next), next)));
+            }
+        }
+    }
+
+    None
+}
+
+// `find_path` on the async task pool, for routes across a continent or more
+pub fn spawn_find_path(
+    grid: &PathGrid,
+    start: IVec2,
+    goal: IVec2,
+    passage: Passage,
+) -> Task<Option<Vec<IVec2>>> {
+    let grid = grid.clone();
+    AsyncComputeTaskPool::get().spawn(async move { find_path(&grid, start, goal, passage) })
+}
+
+// Heads straight for `goal` from the last tile of `path`, stepping along
+// whichever axis is further from it and wrapping the short way
+fn walk_to(path: &mut Vec<IVec2>, goal: IVec2, settings: &WorldSettings) {
+    let size = settings.world_size;
+    let mut tile = *path.last().unwrap();
+
+    loop {
+        let delta = toroidal_delta(tile, goal, settings);
+        if delta == IVec2::ZERO {
+            return;
+        }
+
+        let step = if delta.x.abs() >= delta.y.abs() {
+            IVec2::new(delta.x.signum(), 0)
+        } else {
+            IVec2::new(0, delta.y.signum())
+        };
+        tile = (tile + step).rem_euclid(IVec2::splat(size));
+        path.push(tile);
+    }
+}
+
+// The tiles from `start` to `goal` in a straight line, whatever lies between
+pub fn straight_path(start: IVec2, goal: IVec2, settings: &WorldSettings) -> Vec<IVec2> {
+    let mut path = vec![start];
+    walk_to(&mut path, goal, settings);
+
+    path
+}
+
+// Set while a streamed world's grid is sampled
+#[derive(Resource)]
+pub struct PathGridTask(Task<PathGrid>);
+
+// A streamed world's grid is sampled off the main thread, which takes a moment
+pub fn build_path_grid(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    world_map_query: Query<&WorldMap>,
+    world_data: Single<&WorldData>,
+    road_query: Query<&Road>,
+) {
+    let table = world_data.biomes.clone();
+    if let Ok(world_map) = world_map_query.single() {
+        let mut grid = PathGrid::from_world_map(world_map, table);
+        add_roads(&mut grid, &road_query);
+        commands.insert_resource(grid);
+        return;
+    }
+
+    let sampler = WorldSampler::new(&world_data, settings.world_size);
+    let task =
+        AsyncComputeTaskPool::get().spawn(async move { PathGrid::from_sampler(&sampler, table) });
+    commands.insert_resource(PathGridTask(task));
+}
+
+pub fn poll_path_grid(
+    mut commands: Commands,
+    mut task: ResMut<PathGridTask>,
+    road_query: Query<&Road>,
+) {
+    let Some(mut grid) = check_ready(&mut task.0) else {
+        return;
+    };
+    add_roads(&mut grid, &road_query);
+    commands.remove_resource::<PathGridTask>();
+    commands.insert_resource(grid);
+}
+
+// Roads of a loaded game are down before the grid is
+fn add_roads(grid: &mut PathGrid, road_query: &Query<&Road>) {
+    for road in road_query {
+        grid.add_road(&road.tiles);
+    }
+}
+
+pub fn cleanup_path_grid(mut commands: Commands) {
+    commands.remove_resource::<PathGrid>();
+    commands.remove_resource::<PathGridTask>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One row per y, from y = 0: `.` grassland, `f` forest, `~` ocean
+    fn grid(rows: &[&str]) -> PathGrid {
+        let size = rows.len() as i32;
+        let biomes = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|tile| match tile {
+                'f' => Biome::Forest,
+                '~' => Biome::Ocean,
+                _ => Biome::Grassland,
+            })
+            .collect();
+
+        PathGrid {
+            world_size: size,
+            cell: 1,
+            cells: WorldSettings {
+                world_size: size,
+                ..default()
+            },
+            biomes: Arc::new(biomes),
+            roads: Arc::new(vec![false; (size * size) as usize]),
+            table: Arc::new(BiomeTable::default()),
+        }
+    }
+
+    // Each tile one step from the last, allowing for the wrap
+    fn assert_connected(path: &[IVec2], size: i32) {
+        let settings = WorldSettings {
+            world_size: size,
+            ..default()
+        };
+        for pair in path.windows(2) {
+            let delta = toroidal_delta(pair[0], pair[1], &settings);
+            assert_eq!(delta.x.abs() + delta.y.abs(), 1, "{:?}", path);
+        }
+    }
+
+    #[test]
+    fn paths_detour_around_water() {
+        #[rustfmt::skip]
+        let grid = grid(&[
+            "...~..~.",
+            "...~..~.",
+            "...~..~.",
+            "...~..~.",
+            "...~..~.",
+            "......~.",
+            "...~..~.",
+            "...~..~.",
+        ]);
+
+        let path = find_path(&grid, IVec2::new(1, 1), IVec2::new(5, 1), Passage::LAND).unwrap();
+
+        assert_connected(&path, 8);
+        assert_eq!(path.first(), Some(&IVec2::new(1, 1)));
+        assert_eq!(path.last(), Some(&IVec2::new(5, 1)));
+        assert!(path.contains(&IVec2::new(3, 5)));
+        assert!(path.iter().all(|tile| grid.biome(*tile) != Biome::Ocean));
+        // Down to the gap and back up again
+        assert_eq!(path.len(), 13);
+    }
+
+    #[test]
+    fn paths_wrap_across_the_world_edge() {
+        let grid = grid(&["........"; 8]);
+
+        let path = find_path(&grid, IVec2::new(0, 3), IVec2::new(7, 3), Passage::LAND).unwrap();
+        assert_eq!(path, vec![IVec2::new(0, 3), IVec2::new(7, 3)]);
+
+        let path = find_path(&grid, IVec2::new(2, 0), IVec2::new(2, 6), Passage::LAND).unwrap();
+        assert_eq!(
+            path,
+            vec![IVec2::new(2, 0), IVec2::new(2, 7), IVec2::new(2, 6)]
+        );
+    }
+
+    #[test]
+    fn paths_prefer_a_road_to_rough_ground() {
+        let mut grid = grid(&["ffffffff"; 8]);
+        let road: Vec<IVec2> = (1..=6).map(|x| IVec2::new(x, 3)).collect();
+        grid.add_road(&road);
+
+        let path = find_path(&grid, IVec2::new(1, 2), IVec2::new(6, 2), Passage::LAND).unwrap();
+
+        assert_connected(&path, 8);
+        assert!(road.iter().all(|tile| path.contains(tile)));
+    }
+
+    #[test]
+    fn unreachable_goals_have_no_path() {
+        #[rustfmt::skip]
+        let grid = grid(&[
+            "........",
+            "........",
+            "........",
+            "....~~~.",
+            "....~.~.",
+            "....~~~.",
+            "........",
+            "........",
+        ]);
+
+        assert_eq!(
+            find_path(&grid, IVec2::new(1, 1), IVec2::new(5, 4), Passage::LAND),
+            None
+        );
+        let by_sea = Passage {
+            water: true,
+            mountains: false,
+        };
+        assert!(find_path(&grid, IVec2::new(1, 1), IVec2::new(5, 4), by_sea).is_some());
+        // Nor can a path end in the water itself
+        assert_eq!(
+            find_path(&grid, IVec2::new(1, 1), IVec2::new(4, 4), Passage::LAND),
+            None
+        );
+    }
+}
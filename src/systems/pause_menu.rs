@@ -0,0 +1,406 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{
+        localization::Localized,
+        pause_menu::{PauseMenuAction, PauseMenuUI, PauseSeedText, PausesSim},
+        world_gen::WorldData,
+    },
+    states::game_state::GameState,
+    systems::{
+        bug_report::BugReportRequest,
+        keybindings::{InputAction, InputMap, PendingRebind, open_keybindings_page},
+        localization::Locale,
+        export::{ExportKind, MapExportRequest},
+        map_capture::MapCaptureRequest,
+        options::{GameOptions, open_options_page},
+        save_game::SaveGameRequest,
+    },
+};
+
+// Simulation ticks stop while anything holds the sim paused; rendering and the
+// camera keep running so the map can still be inspected.
+#[derive(Resource, Default)]
+pub struct Pause {
+    pub manual: bool,
+    pub menu_open: bool,
+    // Any `PausesSim` panel is shown
+    pub panel_open: bool,
+}
+
+impl Pause {
+    pub fn is_paused(&self) -> bool {
+        self.manual || self.menu_open || self.panel_open
+    }
+}
+
+pub fn simulation_running(pause: Res<Pause>) -> bool {
+    !pause.is_paused()
+}
+
+pub fn toggle_pause(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut pause: ResMut<Pause>,
+) {
+    if input_map.just_pressed(&input, InputAction::Pause) {
+        pause.manual = !pause.manual;
+    }
+}
+
+// The history, diplomacy and world stats panels hold the sim while they are open,
+// so nothing changes under what they show. The tile inspector doesn't, as every
+// click on the map opens it.
+pub fn pause_for_panels(
+    mut pause: ResMut<Pause>,
+    panel_query: Query<&Visibility, With<PausesSim>>,
+) {
+    let open = panel_query
+        .iter()
+        .any(|visibility| *visibility != Visibility::Hidden);
+    if pause.panel_open != open {
+        pause.panel_open = open;
+    }
+}
+
+pub fn toggle_pause_menu(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut pause: ResMut<Pause>,
+    menu_query: Query<Entity, With<PauseMenuUI>>,
+) {
+    if !input.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    if pause.menu_open {
+        close_pause_menu(&mut commands, &mut pause, &menu_query);
+    } else {
+        open_pause_menu(&mut commands, &mut pause);
+    }
+}
+
+type ButtonChanged = (Changed<Interaction>, With<Button>);
+
+pub fn pause_menu_buttons(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut pause: ResMut<Pause>,
+    input_map: Res<InputMap>,
+    options: Res<GameOptions>,
+    interaction_query: Query<(&Interaction, &PauseMenuAction), ButtonChanged>,
+    menu_query: Query<Entity, With<PauseMenuUI>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match action {
+                PauseMenuAction::Resume => {
+                    close_pause_menu(&mut commands, &mut pause, &menu_query);
+                }
+                PauseMenuAction::Controls => {
+                    open_keybindings_page(&mut commands, &input_map, &menu_query);
+                }
+                PauseMenuAction::Options => {
+                    for entity in &menu_query {
+                        commands.entity(entity).despawn();
+                    }
+                    open_options_page(&mut commands, &options, PauseMenuUI);
+                }
+                PauseMenuAction::SaveGame => {
+                    commands.insert_resource(SaveGameRequest);
+                }
+                PauseMenuAction::ExportMap => {
+                    commands.insert_resource(MapExportRequest(ExportKind::Map));
+                }
+                PauseMenuAction::ExportHeightmap => {
+                    commands.insert_resource(MapExportRequest(ExportKind::Heightmap));
+                }
+                PauseMenuAction::CaptureMap => {
+                    commands.insert_resource(MapCaptureRequest);
+                }
+                PauseMenuAction::ReportBug | PauseMenuAction::ReportBugWithScreenshot => {
+                    // Closing first keeps the menu out of the screenshot
+                    close_pause_menu(&mut commands, &mut pause, &menu_query);
+                    commands.insert_resource(BugReportRequest {
+                        screenshot: matches!(action, PauseMenuAction::ReportBugWithScreenshot),
+                    });
+                }
+                PauseMenuAction::MainMenu => {
+                    next_state.set(GameState::MainMenu);
+                }
+            }
+        }
+    }
+}
+
+pub fn cleanup_pause(
+    mut commands: Commands,
+    mut pause: ResMut<Pause>,
+    query: Query<Entity, With<PauseMenuUI>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    *pause = Pause::default();
+    commands.remove_resource::<PendingRebind>();
+}
+
+// Saving, exports and bug reports write files under the user data folder, which
+// the web build doesn't have, so their buttons are left out there
+pub fn file_action_display() -> Display {
+    if cfg!(target_arch = "wasm32") {
+        Display::None
+    } else {
+        Display::Flex
+    }
+}
+
+pub fn open_pause_menu(commands: &mut Commands, pause: &mut Pause) {
+    pause.menu_open = true;
+
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(16.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        PauseMenuUI,
+        children![
+            (
+                Text::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                PauseSeedText,
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::Resume,
+                children![(
+                    Text::new("Resume"),
+                    Localized::new("pause.resume"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::Controls,
+                children![(
+                    Text::new("Controls"),
+                    Localized::new("pause.controls"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::Options,
+                children![(
+                    Text::new("Options"),
+                    Localized::new("menu.options"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    display: file_action_display(),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::SaveGame,
+                children![(
+                    Text::new("Save Game"),
+                    Localized::new("pause.save_game"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+            (
+                // The exports and the capture share a row to keep the menu on screen
+                Node {
+                    column_gap: Val::Px(16.0),
+                    display: file_action_display(),
+                    ..default()
+                },
+                children![
+                    (
+                        Button,
+                        Node {
+                            padding: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                        PauseMenuAction::ExportMap,
+                        children![(
+                            Text::new("Export Map"),
+                            Localized::new("pause.export_map"),
+                            TextFont {
+                                font_size: 32.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE)
+                        )]
+                    ),
+                    (
+                        Button,
+                        Node {
+                            padding: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                        PauseMenuAction::ExportHeightmap,
+                        children![(
+                            Text::new("Export Heightmap"),
+                            Localized::new("pause.export_heightmap"),
+                            TextFont {
+                                font_size: 32.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE)
+                        )]
+                    ),
+                    (
+                        Button,
+                        Node {
+                            padding: UiRect::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                        PauseMenuAction::CaptureMap,
+                        children![(
+                            Text::new("Capture Map"),
+                            Localized::new("pause.capture_map"),
+                            TextFont {
+                                font_size: 32.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE)
+                        )]
+                    ),
+                ],
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    display: file_action_display(),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::ReportBug,
+                children![(
+                    Text::new("Report Bug"),
+                    Localized::new("pause.report_bug"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    display: file_action_display(),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::ReportBugWithScreenshot,
+                children![(
+                    Text::new("Report Bug + Screenshot"),
+                    Localized::new("pause.report_bug_screenshot"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+            (
+                Button,
+                Node {
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                PauseMenuAction::MainMenu,
+                children![(
+                    Text::new("Main Menu"),
+                    Localized::new("pause.main_menu"),
+                    TextFont {
+                        font_size: 32.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE)
+                )]
+            ),
+        ],
+    ));
+}
+
+// The menu is opened from several places without the world data at hand, so the
+// seed line is written here on the frame after
+pub fn show_pause_seed(
+    locale: Res<Locale>,
+    world_data_query: Query<&WorldData>,
+    mut text_query: Query<&mut Text, Added<PauseSeedText>>,
+) {
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+    for mut text in &mut text_query {
+        text.0 = locale.seed(world_data.seed, world_data.seed_text.as_deref());
+    }
+}
+
+fn close_pause_menu(
+    commands: &mut Commands,
+    pause: &mut Pause,
+    menu_query: &Query<Entity, With<PauseMenuUI>>,
+) {
+    pause.menu_open = false;
+
+    for entity in menu_query {
+        commands.entity(entity).despawn();
+    }
+}
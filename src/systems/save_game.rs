@@ -0,0 +1,1012 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{
+        buildings::{Building, BuildingKind},
+        chronicle::ChronicleEntry,
+        diplomacy::Relation,
+        kingdoms::{
+            AiControlled, Culture, Era, Fame, FoundingParty, Kingdom, OwnedBy, Road, Technology,
+            Treasury,
+        },
+        lairs::{HuntingParty, Lair, LairKind},
+        main_menu::LoadGameStatus,
+        nomads::{Horde, Tributary},
+        settlements::{
+            Farm, FarmOf, FishingGrounds, FoodCapacity, Good, MigrantGroup, Population,
+            ProductionRates, Settlement, Stockpile,
+        },
+        trade::{Caravan, TradeRoute},
+        units::{Army, MoveOrder, Siege, Unit, UnitKind},
+        world::{Biome, ResourceKind, Square, WorldMap},
+        world_gen::WorldData,
+    },
+    states::game_state::GameState,
+    systems::{
+        buildings::spawn_building,
+        chronicle::Chronicle,
+        diplomacy::Diplomacy,
+        farming::spawn_farm,
+        growth::{DEFAULT_DIFFICULTY, Growth},
+        kingdoms::TerritoryMap,
+        lairs::spawn_lair,
+        localization::Locale,
+        season::Season,
+        settlements::spawn_settlement,
+        startup::user_data_dir,
+        units::spawn_unit,
+        wildlife::{SavedWildlife, WildlifeMap},
+        world::{LoadedChunks, WorldSettings},
+        world_gen::{biome_table::Biomes, settlements::SettlementSite},
+        world_record::WorldRecord,
+    },
+};
+
+// A save is the magic and format version, a length-prefixed RON header, the
+// pregenerated map (if any) as fixed-size little-endian records, then the tiles a
+// streamed world has changed as records behind their coordinates
+const SAVE_MAGIC: &[u8; 4] = b"KSAV";
+const SAVE_VERSION: u32 = 2;
+const SAVE_FILE: &str = "world.sav";
+// Biome and resource bytes, then elevation, temperature, moisture and vegetation
+const SQUARE_BYTES: usize = 2 + 4 * 4;
+
+// Everything besides the map needed to pick the world back up. Later sim state
+// goes here too; fields missing from older saves take their defaults.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct SaveHeader {
+    world_data: WorldData,
+    settings: WorldSettings,
+    season: Season,
+    camera_position: Vec2,
+    camera_zoom: f32,
+    kingdoms: Vec<SavedKingdom>,
+    settlements: Vec<SavedSettlement>,
+    // Kingdoms are referred to by their index in `kingdoms`, settlements by theirs
+    // in `settlements`. One gone by the time of saving is None.
+    tile_claims: Vec<(IVec2, Option<usize>)>,
+    chronicle: Vec<ChronicleEntry>,
+    sim: SavedSim,
+    // Name of the growth model played by; empty in saves from before difficulties
+    difficulty: String,
+    record: WorldRecord,
+}
+
+// What kingdoms and settlements have set going, referring to them by index
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SavedSim {
+    relations: Vec<(Option<usize>, Option<usize>, Relation)>,
+    units: Vec<SavedUnit>,
+    buildings: Vec<(BuildingKind, IVec2)>,
+    farms: Vec<SavedFarm>,
+    roads: Vec<Vec<IVec2>>,
+    trade_routes: Vec<SavedTradeRoute>,
+    // Animals in each wildlife cell; empty if their habitats weren't surveyed yet
+    wildlife: Vec<f32>,
+    lairs: Vec<SavedLair>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedKingdom {
+    name: String,
+    color: [f32; 4],
+    culture: Culture,
+    treasury: f32,
+    era: Era,
+    research: f32,
+    // Older saves only held kingdoms the simulation ran
+    #[serde(default)]
+    player: bool,
+    #[serde(default)]
+    fame: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedSettlement {
+    name: String,
+    tile: IVec2,
+    population: f32,
+    food_capacity: f32,
+    stockpile: Stockpile,
+    production: ProductionRates,
+    fishing: FishingGrounds,
+    kingdom: Option<usize>,
+}
+
+// A unit's path is searched again once it is back on the map
+#[derive(Serialize, Deserialize)]
+struct SavedUnit {
+    kind: UnitKind,
+    tile: IVec2,
+    progress: f32,
+    goal: Option<IVec2>,
+    // Kingdom and strength
+    army: Option<(Option<usize>, f32)>,
+    // Settlement and progress
+    siege: Option<(Option<usize>, f32)>,
+    // Kingdom and settlers
+    founding: Option<(Option<usize>, f32)>,
+    migrants: Option<SavedMigrants>,
+    caravan: Option<SavedCaravan>,
+    // Older saves have no hordes
+    #[serde(default)]
+    horde: Option<SavedHorde>,
+    // Kingdom, lair by its index in `lairs`, and strength
+    #[serde(default)]
+    hunt: Option<(Option<usize>, Option<usize>, f32)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedMigrants {
+    from: Option<usize>,
+    destination: Option<Option<usize>>,
+    population: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedCaravan {
+    from: Option<usize>,
+    to: Option<usize>,
+    good: Good,
+    amount: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedHorde {
+    name: String,
+    culture: Culture,
+    strength: f32,
+    summer_pasture: IVec2,
+    winter_pasture: IVec2,
+    bound_for: Option<IVec2>,
+    // Kingdom, years paid and the last year spared
+    tributaries: Vec<(Option<usize>, u32, u32)>,
+    raid_cooldown: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedLair {
+    kind: LairKind,
+    tile: IVec2,
+    strength: f32,
+    raid_cooldown: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedFarm {
+    tile: IVec2,
+    yield_per_year: f32,
+    settlement: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedTradeRoute {
+    from: Option<usize>,
+    to: Option<usize>,
+    tiles: Vec<IVec2>,
+    volume: f32,
+    idle: f32,
+}
+
+impl Default for SaveHeader {
+    fn default() -> Self {
+        SaveHeader {
+            world_data: WorldData::default(),
+            settings: WorldSettings::default(),
+            season: Season::default(),
+            camera_position: Vec2::ZERO,
+            camera_zoom: 1.0,
+            kingdoms: Vec::new(),
+            settlements: Vec::new(),
+            tile_claims: Vec::new(),
+            chronicle: Vec::new(),
+            sim: SavedSim::default(),
+            difficulty: String::new(),
+            record: WorldRecord::default(),
+        }
+    }
+}
+
+pub struct SavedWorld {
+    header: SaveHeader,
+    // None for streamed worlds, which regenerate their chunks from the seed
+    world_map: Option<WorldMap>,
+    // Streamed worlds only, laid back over their chunks as they load
+    edits: Vec<(IVec2, Square)>,
+}
+
+type KingdomState = (
+    Entity,
+    &'static Kingdom,
+    &'static Culture,
+    &'static Treasury,
+    &'static Technology,
+    &'static Fame,
+    Has<AiControlled>,
+);
+
+// What a save keeps of each settlement
+type SettlementState = (
+    Entity,
+    &'static Settlement,
+    &'static Population,
+    &'static FoodCapacity,
+    &'static Stockpile,
+    &'static ProductionRates,
+    &'static FishingGrounds,
+    Option<&'static OwnedBy>,
+);
+
+type UnitState = (
+    &'static Unit,
+    &'static UnitKind,
+    Option<&'static MoveOrder>,
+    Option<&'static Army>,
+    Option<&'static Siege>,
+    Option<&'static FoundingParty>,
+    Option<&'static MigrantGroup>,
+    Option<&'static Caravan>,
+    Option<&'static Horde>,
+    Option<&'static HuntingParty>,
+);
+
+type WorksQuery<'w, 's> = (
+    Query<'w, 's, &'static Building>,
+    Query<'w, 's, (&'static Farm, &'static FarmOf)>,
+    Query<'w, 's, &'static Road>,
+    Query<'w, 's, &'static TradeRoute>,
+);
+
+type GameRecords<'w> = (
+    Res<'w, WorldSettings>,
+    Res<'w, Season>,
+    Res<'w, Chronicle>,
+    Res<'w, Diplomacy>,
+    Res<'w, Growth>,
+    Res<'w, WorldRecord>,
+);
+
+// Tile claims waiting for the territory map of a loaded game. While it exists the
+// settlements and kingdoms come from the save rather than being founded afresh.
+#[derive(Resource)]
+pub struct SavedTerritory(Vec<(IVec2, Option<Entity>)>);
+
+// Set by the pause menu; the world is written on the next frame
+#[derive(Resource)]
+pub struct SaveGameRequest;
+
+#[derive(Resource)]
+pub struct LoadGameTask(Task<io::Result<SavedWorld>>);
+
+fn save_path() -> PathBuf {
+    user_data_dir().join("saves").join(SAVE_FILE)
+}
+
+pub fn save_exists() -> bool {
+    save_path().is_file()
+}
+
+pub fn save_game(
+    mut commands: Commands,
+    (settings, season, chronicle, diplomacy, growth, record): GameRecords,
+    (territory, wildlife, loaded): (
+        Option<Res<TerritoryMap>>,
+        Option<Res<WildlifeMap>>,
+        Res<LoadedChunks>,
+    ),
+    (world_data_query, world_map_query): (Query<&WorldData>, Query<&WorldMap>),
+    camera_query: Single<(&Transform, &Projection), With<Camera2d>>,
+    (kingdom_query, settlement_query): (Query<KingdomState>, Query<SettlementState>),
+    (unit_query, lair_query, (building_query, farm_query, road_query, route_query)): (
+        Query<UnitState>,
+        Query<(Entity, &Lair)>,
+        WorksQuery,
+    ),
+) {
+    commands.remove_resource::<SaveGameRequest>();
+
+    let world_data = match world_data_query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let (transform, projection) = *camera_query;
+    let camera_zoom = match projection {
+        Projection::Orthographic(projection2d) => projection2d.scale,
+        _ => 1.0,
+    };
+    let mut header = SaveHeader {
+        world_data,
+        settings: *settings,
+        season: Season {
+            year: season.year,
+            year_fraction: season.year_fraction,
+        },
+        camera_position: transform.translation.truncate(),
+        camera_zoom,
+        kingdoms: Vec::new(),
+        settlements: Vec::new(),
+        tile_claims: Vec::new(),
+        chronicle: chronicle.entries().to_vec(),
+        sim: SavedSim {
+            roads: road_query.iter().map(|road| road.tiles.clone()).collect(),
+            wildlife: wildlife
+                .and_then(|wildlife| wildlife.surveyed_stock().map(<[f32]>::to_vec))
+                .unwrap_or_default(),
+            ..default()
+        },
+        difficulty: growth.difficulty.clone(),
+        record: record.clone(),
+    };
+
+    let mut kingdoms = Vec::new();
+    for (entity, kingdom, culture, treasury, technology, fame, ai_controlled) in &kingdom_query {
+        kingdoms.push(entity);
+        header.kingdoms.push(SavedKingdom {
+            name: kingdom.name.clone(),
+            color: kingdom.color.to_srgba().to_f32_array(),
+            culture: *culture,
+            treasury: treasury.0,
+            era: technology.era,
+            research: technology.research,
+            player: !ai_controlled,
+            fame: fame.0,
+        });
+    }
+    let kingdom_index = |entity: Entity| kingdoms.iter().position(|&kingdom| kingdom == entity);
+    let settlements: Vec<Entity> = settlement_query.iter().map(|(entity, ..)| entity).collect();
+    let settlement_index = |entity: Entity| {
+        settlements
+            .iter()
+            .position(|&settlement| settlement == entity)
+    };
+
+    for (_, settlement, population, capacity, stockpile, production, fishing, owned_by) in
+        &settlement_query
+    {
+        header.settlements.push(SavedSettlement {
+            name: settlement.name.clone(),
+            tile: settlement.tile,
+            population: population.0,
+            food_capacity: capacity.0,
+            stockpile: *stockpile,
+            production: *production,
+            fishing: *fishing,
+            kingdom: owned_by.and_then(|owned_by| kingdom_index(owned_by.0)),
+        });
+    }
+    if let Some(territory) = territory {
+        header.tile_claims = territory
+            .tile_claims()
+            .map(|(tile, owner)| (tile, owner.and_then(kingdom_index)))
+            .collect();
+    }
+    header.sim.relations = diplomacy
+        .relations()
+        .map(|(a, b, relation)| (kingdom_index(a), kingdom_index(b), relation))
+        .collect();
+
+    let lairs: Vec<Entity> = lair_query.iter().map(|(entity, _)| entity).collect();
+    let lair_index = |entity: Entity| lairs.iter().position(|&lair| lair == entity);
+    header.sim.lairs = lair_query
+        .iter()
+        .map(|(_, lair)| SavedLair {
+            kind: lair.kind,
+            tile: lair.tile,
+            strength: lair.strength,
+            raid_cooldown: lair.raid_cooldown,
+        })
+        .collect();
+
+    for (unit, kind, order, army, siege, party, migrants, caravan, horde, hunt) in &unit_query {
+        header.sim.units.push(SavedUnit {
+            kind: *kind,
+            tile: unit.tile,
+            progress: unit.progress,
+            goal: order.map(|order| order.goal),
+            army: army.map(|army| (kingdom_index(army.kingdom), army.strength)),
+            siege: siege.map(|siege| (settlement_index(siege.settlement), siege.progress)),
+            founding: party.map(|party| (kingdom_index(party.kingdom), party.population)),
+            migrants: migrants.map(|group| SavedMigrants {
+                from: settlement_index(group.from),
+                destination: group.destination.map(settlement_index),
+                population: group.population,
+            }),
+            caravan: caravan.map(|caravan| SavedCaravan {
+                from: settlement_index(caravan.from),
+                to: settlement_index(caravan.to),
+                good: caravan.good,
+                amount: caravan.amount,
+            }),
+            horde: horde.map(|horde| SavedHorde {
+                name: horde.name.clone(),
+                culture: horde.culture,
+                strength: horde.strength,
+                summer_pasture: horde.summer_pasture,
+                winter_pasture: horde.winter_pasture,
+                bound_for: horde.bound_for,
+                tributaries: horde
+                    .tributaries
+                    .iter()
+                    .map(|tributary| {
+                        let kingdom = kingdom_index(tributary.kingdom);
+                        (kingdom, tributary.years, tributary.until_year)
+                    })
+                    .collect(),
+                raid_cooldown: horde.raid_cooldown,
+            }),
+            hunt: hunt.map(|hunt| {
+                let kingdom = kingdom_index(hunt.kingdom);
+                (kingdom, lair_index(hunt.lair), hunt.strength)
+            }),
+        });
+    }
+    header.sim.buildings = building_query
+        .iter()
+        .map(|building| (building.kind, building.tile))
+        .collect();
+    header.sim.farms = farm_query
+        .iter()
+        .map(|(farm, farm_of)| SavedFarm {
+            tile: farm.tile,
+            yield_per_year: farm.yield_per_year,
+            settlement: settlement_index(farm_of.0),
+        })
+        .collect();
+    header.sim.trade_routes = route_query
+        .iter()
+        .map(|route| SavedTradeRoute {
+            from: settlement_index(route.from),
+            to: settlement_index(route.to),
+            tiles: route.tiles.clone(),
+            volume: route.volume,
+            idle: route.idle,
+        })
+        .collect();
+
+    let edits: Vec<(IVec2, Square)> = loaded
+        .edits
+        .iter()
+        .map(|(tile, square)| (*tile, square.clone()))
+        .collect();
+    let path = save_path();
+    match write_save(&path, &header, world_map_query.single().ok(), &edits) {
+        Ok(()) => info!("World saved to {}", path.display()),
+        Err(err) => error!("Could not save the world to {}: {}", path.display(), err),
+    }
+}
+
+// Reads the save off the main thread; large maps take a while
+pub fn start_loading_game(commands: &mut Commands) {
+    let task = AsyncComputeTaskPool::get().spawn(async move { read_save(&save_path()) });
+    commands.insert_resource(LoadGameTask(task));
+}
+
+pub fn poll_loading_game(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut task: ResMut<LoadGameTask>,
+    (mut season, mut chronicle, mut loaded_chunks): (
+        ResMut<Season>,
+        ResMut<Chronicle>,
+        ResMut<LoadedChunks>,
+    ),
+    (biomes, mut growth): (Res<Biomes>, ResMut<Growth>),
+    (locale, mut status_query): (Res<Locale>, Query<&mut Text, With<LoadGameStatus>>),
+    camera_query: Single<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    let Some(loaded) = check_ready(&mut task.0) else {
+        return;
+    };
+    commands.remove_resource::<LoadGameTask>();
+
+    let saved = match loaded {
+        Ok(saved) => saved,
+        Err(err) => {
+            error!("Could not load the saved world: {}", err);
+            for mut status in &mut status_query {
+                status.0 = locale.format("menu.load_failed", &[&err]);
+            }
+            return;
+        }
+    };
+    let mut header = saved.header;
+
+    header.world_data.biomes = biomes.table.clone();
+    commands.spawn(header.world_data);
+    if let Some(world_map) = saved.world_map {
+        commands.spawn(world_map);
+    }
+    commands.insert_resource(header.settings);
+    *season = header.season;
+    *chronicle = Chronicle::restore(header.chronicle);
+    commands.insert_resource(header.record);
+    growth.difficulty = if header.difficulty.is_empty() {
+        DEFAULT_DIFFICULTY.to_string()
+    } else {
+        header.difficulty
+    };
+    loaded_chunks.edits = saved.edits.into_iter().collect();
+
+    let kingdoms: Vec<Entity> = header
+        .kingdoms
+        .into_iter()
+        .map(|kingdom| {
+            let [r, g, b, a] = kingdom.color;
+            let mut entity = commands.spawn((
+                Kingdom {
+                    name: kingdom.name,
+                    color: Color::srgba(r, g, b, a),
+                },
+                kingdom.culture,
+                Treasury(kingdom.treasury),
+                Technology {
+                    era: kingdom.era,
+                    research: kingdom.research,
+                },
+                Fame(kingdom.fame),
+            ));
+            if !kingdom.player {
+                entity.insert(AiControlled);
+            }
+            entity.id()
+        })
+        .collect();
+    let mut settlements = Vec::new();
+    for saved_settlement in header.settlements {
+        let site = SettlementSite {
+            name: saved_settlement.name,
+            tile: saved_settlement.tile,
+            food_capacity: saved_settlement.food_capacity,
+            production: saved_settlement.production,
+            fishing: saved_settlement.fishing,
+        };
+        let settlement = spawn_settlement(&mut commands, site, saved_settlement.population);
+        settlements.push(settlement);
+        commands.entity(settlement).insert(saved_settlement.stockpile);
+        if let Some(&kingdom) = saved_settlement.kingdom.and_then(|index| kingdoms.get(index)) {
+            commands.entity(settlement).insert(OwnedBy(kingdom));
+        }
+    }
+    restore_sim(&mut commands, header.sim, &kingdoms, &settlements);
+    let tile_claims = header
+        .tile_claims
+        .into_iter()
+        .filter_map(|(tile, owner)| match owner {
+            Some(index) => kingdoms.get(index).map(|&kingdom| (tile, Some(kingdom))),
+            None => Some((tile, None)),
+        })
+        .collect();
+    commands.insert_resource(SavedTerritory(tile_claims));
+
+    let (mut transform, mut projection) = camera_query.into_inner();
+    transform.translation.x = header.camera_position.x;
+    transform.translation.y = header.camera_position.y;
+    if let Projection::Orthographic(projection2d) = &mut *projection {
+        projection2d.scale = header.camera_zoom;
+    }
+
+    next_state.set(GameState::Playing);
+}
+
+// Everything that refers to kingdoms and settlements, once they are spawned. A
+// reference to one that was already gone when the game was saved stays dangling,
+// which the systems using it already allow for.
+fn restore_sim(
+    commands: &mut Commands,
+    sim: SavedSim,
+    kingdoms: &[Entity],
+    settlements: &[Entity],
+) {
+    let kingdom = |index: Option<usize>| {
+        index
+            .and_then(|index| kingdoms.get(index).copied())
+            .unwrap_or(Entity::PLACEHOLDER)
+    };
+    let settlement = |index: Option<usize>| {
+        index
+            .and_then(|index| settlements.get(index).copied())
+            .unwrap_or(Entity::PLACEHOLDER)
+    };
+
+    commands.insert_resource(Diplomacy::restore(
+        sim.relations
+            .iter()
+            .map(|&(a, b, relation)| (kingdom(a), kingdom(b), relation)),
+    ));
+
+    let lairs: Vec<Entity> = sim
+        .lairs
+        .iter()
+        .map(|lair| {
+            spawn_lair(
+                commands,
+                lair.kind,
+                lair.tile,
+                lair.strength,
+                lair.raid_cooldown,
+            )
+        })
+        .collect();
+    let lair = |index: Option<usize>| {
+        index
+            .and_then(|index| lairs.get(index).copied())
+            .unwrap_or(Entity::PLACEHOLDER)
+    };
+
+    for saved in sim.units {
+        let unit = spawn_unit(commands, saved.kind, saved.tile);
+        let mut unit = commands.entity(unit);
+        unit.insert(Unit {
+            tile: saved.tile,
+            progress: saved.progress,
+        });
+        if let Some(goal) = saved.goal {
+            unit.insert(MoveOrder::new(goal));
+        }
+        if let Some((index, strength)) = saved.army {
+            unit.insert(Army {
+                kingdom: kingdom(index),
+                strength,
+            });
+        }
+        if let Some((index, progress)) = saved.siege {
+            unit.insert(Siege {
+                settlement: settlement(index),
+                progress,
+            });
+        }
+        if let Some((index, population)) = saved.founding {
+            unit.insert(FoundingParty {
+                kingdom: kingdom(index),
+                population,
+            });
+        }
+        if let Some(group) = saved.migrants {
+            unit.insert(MigrantGroup {
+                from: settlement(group.from),
+                destination: group.destination.map(settlement),
+                population: group.population,
+            });
+        }
+        if let Some(caravan) = saved.caravan {
+            unit.insert(Caravan {
+                from: settlement(caravan.from),
+                to: settlement(caravan.to),
+                good: caravan.good,
+                amount: caravan.amount,
+            });
+        }
+        if let Some(horde) = saved.horde {
+            unit.insert(Horde {
+                name: horde.name,
+                culture: horde.culture,
+                strength: horde.strength,
+                summer_pasture: horde.summer_pasture,
+                winter_pasture: horde.winter_pasture,
+                bound_for: horde.bound_for,
+                tributaries: horde
+                    .tributaries
+                    .into_iter()
+                    .map(|(index, years, until_year)| Tributary {
+                        kingdom: kingdom(index),
+                        years,
+                        until_year,
+                    })
+                    .collect(),
+                raid_cooldown: horde.raid_cooldown,
+            });
+        }
+        if let Some((kingdom_index, lair_index, strength)) = saved.hunt {
+            unit.insert(HuntingParty {
+                kingdom: kingdom(kingdom_index),
+                lair: lair(lair_index),
+                strength,
+            });
+        }
+    }
+
+    for &(kind, tile) in &sim.buildings {
+        spawn_building(commands, kind, tile);
+    }
+    // Farms go with their settlement, so one whose settlement is gone is dropped
+    for farm in &sim.farms {
+        if let Some(&owner) = farm.settlement.and_then(|index| settlements.get(index)) {
+            let entity = spawn_farm(commands, farm.tile, farm.yield_per_year);
+            commands.entity(entity).insert(FarmOf(owner));
+        }
+    }
+    for tiles in sim.roads {
+        commands.spawn(Road { tiles });
+    }
+    for route in sim.trade_routes {
+        commands.spawn(TradeRoute {
+            from: settlement(route.from),
+            to: settlement(route.to),
+            tiles: route.tiles,
+            volume: route.volume,
+            idle: route.idle,
+        });
+    }
+    if !sim.wildlife.is_empty() {
+        commands.insert_resource(SavedWildlife(sim.wildlife));
+    }
+}
+
+// Runs once the territory map exists, before its first claims are made
+pub fn restore_territory(
+    mut commands: Commands,
+    saved: Res<SavedTerritory>,
+    mut territory: ResMut<TerritoryMap>,
+) {
+    territory.restore_tile_claims(saved.0.iter().copied());
+    commands.remove_resource::<SavedTerritory>();
+}
+
+// Written beside the save and renamed over it, so a failed write can't destroy
+// the previous save
+fn write_save(
+    path: &PathBuf,
+    header: &SaveHeader,
+    world_map: Option<&WorldMap>,
+    edits: &[(IVec2, Square)],
+) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let partial = path.with_extension("sav.partial");
+    let header_text = ron::to_string(header).map_err(io::Error::other)?;
+
+    let mut writer = BufWriter::new(File::create(&partial)?);
+    writer.write_all(SAVE_MAGIC)?;
+    writer.write_all(&SAVE_VERSION.to_le_bytes())?;
+    writer.write_all(&(header_text.len() as u32).to_le_bytes())?;
+    writer.write_all(header_text.as_bytes())?;
+
+    match world_map {
+        Some(world_map) => {
+            writer.write_all(&[1])?;
+            writer.write_all(&world_map.width.to_le_bytes())?;
+            writer.write_all(&world_map.height.to_le_bytes())?;
+            for square in world_map.squares() {
+                writer.write_all(&encode_square(&square))?;
+            }
+        }
+        None => writer.write_all(&[0])?,
+    }
+    writer.write_all(&(edits.len() as u32).to_le_bytes())?;
+    for (tile, square) in edits {
+        writer.write_all(&tile.x.to_le_bytes())?;
+        writer.write_all(&tile.y.to_le_bytes())?;
+        writer.write_all(&encode_square(square))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|err| err.into_error())?
+        .sync_all()?;
+
+    fs::rename(&partial, path)
+}
+
+fn read_save(path: &PathBuf) -> io::Result<SavedWorld> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let header = read_header(&mut reader)?;
+
+    let mut has_map = [0; 1];
+    reader.read_exact(&mut has_map)?;
+    let world_map = match has_map[0] {
+        0 => None,
+        _ => {
+            let width = read_u32(&mut reader)?;
+            let height = read_u32(&mut reader)?;
+            let world_size = header.settings.world_size;
+            if world_size <= 0 || width != world_size as u32 || height != world_size as u32 {
+                return Err(invalid_data(&format!(
+                    "map of {}x{} tiles in a world of size {}",
+                    width, height, world_size
+                )));
+            }
+            check_remaining(
+                &mut reader,
+                width as u64 * height as u64 * SQUARE_BYTES as u64,
+                "map",
+            )?;
+            let mut squares = Vec::with_capacity(width as usize * height as usize);
+            let mut record = [0; SQUARE_BYTES];
+            for _ in 0..width as usize * height as usize {
+                reader.read_exact(&mut record)?;
+                squares.push(decode_square(&record)?);
+            }
+            Some(WorldMap::from_squares(width, height, squares))
+        }
+    };
+
+    let edit_count = read_u32(&mut reader)?;
+    check_remaining(
+        &mut reader,
+        edit_count as u64 * (8 + SQUARE_BYTES) as u64,
+        "tile edits",
+    )?;
+    let mut edits = Vec::with_capacity(edit_count as usize);
+    let mut record = [0; SQUARE_BYTES];
+    for _ in 0..edit_count {
+        let tile = IVec2::new(read_u32(&mut reader)? as i32, read_u32(&mut reader)? as i32);
+        reader.read_exact(&mut record)?;
+        edits.push((tile, decode_square(&record)?));
+    }
+
+    Ok(SavedWorld {
+        header,
+        world_map,
+        edits,
+    })
+}
+
+// The saved world's year and record, read from its header alone for the main
+// menu to show
+pub fn saved_record() -> io::Result<(u32, WorldRecord)> {
+    let mut reader = BufReader::new(File::open(save_path())?);
+    let header = read_header(&mut reader)?;
+
+    Ok((header.season.year, header.record))
+}
+
+fn read_header(reader: &mut BufReader<File>) -> io::Result<SaveHeader> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != SAVE_MAGIC {
+        return Err(invalid_data("not a Kingdom Sim save"));
+    }
+    let version = read_u32(reader)?;
+    if version != SAVE_VERSION {
+        return Err(invalid_data(&format!(
+            "unsupported save version {}",
+            version
+        )));
+    }
+
+    let header_len = read_u32(reader)?;
+    check_remaining(reader, header_len as u64, "header")?;
+    let mut header_text = vec![0; header_len as usize];
+    reader.read_exact(&mut header_text)?;
+    let header_text = String::from_utf8(header_text).map_err(io::Error::other)?;
+    let header: SaveHeader = ron::from_str(&header_text).map_err(io::Error::other)?;
+    check_world_size(&header.settings)?;
+
+    Ok(header)
+}
+
+fn encode_square(square: &Square) -> [u8; SQUARE_BYTES] {
+    let mut record = [0; SQUARE_BYTES];
+    record[0] = square.biome as u8;
+    record[1] = square.resources as u8;
+    let values = [
+        square.elevation,
+        square.temperature,
+        square.moisture,
+        square.vegetation,
+    ];
+    for (index, value) in values.iter().enumerate() {
+        record[2 + index * 4..6 + index * 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    record
+}
+
+fn decode_square(record: &[u8; SQUARE_BYTES]) -> io::Result<Square> {
+    let value =
+        |index: usize| f32::from_le_bytes(record[2 + index * 4..6 + index * 4].try_into().unwrap());
+
+    Ok(Square {
+        biome: *Biome::ALL
+            .get(record[0] as usize)
+            .ok_or_else(|| invalid_data("unknown biome"))?,
+        resources: *ResourceKind::ALL
+            .get(record[1] as usize)
+            .ok_or_else(|| invalid_data("unknown resource"))?,
+        elevation: value(0),
+        temperature: value(1),
+        moisture: value(2),
+        vegetation: value(3),
+    })
+}
+
+// Chunks tile the world exactly only when both sizes are powers of two and the
+// chunk is no larger than the world
+fn check_world_size(settings: &WorldSettings) -> io::Result<()> {
+    let power_of_two = |size: i32| size > 0 && size.count_ones() == 1;
+    if !power_of_two(settings.world_size)
+        || !power_of_two(settings.chunk_size)
+        || settings.chunk_size > settings.world_size
+    {
+        return Err(invalid_data(&format!(
+            "world size {} with chunk size {}",
+            settings.world_size, settings.chunk_size
+        )));
+    }
+
+    Ok(())
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+// Guards the lengths read from the file, so a damaged save is refused rather than
+// sizing an allocation
+fn check_remaining(reader: &mut BufReader<File>, bytes: u64, what: &str) -> io::Result<()> {
+    let remaining = reader.get_ref().metadata()?.len().saturating_sub(reader.stream_position()?);
+    if bytes > remaining {
+        return Err(invalid_data(&format!(
+            "{} runs past the end of the save",
+            what
+        )));
+    }
+
+    Ok(())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squares_round_trip_through_their_record() {
+        let square = Square {
+            biome: Biome::ALL[Biome::ALL.len() - 1],
+            elevation: 0.61,
+            temperature: -0.25,
+            moisture: 0.125,
+            vegetation: 0.9,
+            resources: ResourceKind::ALL[ResourceKind::ALL.len() - 1],
+        };
+
+        let record = encode_square(&square);
+        assert_eq!(record.len(), 18);
+        let decoded = decode_square(&record).unwrap();
+        assert_eq!(decoded.biome, square.biome);
+        assert_eq!(decoded.resources, square.resources);
+        assert_eq!(decoded.elevation, square.elevation);
+        assert_eq!(decoded.temperature, square.temperature);
+        assert_eq!(decoded.moisture, square.moisture);
+        assert_eq!(decoded.vegetation, square.vegetation);
+    }
+
+    #[test]
+    fn unknown_biomes_are_refused() {
+        let mut record = encode_square(&Square::default());
+        record[0] = Biome::ALL.len() as u8;
+        assert!(decode_square(&record).is_err());
+    }
+
+    #[test]
+    fn world_sizes_must_split_into_whole_chunks() {
+        let sizes = |world_size, chunk_size| WorldSettings {
+            world_size,
+            chunk_size,
+            ..default()
+        };
+        assert!(check_world_size(&sizes(512, 32)).is_ok());
+        assert!(check_world_size(&sizes(500, 32)).is_err());
+        assert!(check_world_size(&sizes(512, 48)).is_err());
+        assert!(check_world_size(&sizes(32, 64)).is_err());
+        assert!(check_world_size(&sizes(0, 0)).is_err());
+    }
+}
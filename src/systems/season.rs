@@ -0,0 +1,108 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::world::{Biome, Square};
+use crate::systems::clock::GameClock;
+use crate::systems::world::{LoadedChunks, clear_loaded_chunks};
+
+pub const YEAR_LENGTH_SECS: f32 = 240.0;
+const MONTHS_PER_YEAR: u32 = 12;
+const DAYS_PER_MONTH: u32 = 30;
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+pub const SEASON_NAMES: [&str; 4] = ["Winter", "Spring", "Summer", "Autumn"];
+// Peak seasonal swing in °C at the poles; the equator has no seasons
+const SEASONAL_AMPLITUDE: f32 = 15.0;
+const SEA_ICE_TEMPERATURE: f32 = -10.0;
+const SNOW_COVER_TEMPERATURE: f32 = -2.0;
+
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct Season {
+    pub year: u32,
+    // Fraction of the year elapsed, 0.0 is midwinter in the northern hemisphere
+    pub year_fraction: f32,
+}
+
+impl Season {
+    pub fn month(&self) -> u32 {
+        (self.year_fraction * MONTHS_PER_YEAR as f32) as u32 % MONTHS_PER_YEAR
+    }
+
+    // Day of the month, counted from 1
+    pub fn day(&self) -> u32 {
+        let days = self.year_fraction * (MONTHS_PER_YEAR * DAYS_PER_MONTH) as f32;
+        days as u32 % DAYS_PER_MONTH + 1
+    }
+
+    // Seasons counted from the first year, December joining the winter after it
+    pub fn season_number(&self) -> u32 {
+        (self.year * MONTHS_PER_YEAR + self.month() + 1) / 3
+    }
+
+    pub fn month_name(&self) -> &'static str {
+        MONTH_NAMES[self.month() as usize]
+    }
+
+    pub fn name(&self, latitude: f32) -> &'static str {
+        // Southern hemisphere seasons run half a year out of phase
+        let month = if latitude < 0.0 {
+            (self.month() + MONTHS_PER_YEAR / 2) % MONTHS_PER_YEAR
+        } else {
+            self.month()
+        };
+
+        SEASON_NAMES[match month {
+            0 | 1 | 11 => 0,
+            2..=4 => 1,
+            5..=7 => 2,
+            _ => 3,
+        }]
+    }
+
+    // `latitude` is signed in [-1, 1], positive north of the equator
+    pub fn temperature_offset(&self, latitude: f32) -> f32 {
+        -SEASONAL_AMPLITUDE * latitude * (self.year_fraction * TAU).cos()
+    }
+
+    pub fn temperature(&self, square: &Square, latitude: f32) -> f32 {
+        square.temperature + self.temperature_offset(latitude)
+    }
+
+    // Seasonal snow and sea ice laid over the base biome for display
+    pub fn biome(&self, square: &Square, latitude: f32) -> Biome {
+        let temperature = self.temperature(square, latitude);
+
+        match square.biome {
+            Biome::Ice | Biome::Snow => square.biome,
+            _ if temperature < SEA_ICE_TEMPERATURE => Biome::Ice,
+            Biome::Ocean | Biome::ShallowOcean => square.biome,
+            _ if temperature < SNOW_COVER_TEMPERATURE => Biome::Snow,
+            _ => square.biome,
+        }
+    }
+}
+
+pub fn tile_latitude(y: i32, world_size: i32) -> f32 {
+    let half_world = world_size as f32 / 2.0;
+    (y.rem_euclid(world_size) as f32 - half_world) / half_world
+}
+
+pub fn advance_season(
+    clock: Res<GameClock>,
+    mut season: ResMut<Season>,
+    mut loaded: ResMut<LoadedChunks>,
+) {
+    let previous_month = season.month();
+
+    let elapsed = season.year_fraction + clock.delta_secs / YEAR_LENGTH_SECS;
+    season.year += elapsed as u32;
+    season.year_fraction = elapsed.fract();
+
+    // Snow lines only move month to month, so chunks are rebuilt on that cadence
+    if season.month() != previous_month {
+        clear_loaded_chunks(&mut loaded);
+    }
+}
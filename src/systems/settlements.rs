@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    settlements::{
+        FoodCapacity, Population, ProductionRates, Settlement, SettlementTier, Specialization,
+    },
+    world_gen::WorldData,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    economy::founding_stockpile,
+    growth::Growth,
+    localization::Locale,
+    season::{Season, YEAR_LENGTH_SECS},
+    world::{WorldSettings, nearest_copy},
+    world_gen::settlements::{SettlementSite, place_settlements},
+};
+
+// Founders per settlement when the world starts
+const FOUNDING_POPULATION: f32 = 80.0;
+// Below this a settlement is abandoned in all but name; it never drops further
+pub const MIN_POPULATION: f32 = 10.0;
+const MARKER_Z: f32 = 0.45;
+
+pub fn spawn_settlements(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    query: Query<&WorldData>,
+) {
+    let world_data = match query.single() {
+        Ok(data) => data,
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+
+    for site in place_settlements(world_data, &settings) {
+        let population = FOUNDING_POPULATION
+            .min(site.food_capacity)
+            .max(MIN_POPULATION);
+        spawn_settlement(&mut commands, site, population);
+    }
+}
+
+pub fn spawn_settlement(commands: &mut Commands, site: SettlementSite, population: f32) -> Entity {
+    let population = population.max(MIN_POPULATION);
+    let tier = SettlementTier::from_population(population);
+    let position = (site.tile.as_vec2() + Vec2::splat(0.5)).extend(MARKER_Z);
+
+    commands
+        .spawn((
+            Sprite::from_color(tier_color(tier), Vec2::splat(tier.marker_size())),
+            Transform::from_translation(position),
+            Settlement {
+                name: site.name,
+                tile: site.tile,
+            },
+            Population(population),
+            FoodCapacity(site.food_capacity),
+            founding_stockpile(population),
+            Specialization::from_production(&site.production),
+            site.production,
+            site.fishing,
+            tier,
+        ))
+        .id()
+}
+
+// Farms, mines and harbours change what a settlement yields, and so what it
+// lives off
+pub fn specialize_settlements(
+    mut query: Query<(&ProductionRates, &mut Specialization), Changed<ProductionRates>>,
+) {
+    for (production, mut specialization) in &mut query {
+        specialization.set_if_neq(Specialization::from_production(production));
+    }
+}
+
+// Logistic growth toward what the land can feed, with the odd good harvest,
+// famine or plague on top, at the rates of the game's growth model.
+// Overcrowded settlements shrink back toward capacity. Each settlement grows by
+// the model's curve for what it lives off.
+pub fn grow_population(
+    clock: Res<GameClock>,
+    growth: Res<Growth>,
+    season: Res<Season>,
+    locale: Res<Locale>,
+    mut chronicle: ResMut<Chronicle>,
+    mut query: Query<(
+        &Settlement,
+        &mut Population,
+        &FoodCapacity,
+        &mut SettlementTier,
+        &Specialization,
+    )>,
+) {
+    let years = clock.delta_secs / YEAR_LENGTH_SECS;
+    let mut rng = rand::rng();
+
+    for (settlement, mut population, capacity, mut tier, specialization) in &mut query {
+        let model = growth.model_for(*specialization);
+        let mut value = population.0;
+        let capacity = capacity.0.max(MIN_POPULATION);
+        value += model.growth_rate * value * (1.0 - value / capacity) * years;
+
+        if rng.random_bool(model.good_harvest_chance * years as f64) {
+            value *= 1.0 + model.good_harvest_gain;
+        }
+        if rng.random_bool(model.famine_chance * years as f64) {
+            value *= 1.0 - model.famine_loss;
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::Famine,
+                locale.format("chronicle.famine", &[&settlement.name]),
+            );
+        }
+        if rng.random_bool(model.plague_chance * years as f64) {
+            value *= 1.0 - model.plague_loss;
+            chronicle.record(
+                locale.date(&season),
+                ChronicleKind::Disaster,
+                locale.format("chronicle.plague", &[&settlement.name]),
+            );
+        }
+
+        population.0 = value.max(MIN_POPULATION);
+        tier.set_if_neq(SettlementTier::from_population(population.0));
+    }
+}
+
+// Keeps each marker on the copy of its tile nearest the camera, like landmark
+// markers, and redraws it when the settlement changes tier
+pub fn update_settlement_markers(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut marker_query: Query<
+        (
+            &Settlement,
+            Ref<SettlementTier>,
+            &mut Sprite,
+            &mut Transform,
+        ),
+        Without<Camera2d>,
+    >,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for (settlement, tier, mut sprite, mut transform) in &mut marker_query {
+        let nearest = nearest_copy(camera, settlement.tile, &settings);
+        transform.translation = (nearest.as_vec2() + Vec2::splat(0.5)).extend(MARKER_Z);
+
+        if tier.is_changed() {
+            sprite.color = tier_color(*tier);
+            sprite.custom_size = Some(Vec2::splat(tier.marker_size()));
+        }
+    }
+}
+
+pub fn cleanup_settlements(mut commands: Commands, query: Query<Entity, With<Settlement>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn tier_color(tier: SettlementTier) -> Color {
+    match tier {
+        SettlementTier::Hamlet => Color::srgb(0.85, 0.75, 0.55),
+        SettlementTier::Town => Color::srgb(0.95, 0.6, 0.2),
+        SettlementTier::City => Color::srgb(0.95, 0.95, 0.95),
+    }
+}
@@ -0,0 +1,349 @@
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+use bevy::audio::{Decodable, Source, Volume};
+use bevy::prelude::*;
+
+use crate::components::sound::{Ambience, AmbientLoop, MenuMusic};
+use crate::components::world::{Biome, DominantBiome};
+use crate::systems::options::GameOptions;
+use crate::systems::world::{LoadedChunks, WorldSettings};
+
+// The game ships no recordings; every sound is synthesized as it plays
+const SAMPLE_RATE: u32 = 44_100;
+const CLICK_SECS: f64 = 0.05;
+// Chunks either side of the one under the camera that vote on the ambience
+const AMBIENCE_RADIUS_CHUNKS: i32 = 2;
+// Fraction of full volume an ambience gains or loses per second while fading
+const AMBIENCE_FADE_PER_SEC: f32 = 0.5;
+// Seconds per note of the menu music and the chords it arpeggiates, as MIDI
+// note numbers: A minor, F, C, G
+const MUSIC_NOTE_SECS: f64 = 0.45;
+const MUSIC_CHORDS: [[u8; 4]; 4] = [
+    [57, 60, 64, 69],
+    [53, 57, 60, 65],
+    [48, 52, 55, 60],
+    [55, 59, 62, 67],
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Synth {
+    MenuMusic,
+    Click,
+    Ambient(Ambience),
+}
+
+#[derive(Asset, TypePath)]
+pub struct SynthSound(pub Synth);
+
+impl Decodable for SynthSound {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            synth: self.0,
+            sample: 0,
+            noise: 0x9E37_79B9,
+            low: 0.0,
+            phase: 0.0,
+        }
+    }
+}
+
+// Mono samples for one playback. Loops never run out; the click ends on its own.
+pub struct SynthDecoder {
+    synth: Synth,
+    sample: u64,
+    // Xorshift state for the noise behind waves, wind and rustling leaves
+    noise: u32,
+    // One-pole low-pass filter state, turning white noise into a rumble
+    low: f32,
+    // Birdsong sweeps its pitch, so its oscillator keeps a running phase
+    phase: f64,
+}
+
+impl SynthDecoder {
+    fn white_noise(&mut self) -> f32 {
+        self.noise ^= self.noise << 13;
+        self.noise ^= self.noise >> 17;
+        self.noise ^= self.noise << 5;
+        self.noise as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    // Noise with the highs rolled off; a larger `cutoff` lets more through
+    fn filtered_noise(&mut self, cutoff: f32) -> f32 {
+        let noise = self.white_noise();
+        self.low += (noise - self.low) * cutoff;
+        self.low
+    }
+
+    fn click(&self, t: f64) -> f32 {
+        ((TAU * 1400.0 * t).sin() * (-t * 90.0).exp() * 0.4) as f32
+    }
+
+    // Surf rising and breaking on a seven second swell
+    fn waves(&mut self, t: f64) -> f32 {
+        let swell = (0.5 + 0.5 * (TAU * t / 7.0).sin()).powi(3) as f32;
+        let surf = self.filtered_noise(0.03 + 0.05 * swell);
+        surf * (0.15 + 0.85 * swell) * 2.5
+    }
+
+    // Steady moan with slow gusts that brighten as they build
+    fn wind(&mut self, t: f64) -> f32 {
+        let gust = (0.5 + 0.25 * (TAU * t / 11.0).sin() + 0.25 * (TAU * t / 3.7).sin()) as f32;
+        let moan = self.filtered_noise(0.005 + 0.03 * gust);
+        moan * (0.3 + 0.7 * gust) * 5.0
+    }
+
+    // Bursts of three rising chirps every couple of seconds over rustling leaves;
+    // the pitch and whether a burst sings at all vary from burst to burst
+    fn birds(&mut self, t: f64) -> f32 {
+        let rustle = self.filtered_noise(0.08) * 0.08;
+
+        let burst_secs = 2.3;
+        let burst = (t / burst_secs) as u64;
+        let hash = burst.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 40;
+        let sings = !hash.is_multiple_of(3);
+        let in_burst = t % burst_secs;
+        let chirp = (in_burst / 0.15) as u32;
+        let in_chirp = in_burst % 0.15;
+        if !sings || chirp >= 3 || in_chirp >= 0.08 {
+            return rustle;
+        }
+
+        let base = 2600.0 + (hash % 1200) as f64;
+        let frequency = base + 1500.0 * in_chirp / 0.08;
+        self.phase = (self.phase + TAU * frequency / SAMPLE_RATE as f64) % TAU;
+        let envelope = (std::f64::consts::PI * in_chirp / 0.08).sin();
+
+        rustle + (self.phase.sin() * envelope * 0.2) as f32
+    }
+
+    // A soft picked arpeggio over a four chord loop, with the chord root held
+    // underneath
+    fn menu_music(&self, t: f64) -> f32 {
+        let note = (t / MUSIC_NOTE_SECS) as usize;
+        let in_note = t % MUSIC_NOTE_SECS;
+        let chord = MUSIC_CHORDS[(note / 8) % MUSIC_CHORDS.len()];
+        let pitch = chord[[0, 1, 2, 3, 2, 1, 2, 3][note % 8]];
+
+        let frequency = midi_frequency(pitch);
+        let attack = (in_note / 0.005).min(1.0);
+        let pluck = attack
+            * (-in_note * 3.0).exp()
+            * ((TAU * frequency * t).sin() + 0.3 * (TAU * 2.0 * frequency * t).sin());
+        let bass = 0.4 * (TAU * midi_frequency(chord[0] - 12) * t).sin();
+
+        ((pluck + bass) * 0.2) as f32
+    }
+}
+
+fn midi_frequency(note: u8) -> f64 {
+    440.0 * 2f64.powf((note as f64 - 69.0) / 12.0)
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let t = self.sample as f64 / SAMPLE_RATE as f64;
+        self.sample += 1;
+
+        let value = match self.synth {
+            Synth::Click if t >= CLICK_SECS => return None,
+            Synth::Click => self.click(t),
+            Synth::MenuMusic => self.menu_music(t),
+            Synth::Ambient(Ambience::Waves) => self.waves(t),
+            Synth::Ambient(Ambience::Wind) => self.wind(t),
+            Synth::Ambient(Ambience::Birds) => self.birds(t),
+        };
+
+        Some(value.clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        match self.synth {
+            Synth::Click => Some(Duration::from_secs_f64(CLICK_SECS)),
+            _ => None,
+        }
+    }
+}
+
+// Handles to every sound, made once at startup
+#[derive(Resource)]
+pub struct SoundLibrary {
+    menu_music: Handle<SynthSound>,
+    click: Handle<SynthSound>,
+    ambience: [Handle<SynthSound>; Ambience::ALL.len()],
+}
+
+impl SoundLibrary {
+    pub fn new(sounds: &mut Assets<SynthSound>) -> Self {
+        SoundLibrary {
+            menu_music: sounds.add(SynthSound(Synth::MenuMusic)),
+            click: sounds.add(SynthSound(Synth::Click)),
+            ambience: Ambience::ALL
+                .map(|ambience| sounds.add(SynthSound(Synth::Ambient(ambience)))),
+        }
+    }
+}
+
+// Land with no sound of its own, like towns, counts toward no ambience
+fn ambience_for(biome: Biome) -> Option<Ambience> {
+    match biome {
+        Biome::Ocean | Biome::ShallowOcean | Biome::Coast => Some(Ambience::Waves),
+        Biome::Tundra
+        | Biome::Snow
+        | Biome::Ice
+        | Biome::Alpine
+        | Biome::Mountain
+        | Biome::Hill
+        | Biome::ColdDesert
+        | Biome::Desert
+        | Biome::HotDesert => Some(Ambience::Wind),
+        Biome::Forest
+        | Biome::TemperateForest
+        | Biome::TemperateRainforest
+        | Biome::SubtropicalForest
+        | Biome::TropicalRainforest
+        | Biome::BorealForest
+        | Biome::Taiga
+        | Biome::Grassland
+        | Biome::Savanna
+        | Biome::Farmland
+        | Biome::Village => Some(Ambience::Birds),
+        Biome::Urban => None,
+    }
+}
+
+// Keeps playing if the player goes back to the main menu from world setup
+pub fn start_menu_music(
+    mut commands: Commands,
+    sounds: Res<SoundLibrary>,
+    options: Res<GameOptions>,
+    music_query: Query<(), With<MenuMusic>>,
+) {
+    if !music_query.is_empty() {
+        return;
+    }
+
+    commands.spawn((
+        AudioPlayer(sounds.menu_music.clone()),
+        PlaybackSettings::LOOP.with_volume(Volume::Linear(options.music_volume)),
+        MenuMusic,
+    ));
+}
+
+pub fn stop_menu_music(mut commands: Commands, music_query: Query<Entity, With<MenuMusic>>) {
+    for entity in &music_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub fn start_ambient_loops(mut commands: Commands, sounds: Res<SoundLibrary>) {
+    for (ambience, sound) in Ambience::ALL.into_iter().zip(&sounds.ambience) {
+        commands.spawn((
+            AudioPlayer(sound.clone()),
+            PlaybackSettings::LOOP.with_volume(Volume::SILENT),
+            AmbientLoop {
+                ambience,
+                level: 0.0,
+            },
+        ));
+    }
+}
+
+pub fn stop_ambient_loops(mut commands: Commands, loop_query: Query<Entity, With<AmbientLoop>>) {
+    for entity in &loop_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+// The ambience most of the chunks around the camera call for fades in, so
+// panning from a forest out to sea crossfades birdsong into surf
+pub fn update_ambient_loops(
+    time: Res<Time>,
+    options: Res<GameOptions>,
+    settings: Res<WorldSettings>,
+    loaded: Res<LoadedChunks>,
+    camera: Single<&Transform, With<Camera2d>>,
+    chunk_query: Query<&DominantBiome>,
+    mut loop_query: Query<(&mut AmbientLoop, Option<&mut AudioSink>)>,
+) {
+    let center = camera
+        .translation
+        .truncate()
+        .floor()
+        .as_ivec2()
+        .div_euclid(IVec2::splat(settings.chunk_size));
+    let mut votes = [0; Ambience::ALL.len()];
+    for dy in -AMBIENCE_RADIUS_CHUNKS..=AMBIENCE_RADIUS_CHUNKS {
+        for dx in -AMBIENCE_RADIUS_CHUNKS..=AMBIENCE_RADIUS_CHUNKS {
+            let Some(&entity) = loaded.chunks.get(&(center.x + dx, center.y + dy)) else {
+                continue;
+            };
+            if let Ok(dominant) = chunk_query.get(entity)
+                && let Some(ambience) = ambience_for(dominant.0)
+            {
+                votes[ambience as usize] += 1;
+            }
+        }
+    }
+    let chosen = Ambience::ALL
+        .into_iter()
+        .filter(|&ambience| votes[ambience as usize] > 0)
+        .max_by_key(|&ambience| votes[ambience as usize]);
+
+    let step = AMBIENCE_FADE_PER_SEC * time.delta_secs();
+    for (mut ambient, sink) in &mut loop_query {
+        let target = if chosen == Some(ambient.ambience) { 1.0 } else { 0.0 };
+        ambient.level += (target - ambient.level).clamp(-step, step);
+        if let Some(mut sink) = sink {
+            sink.set_volume(Volume::Linear(ambient.level * options.ambience_volume));
+        }
+    }
+}
+
+pub fn apply_music_volume(
+    options: Res<GameOptions>,
+    mut music_query: Query<&mut AudioSink, With<MenuMusic>>,
+) {
+    for mut sink in &mut music_query {
+        sink.set_volume(Volume::Linear(options.music_volume));
+    }
+}
+
+pub fn play_click_sounds(
+    mut commands: Commands,
+    sounds: Res<SoundLibrary>,
+    options: Res<GameOptions>,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+) {
+    if options.sound_volume <= 0.0 {
+        return;
+    }
+
+    for interaction in &button_query {
+        if *interaction == Interaction::Pressed {
+            commands.spawn((
+                AudioPlayer(sounds.click.clone()),
+                PlaybackSettings::DESPAWN.with_volume(Volume::Linear(options.sound_volume)),
+            ));
+        }
+    }
+}
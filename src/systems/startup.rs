@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    components::loading_screen::{LoadingBarFill, LoadingStageText},
+    states::game_state::GameState,
+    systems::{
+        loading_screen::show_progress,
+        tile_material::{
+            BiomeTileMaterial, TileTextures, build_tile_textures, create_biome_tile_material,
+            load_biome_tile_shader,
+        },
+        event_scripts::load_event_scripts,
+        growth::load_growth_models,
+        keybindings::load_input_map,
+        options::load_options,
+        localization::{Locale, load_locale},
+        palette::MapPalette,
+        sound::{SoundLibrary, SynthSound},
+        water_material::{WaterMaterial, create_water_material, load_water_shader},
+        world_gen::biome_table::{BiomeTableAsset, Biomes, load_biomes},
+    },
+};
+
+// Everything the game needs before the main menu, loaded while the splash screen
+// keeps rendering. New startup data (tables, locales, audio) gets a slot here
+// rather than being loaded inside a setup system.
+#[derive(Resource)]
+pub struct StartupLoading {
+    shader: Handle<Shader>,
+    water_shader: Handle<Shader>,
+    // The textures are built once the biome table they are coloured from is in
+    waiting_for_biomes: bool,
+    tile_textures: Option<Task<TileTextures>>,
+}
+
+pub fn start_startup_loading(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut water_materials: ResMut<Assets<WaterMaterial>>,
+    mut sounds: ResMut<Assets<SynthSound>>,
+) {
+    let biomes = load_biomes(&asset_server);
+    let options = load_options();
+    let map_palette = MapPalette::load(&options.palette);
+
+    commands.insert_resource(StartupLoading {
+        shader: load_biome_tile_shader(&asset_server),
+        water_shader: load_water_shader(&asset_server),
+        waiting_for_biomes: true,
+        tile_textures: None,
+    });
+    commands.insert_resource(create_water_material(&mut water_materials));
+    commands.insert_resource(biomes);
+    commands.insert_resource(load_event_scripts(&asset_server));
+    commands.insert_resource(load_growth_models(&asset_server));
+    commands.insert_resource(map_palette);
+    commands.insert_resource(load_locale(&options.language));
+    commands.insert_resource(SoundLibrary::new(&mut sounds));
+    commands.insert_resource(load_input_map());
+    commands.insert_resource(options);
+}
+
+// Per-user folder for settings and bug reports
+pub fn user_data_dir() -> PathBuf {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    home.join(".kingdom_sim")
+}
+
+// Reads a RON settings file from the user data folder. A missing file is normal
+// on first run; an unreadable one is reported and ignored rather than blocking
+// startup.
+pub fn read_user_config<T: DeserializeOwned>(file: &str) -> Option<T> {
+    let path = user_data_dir().join(file);
+    let contents = fs::read_to_string(&path).ok()?;
+
+    ron::from_str(&contents)
+        .inspect_err(|err| warn!("Ignoring unreadable settings in {}: {}", path.display(), err))
+        .ok()
+}
+
+pub fn write_user_config<T: Serialize>(file: &str, value: &T) {
+    let path = user_data_dir().join(file);
+    let written = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|contents| {
+            fs::create_dir_all(path.parent().unwrap_or(&user_data_dir()))
+                .and_then(|_| fs::write(&path, contents))
+                .map_err(|err| err.to_string())
+        });
+    if let Err(err) = written {
+        error!("Could not save settings to {}: {}", path.display(), err);
+    }
+}
+
+pub fn track_startup_loading(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    (asset_server, mut loading): (Res<AssetServer>, ResMut<StartupLoading>),
+    (mut biomes, biome_assets, map_palette, locale): (
+        ResMut<Biomes>,
+        Res<Assets<BiomeTableAsset>>,
+        Res<MapPalette>,
+        Res<Locale>,
+    ),
+    (mut images, mut materials): (ResMut<Assets<Image>>, ResMut<Assets<BiomeTileMaterial>>),
+    mut bar_query: Query<&mut Node, With<LoadingBarFill>>,
+    mut text_query: Query<&mut Text, With<LoadingStageText>>,
+) {
+    // A missing or broken file leaves the built-in table
+    if loading.waiting_for_biomes
+        && (asset_server.is_loaded(&biomes.handle)
+            || asset_server.load_state(&biomes.handle).is_failed())
+    {
+        if let Some(asset) = biome_assets.get(&biomes.handle) {
+            biomes.table = asset.0.clone();
+        }
+        let table = biomes.table.clone();
+        let palette = map_palette.palette.clone();
+        let task =
+            AsyncComputeTaskPool::get().spawn(async move { build_tile_textures(&table, &palette) });
+        loading.tile_textures = Some(task);
+        loading.waiting_for_biomes = false;
+    }
+
+    if let Some(task) = loading.tile_textures.as_mut()
+        && let Some(textures) = check_ready(task)
+    {
+        let handle = create_biome_tile_material(textures, &mut images, &mut materials);
+        commands.insert_resource(handle);
+        loading.tile_textures = None;
+    }
+
+    // A failed shader still lets the game start; the tiles just render untextured
+    let shader_ready = |shader: &Handle<Shader>| {
+        asset_server.is_loaded_with_dependencies(shader)
+            || asset_server.load_state(shader).is_failed()
+    };
+    let shader_done = shader_ready(&loading.shader) && shader_ready(&loading.water_shader);
+    let textures_done = !loading.waiting_for_biomes && loading.tile_textures.is_none();
+
+    let (fraction, id) = match (shader_done, textures_done) {
+        (false, _) => (0.25, "startup.shaders"),
+        (true, false) => (0.75, "startup.textures"),
+        (true, true) => (1.0, "startup.ready"),
+    };
+    show_progress(fraction, locale.text(id), &mut bar_query, &mut text_query);
+
+    if shader_done && textures_done {
+        commands.remove_resource::<StartupLoading>();
+        next_state.set(GameState::MainMenu);
+    }
+}
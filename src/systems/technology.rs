@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use crate::components::{
+    chronicle::ChronicleKind,
+    kingdoms::{Kingdom, KingdomSettlements, Technology},
+    settlements::Population,
+};
+use crate::systems::{
+    chronicle::Chronicle,
+    clock::GameClock,
+    localization::Locale,
+    notifications::Notifications,
+    season::{Season, YEAR_LENGTH_SECS},
+};
+
+// Kingdoms count up their learning a few times a month
+const RESEARCH_TICK_SECS: f32 = 10.0;
+// Research each subject brings in a year
+const RESEARCH_PER_PERSON: f32 = 0.1;
+
+#[derive(Resource, Default)]
+pub struct ResearchTimer {
+    pub cooldown: f32,
+}
+
+// Every kingdom learns in step with how many people it holds, and enters the
+// next era once it has learned enough
+pub fn advance_research(
+    clock: Res<GameClock>,
+    (season, locale): (Res<Season>, Res<Locale>),
+    mut timer: ResMut<ResearchTimer>,
+    mut notifications: ResMut<Notifications>,
+    mut chronicle: ResMut<Chronicle>,
+    mut kingdom_query: Query<(&Kingdom, &KingdomSettlements, &mut Technology)>,
+    population_query: Query<&Population>,
+) {
+    timer.cooldown -= clock.delta_secs;
+    if timer.cooldown > 0.0 {
+        return;
+    }
+    timer.cooldown = RESEARCH_TICK_SECS;
+    let years = RESEARCH_TICK_SECS / YEAR_LENGTH_SECS;
+
+    for (kingdom, settlements, mut technology) in &mut kingdom_query {
+        let subjects: f32 = settlements
+            .iter()
+            .filter_map(|settlement| population_query.get(settlement).ok())
+            .map(|population| population.0)
+            .sum();
+        technology.research += subjects * RESEARCH_PER_PERSON * years;
+
+        while let Some((era, needed)) = technology.era.next()
+            && technology.research >= needed
+        {
+            technology.era = era;
+            let era_name = locale.name("era", era, era.name());
+            let text = locale.format("chronicle.era", &[&kingdom.name, &era_name]);
+            chronicle.record(locale.date(&season), ChronicleKind::Discovery, text.clone());
+            notifications.push(text);
+        }
+    }
+}
+
+pub fn cleanup_technology(mut timer: ResMut<ResearchTimer>) {
+    *timer = ResearchTimer::default();
+}
@@ -0,0 +1,297 @@
+use std::f64::consts::TAU;
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    prelude::*,
+    render::render_resource::{AsBindGroup, Extent3d, TextureDimension, TextureFormat},
+    shader::ShaderRef,
+    sprite_render::Material2d,
+};
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::components::world::Biome;
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::palette::Palette;
+use crate::systems::world::MapOverlay;
+use crate::systems::world_gen::biome_table::BiomeTable;
+
+const SHADER_ASSET_PATH: &str = "shaders/biome_tiles.wgsl";
+const TEXTURE_SIZE: u32 = 32;
+const TEXTURE_LAYERS: u32 = 5;
+// How many tiles one repeat of a biome texture covers
+const TILES_PER_TEXTURE: f32 = 4.0;
+// Projection scale at which textures start to fade in, and where they reach full strength
+const DETAIL_FADE_START: f32 = 0.25;
+const DETAIL_FADE_END: f32 = 0.1;
+
+const WATER_LAYER: u32 = 0;
+const GRASS_LAYER: u32 = 1;
+const SAND_LAYER: u32 = 2;
+const ROCK_LAYER: u32 = 3;
+const SNOW_LAYER: u32 = 4;
+
+// One colour texture per biome, in atlas layer order
+const ATLAS_BIOMES: [Biome; 24] = [
+    Biome::Ocean,
+    Biome::ShallowOcean,
+    Biome::Coast,
+    Biome::Grassland,
+    Biome::Forest,
+    Biome::Desert,
+    Biome::Hill,
+    Biome::Mountain,
+    Biome::Ice,
+    Biome::Alpine,
+    Biome::Snow,
+    Biome::Tundra,
+    Biome::BorealForest,
+    Biome::Taiga,
+    Biome::ColdDesert,
+    Biome::TemperateForest,
+    Biome::TemperateRainforest,
+    Biome::HotDesert,
+    Biome::Savanna,
+    Biome::SubtropicalForest,
+    Biome::TropicalRainforest,
+    Biome::Farmland,
+    Biome::Village,
+    Biome::Urban,
+];
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct BiomeTileMaterial {
+    // x: texture strength (0 = flat colors), y: tiles per texture repeat,
+    // z: 1 to draw biomes from the atlas instead of vertex colors
+    #[uniform(0)]
+    pub params: Vec4,
+    #[texture(1, dimension = "2d_array")]
+    #[sampler(2)]
+    pub textures: Handle<Image>,
+    #[texture(3, dimension = "2d_array")]
+    #[sampler(4)]
+    pub atlas: Handle<Image>,
+}
+
+// Whether biomes are drawn from the texture atlas or as the flat vertex colors
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TileRenderMode {
+    #[default]
+    Colors,
+    Textured,
+}
+
+impl Material2d for BiomeTileMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+}
+
+#[derive(Resource)]
+pub struct BiomeTileMaterialHandle(pub Handle<BiomeTileMaterial>);
+
+pub fn load_biome_tile_shader(asset_server: &AssetServer) -> Handle<Shader> {
+    asset_server.load(SHADER_ASSET_PATH)
+}
+
+pub fn create_biome_tile_material(
+    textures: TileTextures,
+    images: &mut Assets<Image>,
+    materials: &mut Assets<BiomeTileMaterial>,
+) -> BiomeTileMaterialHandle {
+    let material = materials.add(BiomeTileMaterial {
+        params: Vec4::new(0.0, TILES_PER_TEXTURE, 0.0, 0.0),
+        textures: images.add(textures.detail),
+        atlas: images.add(textures.atlas),
+    });
+
+    BiomeTileMaterialHandle(material)
+}
+
+pub fn toggle_tile_render_mode(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut mode: ResMut<TileRenderMode>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleTileRender) {
+        *mode = match *mode {
+            TileRenderMode::Colors => TileRenderMode::Textured,
+            TileRenderMode::Textured => TileRenderMode::Colors,
+        };
+    }
+}
+
+pub fn update_tile_detail(
+    projection: Single<&Projection, With<Camera2d>>,
+    handle: Res<BiomeTileMaterialHandle>,
+    mode: Res<TileRenderMode>,
+    overlay: Res<MapOverlay>,
+    mut materials: ResMut<Assets<BiomeTileMaterial>>,
+) {
+    let Projection::Orthographic(projection2d) = *projection else {
+        return;
+    };
+
+    let detail = ((DETAIL_FADE_START - projection2d.scale) / (DETAIL_FADE_START - DETAIL_FADE_END))
+        .clamp(0.0, 1.0);
+    // Data overlays carry their meaning in the vertex colors, so they never use the atlas
+    let textured = match (*mode, *overlay) {
+        (
+            TileRenderMode::Textured,
+            MapOverlay::Biome
+            | MapOverlay::Political
+            | MapOverlay::Trade
+            | MapOverlay::Wildlife,
+        ) => 1.0,
+        _ => 0.0,
+    };
+
+    // Only touch the asset when a value moves, otherwise it is re-uploaded every frame
+    if let Some(material) = materials.get(&handle.0)
+        && (material.params.x - detail).abs() < 0.01
+        && material.params.z == textured
+    {
+        return;
+    }
+
+    if let Some(material) = materials.get_mut(&handle.0) {
+        material.params.x = detail;
+        material.params.z = textured;
+    }
+}
+
+pub fn biome_texture_layer(biome: Biome) -> u32 {
+    match biome {
+        Biome::Ocean | Biome::ShallowOcean => WATER_LAYER,
+        Biome::Coast | Biome::Desert | Biome::HotDesert | Biome::ColdDesert => SAND_LAYER,
+        Biome::Hill
+        | Biome::Mountain
+        | Biome::Alpine
+        | Biome::Tundra
+        | Biome::Village
+        | Biome::Urban => ROCK_LAYER,
+        Biome::Ice | Biome::Snow => SNOW_LAYER,
+        _ => GRASS_LAYER,
+    }
+}
+
+// Atlas layer of a biome's colour texture
+pub fn biome_atlas_layer(biome: Biome) -> u32 {
+    ATLAS_BIOMES
+        .iter()
+        .position(|&atlas_biome| atlas_biome == biome)
+        .unwrap_or(0) as u32
+}
+
+pub fn neighbour_texture_layer(own_layer: u32, neighbours: [Biome; 4]) -> u32 {
+    let mut counts = [0u32; TEXTURE_LAYERS as usize];
+    for neighbour in neighbours {
+        counts[biome_texture_layer(neighbour) as usize] += 1;
+    }
+    counts[own_layer as usize] = 0;
+
+    match counts.iter().enumerate().max_by_key(|&(_, count)| *count) {
+        Some((layer, &count)) if count > 0 => layer as u32,
+        _ => own_layer,
+    }
+}
+
+// Grey detail layers and the coloured biome atlas, built together off the main thread
+pub struct TileTextures {
+    pub detail: Image,
+    pub atlas: Image,
+}
+
+pub fn build_tile_textures(biomes: &BiomeTable, palette: &Palette) -> TileTextures {
+    let noise = OpenSimplex::new(0);
+
+    let mut detail = Vec::with_capacity((TEXTURE_SIZE * TEXTURE_SIZE * TEXTURE_LAYERS * 4) as usize);
+    for layer in 0..TEXTURE_LAYERS {
+        for y in 0..TEXTURE_SIZE {
+            for x in 0..TEXTURE_SIZE {
+                let byte = (detail_value(&noise, layer, x, y) * 255.0) as u8;
+                detail.extend_from_slice(&[byte, byte, byte, 255]);
+            }
+        }
+    }
+
+    TileTextures {
+        detail: texture_array(detail, TEXTURE_LAYERS),
+        atlas: build_biome_atlas(biomes, palette),
+    }
+}
+
+// Each biome's flat colour with the detail pattern of its material baked in; small
+// enough to rebuild on the spot when the palette changes
+pub fn build_biome_atlas(biomes: &BiomeTable, palette: &Palette) -> Image {
+    let noise = OpenSimplex::new(0);
+
+    let mut atlas = Vec::with_capacity(TEXTURE_SIZE as usize * TEXTURE_SIZE as usize * ATLAS_BIOMES.len() * 4);
+    for biome in ATLAS_BIOMES {
+        let [r, g, b, _] = palette.biome_color(biome, biomes);
+        let layer = biome_texture_layer(biome);
+
+        for y in 0..TEXTURE_SIZE {
+            for x in 0..TEXTURE_SIZE {
+                let shade = detail_value(&noise, layer, x, y) as f32 * 2.0;
+                let channel = |c: f32| ((c * shade).clamp(0.0, 1.0) * 255.0) as u8;
+                atlas.extend_from_slice(&[channel(r), channel(g), channel(b), 255]);
+            }
+        }
+    }
+
+    texture_array(atlas, ATLAS_BIOMES.len() as u32)
+}
+
+// One texel of a detail layer, stored around mid-grey; the shader doubles it so
+// 0.5 leaves colors untouched
+fn detail_value(noise: &OpenSimplex, layer: u32, x: u32, y: u32) -> f64 {
+    let (frequency, contrast) = match layer {
+        WATER_LAYER => (1.0, 0.08),
+        GRASS_LAYER => (6.0, 0.25),
+        SAND_LAYER => (3.0, 0.15),
+        ROCK_LAYER => (2.0, 0.35),
+        _ => (1.5, 0.05),
+    };
+
+    // Sample on a 4D torus so the texture repeats without seams
+    let u = x as f64 / TEXTURE_SIZE as f64 * TAU;
+    let v = y as f64 / TEXTURE_SIZE as f64 * TAU;
+    let sample = noise.get([
+        u.cos() * frequency,
+        u.sin() * frequency,
+        v.cos() * frequency,
+        v.sin() * frequency + layer as f64 * 10.0,
+    ]);
+
+    let sample = if layer == ROCK_LAYER {
+        // Ridged noise reads as cracks and strata
+        1.0 - 2.0 * sample.abs()
+    } else {
+        sample
+    };
+
+    (0.5 + sample * contrast).clamp(0.0, 1.0)
+}
+
+fn texture_array(data: Vec<u8>, layers: u32) -> Image {
+    let mut image = Image::new(
+        Extent3d {
+            width: TEXTURE_SIZE,
+            height: TEXTURE_SIZE,
+            depth_or_array_layers: layers,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::nearest()
+    });
+
+    image
+}
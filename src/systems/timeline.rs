@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::components::{
+    kingdoms::Kingdom,
+    timeline::{TimelineCaption, TimelineUI},
+};
+use crate::systems::{
+    keybindings::{InputAction, InputMap, key_name},
+    kingdoms::TerritoryMap,
+    localization::Locale,
+    season::Season,
+    world::{LoadedChunks, MapOverlay, clear_loaded_chunks},
+};
+
+// Past this many snapshots every other one is dropped, so long games keep a
+// coarser history rather than an ever larger one
+const MAX_SNAPSHOTS: usize = 256;
+
+// Who held each territory cell at the end of every year the borders changed
+// in, for the political map to be shown as it was (Y)
+#[derive(Resource, Default)]
+pub struct TerritoryHistory {
+    snapshots: BTreeMap<u32, Vec<Option<Entity>>>,
+    // Every kingdom seen, so fallen ones still have their colour in the past
+    colors: HashMap<Entity, Color>,
+    // Year being played when the owners were last looked at
+    year: Option<u32>,
+    // Year the political map shows, or None for the present
+    viewed: Option<u32>,
+}
+
+impl TerritoryHistory {
+    // Owners of the cells at the end of the viewed year: the last snapshot
+    // taken up to it
+    pub fn viewed_owners(&self) -> Option<&[Option<Entity>]> {
+        let year = self.viewed?;
+        self.snapshots
+            .range(..=year)
+            .next_back()
+            .map(|(_, owners)| owners.as_slice())
+    }
+
+    pub fn viewing_past(&self) -> bool {
+        self.viewed.is_some()
+    }
+
+    pub fn color(&self, kingdom: Entity) -> Option<Color> {
+        self.colors.get(&kingdom).copied()
+    }
+
+    // Keeps the owners as they stood at the end of the year, unless the
+    // borders haven't moved since the last snapshot
+    fn record(&mut self, year: u32, owners: &[Option<Entity>]) {
+        if self
+            .snapshots
+            .values()
+            .next_back()
+            .is_some_and(|last| last == owners)
+        {
+            return;
+        }
+        self.snapshots.insert(year, owners.to_vec());
+
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            let years: Vec<u32> = self.snapshots.keys().copied().collect();
+            for year in years[1..years.len() - 1].iter().step_by(2) {
+                self.snapshots.remove(year);
+            }
+        }
+    }
+
+    // Moves the viewed year one back or forward, between the first year
+    // recorded and the present
+    fn step(&mut self, earlier: bool, current_year: u32) {
+        let Some(&first) = self.snapshots.keys().next() else {
+            return;
+        };
+        let last = current_year.saturating_sub(1).max(first);
+        self.viewed = match (self.viewed, earlier) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(year), true) => Some(year.saturating_sub(1).max(first)),
+            (Some(year), false) if year >= last => None,
+            (Some(year), false) => Some(year + 1),
+        };
+    }
+
+    fn caption(&self, locale: &Locale) -> String {
+        match self.viewed {
+            _ if self.snapshots.is_empty() => locale.text("timeline.empty").to_string(),
+            Some(year) => locale.format("timeline.year", &[&year]),
+            None => locale.text("timeline.present").to_string(),
+        }
+    }
+}
+
+// Snapshots the territory as each year ends
+pub fn record_territory(
+    season: Res<Season>,
+    territory: Res<TerritoryMap>,
+    mut history: ResMut<TerritoryHistory>,
+    kingdom_query: Query<(Entity, &Kingdom)>,
+) {
+    // Read without touching, so the map is only redrawn when a year ends
+    if history.year == Some(season.year) {
+        return;
+    }
+    if let Some(year) = history.year {
+        history.record(year, territory.cell_owners());
+        for (entity, kingdom) in &kingdom_query {
+            history.colors.insert(entity, kingdom.color);
+        }
+    }
+    history.year = Some(season.year);
+}
+
+pub fn toggle_timeline(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    (input_map, locale): (Res<InputMap>, Res<Locale>),
+    (mut overlay, mut loaded): (ResMut<MapOverlay>, ResMut<LoadedChunks>),
+    mut history: ResMut<TerritoryHistory>,
+    ui_query: Query<Entity, With<TimelineUI>>,
+) {
+    if !input_map.just_pressed(&input, InputAction::ToggleTimeline) {
+        return;
+    }
+
+    if !ui_query.is_empty() {
+        for entity in &ui_query {
+            commands.entity(entity).despawn();
+        }
+        history.viewed = None;
+        return;
+    }
+
+    // The timeline is drawn on the political map
+    if *overlay != MapOverlay::Political {
+        *overlay = MapOverlay::Political;
+        clear_loaded_chunks(&mut loaded);
+    }
+
+    commands.spawn((
+        TimelineUI,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(70.0),
+            left: Val::Percent(50.0),
+            width: Val::Px(320.0),
+            margin: UiRect::left(Val::Px(-160.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        children![
+            (
+                TimelineCaption,
+                Text::new(history.caption(&locale)),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ),
+            (
+                Text::new(locale.format(
+                    "timeline.keys",
+                    &[
+                        &key_name(input_map.key(InputAction::TimelineEarlier)),
+                        &key_name(input_map.key(InputAction::TimelineLater)),
+                        &key_name(input_map.key(InputAction::ToggleTimeline)),
+                    ],
+                )),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ),
+        ],
+    ));
+}
+
+pub fn timeline_controls(
+    input: Res<ButtonInput<KeyCode>>,
+    (input_map, locale, season): (Res<InputMap>, Res<Locale>, Res<Season>),
+    mut history: ResMut<TerritoryHistory>,
+    ui_query: Query<(), With<TimelineUI>>,
+    mut caption_query: Query<&mut Text, With<TimelineCaption>>,
+) {
+    if ui_query.is_empty() {
+        return;
+    }
+
+    if input_map.just_pressed(&input, InputAction::TimelineEarlier) {
+        history.step(true, season.year);
+    } else if input_map.just_pressed(&input, InputAction::TimelineLater) {
+        history.step(false, season.year);
+    } else {
+        return;
+    }
+
+    for mut caption in &mut caption_query {
+        caption.0 = history.caption(&locale);
+    }
+}
+
+pub fn cleanup_timeline(
+    mut commands: Commands,
+    mut history: ResMut<TerritoryHistory>,
+    ui_query: Query<Entity, With<TimelineUI>>,
+) {
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+    *history = TerritoryHistory::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners(kingdom: u32) -> Vec<Option<Entity>> {
+        vec![Some(Entity::from_raw_u32(kingdom).unwrap()), None]
+    }
+
+    #[test]
+    fn a_past_year_shows_the_last_snapshot_taken_by_then() {
+        let mut history = TerritoryHistory::default();
+        history.record(0, &owners(1));
+        // Unchanged borders take no snapshot
+        history.record(1, &owners(1));
+        history.record(2, &owners(2));
+        assert_eq!(history.snapshots.len(), 2);
+
+        history.step(true, 5);
+        assert_eq!(history.viewed, Some(4));
+        assert_eq!(history.viewed_owners(), Some(owners(2).as_slice()));
+        history.viewed = Some(1);
+        assert_eq!(history.viewed_owners(), Some(owners(1).as_slice()));
+
+        history.step(true, 5);
+        history.step(true, 5);
+        assert_eq!(history.viewed, Some(0));
+        history.viewed = Some(4);
+        history.step(false, 5);
+        assert_eq!(history.viewed, None);
+    }
+
+    #[test]
+    fn a_long_history_is_thinned_keeping_both_ends() {
+        let mut history = TerritoryHistory::default();
+        for year in 0..=MAX_SNAPSHOTS as u32 {
+            history.record(year, &owners(year + 1));
+        }
+        assert!(history.snapshots.len() <= MAX_SNAPSHOTS / 2 + 1);
+        assert!(history.snapshots.contains_key(&0));
+        assert!(history.snapshots.contains_key(&(MAX_SNAPSHOTS as u32)));
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+
+use crate::components::{
+    kingdoms::{OwnedBy, Treasury},
+    settlements::{Good, Population, Settlement, Stockpile},
+    trade::{Caravan, TradeRoute},
+    units::{MoveOrder, Unit, UnitKind},
+};
+use crate::systems::{
+    clock::GameClock,
+    season::YEAR_LENGTH_SECS,
+    units::spawn_unit,
+    world::{MapOverlay, WorldSettings, nearest_copy, toroidal_delta, toroidal_distance},
+};
+
+// Settlements look for trade a few times a year
+const TRADE_TICK_SECS: f32 = 15.0;
+// Stock a settlement wants of each good for every head, in `Good::ALL` order.
+// Anything past twice this is surplus; anything short of it is a deficit.
+const STOCK_PER_HEAD: [f32; Good::ALL.len()] = [1.0, 0.5, 0.3, 0.2, 0.1, 0.05];
+const CARAVAN_CAPACITY: f32 = 80.0;
+// Loads smaller than this aren't worth the journey
+const MIN_CARAVAN_LOAD: f32 = 10.0;
+const MAX_TRADE_DISTANCE: f32 = 250.0;
+// Routes nobody has used for this long fade from the trade map
+const ROUTE_FADE_SECS: f32 = 2.0 * YEAR_LENGTH_SECS;
+const DASH_LENGTH: f32 = 2.0;
+const DASH_PERIOD: f32 = 4.0;
+// Tiles per second the dashes drift toward the buyer
+const DASH_SPEED: f32 = 3.0;
+const ROUTE_COLOR: Color = Color::srgba(0.95, 0.8, 0.25, 0.9);
+
+#[derive(Resource, Default)]
+pub struct TradeTimer {
+    pub cooldown: f32,
+}
+
+// Each settlement with no caravan on the road sends its largest surplus to the
+// nearest settlement short of that good
+pub fn dispatch_caravans(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    settings: Res<WorldSettings>,
+    mut timer: ResMut<TradeTimer>,
+    mut settlement_query: Query<(Entity, &Settlement, &Population, &mut Stockpile)>,
+    caravan_query: Query<&Caravan>,
+) {
+    timer.cooldown -= clock.delta_secs;
+    if timer.cooldown > 0.0 {
+        return;
+    }
+    timer.cooldown = TRADE_TICK_SECS;
+
+    let markets: Vec<(
+        Entity,
+        IVec2,
+        [f32; Good::ALL.len()],
+        [f32; Good::ALL.len()],
+    )> = settlement_query
+        .iter()
+        .map(|(entity, settlement, population, stockpile)| {
+            let mut surplus = [0.0; Good::ALL.len()];
+            let mut deficit = [0.0; Good::ALL.len()];
+            for good in Good::ALL {
+                let wanted = population.0 * STOCK_PER_HEAD[good as usize];
+                surplus[good as usize] = stockpile.get(good) - 2.0 * wanted;
+                deficit[good as usize] = wanted - stockpile.get(good);
+            }
+            (entity, settlement.tile, surplus, deficit)
+        })
+        .collect();
+
+    for &(seller, tile, surplus, _) in &markets {
+        if caravan_query.iter().any(|caravan| caravan.from == seller) {
+            continue;
+        }
+
+        let Some(good) = Good::ALL
+            .into_iter()
+            .filter(|good| surplus[*good as usize] >= MIN_CARAVAN_LOAD)
+            .max_by(|a, b| surplus[*a as usize].total_cmp(&surplus[*b as usize]))
+        else {
+            continue;
+        };
+
+        let buyer = markets
+            .iter()
+            .filter(|(buyer, .., deficit)| *buyer != seller && deficit[good as usize] > 0.0)
+            .map(|&(buyer, buyer_tile, _, deficit)| {
+                (
+                    buyer,
+                    deficit,
+                    toroidal_distance(tile, buyer_tile, &settings),
+                )
+            })
+            .filter(|(.., distance)| *distance <= MAX_TRADE_DISTANCE)
+            .min_by(|(.., a), (.., b)| a.total_cmp(b));
+        let Some((buyer, deficit, _)) = buyer else {
+            continue;
+        };
+
+        let amount = surplus[good as usize]
+            .min(deficit[good as usize].max(MIN_CARAVAN_LOAD))
+            .min(CARAVAN_CAPACITY);
+        let Ok((.., mut stockpile)) = settlement_query.get_mut(seller) else {
+            continue;
+        };
+        stockpile.0[good as usize] -= amount;
+
+        let goal = markets
+            .iter()
+            .find(|(entity, ..)| *entity == buyer)
+            .map(|(_, tile, ..)| *tile)
+            .unwrap_or(tile);
+        let trader = spawn_unit(&mut commands, UnitKind::Trader, tile);
+        commands.entity(trader).insert((
+            MoveOrder::new(goal),
+            Caravan {
+                from: seller,
+                to: buyer,
+                good,
+                amount,
+            },
+        ));
+    }
+}
+
+// Caravans unload where their journey ends. The goods' worth is shared between
+// the two kingdoms, and the way taken is kept as a trade route.
+pub fn deliver_caravans(
+    mut commands: Commands,
+    mut stockpile_query: Query<&mut Stockpile>,
+    owner_query: Query<&OwnedBy>,
+    mut treasury_query: Query<&mut Treasury>,
+    mut route_query: Query<&mut TradeRoute>,
+    caravan_query: Query<(Entity, &Unit, &Caravan, Option<&MoveOrder>)>,
+) {
+    for (entity, unit, caravan, order) in &caravan_query {
+        let Some(order) = order else {
+            // No way through; the goods go back to the seller
+            if let Ok(mut stockpile) = stockpile_query.get_mut(caravan.from) {
+                stockpile.0[caravan.good as usize] += caravan.amount;
+            }
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if order.path.is_empty() || order.next < order.path.len() {
+            continue;
+        }
+        commands.entity(entity).despawn();
+
+        // The buyer may have been abandoned while the goods were on the road
+        let Ok(mut stockpile) = stockpile_query.get_mut(caravan.to) else {
+            continue;
+        };
+        stockpile.0[caravan.good as usize] += caravan.amount;
+        info!(
+            "Trader delivered {:.0} {} at {}, {}",
+            caravan.amount,
+            caravan.good.name(),
+            unit.tile.x,
+            unit.tile.y
+        );
+
+        let worth = caravan.amount * caravan.good.value() / 2.0;
+        for settlement in [caravan.from, caravan.to] {
+            if let Ok(owner) = owner_query.get(settlement)
+                && let Ok(mut treasury) = treasury_query.get_mut(owner.0)
+            {
+                treasury.0 += worth;
+            }
+        }
+
+        let known = route_query
+            .iter_mut()
+            .find(|route| route.from == caravan.from && route.to == caravan.to);
+        match known {
+            Some(mut route) => {
+                route.tiles = order.path.clone();
+                route.volume += caravan.amount;
+                route.idle = 0.0;
+            }
+            None => {
+                commands.spawn(TradeRoute {
+                    from: caravan.from,
+                    to: caravan.to,
+                    tiles: order.path.clone(),
+                    volume: caravan.amount,
+                    idle: 0.0,
+                });
+            }
+        }
+    }
+}
+
+pub fn fade_trade_routes(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut route_query: Query<(Entity, &mut TradeRoute)>,
+) {
+    for (entity, mut route) in &mut route_query {
+        route.idle += clock.delta_secs;
+        if route.idle > ROUTE_FADE_SECS {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Dashed lines drifting from seller to buyer on the trade map, laid out from the
+// copy of the route nearest the camera like roads
+pub fn draw_trade_routes(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    overlay: Res<MapOverlay>,
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    route_query: Query<&TradeRoute>,
+) {
+    if *overlay != MapOverlay::Trade {
+        return;
+    }
+    let camera = camera_query.translation.truncate().as_ivec2();
+    let phase = time.elapsed_secs() * DASH_SPEED;
+
+    for route in &route_query {
+        let Some(&first) = route.tiles.first() else {
+            continue;
+        };
+        let mut tile = nearest_copy(camera, first, &settings);
+        for (step, pair) in route.tiles.windows(2).enumerate() {
+            let start = tile;
+            tile += toroidal_delta(pair[0], pair[1], &settings);
+            if (step as f32 - phase).rem_euclid(DASH_PERIOD) < DASH_LENGTH {
+                gizmos.line_2d(
+                    start.as_vec2() + Vec2::splat(0.5),
+                    tile.as_vec2() + Vec2::splat(0.5),
+                    ROUTE_COLOR,
+                );
+            }
+        }
+    }
+}
+
+pub fn cleanup_trade(
+    mut commands: Commands,
+    mut timer: ResMut<TradeTimer>,
+    query: Query<Entity, With<TradeRoute>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    *timer = TradeTimer::default();
+}
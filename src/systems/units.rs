@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+
+use crate::components::{
+    kingdoms::{FoundingParty, KingdomSettlements, OwnedBy, Technology},
+    settlements::Settlement,
+    trade::Caravan,
+    units::{Army, MoveOrder, PathSearch, Unit, UnitKind},
+    world::TileInspectorUI,
+};
+use crate::systems::{
+    clock::GameClock,
+    pathfinding::{BASE_COST, Passage, PathGrid, spawn_find_path, straight_path},
+    pause_menu::Pause,
+    world::{WorldSettings, cursor_tile, nearest_copy, toroidal_direction, toroidal_distance},
+};
+
+const UNIT_SIZE: f32 = 1.5;
+const SELECTED_SCALE: f32 = 1.6;
+// Above settlement and landmark markers
+const UNIT_Z: f32 = 0.6;
+// Tiles from the cursor a click still picks a unit at
+const PICK_RADIUS: f32 = 1.5;
+
+#[derive(Resource, Default)]
+pub struct SelectedUnit(pub Option<Entity>);
+
+// Scouts cross mountain passes and traders sail the sea lanes; monster hunters
+// do both, and settlers, armies and herds keep to open land
+pub fn unit_passage(kind: UnitKind) -> Passage {
+    match kind {
+        UnitKind::Scout => Passage {
+            mountains: true,
+            ..Passage::LAND
+        },
+        UnitKind::Trader => Passage {
+            water: true,
+            ..Passage::LAND
+        },
+        UnitKind::Hunters => Passage {
+            water: true,
+            mountains: true,
+        },
+        UnitKind::Settler | UnitKind::Army | UnitKind::Migrants | UnitKind::Nomads => {
+            Passage::LAND
+        }
+    }
+}
+
+pub fn spawn_unit(commands: &mut Commands, kind: UnitKind, tile: IVec2) -> Entity {
+    let color = match kind {
+        UnitKind::Settler => Color::srgb(0.35, 0.8, 0.35),
+        UnitKind::Trader => Color::srgb(0.95, 0.8, 0.25),
+        UnitKind::Scout => Color::srgb(0.3, 0.6, 0.95),
+        UnitKind::Army => Color::srgb(0.85, 0.2, 0.2),
+        UnitKind::Migrants => Color::srgb(0.8, 0.65, 0.5),
+        UnitKind::Nomads => Color::srgb(0.6, 0.45, 0.25),
+        UnitKind::Hunters => Color::srgb(0.75, 0.3, 0.75),
+    };
+
+    commands
+        .spawn((
+            Sprite::from_color(color, Vec2::splat(UNIT_SIZE)),
+            Transform::from_translation((tile.as_vec2() + Vec2::splat(0.5)).extend(UNIT_Z)),
+            Unit {
+                tile,
+                progress: 0.0,
+            },
+            kind,
+        ))
+        .id()
+}
+
+// Every kingdom starts with a scout at one of its settlements
+pub fn spawn_scouts(
+    mut commands: Commands,
+    kingdom_query: Query<&KingdomSettlements>,
+    settlement_query: Query<&Settlement>,
+) {
+    for settlements in &kingdom_query {
+        let home = settlements
+            .iter()
+            .find_map(|entity| settlement_query.get(entity).ok());
+        if let Some(home) = home {
+            spawn_unit(&mut commands, UnitKind::Scout, home.tile);
+        }
+    }
+}
+
+// Left click picks the unit under the cursor, right click sends the picked unit
+// to the tile under the cursor
+pub fn select_unit(
+    mut commands: Commands,
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    window_query: Single<&Window>,
+    (mouse, pause): (Res<ButtonInput<MouseButton>>, Res<Pause>),
+    (settings, mut selected): (Res<WorldSettings>, ResMut<SelectedUnit>),
+    inspector_query: Query<&Interaction, With<TileInspectorUI>>,
+    unit_query: Query<(Entity, &Unit)>,
+) {
+    let over_inspector = inspector_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    if pause.menu_open || over_inspector {
+        return;
+    }
+
+    let (camera, camera_transform) = *camera_query;
+    let Some(tile) = cursor_tile(camera, camera_transform, &window_query) else {
+        return;
+    };
+    let size = settings.world_size;
+    let tile = IVec2::new(tile.x.rem_euclid(size), tile.y.rem_euclid(size));
+
+    if mouse.just_pressed(MouseButton::Left) {
+        selected.0 = unit_query
+            .iter()
+            .map(|(entity, unit)| (entity, toroidal_distance(tile, unit.tile, &settings)))
+            .filter(|(_, distance)| *distance <= PICK_RADIUS)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity);
+    }
+
+    if mouse.just_pressed(MouseButton::Right)
+        && let Some(unit) = selected.0
+        && unit_query.contains(unit)
+    {
+        commands
+            .entity(unit)
+            .remove::<PathSearch>()
+            .insert(MoveOrder::new(tile));
+    }
+}
+
+// Searches a route for each new order. Until a streamed world's grid has been
+// sampled, units head straight for the goal across whatever lies between.
+pub fn start_path_searches(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    grid: Option<Res<PathGrid>>,
+    mut order_query: Query<(Entity, &Unit, &UnitKind, &mut MoveOrder), Without<PathSearch>>,
+) {
+    for (entity, unit, kind, mut order) in &mut order_query {
+        if !order.path.is_empty() {
+            continue;
+        }
+
+        match grid.as_deref() {
+            Some(grid) => {
+                let task = spawn_find_path(grid, unit.tile, order.goal, unit_passage(*kind));
+                commands.entity(entity).insert(PathSearch(task));
+            }
+            None => order.path = straight_path(unit.tile, order.goal, &settings),
+        }
+    }
+}
+
+pub fn poll_path_searches(
+    mut commands: Commands,
+    mut search_query: Query<(Entity, &UnitKind, &mut MoveOrder, &mut PathSearch)>,
+) {
+    for (entity, kind, mut order, mut search) in &mut search_query {
+        let Some(path) = check_ready(&mut search.0) else {
+            continue;
+        };
+        commands.entity(entity).remove::<PathSearch>();
+
+        match path {
+            Some(path) => order.path = path,
+            None => {
+                info!(
+                    "{} can't reach {}, {}",
+                    kind.name(),
+                    order.goal.x,
+                    order.goal.y
+                );
+                commands.entity(entity).remove::<MoveOrder>();
+            }
+        }
+    }
+}
+
+// A unit under orders, with whatever it is out on the road as
+type MarchingUnit = (
+    Entity,
+    &'static mut Unit,
+    &'static UnitKind,
+    &'static mut MoveOrder,
+    Option<&'static Army>,
+    Option<&'static FoundingParty>,
+    Option<&'static Caravan>,
+);
+
+// Walks each unit along its path, slower over rough ground. Armies, settlers and
+// traders go faster the further their kingdom's era has come.
+pub fn advance_units(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    grid: Option<Res<PathGrid>>,
+    owner_query: Query<&OwnedBy>,
+    tech_query: Query<&Technology>,
+    mut unit_query: Query<MarchingUnit>,
+) {
+    for (entity, mut unit, kind, mut order, army, party, caravan) in &mut unit_query {
+        if order.path.is_empty() {
+            continue;
+        }
+        if order.next >= order.path.len() {
+            unit.progress = 0.0;
+            commands.entity(entity).remove::<MoveOrder>();
+            continue;
+        }
+
+        let next_tile = order.path[order.next];
+        let cost = grid
+            .as_deref()
+            .and_then(|grid| grid.movement_cost(next_tile, unit_passage(*kind)))
+            .unwrap_or(BASE_COST);
+        let kingdom = army
+            .map(|army| army.kingdom)
+            .or(party.map(|party| party.kingdom))
+            .or(caravan
+                .and_then(|caravan| owner_query.get(caravan.from).ok())
+                .map(|owner| owner.0));
+        let pace = kingdom
+            .and_then(|kingdom| tech_query.get(kingdom).ok())
+            .map(|technology| technology.era.unit_speed())
+            .unwrap_or(1.0);
+        unit.progress += kind.speed() * pace * clock.delta_secs * BASE_COST as f32 / cost as f32;
+
+        if unit.progress >= 1.0 {
+            unit.progress -= 1.0;
+            unit.tile = next_tile;
+            order.next += 1;
+        }
+    }
+}
+
+// Draws each unit on the copy of its tile nearest the camera, part way to the
+// next tile of its path
+pub fn update_unit_sprites(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    selected: Res<SelectedUnit>,
+    mut unit_query: Query<(Entity, &Unit, Option<&MoveOrder>, &mut Transform), Without<Camera2d>>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for (entity, unit, order, mut transform) in &mut unit_query {
+        let nearest = nearest_copy(camera, unit.tile, &settings);
+        let heading = order
+            .and_then(|order| order.path.get(order.next))
+            .map(|next| toroidal_direction(unit.tile, *next, &settings))
+            .unwrap_or(Vec2::ZERO);
+
+        let position = nearest.as_vec2() + Vec2::splat(0.5) + heading * unit.progress;
+        transform.translation = position.extend(UNIT_Z);
+        transform.scale = Vec3::splat(if selected.0 == Some(entity) {
+            SELECTED_SCALE
+        } else {
+            1.0
+        });
+    }
+}
+
+pub fn cleanup_units(
+    mut commands: Commands,
+    mut selected: ResMut<SelectedUnit>,
+    query: Query<Entity, With<Unit>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    selected.0 = None;
+}
@@ -0,0 +1,40 @@
+use bevy::{
+    prelude::*, render::render_resource::AsBindGroup, shader::ShaderRef,
+    sprite_render::Material2d,
+};
+
+const SHADER_ASSET_PATH: &str = "shaders/water.wgsl";
+// Brightness swing of the waves either side of the tile's own colour
+const WAVE_STRENGTH: f32 = 0.06;
+const WAVE_SPEED: f32 = 1.2;
+const FOAM_STRENGTH: f32 = 0.6;
+
+// Animated open water, drawn over the finest chunk meshes. Vertex colors carry
+// the depth shading; the shader adds moving waves and surf along coasts.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+pub struct WaterMaterial {
+    // x: wave strength, y: wave speed, z: foam strength
+    #[uniform(0)]
+    pub params: Vec4,
+}
+
+impl Material2d for WaterMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+}
+
+#[derive(Resource)]
+pub struct WaterMaterialHandle(pub Handle<WaterMaterial>);
+
+pub fn load_water_shader(asset_server: &AssetServer) -> Handle<Shader> {
+    asset_server.load(SHADER_ASSET_PATH)
+}
+
+pub fn create_water_material(materials: &mut Assets<WaterMaterial>) -> WaterMaterialHandle {
+    let material = materials.add(WaterMaterial {
+        params: Vec4::new(WAVE_STRENGTH, WAVE_SPEED, FOAM_STRENGTH, 0.0),
+    });
+
+    WaterMaterialHandle(material)
+}
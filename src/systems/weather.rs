@@ -0,0 +1,218 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use kingdom_worldgen::{sampler::WorldSampler, wind::prevailing_wind};
+use rand::Rng;
+
+use crate::components::{
+    weather::{Storm, StormEye},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    clock::GameClock,
+    season::{Season, tile_latitude},
+    world::WorldSettings,
+};
+
+const STORM_SPAWN_INTERVAL_SECS: f32 = 3.0;
+const MAX_STORMS: usize = 6;
+// Storms need warm water to form and to keep strengthening
+const WARM_OCEAN_TEMPERATURE: f32 = 25.0;
+// Signed-latitude band storms form in; too close to the equator there is no spin
+const STORM_BAND_MIN: f32 = 0.05;
+const STORM_BAND_MAX: f32 = 0.25;
+// Tiles per simulated second
+const STORM_SPEED: f32 = 10.0;
+// Poleward drift added to the steering wind, which turns storms out of the
+// trade winds into the westerlies where they recurve east
+const POLEWARD_DRIFT: f32 = 0.6;
+const STRENGTHEN_RATE: f32 = 0.08;
+const COOL_WATER_DECAY: f32 = 0.05;
+const LANDFALL_DECAY: f32 = 0.25;
+const MAX_STORM_AGE_SECS: f32 = 120.0;
+const HURRICANE_INTENSITY: f32 = 0.6;
+const SPIN_SPEED: f32 = 1.5;
+// Icon radius in tiles at full intensity
+const STORM_RADIUS: f32 = 24.0;
+const STORM_Z: f32 = 2.0;
+
+#[derive(Resource, Default)]
+pub struct StormSeason {
+    pub spawn_cooldown: f32,
+}
+
+// Tries one warm tropical ocean tile every few seconds. Each hemisphere only
+// breeds storms in its summer and autumn.
+pub fn spawn_storms(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    mut storm_season: ResMut<StormSeason>,
+    (mut meshes, mut materials): (ResMut<Assets<Mesh>>, ResMut<Assets<ColorMaterial>>),
+    (settings, season): (Res<WorldSettings>, Res<Season>),
+    storm_query: Query<(), With<Storm>>,
+    world_data_query: Query<&WorldData>,
+) {
+    storm_season.spawn_cooldown -= clock.delta_secs;
+    if storm_season.spawn_cooldown > 0.0 || storm_query.iter().count() >= MAX_STORMS {
+        return;
+    }
+    storm_season.spawn_cooldown = STORM_SPAWN_INTERVAL_SECS;
+
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+
+    let mut rng = rand::rng();
+    let hemisphere = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+    let latitude = hemisphere * rng.random_range(STORM_BAND_MIN..STORM_BAND_MAX);
+    let half_world = settings.world_size as f32 / 2.0;
+    let x = rng.random_range(0..settings.world_size);
+    let y = (half_world + latitude * half_world) as i32;
+
+    if !matches!(season.name(latitude), "Summer" | "Autumn") {
+        return;
+    }
+
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    if sea_temperature(&sampler, &season, x, y, settings.world_size)
+        .is_none_or(|temperature| temperature < WARM_OCEAN_TEMPERATURE)
+    {
+        return;
+    }
+
+    let cloud_color = Color::srgba(0.92, 0.94, 0.97, 0.55);
+    // Three curved bands around the core, tangent to it, so the spin reads
+    let arms = (0..3).map(move |arm| {
+        let angle = arm as f32 * TAU / 3.0;
+        (
+            Sprite::from_color(cloud_color, Vec2::new(0.9, 0.22)),
+            Transform::from_translation((Vec2::from_angle(angle) * 0.85).extend(0.0))
+                .with_rotation(Quat::from_rotation_z(angle + TAU / 4.0)),
+        )
+    });
+
+    commands
+        .spawn((
+            Storm {
+                position: Vec2::new(x as f32, y as f32),
+                intensity: 0.1,
+                age: 0.0,
+                spin: 0.0,
+            },
+            Mesh2d(meshes.add(Circle::new(0.6))),
+            MeshMaterial2d(materials.add(cloud_color)),
+            Transform::from_xyz(x as f32, y as f32, STORM_Z),
+            Children::spawn(SpawnIter(arms)),
+        ))
+        .with_child((
+            StormEye,
+            Mesh2d(meshes.add(Circle::new(0.15))),
+            MeshMaterial2d(materials.add(Color::srgba(0.15, 0.15, 0.2, 0.8))),
+            Transform::from_xyz(0.0, 0.0, 0.01),
+            Visibility::Hidden,
+        ));
+}
+
+// Steers each storm with the prevailing wind plus a poleward drift, so storms
+// run west along the trades and recurve east once they reach the westerlies.
+// They feed on warm water and die out over land or cool seas.
+pub fn move_storms(
+    mut commands: Commands,
+    clock: Res<GameClock>,
+    settings: Res<WorldSettings>,
+    season: Res<Season>,
+    mut storm_query: Query<(Entity, &mut Storm)>,
+    world_data_query: Query<&WorldData>,
+) {
+    if storm_query.is_empty() {
+        return;
+    }
+    let Ok(world_data) = world_data_query.single() else {
+        return;
+    };
+
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let size = settings.world_size as f32;
+    let dt = clock.delta_secs;
+
+    for (entity, mut storm) in &mut storm_query {
+        let tile = storm.position.floor().as_ivec2();
+        let latitude = tile_latitude(tile.y, settings.world_size);
+        let hemisphere = latitude.signum();
+
+        let (wind_x, wind_y) = prevailing_wind(latitude as f64, world_data);
+        let heading = Vec2::new(wind_x as f32, wind_y as f32 + POLEWARD_DRIFT * hemisphere)
+            .normalize_or_zero();
+        storm.position =
+            (storm.position + heading * STORM_SPEED * dt).rem_euclid(Vec2::splat(size));
+
+        let change = match sea_temperature(&sampler, &season, tile.x, tile.y, settings.world_size) {
+            Some(temperature) if temperature >= WARM_OCEAN_TEMPERATURE => STRENGTHEN_RATE,
+            Some(_) => -COOL_WATER_DECAY,
+            None => -LANDFALL_DECAY,
+        };
+        storm.intensity = (storm.intensity + change * dt).min(1.0);
+        storm.age += dt;
+        storm.spin += SPIN_SPEED * hemisphere * dt;
+
+        if storm.intensity <= 0.0 || storm.age >= MAX_STORM_AGE_SECS {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Places each storm icon on the copy of the world nearest the camera and sizes
+// it by intensity; hurricanes show an eye
+pub fn update_storm_icons(
+    settings: Res<WorldSettings>,
+    camera_query: Single<&Transform, (With<Camera2d>, Without<Storm>)>,
+    mut storm_query: Query<(&Storm, &mut Transform, &Children)>,
+    mut eye_query: Query<&mut Visibility, With<StormEye>>,
+) {
+    let camera = camera_query.translation.truncate();
+    let size = settings.world_size as f32;
+
+    for (storm, mut transform, children) in &mut storm_query {
+        let mut offset = (storm.position - camera).rem_euclid(Vec2::splat(size));
+        offset = Vec2::select(offset.cmpge(Vec2::splat(size / 2.0)), offset - size, offset);
+
+        transform.translation = (camera + offset).extend(STORM_Z);
+        transform.rotation = Quat::from_rotation_z(storm.spin);
+        transform.scale = Vec3::splat(STORM_RADIUS * (0.4 + 0.6 * storm.intensity));
+
+        let shown = if storm.intensity >= HURRICANE_INTENSITY {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        for child in children {
+            if let Ok(mut visibility) = eye_query.get_mut(*child) {
+                visibility.set_if_neq(shown);
+            }
+        }
+    }
+}
+
+pub fn cleanup_storms(mut commands: Commands, query: Query<Entity, With<Storm>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    commands.insert_resource(StormSeason::default());
+}
+
+// Seasonal surface temperature of the tile, or None on land
+fn sea_temperature(
+    sampler: &WorldSampler,
+    season: &Season,
+    x: i32,
+    y: i32,
+    world_size: i32,
+) -> Option<f32> {
+    let square = sampler.square(x, y);
+    if square.elevation as f64 >= sampler.world_data().sea_level() {
+        return None;
+    }
+
+    Some(season.temperature(&square, tile_latitude(y, world_size)))
+}
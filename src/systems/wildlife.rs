@@ -0,0 +1,336 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+
+use crate::components::{
+    settlements::{
+        FishingGrounds, FoodCapacity, Good, Population, ProductionRates, Settlement, Stockpile,
+    },
+    wildlife::{Species, WildlifeLayer},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    clock::GameClock,
+    economy::labour_share,
+    season::YEAR_LENGTH_SECS,
+    world::{MapOverlay, WorldSettings},
+    world_gen::wildlife::{Habitats, survey_habitats},
+};
+
+// Wildlife is counted on a coarse grid, like territory and regions
+const WILDLIFE_CELL: i32 = 16;
+const WILDLIFE_TICK_SECS: f32 = 5.0;
+// Yearly growth of a herd far below what its land carries; it slows to nothing
+// as the land fills up
+const GROWTH_RATE: f32 = 0.5;
+// Share of a cell's capacity that strays back in from the wild around it, so
+// hunted-out land recovers
+const RESTOCK_SHARE: f32 = 0.05;
+// Cells from a settlement its hunters and fishing boats range over
+const HUNT_RADIUS: i32 = 2;
+// Share of people who hunt, and the game each brings in a year
+const HUNTER_SHARE: f32 = 0.1;
+const CATCH_PER_HUNTER: f32 = 5.0;
+// Hunters stay home while the larder holds this many years of meat and fish
+const LARDER_YEARS: f32 = 0.5;
+// Animals per tile shown at full strength on the wildlife map
+const FULL_DENSITY: f32 = 0.6;
+const WILDLIFE_ALPHA: f32 = 0.7;
+// Over the chunk meshes, beside the territory tint
+const WILDLIFE_Z: f32 = 0.15;
+
+// Animals living in each coarse cell. Their habitats are sampled once per
+// world, off the main thread; herds start out at what their land carries.
+#[derive(Resource)]
+pub struct WildlifeMap {
+    cells_per_side: i32,
+    // Species and capacity of each cell, once surveyed
+    habitat: Option<Habitats>,
+    task: Option<Task<Habitats>>,
+    stock: Vec<f32>,
+    image: Handle<Image>,
+    cooldown: f32,
+    dirty: bool,
+}
+
+impl WildlifeMap {
+    // Species, animals and capacity of the cell holding a tile
+    pub fn at(&self, tile: IVec2) -> Option<(Species, f32, f32)> {
+        let cell = tile.div_euclid(IVec2::splat(WILDLIFE_CELL));
+        let index = wildlife_index(cell, self.cells_per_side);
+        let (species, capacity) = self.habitat.as_ref()?[index]?;
+
+        Some((species, self.stock[index], capacity))
+    }
+
+    // Animals in each cell, once the habitats have been surveyed
+    pub fn surveyed_stock(&self) -> Option<&[f32]> {
+        self.habitat.as_ref().map(|_| self.stock.as_slice())
+    }
+}
+
+// Herds from a loaded game, which take the place of fully stocked land once the
+// habitats are surveyed again
+#[derive(Resource)]
+pub struct SavedWildlife(pub Vec<f32>);
+
+pub fn setup_wildlife(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    settings: Res<WorldSettings>,
+    query: Query<&WorldData>,
+) {
+    let world_data = match query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let cells = (settings.world_size / WILDLIFE_CELL).max(1);
+
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: cells as u32,
+            height: cells as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.sampler = ImageSampler::nearest();
+    let image = images.add(image);
+
+    for _ in 0..9 {
+        commands.spawn((
+            WildlifeLayer,
+            Sprite {
+                image: image.clone(),
+                custom_size: Some(Vec2::splat(settings.world_size as f32)),
+                ..default()
+            },
+            Transform::default(),
+            Visibility::Hidden,
+        ));
+    }
+
+    let world_size = settings.world_size;
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { survey_habitats(&world_data, world_size, cells, WILDLIFE_CELL) });
+    commands.insert_resource(WildlifeMap {
+        cells_per_side: cells,
+        habitat: None,
+        task: Some(task),
+        stock: vec![0.0; (cells * cells) as usize],
+        image,
+        cooldown: WILDLIFE_TICK_SECS,
+        dirty: false,
+    });
+}
+
+pub fn poll_wildlife_survey(
+    mut commands: Commands,
+    mut wildlife: ResMut<WildlifeMap>,
+    saved: Option<Res<SavedWildlife>>,
+) {
+    let Some(task) = wildlife.task.as_mut() else {
+        return;
+    };
+    let Some(habitat) = check_ready(task) else {
+        return;
+    };
+
+    wildlife.stock = match saved {
+        Some(saved) if saved.0.len() == habitat.len() => saved.0.clone(),
+        _ => habitat
+            .iter()
+            .map(|cell| cell.map(|(_, capacity)| capacity).unwrap_or(0.0))
+            .collect(),
+    };
+    commands.remove_resource::<SavedWildlife>();
+    wildlife.habitat = Some(habitat);
+    wildlife.task = None;
+    wildlife.dirty = true;
+}
+
+// Herds grow back toward what their land carries. Each coastal settlement's
+// catch since the last tick comes out of the shoals around it, and its boats
+// land less while those shoals are thin. Then hunters take game from the land
+// around their settlement, richest cells first.
+pub fn update_wildlife(
+    clock: Res<GameClock>,
+    mut wildlife: ResMut<WildlifeMap>,
+    mut settlement_query: Query<(
+        &Settlement,
+        &Population,
+        &FoodCapacity,
+        &mut Stockpile,
+        &mut ProductionRates,
+        &mut FishingGrounds,
+    )>,
+) {
+    wildlife.cooldown -= clock.delta_secs;
+    if wildlife.cooldown > 0.0 {
+        return;
+    }
+    wildlife.cooldown = WILDLIFE_TICK_SECS;
+
+    let WildlifeMap {
+        cells_per_side,
+        habitat: Some(habitat),
+        stock,
+        dirty,
+        ..
+    } = &mut *wildlife
+    else {
+        return;
+    };
+    let years = WILDLIFE_TICK_SECS / YEAR_LENGTH_SECS;
+
+    for (stock, cell) in stock.iter_mut().zip(habitat.iter()) {
+        if let Some((_, capacity)) = cell {
+            let grown = GROWTH_RATE * *stock * (1.0 - *stock / capacity);
+            *stock = (*stock + (grown + RESTOCK_SHARE * capacity) * years).min(*capacity);
+        }
+    }
+    *dirty = true;
+
+    for (settlement, population, capacity, mut stockpile, mut production, mut fishing) in
+        &mut settlement_query
+    {
+        let home = settlement.tile.div_euclid(IVec2::splat(WILDLIFE_CELL));
+        let (shoals, mut grounds): (Vec<usize>, Vec<usize>) = (-HUNT_RADIUS..=HUNT_RADIUS)
+            .flat_map(|dy| (-HUNT_RADIUS..=HUNT_RADIUS).map(move |dx| IVec2::new(dx, dy)))
+            .map(|offset| wildlife_index(home + offset, *cells_per_side))
+            .filter(|index| habitat[*index].is_some())
+            .partition(|index| matches!(habitat[*index], Some((Species::FishShoal, _))));
+
+        if fishing.full_yield > 0.0 {
+            let held: f32 = shoals.iter().map(|index| stock[*index]).sum();
+            let carried: f32 = shoals
+                .iter()
+                .filter_map(|index| habitat[*index])
+                .map(|(_, carries)| carries)
+                .sum();
+            let landed =
+                production.get(Good::Fish) * labour_share(population.0, capacity.0) * years;
+            if held > 0.0 {
+                for index in &shoals {
+                    stock[*index] -= landed * stock[*index] / held;
+                }
+            }
+            // Water too small for the wildlife map to track is fished as if
+            // always full
+            fishing.shoals = if carried > 0.0 {
+                ((held - landed) / carried).clamp(0.0, 1.0)
+            } else {
+                1.0
+            };
+            production.0[Good::Fish as usize] = fishing.full_yield * fishing.shoals;
+        }
+
+        let larder = stockpile.get(Good::Game) + stockpile.get(Good::Fish);
+        if larder >= population.0 * LARDER_YEARS {
+            continue;
+        }
+        grounds.sort_by(|a, b| stock[*b].total_cmp(&stock[*a]));
+
+        let mut wanted = population.0 * HUNTER_SHARE * CATCH_PER_HUNTER * years;
+        for index in grounds {
+            if wanted <= 0.0 {
+                break;
+            }
+            let Some((species, _)) = habitat[index] else {
+                continue;
+            };
+            let caught = wanted.min(stock[index]);
+            stock[index] -= caught;
+            stockpile.0[species.good() as usize] += caught;
+            wanted -= caught;
+        }
+    }
+}
+
+type WildlifeLayerOnly = (With<WildlifeLayer>, Without<Camera2d>);
+
+// Shows how well stocked each cell is on the wildlife map, tinted by species
+pub fn update_wildlife_layer(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    overlay: Res<MapOverlay>,
+    settings: Res<WorldSettings>,
+    mut wildlife: ResMut<WildlifeMap>,
+    mut images: ResMut<Assets<Image>>,
+    mut layer_query: Query<(&mut Transform, &mut Visibility), WildlifeLayerOnly>,
+) {
+    let shown = *overlay == MapOverlay::Wildlife;
+    for (_, mut visibility) in &mut layer_query {
+        visibility.set_if_neq(if shown {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+    if !shown {
+        return;
+    }
+
+    let world_size = settings.world_size as f32;
+    let camera = camera_query.translation.truncate();
+    let origin = (camera / world_size).floor() * world_size + Vec2::splat(world_size / 2.0);
+    let offsets = (-1..=1).flat_map(|x| (-1..=1).map(move |y| Vec2::new(x as f32, y as f32)));
+    for ((mut transform, _), offset) in layer_query.iter_mut().zip(offsets) {
+        transform.translation = (origin + offset * world_size).extend(WILDLIFE_Z);
+    }
+
+    if !wildlife.dirty {
+        return;
+    }
+    wildlife.dirty = false;
+    let Some(habitat) = wildlife.habitat.as_ref() else {
+        return;
+    };
+
+    let cells = wildlife.cells_per_side;
+    let full = FULL_DENSITY * (WILDLIFE_CELL * WILDLIFE_CELL) as f32;
+    let mut pixels = Vec::with_capacity((cells * cells * 4) as usize);
+    // Image rows run top down, world rows bottom up
+    for row in (0..cells).rev() {
+        for column in 0..cells {
+            let index = wildlife_index(IVec2::new(column, row), cells);
+            let color = habitat[index]
+                .map(|(species, _)| {
+                    let strength = (wildlife.stock[index] / full).min(1.0);
+                    species
+                        .color()
+                        .with_alpha(WILDLIFE_ALPHA * strength)
+                        .to_srgba()
+                        .to_u8_array()
+                })
+                .unwrap_or([0, 0, 0, 0]);
+            pixels.extend_from_slice(&color);
+        }
+    }
+    if let Some(image) = images.get_mut(&wildlife.image) {
+        image.data = Some(pixels);
+    }
+}
+
+pub fn cleanup_wildlife(mut commands: Commands, query: Query<Entity, With<WildlifeLayer>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<WildlifeMap>();
+    commands.remove_resource::<SavedWildlife>();
+}
+
+fn wildlife_index(cell: IVec2, cells: i32) -> usize {
+    let cell = cell.rem_euclid(IVec2::splat(cells));
+    (cell.y * cells + cell.x) as usize
+}
@@ -1,52 +1,495 @@
 use bevy::platform::collections::HashMap;
 use bevy::{
-    asset::RenderAssetUsages, math::ops::powf, prelude::*,
+    asset::RenderAssetUsages,
+    camera::Viewport,
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    math::ops::powf,
+    prelude::*,
     render::render_resource::PrimitiveTopology::TriangleList,
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
 };
 use bevy_mesh::Indices;
+use kingdom_worldgen::{
+    HALO, MAX_ELEVATION, generate_chunk_data, sampler::WorldSampler,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
+use crate::components::ambient::AmbientEffect;
+use crate::components::events::Disaster;
+use crate::components::kingdoms::{Fame, Kingdom, KingdomSettlements, Technology, Treasury};
+use crate::components::lairs::Lair;
+use crate::components::localization::Localized;
+use crate::components::settlements::{
+    Farm, Farms, FishingGrounds, FoodCapacity, Good, Population, ProductionModifier,
+    ProductionRates, Settlement, SettlementTier, Specialization, Stockpile,
+};
 use crate::components::world::*;
 use crate::components::world_gen::WorldData;
-use crate::states::game_state::GameState;
-use crate::systems::world_gen::{generate_chunk_data, generate_square_at_position};
+use crate::systems::economy::labour_share;
+use crate::systems::kingdoms::TerritoryMap;
+use crate::systems::wildlife::WildlifeMap;
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::localization::Locale;
+use crate::systems::palette::{MapPalette, Palette};
+use crate::systems::pause_menu::Pause;
+use crate::systems::season::{Season, YEAR_LENGTH_SECS, tile_latitude};
+use crate::systems::water_material::WaterMaterialHandle;
+use crate::systems::tile_material::{
+    BiomeTileMaterialHandle, biome_atlas_layer, biome_texture_layer, neighbour_texture_layer,
+};
+use crate::systems::world_gen::{
+    WorldRegeneration, biome_table::BiomeTable, regions::RegionMap,
+};
 
-pub const WORLD_SIZE: i32 = 8192;
-pub const CHUNK_SIZE: i32 = 64;
-pub const CHUNKS_SIZE: i32 = WORLD_SIZE / CHUNK_SIZE;
-pub const HALO: i32 = 1;
-pub const MAX_ELEVATION: f64 = 100.0;
-const VIEW_RADIUS: i32 = 1;
+pub const DEFAULT_WORLD_SIZE: i32 = 8192;
+pub const DEFAULT_CHUNK_SIZE: i32 = 64;
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+const SPINNER_FPS: f32 = 8.0;
+const BORDER_DITHER_CHANCE: f32 = 0.35;
+// Tiles per side of one quad at each level of detail, finest first
+const LOD_BLOCKS: [i32; 3] = [1, 2, 4];
+const LOD_LEVELS: usize = LOD_BLOCKS.len();
+// Camera projection scale at which each coarser level takes over
+const LOD_ZOOM_THRESHOLDS: [f32; 2] = [2.0, 6.0];
+// Most chunks out from the camera's chunk loaded at each level of detail, so the
+// widest views stay affordable. The camera zooms out no further than the last
+// one fills.
+const MAX_VIEW_RADIUS: [i32; LOD_LEVELS] = [10, 12, 14];
+
+#[derive(Resource, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WorldSettings {
+    pub world_size: i32,
+    pub chunk_size: i32,
+    // Generate chunks on demand around the camera instead of the whole map up front
+    pub streaming: bool,
+    // Sample the noise fields of a pregenerated world in a compute shader
+    #[serde(default)]
+    pub gpu_generation: bool,
+    // Peoples start as migrating bands that settle the land themselves, rather
+    // than as kingdoms already founded
+    #[serde(default)]
+    pub migration_start: bool,
+    // Dragons and sea serpents make their lairs in the world
+    #[serde(default)]
+    pub fantasy: bool,
+}
+
+impl Default for WorldSettings {
+    fn default() -> Self {
+        WorldSettings {
+            world_size: DEFAULT_WORLD_SIZE,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            streaming: true,
+            gpu_generation: false,
+            migration_start: false,
+            fantasy: false,
+        }
+    }
+}
+
+impl WorldSettings {
+    pub fn chunks_per_side(&self) -> i32 {
+        self.world_size / self.chunk_size
+    }
+}
 
 #[derive(Resource)]
 pub struct LoadedChunks {
     pub chunks: HashMap<(i32, i32), Entity>,
+    // Streamed tile data for the chunks in view, kept so that overlay, season and
+    // lighting changes only recolour meshes instead of re-sampling the world
+    pub chunk_data: HashMap<(i32, i32), Vec<Square>>,
+    // Noise generators for the streamed world, built for its first chunk and
+    // shared with every chunk task after
+    pub sampler: Option<Arc<WorldSampler>>,
+    // Chunk meshes being built on the async compute pool
+    pub pending: HashMap<(i32, i32), Task<BuiltChunk>>,
+    // Meshes waiting on a rebuild, left on screen until their replacement arrives
+    pub stale: HashMap<(i32, i32), Entity>,
+    // Tiles of a streamed world changed since it was generated, keyed by their
+    // base-world coordinate and laid over the sampled squares of every chunk
+    // showing them. Pregenerated worlds change their `WorldMap` instead.
+    pub edits: HashMap<IVec2, Square>,
+}
+
+// What a chunk task hands back: its tile data, kept for streamed worlds, and the
+// meshes to spawn. `chunk` is the coordinate the meshes were built at, which
+// differs from the task's key if the camera wrapped while it ran.
+pub struct BuiltChunk {
+    chunk: (i32, i32),
+    squares: Vec<Square>,
+    lod_meshes: [Mesh; LOD_LEVELS],
+    water: Option<Mesh>,
+    dominant_biome: Biome,
+}
+
+// Where a chunk task gets its tiles: already gathered, or sampled from the seed
+// with the chunk's edits laid over them
+enum ChunkTiles {
+    Ready(Vec<Square>),
+    Sampled(Arc<WorldSampler>, Vec<(usize, Square)>),
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MapOverlay {
+    #[default]
+    Biome,
+    Resources,
+    Temperature,
+    Moisture,
+    Elevation,
+    // The biome map under a tint for each kingdom's territory
+    Political,
+    // The biome map with the trade routes in use drawn over it
+    Trade,
+    // The biome map under a tint for how well stocked each cell is with game
+    Wildlife,
+}
+
+// Value range a heatmap overlay spreads across its colour ramp, in display units
+#[derive(Clone, Copy)]
+pub struct HeatmapScale {
+    // String ID of what the overlay shows
+    pub label: &'static str,
+    pub unit: &'static str,
+    pub min: f32,
+    pub max: f32,
+}
+
+// Height of one elevation step, used to show the elevation overlay in metres
+pub const METRES_PER_ELEVATION: f32 = 60.0;
+
+impl MapOverlay {
+    // Elevation is shown in metres above `sea_level`, the world's
+    pub fn heatmap_scale(&self, sea_level: f32) -> Option<HeatmapScale> {
+        match self {
+            MapOverlay::Biome
+            | MapOverlay::Resources
+            | MapOverlay::Political
+            | MapOverlay::Trade
+            | MapOverlay::Wildlife => None,
+            MapOverlay::Temperature => Some(HeatmapScale {
+                label: "legend.temperature",
+                unit: "°C",
+                min: -30.0,
+                max: 35.0,
+            }),
+            MapOverlay::Moisture => Some(HeatmapScale {
+                label: "legend.moisture",
+                unit: "%",
+                min: 0.0,
+                max: 100.0,
+            }),
+            MapOverlay::Elevation => Some(HeatmapScale {
+                label: "legend.elevation",
+                unit: "m",
+                min: -sea_level * METRES_PER_ELEVATION,
+                max: (MAX_ELEVATION as f32 - sea_level) * METRES_PER_ELEVATION,
+            }),
+        }
+    }
+
+    // The square's value for this overlay in the same units as `heatmap_scale`
+    pub fn heatmap_value(&self, square: &Square, sea_level: f32) -> f32 {
+        match self {
+            MapOverlay::Temperature => square.temperature,
+            MapOverlay::Moisture => square.moisture * 100.0,
+            MapOverlay::Elevation => (square.elevation - sea_level) * METRES_PER_ELEVATION,
+            MapOverlay::Biome
+            | MapOverlay::Resources
+            | MapOverlay::Political
+            | MapOverlay::Trade
+            | MapOverlay::Wildlife => 0.0,
+        }
+    }
+}
+
+// Tile grid (L) for lining things up; only drawn once tiles are big enough on
+// screen for the lines not to swamp them
+#[derive(Resource, Default)]
+pub struct TileGrid {
+    pub enabled: bool,
+}
+
+// Gizmo group for the heavier lines on chunk borders
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct ChunkGridGizmos;
+
+// Relief shading light. Azimuth is measured clockwise from north (up the map) and
+// altitude above the horizon, both in degrees.
+#[derive(Resource, Clone, Copy)]
+pub struct Hillshade {
+    pub azimuth: f32,
+    pub altitude: f32,
+    // Vertical exaggeration; elevation steps are small next to a tile's width
+    pub exaggeration: f32,
+}
+
+impl Default for Hillshade {
+    fn default() -> Self {
+        // Cartographic convention: light from the north-west
+        Hillshade {
+            azimuth: 315.0,
+            altitude: 45.0,
+            exaggeration: 4.0,
+        }
+    }
+}
+
+impl Hillshade {
+    // Brightness multiplier for a tile with the given elevation gradient; exactly
+    // 1.0 on flat ground
+    fn shade(&self, slope: Vec2) -> f32 {
+        let azimuth = self.azimuth.to_radians();
+        let altitude = self.altitude.to_radians();
+        let light = Vec3::new(
+            azimuth.sin() * altitude.cos(),
+            azimuth.cos() * altitude.cos(),
+            altitude.sin(),
+        );
+        let normal = Vec3::new(-slope.x * self.exaggeration, -slope.y * self.exaggeration, 1.0)
+            .normalize();
+
+        (normal.dot(light) / light.z).clamp(0.6, 1.4)
+    }
+}
+
+const LIGHT_ROTATION_STEP: f32 = 45.0;
+// Animated water sits just above its chunk's own tiles
+const WATER_Z: f32 = 0.01;
+// Above the chunk meshes, below landmark markers
+const HIGHLIGHT_Z: f32 = 0.2;
+// Tiles from a settlement's centre that still select it in the inspector
+const SETTLEMENT_PICK_RADIUS: f32 = 2.5;
+// Widest zoom (world units per pixel) the tile grid is drawn at
+const GRID_MAX_SCALE: f32 = 0.25;
+
+// Streams chunks ahead of a panning camera so fast scrolling doesn't outrun loading
+#[derive(Resource)]
+pub struct ChunkPrefetch {
+    // How many chunks ahead of the camera, along its heading, get loaded early
+    pub ring: i32,
+    // Smoothed camera velocity in tiles per second
+    velocity: Vec2,
+    last_position: Option<Vec2>,
+}
+
+impl Default for ChunkPrefetch {
+    fn default() -> Self {
+        ChunkPrefetch {
+            ring: 2,
+            velocity: Vec2::ZERO,
+            last_position: None,
+        }
+    }
+}
+
+// Below this speed in tiles per second the camera counts as still
+const PREFETCH_MIN_SPEED: f32 = 50.0;
+// Weight of the newest frame in the smoothed velocity
+const VELOCITY_SMOOTHING: f32 = 0.2;
+
+// Mouse camera options: wheel zoom per notch, and which way wheel and drag move
+#[derive(Resource)]
+pub struct CameraControls {
+    pub zoom_speed: f32,
+    pub invert_zoom: bool,
+    pub invert_drag: bool,
+    // Stop at the map edges instead of wrapping around the torus
+    pub clamp_to_world: bool,
+}
+
+impl Default for CameraControls {
+    fn default() -> Self {
+        CameraControls {
+            zoom_speed: 0.15,
+            invert_zoom: false,
+            invert_drag: false,
+            clamp_to_world: false,
+        }
+    }
 }
 
+// Tile picked with a left click, wrapped into the world, for inspection and
+// later build commands to act on
+#[derive(Resource, Default)]
+pub struct SelectedTile(pub Option<IVec2>);
+
 #[derive(Resource, Default)]
 pub struct CameraChunk {
     pub x: i32,
     pub y: i32,
 }
 
+pub fn setup_camera(mut commands: Commands, window: Single<&Window>) {
+    let window_size = window.resolution.physical_size().as_vec2();
+    commands.spawn((
+        Camera2d,
+        Camera {
+            viewport: Some(Viewport {
+                physical_position: UVec2::ZERO,
+                physical_size: window_size.as_uvec2(),
+                ..default()
+            }),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 1000.0),
+    ));
+}
+
+pub fn update_camera_chunk(
+    camera_q: Query<&Transform, With<Camera2d>>,
+    mut camera_chunk: ResMut<CameraChunk>,
+    settings: Res<WorldSettings>,
+) {
+    let transform = camera_q.single();
+    let chunk = world_pos_to_chunk(transform.unwrap().translation, settings.chunk_size);
+    camera_chunk.x = chunk.x;
+    camera_chunk.y = chunk.y;
+}
+
+fn world_pos_to_chunk(pos: Vec3, chunk_size: i32) -> IVec2 {
+    IVec2::new(
+        (pos.x.floor() as i32).div_euclid(chunk_size),
+        (pos.y.floor() as i32).div_euclid(chunk_size),
+    )
+}
+
+// Keeps the camera on the base copy of the torus so its coordinates never drift
+// far enough to lose precision. Everything already placed in world space shifts
+// by the same whole number of worlds, so crossing an edge is seamless and no
+// chunk has to be rebuilt.
+pub fn wrap_camera(
+    mut camera_query: Single<&mut Transform, (With<Camera2d>, Without<AmbientEffect>)>,
+    settings: Res<WorldSettings>,
+    mut loaded: ResMut<LoadedChunks>,
+    mut prefetch: ResMut<ChunkPrefetch>,
+    mut chunk_query: Query<(&mut ChunkCoord, &mut Transform), Without<Camera2d>>,
+    mut effect_query: Query<&mut Transform, (With<AmbientEffect>, Without<ChunkCoord>)>,
+) {
+    let size = settings.world_size as f32;
+    let position = camera_query.translation.truncate();
+    let shift = (position.rem_euclid(Vec2::splat(size)) - position).round();
+    if shift == Vec2::ZERO {
+        return;
+    }
+
+    camera_query.translation += shift.extend(0.0);
+    if let Some(last_position) = prefetch.last_position.as_mut() {
+        *last_position += shift;
+    }
+
+    let chunk_shift = shift.as_ivec2() / settings.chunk_size;
+    let offset = |(x, y): (i32, i32)| (x + chunk_shift.x, y + chunk_shift.y);
+    loaded.chunks = loaded
+        .chunks
+        .drain()
+        .map(|(chunk, entity)| (offset(chunk), entity))
+        .collect();
+    loaded.chunk_data = loaded
+        .chunk_data
+        .drain()
+        .map(|(chunk, squares)| (offset(chunk), squares))
+        .collect();
+    loaded.pending = loaded
+        .pending
+        .drain()
+        .map(|(chunk, task)| (offset(chunk), task))
+        .collect();
+    loaded.stale = loaded
+        .stale
+        .drain()
+        .map(|(chunk, entity)| (offset(chunk), entity))
+        .collect();
+
+    // Chunk meshes keep their original vertex positions; the transform carries the shift
+    for (mut coord, mut transform) in &mut chunk_query {
+        coord.0 += chunk_shift;
+        transform.translation += shift.extend(0.0);
+    }
+    for mut transform in &mut effect_query {
+        transform.translation += shift.extend(0.0);
+    }
+}
+
+// With `CameraControls::clamp_to_world`, keeps the view inside the world
+// rectangle at the current zoom. A view wider than the world stays centred on it.
+pub fn clamp_camera(
+    camera_controls: Res<CameraControls>,
+    settings: Res<WorldSettings>,
+    camera_query: Single<(&Camera, &mut Transform, &Projection), With<Camera2d>>,
+) {
+    if !camera_controls.clamp_to_world {
+        return;
+    }
+    let (camera, mut transform, projection) = camera_query.into_inner();
+    let (Projection::Orthographic(projection2d), Some(viewport)) =
+        (projection, camera.logical_viewport_rect())
+    else {
+        return;
+    };
+
+    let size = settings.world_size as f32;
+    let half_view = viewport.half_size() * projection2d.scale;
+    for axis in 0..2 {
+        transform.translation[axis] = if half_view[axis] * 2.0 >= size {
+            size / 2.0
+        } else {
+            transform.translation[axis].clamp(half_view[axis], size - half_view[axis])
+        };
+    }
+}
+
+pub fn track_camera_velocity(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    time: Res<Time>,
+    mut prefetch: ResMut<ChunkPrefetch>,
+) {
+    let position = camera_query.translation.truncate();
+    let delta_secs = time.delta_secs();
+
+    if let Some(last_position) = prefetch.last_position
+        && delta_secs > 0.0
+    {
+        let frame_velocity = (position - last_position) / delta_secs;
+        prefetch.velocity = prefetch.velocity.lerp(frame_velocity, VELOCITY_SMOOTHING);
+    }
+    prefetch.last_position = Some(position);
+}
+
+// Queues the chunks around the camera, and ahead of it when moving, for meshing on
+// the async compute pool. Meshes are spawned by `spawn_built_chunks` as their
+// tasks finish.
 pub fn update_chunks(
     mut commands: Commands,
     mut loaded: ResMut<LoadedChunks>,
-    camera_chunk: Res<CameraChunk>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    query: Query<&WorldData>,
+    (camera_chunk, projection): (Res<CameraChunk>, Single<&Projection, With<Camera2d>>),
+    prefetch: Res<ChunkPrefetch>,
+    (overlay, season, hillshade, map_palette): (
+        Res<MapOverlay>,
+        Res<Season>,
+        Res<Hillshade>,
+        Res<MapPalette>,
+    ),
+    settings: Res<WorldSettings>,
+    (world_map_query, world_data_query): (Query<&WorldMap>, Query<&WorldData>),
 ) {
-    let world_data = match query.single() {
-        Ok(map) => map,
+    // A pregenerated map is meshed from memory; otherwise chunk data is streamed
+    // straight from the world seed
+    let world_map = world_map_query.single().ok();
+    let world_data = match world_data_query.single() {
+        Ok(data) => data,
         Err(err) => {
-            error!("WorldMap query failed: {:?}", err);
+            error!("WorldData query failed: {:?}", err);
             return;
         }
     };
     let mut needed_chunks = HashMap::new();
+    let radius = view_radius(*projection, &settings);
 
-    for x in -VIEW_RADIUS..=VIEW_RADIUS {
-        for y in -VIEW_RADIUS..=VIEW_RADIUS {
+    for x in -radius.x..=radius.x {
+        for y in -radius.y..=radius.y {
             let chunk_x = camera_chunk.x + x;
             let chunk_y = camera_chunk.y + y;
 
@@ -54,62 +497,378 @@ pub fn update_chunks(
         }
     }
 
-    for (&(chunk_x, chunk_y), &entity) in loaded.chunks.iter() {
-        if !needed_chunks.contains_key(&(chunk_x, chunk_y)) {
-            commands.entity(entity).despawn();
+    // Also keep the view's worth of chunks around points further along the heading
+    if prefetch.velocity.length() >= PREFETCH_MIN_SPEED {
+        let heading = prefetch.velocity.normalize();
+        for step in 1..=prefetch.ring {
+            let ahead = (heading * step as f32).round().as_ivec2();
+            for x in -radius.x..=radius.x {
+                for y in -radius.y..=radius.y {
+                    let chunk_x = camera_chunk.x + ahead.x + x;
+                    let chunk_y = camera_chunk.y + ahead.y + y;
+
+                    needed_chunks.insert((chunk_x, chunk_y), true);
+                }
+            }
         }
     }
 
+    // Evict chunks that left the view; they are rebuilt from scratch if revisited.
+    // Dropping a pending task cancels it.
+    let LoadedChunks {
+        chunks,
+        chunk_data,
+        sampler,
+        pending,
+        stale,
+        edits,
+    } = &mut *loaded;
+    for loaded_chunks in [&mut *chunks, &mut *stale] {
+        loaded_chunks.retain(|chunk, &mut entity| {
+            let keep = needed_chunks.contains_key(chunk);
+            if !keep {
+                commands.entity(entity).despawn();
+            }
+            keep
+        });
+    }
+    chunk_data.retain(|chunk, _| needed_chunks.contains_key(chunk));
+    pending.retain(|chunk, _| needed_chunks.contains_key(chunk));
+
+    let style = ChunkStyle {
+        overlay: *overlay,
+        season: season.clone(),
+        hillshade: *hillshade,
+        biomes: world_data.biomes.clone(),
+        palette: map_palette.palette.clone(),
+        sea_level: world_data.sea_level() as f32,
+    };
+    let task_pool = AsyncComputeTaskPool::get();
     for (&(chunk_x, chunk_y), _) in needed_chunks.iter() {
-        if !loaded.chunks.contains_key(&(chunk_x, chunk_y)) {
-            let mesh = generate_chunk_stream(chunk_x, chunk_y, world_data);
+        if chunks.contains_key(&(chunk_x, chunk_y)) || pending.contains_key(&(chunk_x, chunk_y))
+        {
+            continue;
+        }
+
+        let tiles = match world_map {
+            Some(world_map) => {
+                ChunkTiles::Ready(pregenerated_chunk_data(chunk_x, chunk_y, world_map, &settings))
+            }
+            None => match chunk_data.get(&(chunk_x, chunk_y)) {
+                Some(squares) => ChunkTiles::Ready(squares.clone()),
+                None => ChunkTiles::Sampled(
+                    Arc::clone(sampler.get_or_insert_with(|| {
+                        Arc::new(WorldSampler::new(world_data, settings.world_size))
+                    })),
+                    chunk_edits(chunk_x, chunk_y, edits, &settings),
+                ),
+            },
+        };
+
+        let settings = *settings;
+        let style = style.clone();
+        let task = task_pool.spawn(async move {
+            let squares = match tiles {
+                ChunkTiles::Ready(squares) => squares,
+                ChunkTiles::Sampled(sampler, edits) => {
+                    let mut squares =
+                        generate_chunk_data(chunk_x, chunk_y, settings.chunk_size, &sampler);
+                    for (index, square) in edits {
+                        squares[index] = square;
+                    }
+                    squares
+                }
+            };
+            let (lod_meshes, water, dominant_biome) =
+                generate_chunk_stream(chunk_x, chunk_y, &squares, &settings, &style);
+            BuiltChunk {
+                chunk: (chunk_x, chunk_y),
+                squares,
+                lod_meshes,
+                water,
+                dominant_biome,
+            }
+        });
+        pending.insert((chunk_x, chunk_y), task);
+    }
+}
+
+// Spawns the meshes of every chunk task that finished since last frame, replacing
+// any stale mesh still standing in for the chunk
+pub fn spawn_built_chunks(
+    mut commands: Commands,
+    mut loaded: ResMut<LoadedChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    tile_material: Res<BiomeTileMaterialHandle>,
+    water_material: Res<WaterMaterialHandle>,
+    settings: Res<WorldSettings>,
+    world_map_query: Query<(), With<WorldMap>>,
+) {
+    let streamed = world_map_query.is_empty();
+    let built: Vec<((i32, i32), BuiltChunk)> = loaded
+        .pending
+        .iter_mut()
+        .filter_map(|(&chunk, task)| Some((chunk, check_ready(task)?)))
+        .collect();
+
+    for ((chunk_x, chunk_y), chunk) in built {
+        loaded.pending.remove(&(chunk_x, chunk_y));
+        if let Some(entity) = loaded.stale.remove(&(chunk_x, chunk_y)) {
+            commands.entity(entity).despawn();
+        }
+        if streamed {
+            loaded.chunk_data.insert((chunk_x, chunk_y), chunk.squares);
+        }
 
-            let entity = commands
-                .spawn((
-                    Mesh2d(meshes.add(mesh)),
-                    MeshMaterial2d(materials.add(ColorMaterial::from(Color::WHITE))),
-                    Transform::default(),
-                ))
-                .id();
+        let lod_meshes = chunk.lod_meshes.map(|mesh| meshes.add(mesh));
+        // Vertices sit where the chunk was when its task started; shift them by the
+        // worlds the camera has wrapped since, as `wrap_camera` does for placed meshes
+        let (built_x, built_y) = chunk.chunk;
+        let offset = IVec2::new(chunk_x - built_x, chunk_y - built_y) * settings.chunk_size;
 
-            loaded.chunks.insert((chunk_x, chunk_y), entity);
+        let entity = commands
+            .spawn((
+                Mesh2d(lod_meshes[0].clone()),
+                MeshMaterial2d(tile_material.0.clone()),
+                Transform::from_translation(offset.as_vec2().extend(0.0)),
+                DominantBiome(chunk.dominant_biome),
+                ChunkCoord(IVec2::new(chunk_x, chunk_y)),
+                ChunkLod {
+                    meshes: Vec::from(lod_meshes),
+                    level: 0,
+                },
+            ))
+            .id();
+
+        if let Some(water) = chunk.water {
+            commands.entity(entity).with_child((
+                ChunkWater,
+                Mesh2d(meshes.add(water)),
+                MeshMaterial2d(water_material.0.clone()),
+                Transform::from_xyz(0.0, 0.0, WATER_Z),
+            ));
         }
+
+        loaded.chunks.insert((chunk_x, chunk_y), entity);
     }
 }
 
+pub fn setup_chunk_spinner(mut commands: Commands) {
+    commands.spawn((
+        ChunkSpinnerUI,
+        Text::new(""),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
 
+// Shown while any chunk in view has nothing on screen yet, neither its mesh nor
+// a stale one awaiting a rebuild
+pub fn update_chunk_spinner(
+    time: Res<Time>,
+    loaded: Res<LoadedChunks>,
+    camera_chunk: Res<CameraChunk>,
+    settings: Res<WorldSettings>,
+    projection: Single<&Projection, With<Camera2d>>,
+    spinner_query: Single<(&mut Text, &mut Visibility), With<ChunkSpinnerUI>>,
+    locale: Res<Locale>,
+) {
+    let (mut text, mut visibility) = spinner_query.into_inner();
+    let radius = view_radius(*projection, &settings);
+    let waiting = (-radius.x..=radius.x)
+        .flat_map(|x| (-radius.y..=radius.y).map(move |y| (x, y)))
+        .map(|(x, y)| (camera_chunk.x + x, camera_chunk.y + y))
+        .any(|chunk| !loaded.chunks.contains_key(&chunk) && !loaded.stale.contains_key(&chunk));
+
+    if !waiting {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
 
-pub fn generate_chunk_stream(chunk_x: i32, chunk_y: i32, world_data: &WorldData) -> Mesh {
+    visibility.set_if_neq(Visibility::Inherited);
+    let frame = (time.elapsed_secs() * SPINNER_FPS) as usize % SPINNER_FRAMES.len();
+    text.0 = locale.format("hud.building_map", &[&SPINNER_FRAMES[frame]]);
+}
+
+// Hides chunk meshes outside the camera's orthographic view, which already
+// accounts for zoom, so only what is on screen gets drawn
+pub fn cull_chunks(
+    camera_query: Single<(&Transform, &Projection), With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut chunk_query: Query<(&ChunkCoord, &mut Visibility)>,
+) {
+    let (camera_transform, projection) = *camera_query;
+    let Projection::Orthographic(projection2d) = projection else {
+        return;
+    };
+
+    let center = camera_transform.translation.truncate();
+    let view = Rect::from_corners(center + projection2d.area.min, center + projection2d.area.max);
+    let chunk_size = settings.chunk_size as f32;
+
+    for (coord, mut visibility) in &mut chunk_query {
+        let min = coord.0.as_vec2() * chunk_size;
+        let chunk = Rect::from_corners(min, min + Vec2::splat(chunk_size));
+
+        let shown = if view.intersect(chunk).is_empty() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        visibility.set_if_neq(shown);
+    }
+}
+
+// The level of detail drawn at a camera projection scale
+fn lod_level(scale: f32) -> usize {
+    LOD_ZOOM_THRESHOLDS
+        .iter()
+        .filter(|&&threshold| scale >= threshold)
+        .count()
+}
+
+// Chunks out from the camera's chunk, along each axis, that the orthographic view
+// reaches at its current zoom, plus one so panning doesn't show an unloaded edge
+fn view_radius(projection: &Projection, settings: &WorldSettings) -> IVec2 {
+    let Projection::Orthographic(projection2d) = projection else {
+        return IVec2::ONE;
+    };
+    let half_view = projection2d.area.half_size() / settings.chunk_size as f32;
+    let cap = MAX_VIEW_RADIUS[lod_level(projection2d.scale)];
+    (half_view.ceil().as_ivec2() + IVec2::ONE).min(IVec2::splat(cap))
+}
+
+// Swaps each chunk to the mesh resolution that suits the current zoom, so
+// zoomed-out views don't draw sub-pixel tile quads
+pub fn update_chunk_lod(
+    camera_query: Single<&Projection, With<Camera2d>>,
+    mut chunk_query: Query<(&mut ChunkLod, &mut Mesh2d, Option<&Children>)>,
+    mut water_query: Query<&mut Visibility, With<ChunkWater>>,
+) {
+    let Projection::Orthographic(projection2d) = *camera_query else {
+        return;
+    };
+
+    let level = lod_level(projection2d.scale);
+
+    // Coarser meshes keep their own static water
+    let water_visibility = if level == 0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    for (mut lod, mut mesh, children) in &mut chunk_query {
+        if lod.level != level {
+            lod.level = level;
+            mesh.0 = lod.meshes[level].clone();
+
+            for child in children.into_iter().flatten() {
+                if let Ok(mut visibility) = water_query.get_mut(*child) {
+                    *visibility = water_visibility;
+                }
+            }
+        }
+    }
+}
+
+// Everything besides the tiles that decides how chunk meshes are coloured, cloned
+// into each chunk task
+#[derive(Clone)]
+pub struct ChunkStyle {
+    pub overlay: MapOverlay,
+    pub season: Season,
+    pub hillshade: Hillshade,
+    pub biomes: Arc<BiomeTable>,
+    pub palette: Arc<Palette>,
+    pub sea_level: f32,
+}
+
+// `squares` is the chunk's data from `generate_chunk_data` or `pregenerated_chunk_data`,
+// HALO ring included
+pub fn generate_chunk_stream(
+    chunk_x: i32,
+    chunk_y: i32,
+    squares: &[Square],
+    settings: &WorldSettings,
+    style: &ChunkStyle,
+) -> ([Mesh; LOD_LEVELS], Option<Mesh>, Biome) {
+    let ChunkStyle {
+        overlay,
+        season,
+        hillshade,
+        biomes,
+        palette,
+        sea_level,
+    } = style;
+    let overlay = *overlay;
+    let chunk_size = settings.chunk_size;
     let mut mesh = Mesh::new(TriangleList, RenderAssetUsages::default());
     let mut positions = Vec::new();
     let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut atlas_uvs = Vec::new();
     let mut indices = Vec::new();
     let mut index_offset = 0;
 
-    let squares = generate_chunk_data(chunk_x, chunk_y, world_data);
+    let mut biome_counts: HashMap<Biome, u32> = HashMap::new();
+
+    // Chunk data carries a HALO ring, so edge tiles still see their neighbours
+    let side = chunk_size + 2 * HALO;
+    let halo_index = |x: i32, y: i32| ((y + HALO) * side + (x + HALO)) as usize;
 
-    for x_local in 0..CHUNK_SIZE {
-        for y_local in 0..CHUNK_SIZE {
-            let x_i32 = x_local + (chunk_x * CHUNK_SIZE);
-            let y_i32 = y_local + (chunk_y * CHUNK_SIZE);
+    for x_local in 0..chunk_size {
+        for y_local in 0..chunk_size {
+            let x_i32 = x_local + (chunk_x * chunk_size);
+            let y_i32 = y_local + (chunk_y * chunk_size);
 
             let x = x_i32 as f32;
             let y = y_i32 as f32;
 
-            let index = (y_local * (CHUNK_SIZE + HALO) + x_local) as usize;
-            let square = &squares[index];
+            let square = &squares[halo_index(x_local, y_local)];
+            let neighbour_squares = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                .map(|(dx, dy)| &squares[halo_index(x_local + dx, y_local + dy)]);
+            let neighbours = neighbour_squares.map(|neighbour| neighbour.biome);
+            let slope = elevation_slope(neighbour_squares);
+            *biome_counts.entry(square.biome).or_insert(0) += 1;
 
             positions.push([x, y, 0.0]); // v0
             positions.push([x + 1.0, y, 0.0]); // v1
             positions.push([x + 1.0, y + 1.0, 0.0]); // v2
             positions.push([x, y + 1.0, 0.0]); // v3
 
-            let color = biome_to_color(square.biome);
+            let shown = match overlay {
+                MapOverlay::Biome
+                | MapOverlay::Political
+                | MapOverlay::Trade
+                | MapOverlay::Wildlife => {
+                    dithered_square(x_i32, y_i32, square, neighbour_squares)
+                }
+                _ => square,
+            };
+            let biome = season.biome(shown, tile_latitude(y_i32, settings.world_size));
+            let color = tile_color(shown, biome, overlay, biomes, palette, *sea_level);
+            let color = shaded(color, biome, hillshade, slope);
             colors.push(color);
             colors.push(color);
             colors.push(color);
             colors.push(color);
 
+            let layer = biome_texture_layer(square.biome);
+            let tile_uv = [layer as f32, neighbour_texture_layer(layer, neighbours) as f32];
+            uvs.extend_from_slice(&[tile_uv; 4]);
+            atlas_uvs.extend_from_slice(&[[biome_atlas_layer(biome) as f32, 0.0]; 4]);
+
             indices.extend_from_slice(&[
                 index_offset,
                 index_offset + 1,
@@ -124,47 +883,68 @@ pub fn generate_chunk_stream(chunk_x: i32, chunk_y: i32, world_data: &WorldData)
     }
 
     mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, atlas_uvs);
 
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
     mesh.insert_indices(Indices::U32(indices));
 
-    return mesh;
-}
+    let dominant_biome = biome_counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(biome, _)| biome)
+        .unwrap_or_default();
 
-pub fn render_world(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    query: Query<&WorldMap>,
-) {
-    let world_map = match query.single() {
-        Ok(map) => map,
-        Err(err) => {
-            error!("WorldMap query failed: {:?}", err);
-            return;
+    let origin = IVec2::new(chunk_x, chunk_y) * chunk_size;
+    let lod_meshes = [
+        mesh,
+        block_mesh(origin, LOD_BLOCKS[1], settings, style, |x, y| {
+            &squares[halo_index(x, y)]
+        }),
+        block_mesh(origin, LOD_BLOCKS[2], settings, style, |x, y| {
+            &squares[halo_index(x, y)]
+        }),
+    ];
+    // Data overlays colour water by their own values, so only the biome maps animate it
+    let water = match overlay {
+        MapOverlay::Biome
+        | MapOverlay::Political
+        | MapOverlay::Trade
+        | MapOverlay::Wildlife => {
+            water_mesh(origin, settings, style, |x, y| &squares[halo_index(x, y)])
         }
+        _ => None,
     };
 
-    for chunk_x in 0..CHUNKS_SIZE {
-        for chunk_y in 0..CHUNKS_SIZE {
-            let mesh = generate_chunk(chunk_x, chunk_y, &world_map);
-
-            commands.spawn((
-                Mesh2d(meshes.add(mesh)),
-                MeshMaterial2d(materials.add(ColorMaterial::from(Color::WHITE))),
-                Transform::default(),
-            ));
-        }
-    }
+    (lod_meshes, water, dominant_biome)
 }
 
+type WorldUI = Or<(With<BiomeDisplayUI>, With<TileInspectorUI>, With<ChunkSpinnerUI>)>;
+
 pub fn cleanup_world(
     mut commands: Commands,
     world_query: Query<Entity, With<WorldMap>>,
     world_data_query: Query<Entity, With<crate::components::world_gen::WorldData>>,
-    mesh_query: Query<Entity, With<Mesh2d>>,
-    ui_query: Query<Entity, With<BiomeDisplayUI>>,
+    mesh_query: Query<Entity, (With<Mesh2d>, Without<ChildOf>)>,
+    ui_query: Query<Entity, WorldUI>,
+    (landmark_query, highlight_query): (
+        Query<Entity, With<Landmark>>,
+        Query<Entity, With<TileHighlight>>,
+    ),
+    (mut loaded, mut selected, regeneration): (
+        ResMut<LoadedChunks>,
+        ResMut<SelectedTile>,
+        Option<Res<WorldRegeneration>>,
+    ),
 ) {
+    // The meshes are despawned below; a new world must not find them still listed
+    loaded.chunks.clear();
+    loaded.chunk_data.clear();
+    loaded.sampler = None;
+    loaded.pending.clear();
+    loaded.stale.clear();
+    loaded.edits.clear();
+
     for entity in world_query {
         commands.entity(entity).despawn();
     }
@@ -173,108 +953,520 @@ pub fn cleanup_world(
         commands.entity(entity).despawn();
     }
 
-    for entity in world_data_query {
-        commands.entity(entity).despawn();
+    // Regenerating in place reuses the world data for the next world
+    if regeneration.is_some() {
+        commands.remove_resource::<WorldRegeneration>();
+    } else {
+        for entity in world_data_query {
+            commands.entity(entity).despawn();
+        }
     }
 
     for entity in ui_query {
         commands.entity(entity).despawn();
     }
+
+    for entity in landmark_query {
+        commands.entity(entity).despawn();
+    }
+
+    for entity in highlight_query {
+        commands.entity(entity).despawn();
+    }
+    selected.0 = None;
 }
 
+// Runs every frame rather than on the fixed step so accumulated mouse motion and
+// scroll are applied exactly once
 pub fn controls(
-    camera_query: Single<(&mut Transform, &mut Projection)>,
+    camera_query: Single<(&Camera, &mut Transform, &mut Projection), With<Camera2d>>,
+    window_query: Single<&Window>,
     input: Res<ButtonInput<KeyCode>>,
-    mut next_state: ResMut<NextState<GameState>>,
-    time: Res<Time<Fixed>>,
+    input_map: Res<InputMap>,
+    (mouse, motion, scroll): (
+        Res<ButtonInput<MouseButton>>,
+        Res<AccumulatedMouseMotion>,
+        Res<AccumulatedMouseScroll>,
+    ),
+    (camera_controls, settings): (Res<CameraControls>, Res<WorldSettings>),
+    time: Res<Time>,
 ) {
-    let (mut transform, mut projection) = camera_query.into_inner();
+    let (camera, mut transform, mut projection) = camera_query.into_inner();
 
     let fspeed = 600.0 * time.delta_secs();
 
     // Camera movement controls
-    if input.pressed(KeyCode::KeyW) {
+    if input_map.pressed(&input, InputAction::PanUp) {
         transform.translation.y += fspeed;
     }
-    if input.pressed(KeyCode::KeyS) {
+    if input_map.pressed(&input, InputAction::PanDown) {
         transform.translation.y -= fspeed;
     }
-    if input.pressed(KeyCode::KeyA) {
+    if input_map.pressed(&input, InputAction::PanLeft) {
         transform.translation.x -= fspeed;
     }
-    if input.pressed(KeyCode::KeyD) {
+    if input_map.pressed(&input, InputAction::PanRight) {
         transform.translation.x += fspeed;
     }
 
+    let Projection::Orthographic(projection2d) = &mut *projection else {
+        return;
+    };
+
+    // Middle or right drag grabs the map, so it follows the cursor
+    if mouse.any_pressed([MouseButton::Middle, MouseButton::Right]) {
+        let direction = if camera_controls.invert_drag { -1.0 } else { 1.0 };
+        let delta = motion.delta * projection2d.scale * direction;
+        transform.translation.x -= delta.x;
+        transform.translation.y += delta.y;
+    }
+
     // Camera zoom controls
-    if let Projection::Orthographic(projection2d) = &mut *projection {
-        if input.pressed(KeyCode::Comma) {
-            projection2d.scale *= powf(4.0f32, time.delta_secs());
+    let mut zoom = 1.0;
+    if input_map.pressed(&input, InputAction::ZoomOut) {
+        zoom *= powf(4.0f32, time.delta_secs());
+    }
+
+    if input_map.pressed(&input, InputAction::ZoomIn) {
+        zoom *= powf(0.25f32, time.delta_secs());
+    }
+
+    // Wheel up zooms in
+    let notches = if camera_controls.invert_zoom {
+        scroll.delta.y
+    } else {
+        -scroll.delta.y
+    };
+    if notches != 0.0 {
+        zoom *= powf(1.0 + camera_controls.zoom_speed, notches);
+    }
+
+    // No further out than the coarsest level's chunk cap can fill, also after the
+    // window grows
+    let half_view = projection2d.area.half_size().max_element() / projection2d.scale;
+    let reach = ((MAX_VIEW_RADIUS[LOD_LEVELS - 1] - 1) * settings.chunk_size) as f32;
+    if half_view > 0.0 {
+        zoom = zoom.min(reach / half_view / projection2d.scale);
+    }
+
+    // Keep the world point under the cursor where it is; with the cursor off the
+    // window, zoom about the middle of the view
+    if let Some(cursor) = window_query.cursor_position()
+        && let Some(viewport) = camera.logical_viewport_rect()
+    {
+        let offset = (cursor - viewport.center()) * Vec2::new(1.0, -1.0);
+        transform.translation += (offset * projection2d.scale * (1.0 - zoom)).extend(0.0);
+    }
+    projection2d.scale *= zoom;
+}
+
+pub fn toggle_map_overlay(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut overlay: ResMut<MapOverlay>,
+    mut loaded: ResMut<LoadedChunks>,
+) {
+    let requested = if input_map.just_pressed(&input, InputAction::OverlayBiome) {
+        MapOverlay::Biome
+    } else if input_map.just_pressed(&input, InputAction::OverlayResources) {
+        MapOverlay::Resources
+    } else if input_map.just_pressed(&input, InputAction::OverlayTemperature) {
+        MapOverlay::Temperature
+    } else if input_map.just_pressed(&input, InputAction::OverlayMoisture) {
+        MapOverlay::Moisture
+    } else if input_map.just_pressed(&input, InputAction::OverlayElevation) {
+        MapOverlay::Elevation
+    } else if input_map.just_pressed(&input, InputAction::OverlayPolitical) {
+        MapOverlay::Political
+    } else if input_map.just_pressed(&input, InputAction::OverlayTrade) {
+        MapOverlay::Trade
+    } else if input_map.just_pressed(&input, InputAction::OverlayWildlife) {
+        MapOverlay::Wildlife
+    } else {
+        return;
+    };
+
+    if *overlay == requested {
+        return;
+    }
+
+    *overlay = requested;
+    clear_loaded_chunks(&mut loaded);
+}
+
+// [ and ] swing the relief light around the compass; chunks are rebuilt to pick it up
+pub fn rotate_hillshade_light(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut hillshade: ResMut<Hillshade>,
+    mut loaded: ResMut<LoadedChunks>,
+) {
+    let step = if input_map.just_pressed(&input, InputAction::RotateLightLeft) {
+        -LIGHT_ROTATION_STEP
+    } else if input_map.just_pressed(&input, InputAction::RotateLightRight) {
+        LIGHT_ROTATION_STEP
+    } else {
+        return;
+    };
+
+    hillshade.azimuth = (hillshade.azimuth + step).rem_euclid(360.0);
+    clear_loaded_chunks(&mut loaded);
+}
+
+pub fn toggle_tile_grid(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut grid: ResMut<TileGrid>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleGrid) {
+        grid.enabled = !grid.enabled;
+    }
+}
+
+pub fn draw_tile_grid(
+    grid: Res<TileGrid>,
+    settings: Res<WorldSettings>,
+    camera_query: Single<(&Transform, &Projection), With<Camera2d>>,
+    mut tile_gizmos: Gizmos,
+    mut chunk_gizmos: Gizmos<ChunkGridGizmos>,
+) {
+    let (camera_transform, projection) = *camera_query;
+    let Projection::Orthographic(projection2d) = projection else {
+        return;
+    };
+    if !grid.enabled || projection2d.scale > GRID_MAX_SCALE {
+        return;
+    }
+
+    let center = camera_transform.translation.truncate();
+    let min = (center + projection2d.area.min).floor().as_ivec2();
+    let max = (center + projection2d.area.max).ceil().as_ivec2();
+    let tile_color = Color::srgba(0.0, 0.0, 0.0, 0.25);
+    let chunk_color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+    for x in min.x..=max.x {
+        let start = Vec2::new(x as f32, min.y as f32);
+        let end = Vec2::new(x as f32, max.y as f32);
+        if x.rem_euclid(settings.chunk_size) == 0 {
+            chunk_gizmos.line_2d(start, end, chunk_color);
+        } else {
+            tile_gizmos.line_2d(start, end, tile_color);
         }
+    }
+    for y in min.y..=max.y {
+        let start = Vec2::new(min.x as f32, y as f32);
+        let end = Vec2::new(max.x as f32, y as f32);
+        if y.rem_euclid(settings.chunk_size) == 0 {
+            chunk_gizmos.line_2d(start, end, chunk_color);
+        } else {
+            tile_gizmos.line_2d(start, end, tile_color);
+        }
+    }
+}
 
-        if input.pressed(KeyCode::Period) {
-            projection2d.scale *= powf(0.25f32, time.delta_secs());
+// Queues every loaded chunk for a rebuild with current colors. The old meshes stay
+// up until their replacements are spawned, and tasks started with the old colors
+// are dropped.
+pub fn clear_loaded_chunks(loaded: &mut LoadedChunks) {
+    let LoadedChunks {
+        chunks,
+        pending,
+        stale,
+        ..
+    } = loaded;
+    stale.extend(chunks.drain());
+    pending.clear();
+}
+
+// Changes one tile of either kind of world and rebuilds the chunks showing it
+pub fn set_tile(
+    tile: IVec2,
+    square: &Square,
+    settings: &WorldSettings,
+    loaded: &mut LoadedChunks,
+    world_map: Option<&mut WorldMap>,
+) {
+    match world_map {
+        Some(world_map) => {
+            world_map.set_square(index_toroidal(tile.x, tile.y, settings), square);
+        }
+        None => {
+            let base = tile.rem_euclid(IVec2::splat(settings.world_size));
+            loaded.edits.insert(base, square.clone());
         }
     }
+    remesh_tile(loaded, settings, tile);
+}
+
+// Rebuilds every loaded copy of the chunks a changed tile shows in: its own, and
+// any neighbouring chunk whose edge dithers against it. Streamed tile data cached
+// for them is dropped so they are sampled again with the change laid over.
+pub fn remesh_tile(
+    loaded: &mut LoadedChunks,
+    settings: &WorldSettings,
+    tile: IVec2,
+) {
+    let chunks_per_side = (settings.world_size / settings.chunk_size).max(1);
+    let base_chunk = |chunk: IVec2| chunk.rem_euclid(IVec2::splat(chunks_per_side));
+    let stale: Vec<IVec2> = [IVec2::ZERO, IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+        .iter()
+        .map(|offset| base_chunk((tile + *offset).div_euclid(IVec2::splat(settings.chunk_size))))
+        .collect();
+
+    let changed = |&(x, y): &(i32, i32)| stale.contains(&base_chunk(IVec2::new(x, y)));
+
+    let LoadedChunks {
+        chunks,
+        chunk_data,
+        pending,
+        stale: stale_chunks,
+        ..
+    } = loaded;
+    stale_chunks.extend(chunks.extract_if(|chunk, _| changed(chunk)));
+    chunk_data.retain(|chunk, _| !changed(chunk));
+    pending.retain(|chunk, _| !changed(chunk));
+}
+
+// The edits falling on a chunk or its HALO ring, as indices into its squares. The
+// chunk may be a copy of a base-world chunk after the camera wrapped.
+fn chunk_edits(
+    chunk_x: i32,
+    chunk_y: i32,
+    edits: &HashMap<IVec2, Square>,
+    settings: &WorldSettings,
+) -> Vec<(usize, Square)> {
+    let side = settings.chunk_size + 2 * HALO;
+    let origin = IVec2::new(chunk_x, chunk_y) * settings.chunk_size - IVec2::splat(HALO);
+    edits
+        .iter()
+        .filter_map(|(tile, square)| {
+            let local = (*tile - origin).rem_euclid(IVec2::splat(settings.world_size));
+            (local.x < side && local.y < side)
+                .then(|| ((local.y * side + local.x) as usize, square.clone()))
+        })
+        .collect()
+}
+
+// Border tiles sometimes show a neighbouring biome instead of their own, which
+// breaks up hard single-tile edges at zoomed-out scale. Coastlines stay crisp.
+fn dithered_square<'a>(x: i32, y: i32, square: &'a Square, neighbours: [&'a Square; 4]) -> &'a Square {
+    let roll = tile_hash(x, y);
+    if roll >= BORDER_DITHER_CHANCE {
+        return square;
+    }
 
-    if input.pressed(KeyCode::Escape) {
-        next_state.set(GameState::MainMenu);
+    // Reuse the roll to pick the neighbour so the choice is stable between rebuilds
+    let neighbour = neighbours[(roll / BORDER_DITHER_CHANCE * 4.0) as usize % 4];
+    if neighbour.biome == square.biome || is_water(neighbour.biome) != is_water(square.biome) {
+        square
+    } else {
+        neighbour
     }
 }
 
-fn biome_to_color(biome: Biome) -> [f32; 4] {
-    match biome {
-        Biome::Ocean => [0.0, 0.0, 0.5, 1.0],
-        Biome::Coast => [0.8, 0.8, 0.3, 1.0],
-        Biome::Grassland => [0.2, 0.8, 0.2, 1.0],
-        Biome::Forest => [0.1, 0.5, 0.1, 1.0],
-        Biome::Desert => [0.9, 0.8, 0.3, 1.0],
-        Biome::Hill => [0.6, 0.5, 0.3, 1.0],
-        Biome::Mountain => [0.5, 0.5, 0.5, 1.0],
-        Biome::Ice => [0.68, 0.85, 0.90, 1.0],
-        Biome::Alpine => [0.7, 0.7, 0.7, 1.0],
-        Biome::Snow => [0.95, 0.95, 1.0, 1.0],
-        Biome::Tundra => [0.8, 0.7, 0.6, 1.0],
-        Biome::BorealForest => [0.2, 0.4, 0.2, 1.0],
-        Biome::Taiga => [0.3, 0.5, 0.3, 1.0],
-        Biome::ColdDesert => [0.8, 0.7, 0.5, 1.0],
-        Biome::TemperateForest => [0.15, 0.6, 0.15, 1.0],
-        Biome::TemperateRainforest => [0.1, 0.7, 0.2, 1.0],
-        Biome::HotDesert => [1.0, 0.85, 0.3, 1.0],
-        Biome::Savanna => [0.8, 0.8, 0.2, 1.0],
-        Biome::SubtropicalForest => [0.2, 0.7, 0.3, 1.0],
-        Biome::TropicalRainforest => [0.0, 0.6, 0.1, 1.0],
+fn is_water(biome: Biome) -> bool {
+    matches!(biome, Biome::Ocean | Biome::ShallowOcean)
+}
+
+// Cheap deterministic value in [0, 1) per tile
+fn tile_hash(x: i32, y: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343) ^ (y as u32).wrapping_mul(0xd816_3841);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
+pub fn tile_color(
+    square: &Square,
+    biome: Biome,
+    overlay: MapOverlay,
+    biomes: &BiomeTable,
+    palette: &Palette,
+    sea_level: f32,
+) -> [f32; 4] {
+    let base = match biome {
+        Biome::Ocean | Biome::ShallowOcean => palette.ocean_color(square.elevation, sea_level),
+        _ => palette.biome_color(biome, biomes),
+    };
+
+    match overlay {
+        MapOverlay::Biome
+        | MapOverlay::Political
+        | MapOverlay::Trade
+        | MapOverlay::Wildlife => base,
+        MapOverlay::Resources => match resource_to_color(square.resources) {
+            Some(color) => color,
+            None => {
+                let [r, g, b, a] = base;
+                [r * 0.35, g * 0.35, b * 0.35, a]
+            }
+        },
+        MapOverlay::Temperature | MapOverlay::Moisture | MapOverlay::Elevation => {
+            match overlay.heatmap_scale(sea_level) {
+                Some(scale) => {
+                    let value = overlay.heatmap_value(square, sea_level);
+                    heatmap_color((value - scale.min) / (scale.max - scale.min))
+                }
+                None => base,
+            }
+        }
+    }
+}
+
+// Elevation gradient across a tile from its right, left, up and down neighbours
+fn elevation_slope(neighbours: [&Square; 4]) -> Vec2 {
+    Vec2::new(
+        (neighbours[0].elevation - neighbours[1].elevation) / 2.0,
+        (neighbours[2].elevation - neighbours[3].elevation) / 2.0,
+    )
+}
+
+// Relief shading for land; the sea surface stays flat
+fn shaded(color: [f32; 4], biome: Biome, hillshade: &Hillshade, slope: Vec2) -> [f32; 4] {
+    if is_water(biome) {
+        return color;
+    }
+
+    let shade = hillshade.shade(slope);
+    let [r, g, b, a] = color;
+    [
+        (r * shade).min(1.0),
+        (g * shade).min(1.0),
+        (b * shade).min(1.0),
+        a,
+    ]
+}
+
+// Cold-to-hot colour ramp shared by every heatmap overlay and its legend; `t` is
+// the position between the scale's min (0.0) and max (1.0)
+pub fn heatmap_color(t: f32) -> [f32; 4] {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.1, 0.15, 0.6],
+        [0.1, 0.6, 0.85],
+        [0.3, 0.75, 0.3],
+        [0.95, 0.85, 0.2],
+        [0.8, 0.15, 0.1],
+    ];
+
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let index = (scaled as usize).min(STOPS.len() - 2);
+    let local = scaled - index as f32;
+    let (from, to) = (STOPS[index], STOPS[index + 1]);
+
+    [
+        from[0] + (to[0] - from[0]) * local,
+        from[1] + (to[1] - from[1]) * local,
+        from[2] + (to[2] - from[2]) * local,
+        1.0,
+    ]
+}
+
+fn resource_to_color(resource: ResourceKind) -> Option<[f32; 4]> {
+    match resource {
+        ResourceKind::None => None,
+        ResourceKind::Iron => Some([0.65, 0.3, 0.2, 1.0]),
+        ResourceKind::Stone => Some([0.75, 0.75, 0.7, 1.0]),
+        ResourceKind::Gold => Some([1.0, 0.85, 0.0, 1.0]),
+        ResourceKind::FertileSoil => Some([0.45, 0.3, 0.1, 1.0]),
+        ResourceKind::Timber => Some([0.0, 0.45, 0.15, 1.0]),
     }
 }
 
-fn generate_chunk(chunk_x: i32, chunk_y: i32, world_map: &WorldMap) -> Mesh {
+// Pregenerated tiles laid out like `generate_chunk_data`, HALO ring included, so
+// both kinds of world mesh through `generate_chunk_stream`
+fn pregenerated_chunk_data(
+    chunk_x: i32,
+    chunk_y: i32,
+    world_map: &WorldMap,
+    settings: &WorldSettings,
+) -> Vec<Square> {
+    let chunk_size = settings.chunk_size;
+    let side = chunk_size + 2 * HALO;
+    (0..side * side)
+        .map(|i| {
+            let x = chunk_x * chunk_size + i % side - HALO;
+            let y = chunk_y * chunk_size + i / side - HALO;
+            world_map.square(index_toroidal(x, y, settings))
+        })
+        .collect()
+}
+
+// Coarse mesh for zoomed-out views: one quad per `block`×`block` group of tiles,
+// coloured by the group's most common biome. `origin` is the chunk's first tile and
+// `square_at` takes chunk-local coordinates.
+fn block_mesh<'a>(
+    origin: IVec2,
+    block: i32,
+    settings: &WorldSettings,
+    style: &ChunkStyle,
+    square_at: impl Fn(i32, i32) -> &'a Square,
+) -> Mesh {
+    let chunk_size = settings.chunk_size;
     let mut mesh = Mesh::new(TriangleList, RenderAssetUsages::default());
     let mut positions = Vec::new();
     let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut atlas_uvs = Vec::new();
     let mut indices = Vec::new();
     let mut index_offset = 0;
 
-    for x in 0..CHUNK_SIZE {
-        for y in 0..CHUNK_SIZE {
-            let x_i32 = x + (chunk_x * CHUNK_SIZE);
-            let y_i32 = y + (chunk_y * CHUNK_SIZE);
+    for block_x in (0..chunk_size).step_by(block as usize) {
+        for block_y in (0..chunk_size).step_by(block as usize) {
+            let mut biome_counts: HashMap<Biome, u32> = HashMap::new();
+            for x_local in block_x..block_x + block {
+                for y_local in block_y..block_y + block {
+                    *biome_counts.entry(square_at(x_local, y_local).biome).or_insert(0) += 1;
+                }
+            }
+            let majority = biome_counts
+                .into_iter()
+                .max_by_key(|&(_, count)| count)
+                .map(|(biome, _)| biome)
+                .unwrap_or_default();
+
+            // Colour the block from one of its tiles that has the majority biome
+            let representative = (block_x..block_x + block)
+                .flat_map(|x_local| (block_y..block_y + block).map(move |y_local| (x_local, y_local)))
+                .map(|(x_local, y_local)| square_at(x_local, y_local))
+                .find(|square| square.biome == majority)
+                .unwrap_or_else(|| square_at(block_x, block_y));
 
+            let x_i32 = block_x + origin.x;
+            let y_i32 = block_y + origin.y;
             let x = x_i32 as f32;
             let y = y_i32 as f32;
+            let size = block as f32;
 
-            let index = index_toroidal(x_i32, y_i32, WORLD_SIZE as i32);
-            let square = &world_map.squares[index];
+            positions.push([x, y, 0.0]);
+            positions.push([x + size, y, 0.0]);
+            positions.push([x + size, y + size, 0.0]);
+            positions.push([x, y + size, 0.0]);
 
-            positions.push([x, y, 0.0]); // v0
-            positions.push([x + 1.0, y, 0.0]); // v1
-            positions.push([x + 1.0, y + 1.0, 0.0]); // v2
-            positions.push([x, y + 1.0, 0.0]); // v3
+            let latitude = tile_latitude(y_i32 + block / 2, settings.world_size);
+            let biome = style.season.biome(representative, latitude);
+            // Slope across the block, read along its middle row and column
+            let (first, last, middle) = (block_x, block_x + block - 1, block / 2);
+            let slope = Vec2::new(
+                square_at(last, block_y + middle).elevation
+                    - square_at(first, block_y + middle).elevation,
+                square_at(block_x + middle, block_y + block - 1).elevation
+                    - square_at(block_x + middle, block_y).elevation,
+            ) / (block - 1) as f32;
+            let color = tile_color(
+                representative,
+                biome,
+                style.overlay,
+                &style.biomes,
+                &style.palette,
+                style.sea_level,
+            );
+            let color = shaded(color, biome, &style.hillshade, slope);
+            colors.extend_from_slice(&[color; 4]);
 
-            let color = biome_to_color(square.biome);
-            colors.push(color);
-            colors.push(color);
-            colors.push(color);
-            colors.push(color);
+            let layer = biome_texture_layer(majority) as f32;
+            uvs.extend_from_slice(&[[layer, layer]; 4]);
+            atlas_uvs.extend_from_slice(&[[biome_atlas_layer(biome) as f32, 0.0]; 4]);
 
             indices.extend_from_slice(&[
                 index_offset,
@@ -290,23 +1482,135 @@ fn generate_chunk(chunk_x: i32, chunk_y: i32, world_map: &WorldMap) -> Mesh {
     }
 
     mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, atlas_uvs);
 
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
     mesh.insert_indices(Indices::U32(indices));
 
-    return mesh;
+    mesh
 }
 
-fn wrap(v: i32, max: i32) -> i32 {
+// Open water for the animated water material, drawn over the finest mesh. Seasonal
+// sea ice is left out so it shows through. UV x is 1 on corners shared with land
+// and 0 out at sea, which the shader turns into surf.
+fn water_mesh<'a>(
+    origin: IVec2,
+    settings: &WorldSettings,
+    style: &ChunkStyle,
+    square_at: impl Fn(i32, i32) -> &'a Square,
+) -> Option<Mesh> {
+    let chunk_size = settings.chunk_size;
+    let mut positions = Vec::new();
+    let mut colors = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    let mut index_offset = 0;
+
+    let open_water_at = |x_local: i32, y_local: i32| {
+        let latitude = tile_latitude(origin.y + y_local, settings.world_size);
+        let biome = style.season.biome(square_at(x_local, y_local), latitude);
+        is_water(biome).then_some(biome)
+    };
+
+    for x_local in 0..chunk_size {
+        for y_local in 0..chunk_size {
+            let Some(biome) = open_water_at(x_local, y_local) else {
+                continue;
+            };
+
+            let x = (origin.x + x_local) as f32;
+            let y = (origin.y + y_local) as f32;
+            let square = square_at(x_local, y_local);
+            let color = tile_color(
+                square,
+                biome,
+                MapOverlay::Biome,
+                &style.biomes,
+                &style.palette,
+                style.sea_level,
+            );
+
+            for (dx, dy) in [(0, 0), (1, 0), (1, 1), (0, 1)] {
+                // A corner touches this tile and the three diagonal to it
+                let shore = [(dx - 1, dy - 1), (dx, dy - 1), (dx - 1, dy), (dx, dy)]
+                    .iter()
+                    .any(|&(cx, cy)| open_water_at(x_local + cx, y_local + cy).is_none());
+
+                positions.push([x + dx as f32, y + dy as f32, 0.0]);
+                colors.push(color);
+                uvs.push([if shore { 1.0 } else { 0.0 }, 0.0]);
+            }
+
+            indices.extend_from_slice(&[
+                index_offset,
+                index_offset + 1,
+                index_offset + 2,
+                index_offset + 2,
+                index_offset + 3,
+                index_offset,
+            ]);
+
+            index_offset += 4;
+        }
+    }
+
+    if positions.is_empty() {
+        return None;
+    }
+
+    let mut mesh = Mesh::new(TriangleList, RenderAssetUsages::default());
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_indices(Indices::U32(indices));
+
+    Some(mesh)
+}
+
+pub fn wrap(v: i32, max: i32) -> i32 {
     ((v % max) + max) % max
 }
 
-fn index_toroidal(x: i32, y: i32, size: i32) -> usize {
+pub fn index_toroidal(x: i32, y: i32, settings: &WorldSettings) -> usize {
+    let size = settings.world_size;
     let wx = wrap(x, size);
     let wy = wrap(y, size);
     (wy * size + wx) as usize
 }
 
+// Shortest signed step from `from` to `to` along one axis, going across the
+// world edge when that is shorter. Halfway round resolves to the negative side.
+pub fn wrap_delta(from: i32, to: i32, settings: &WorldSettings) -> i32 {
+    let size = settings.world_size;
+    let delta = wrap(to - from, size);
+    if delta >= size / 2 { delta - size } else { delta }
+}
+
+pub fn toroidal_delta(from: IVec2, to: IVec2, settings: &WorldSettings) -> IVec2 {
+    IVec2::new(
+        wrap_delta(from.x, to.x, settings),
+        wrap_delta(from.y, to.y, settings),
+    )
+}
+
+// Copy of `tile` nearest the camera, for drawing things on the side of the seam in view
+pub fn nearest_copy(camera: IVec2, tile: IVec2, settings: &WorldSettings) -> IVec2 {
+    camera + toroidal_delta(camera, tile, settings)
+}
+
+pub fn toroidal_distance(from: IVec2, to: IVec2, settings: &WorldSettings) -> f32 {
+    toroidal_delta(from, to, settings).as_vec2().length()
+}
+
+// Unit vector pointing along the shortest path, or zero when both tiles coincide
+pub fn toroidal_direction(from: IVec2, to: IVec2, settings: &WorldSettings) -> Vec2 {
+    toroidal_delta(from, to, settings)
+        .as_vec2()
+        .normalize_or_zero()
+}
+
 pub fn setup_biome_display(mut commands: Commands) {
     commands.spawn((
         BiomeDisplayUI,
@@ -326,53 +1630,602 @@ pub fn setup_biome_display(mut commands: Commands) {
 }
 
 pub fn update_biome_display(
-    camera_query: Single<(&Camera, &GlobalTransform)>,
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
     window_query: Single<&Window>,
-    world_query: Single<&WorldMap>,
+    (world_query, region_map, region_query): (
+        Query<&WorldMap>,
+        Option<Res<RegionMap>>,
+        Query<&Region>,
+    ),
+    (season, locale): (Res<Season>, Res<Locale>),
+    settings: Res<WorldSettings>,
     mut ui_query: Single<&mut Text, With<BiomeDisplayUI>>,
 ) {
     let (camera, camera_transform) = *camera_query;
-    let window = *window_query;
-    let world_map = *world_query;
-
-    if let Some(cursor_position) = window.cursor_position() {
-        if let Ok(world_position) = camera.viewport_to_world(camera_transform, cursor_position) {
-            let x = world_position.origin.x as i32;
-            let y = world_position.origin.y as i32;
-
-            // Clamp to world bounds
-            if x >= 0 && x < WORLD_SIZE && y >= 0 && y < WORLD_SIZE {
-                let index = index_toroidal(x, y, WORLD_SIZE);
-                let square = &world_map.squares[index];
-                
-                let biome_name = match square.biome {
-                    Biome::Ocean => "Ocean",
-                    Biome::Coast => "Coast",
-                    Biome::Grassland => "Grassland",
-                    Biome::Forest => "Forest",
-                    Biome::Desert => "Desert",
-                    Biome::Hill => "Hill",
-                    Biome::Mountain => "Mountain",
-                    Biome::Ice => "Ice",
-                    Biome::Alpine => "Alpine",
-                    Biome::Snow => "Snow",
-                    Biome::Tundra => "Tundra",
-                    Biome::BorealForest => "Boreal Forest",
-                    Biome::Taiga => "Taiga",
-                    Biome::ColdDesert => "Cold Desert",
-                    Biome::TemperateForest => "Temperate Forest",
-                    Biome::TemperateRainforest => "Temperate Rainforest",
-                    Biome::HotDesert => "Hot Desert",
-                    Biome::Savanna => "Savanna",
-                    Biome::SubtropicalForest => "Subtropical Forest",
-                    Biome::TropicalRainforest => "Tropical Rainforest",
-                };
-
-                ui_query.0 = format!(
-                    "Biome: {}\nElevation: {:.1}\nTemperature: {:.1}°C\nMoisture: {:.1}",
-                    biome_name, square.elevation, square.temperature, square.moisture
-                );
+    let Some(tile) = cursor_tile(camera, camera_transform, &window_query) else {
+        return;
+    };
+    let (x, y) = (tile.x, tile.y);
+
+    let region_names = region_names(region_map.as_deref(), &region_query, &locale, x, y);
+    let mut lines = coordinate_lines(tile, &settings, &locale).to_vec();
+    if !region_names.is_empty() {
+        lines.push(locale.format("hud.region", &[&region_names.join(", ")]));
+    }
+
+    // Per-tile details need the whole map in memory, so streamed worlds
+    // only show the region
+    if let Ok(world_map) = world_query.single() {
+        let index = index_toroidal(x, y, &settings);
+        let square = &world_map.square(index);
+        let latitude = tile_latitude(y, settings.world_size);
+
+        lines.extend(terrain_lines(square, latitude, &season, &locale));
+    }
+
+    ui_query.0 = lines.join("\n");
+}
+
+// Tile position in the base world, and as a latitude and longitude with the
+// equator across the middle of the map and the prime meridian down its centre
+fn coordinate_lines(tile: IVec2, settings: &WorldSettings, locale: &Locale) -> [String; 2] {
+    let size = settings.world_size;
+    let (x, y) = (wrap(tile.x, size), wrap(tile.y, size));
+    let latitude = tile_latitude(y, size) * 90.0;
+    let longitude = x as f32 / size as f32 * 360.0 - 180.0;
+    let north_south = if latitude >= 0.0 { "compass.north" } else { "compass.south" };
+    let east_west = if longitude >= 0.0 { "compass.east" } else { "compass.west" };
+
+    [
+        locale.format("hud.tile", &[&x, &y]),
+        locale.format(
+            "hud.lat_lon",
+            &[
+                &format!("{:.1}°{}", latitude.abs(), locale.text(north_south)),
+                &format!("{:.1}°{}", longitude.abs(), locale.text(east_west)),
+            ],
+        ),
+    ]
+}
+
+// Biome and climate of a tile this season, shared by the hover readout and the
+// tile inspector
+fn terrain_lines(square: &Square, latitude: f32, season: &Season, locale: &Locale) -> [String; 5] {
+    let temperature = format!("{:.1}", season.temperature(square, latitude));
+    [
+        locale.format("hud.biome", &[&locale.biome(season.biome(square, latitude))]),
+        locale.format("hud.elevation", &[&format!("{:.1}", square.elevation)]),
+        locale.format("hud.temperature", &[&temperature, &locale.season(season.name(latitude))]),
+        locale.format("hud.moisture", &[&format!("{:.1}", square.moisture)]),
+        locale.format("hud.vegetation", &[&format!("{:.1}", square.vegetation)]),
+    ]
+}
+
+pub fn setup_tile_inspector(mut commands: Commands) {
+    commands.spawn((
+        TileInspectorUI,
+        // Blocks clicks so the panel can't select the tile underneath it
+        Interaction::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        Visibility::Hidden,
+        children![
+            (
+                TileInspectorText,
+                Text::new(""),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ),
+            (
+                Button,
+                TileInspectorClose,
+                Node {
+                    padding: UiRect::all(Val::Px(6.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                children![(
+                    Text::new("Close"),
+                    Localized::new("inspector.close"),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                )],
+            ),
+        ],
+    ));
+}
+
+// What the inspector lists for the kingdom holding a tile
+type InspectedKingdom = (
+    &'static Kingdom,
+    &'static KingdomSettlements,
+    &'static Treasury,
+    &'static Technology,
+    &'static Fame,
+);
+
+// What the inspector lists for a settlement
+type InspectedSettlement = (
+    &'static Settlement,
+    &'static Population,
+    &'static FoodCapacity,
+    &'static SettlementTier,
+    &'static Stockpile,
+    &'static ProductionRates,
+    Option<&'static Farms>,
+    Option<&'static ProductionModifier>,
+    Option<&'static FishingGrounds>,
+    &'static Specialization,
+);
+
+type InspectorPanel<'w, 's> = (
+    Single<'w, 's, &'static mut Visibility, With<TileInspectorUI>>,
+    Single<'w, 's, &'static mut Text, With<TileInspectorText>>,
+);
+
+// Fills the panel for the selected tile. Whole-map worlds know every tile;
+// streamed worlds know the chunks currently cached around the camera.
+pub fn update_tile_inspector(
+    (selected, settings, loaded): (Res<SelectedTile>, Res<WorldSettings>, Res<LoadedChunks>),
+    (season, locale): (Res<Season>, Res<Locale>),
+    (world_query, region_map, region_query): (
+        Query<&WorldMap>,
+        Option<Res<RegionMap>>,
+        Query<&Region>,
+    ),
+    (territory, wildlife): (Option<Res<TerritoryMap>>, Option<Res<WildlifeMap>>),
+    (kingdom_query, settlement_query): (
+        Query<InspectedKingdom>,
+        Query<InspectedSettlement>,
+    ),
+    (farm_query, disaster_query, lair_query): (Query<&Farm>, Query<&Disaster>, Query<&Lair>),
+    (mut panel_query, mut text_query): InspectorPanel,
+) {
+    let Some(tile) = selected.0 else {
+        panel_query.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    panel_query.set_if_neq(Visibility::Inherited);
+
+    let mut lines = vec![locale.format("hud.tile", &[&tile.x, &tile.y])];
+    let region_names = region_names(
+        region_map.as_deref(),
+        &region_query,
+        &locale,
+        tile.x,
+        tile.y,
+    );
+    if !region_names.is_empty() {
+        lines.push(locale.format("hud.region", &[&region_names.join(", ")]));
+    }
+    let owner = territory
+        .and_then(|territory| territory.owner_at(tile.x, tile.y))
+        .and_then(|kingdom| kingdom_query.get(kingdom).ok());
+    if let Some((kingdom, settlements, treasury, technology, fame)) = owner {
+        let era = technology.era;
+        lines.push(locale.format(
+            "inspector.owner",
+            &[
+                &kingdom.name,
+                &settlements.len(),
+                &format!("{:.0}", treasury.0),
+                &locale.name("era", era, era.name()),
+            ],
+        ));
+        if fame.0 > 0 {
+            lines.push(locale.format("inspector.fame", &[&fame.0]));
+        }
+    }
+
+    // Markers cover a few tiles, so a click near one picks the settlement
+    let settlement = settlement_query.iter().find(|(settlement, ..)| {
+        toroidal_distance(tile, settlement.tile, &settings) <= SETTLEMENT_PICK_RADIUS
+    });
+    if let Some((
+        settlement,
+        population,
+        capacity,
+        tier,
+        stockpile,
+        production,
+        farms,
+        modifier,
+        fishing,
+        specialization,
+    )) = settlement
+    {
+        let tier = locale.name("tier", *tier, tier.name());
+        lines.push(format!("{}: {}", tier, settlement.name));
+        lines.push(locale.format(
+            "inspector.population",
+            &[&format!("{:.0}", population.0), &format!("{:.0}", capacity.0)],
+        ));
+        lines.push(locale.format(
+            "inspector.specialization",
+            &[&locale.name("specialization", specialization, specialization.name())],
+        ));
+        if let Some(farms) = farms {
+            lines.push(locale.format("inspector.farms", &[&farms.len()]));
+        }
+
+        // Goods the settlement neither makes nor holds are left out. Grain and
+        // fish are broken down by where they come from.
+        let labour = labour_share(population.0, capacity.0);
+        let farmed: f32 = farms
+            .iter()
+            .flat_map(|farms| farms.iter())
+            .filter_map(|farm| farm_query.get(farm).ok())
+            .map(|farm| farm.yield_per_year)
+            .sum();
+        for good in Good::ALL {
+            let share = modifier.map(|modifier| modifier.0[good as usize]).unwrap_or(1.0);
+            let output = production.get(good) * labour * share;
+            if output <= 0.0 && stockpile.get(good) <= 0.0 {
+                continue;
+            }
+            let sources = match (good, fishing) {
+                (Good::Grain, _) if farmed > 0.0 => locale.format(
+                    "inspector.grain_sources",
+                    &[
+                        &format!("{:.0}", (production.get(good) - farmed) * labour * share),
+                        &format!("{:.0}", farmed * labour * share),
+                    ],
+                ),
+                (Good::Fish, Some(fishing)) if fishing.water_tiles > 0 => locale.format(
+                    "inspector.fish_sources",
+                    &[
+                        &fishing.water_tiles,
+                        &format!("{:.0}", fishing.shoals * 100.0),
+                    ],
+                ),
+                _ => String::new(),
+            };
+            lines.push(locale.format(
+                "inspector.good",
+                &[
+                    &locale.name("good", good, good.name()),
+                    &format!("{:.0}", stockpile.get(good)),
+                    &format!("{:.0}", output),
+                    &sources,
+                ],
+            ));
+        }
+    }
+
+    if let Some(farm) = farm_query.iter().find(|farm| farm.tile == tile) {
+        let yield_per_year = format!("{:.0}", farm.yield_per_year);
+        lines.push(locale.format("inspector.farm", &[&yield_per_year]));
+    }
+    if let Some((species, stock, capacity)) = wildlife.and_then(|wildlife| wildlife.at(tile)) {
+        lines.push(locale.format(
+            "inspector.wildlife",
+            &[
+                &locale.name("species", species, species.name()),
+                &format!("{:.0}", stock),
+                &format!("{:.0}", capacity),
+            ],
+        ));
+    }
+    for disaster in &disaster_query {
+        if toroidal_distance(tile, disaster.tile, &settings) <= disaster.kind.radius() {
+            let kind = disaster.kind;
+            lines.push(locale.format(
+                "inspector.disaster",
+                &[
+                    &locale.name("disaster", kind, kind.name()),
+                    &(disaster.remaining / YEAR_LENGTH_SECS * 12.0).ceil(),
+                ],
+            ));
+        }
+    }
+
+    if let Some(lair) = lair_query
+        .iter()
+        .find(|lair| toroidal_distance(tile, lair.tile, &settings) <= 1.0)
+    {
+        let kind = lair.kind;
+        lines.push(locale.format("inspector.lair", &[&locale.name("lair", kind, kind.name())]));
+    }
+
+    match known_square(tile, &settings, &loaded, world_query.single().ok()) {
+        Some(square) => {
+            let latitude = tile_latitude(tile.y, settings.world_size);
+            lines.extend(terrain_lines(&square, latitude, &season, &locale));
+            let resources = square.resources;
+            let resources = locale.name("resource", resources, resources.name());
+            lines.push(locale.format("inspector.resources", &[&resources]));
+        }
+        None => lines.push(locale.text("inspector.out_of_view").to_string()),
+    }
+
+    let text = lines.join("\n");
+    if text_query.0 != text {
+        text_query.0 = text;
+    }
+}
+
+pub fn close_tile_inspector(
+    mut selected: ResMut<SelectedTile>,
+    button_query: Query<&Interaction, (With<TileInspectorClose>, Changed<Interaction>)>,
+) {
+    if button_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        selected.0 = None;
+    }
+}
+
+pub fn setup_tile_highlight(mut commands: Commands) {
+    commands.spawn((
+        TileHighlight::Hover,
+        Sprite::from_color(Color::srgba(1.0, 1.0, 1.0, 0.3), Vec2::ONE),
+        Transform::from_xyz(0.0, 0.0, HIGHLIGHT_Z),
+        Visibility::Hidden,
+    ));
+    commands.spawn((
+        TileHighlight::Selected,
+        Sprite::from_color(Color::srgba(1.0, 0.85, 0.2, 0.5), Vec2::ONE),
+        Transform::from_xyz(0.0, 0.0, HIGHLIGHT_Z),
+        Visibility::Hidden,
+    ));
+}
+
+// Left click selects the tile under the cursor, right click clears the selection
+pub fn select_tile(
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    window_query: Single<&Window>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    pause: Res<Pause>,
+    settings: Res<WorldSettings>,
+    mut selected: ResMut<SelectedTile>,
+    inspector_query: Query<&Interaction, With<TileInspectorUI>>,
+) {
+    // Clicks on the pause menu and the inspector are meant for their buttons
+    let over_inspector = inspector_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    if pause.menu_open || over_inspector {
+        return;
+    }
+
+    if mouse.just_pressed(MouseButton::Right) {
+        selected.0 = None;
+    }
+
+    let (camera, camera_transform) = *camera_query;
+    if mouse.just_pressed(MouseButton::Left)
+        && let Some(tile) = cursor_tile(camera, camera_transform, &window_query)
+    {
+        let size = settings.world_size;
+        selected.0 = Some(IVec2::new(wrap(tile.x, size), wrap(tile.y, size)));
+    }
+}
+
+pub fn update_tile_highlight(
+    camera_query: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    window_query: Single<&Window>,
+    settings: Res<WorldSettings>,
+    selected: Res<SelectedTile>,
+    mut highlight_query: Query<(&TileHighlight, &mut Transform, &mut Visibility)>,
+) {
+    let (camera, camera_transform) = *camera_query;
+    let hovered = cursor_tile(camera, camera_transform, &window_query);
+    let camera_tile = camera_transform.translation().truncate().floor().as_ivec2();
+
+    for (highlight, mut transform, mut visibility) in &mut highlight_query {
+        let tile = match highlight {
+            TileHighlight::Hover => hovered,
+            // The selection is stored wrapped; draw the copy nearest the camera
+            TileHighlight::Selected => selected
+                .0
+                .map(|tile| nearest_copy(camera_tile, tile, &settings)),
+        };
+
+        match tile {
+            Some(tile) => {
+                transform.translation = (tile.as_vec2() + Vec2::splat(0.5)).extend(HIGHLIGHT_Z);
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+}
+
+// Names of the regions holding the tile, each with its trade specialties
+fn region_names(
+    region_map: Option<&RegionMap>,
+    region_query: &Query<&Region>,
+    locale: &Locale,
+    x: i32,
+    y: i32,
+) -> Vec<String> {
+    let Some(region_map) = region_map else {
+        return Vec::new();
+    };
+
+    region_map
+        .regions_at(x, y)
+        .iter()
+        .flatten()
+        .filter_map(|&entity| region_query.get(entity).ok())
+        .map(|region| match region.specialties.is_empty() {
+            true => region.name.clone(),
+            false => {
+                let goods: Vec<&str> = region
+                    .specialties
+                    .iter()
+                    .map(|good| locale.name("good", good, good.name()))
+                    .collect();
+                format!("{} ({})", region.name, goods.join(", "))
+            }
+        })
+        .collect()
+}
+
+// The tile's square from the whole map, or on a streamed world its edit or, if its
+// chunk is loaded, the chunk cache's
+pub fn known_square(
+    tile: IVec2,
+    settings: &WorldSettings,
+    loaded: &LoadedChunks,
+    world_map: Option<&WorldMap>,
+) -> Option<Square> {
+    if let Some(world_map) = world_map {
+        return Some(world_map.square(index_toroidal(tile.x, tile.y, settings)));
+    }
+    let size = settings.world_size;
+    if let Some(square) = loaded.edits.get(&tile.rem_euclid(IVec2::splat(size))) {
+        return Some(square.clone());
+    }
+
+    // The cache may hold the chunk under a shifted key after the camera wrapped,
+    // so try every copy of the tile next to the base world
+    let chunk_size = settings.chunk_size;
+    let halo_side = chunk_size + 2 * HALO;
+    [-size, 0, size]
+        .iter()
+        .flat_map(|&dx| [-size, 0, size].map(|dy| IVec2::new(tile.x + dx, tile.y + dy)))
+        .find_map(|copy| {
+            let chunk = (copy.x.div_euclid(chunk_size), copy.y.div_euclid(chunk_size));
+            let data = loaded.chunk_data.get(&chunk)?;
+            let local_x = copy.x.rem_euclid(chunk_size) + HALO;
+            let local_y = copy.y.rem_euclid(chunk_size) + HALO;
+            data.get((local_y * halo_side + local_x) as usize).cloned()
+        })
+}
+
+// Tile under the cursor, unwrapped like the camera; None when the cursor is
+// outside the window
+pub fn cursor_tile(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    window: &Window,
+) -> Option<IVec2> {
+    let cursor_position = window.cursor_position()?;
+    let world_position = camera.viewport_to_world(camera_transform, cursor_position).ok()?;
+
+    Some(world_position.origin.truncate().floor().as_ivec2())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: i32 = 16;
+
+    fn settings() -> WorldSettings {
+        WorldSettings {
+            world_size: SIZE,
+            chunk_size: 4,
+            ..default()
+        }
+    }
+
+    // Every pair of tiles along one axis, including ones given outside the world
+    fn pairs() -> impl Iterator<Item = (i32, i32)> {
+        (-SIZE..2 * SIZE).flat_map(|from| (-SIZE..2 * SIZE).map(move |to| (from, to)))
+    }
+
+    #[test]
+    fn wrap_delta_takes_the_short_way_round() {
+        let settings = settings();
+        for (from, to) in pairs() {
+            let delta = wrap_delta(from, to, &settings);
+            assert!((-SIZE / 2..SIZE / 2).contains(&delta), "{from} -> {to}: {delta}");
+            assert_eq!(wrap(from + delta, SIZE), wrap(to, SIZE), "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn nearest_copy_is_the_same_tile_within_half_a_world() {
+        let settings = settings();
+        for (from, to) in pairs() {
+            let camera = IVec2::new(from, to);
+            let tile = IVec2::new(to, from);
+            let nearest = nearest_copy(camera, tile, &settings);
+            assert_eq!(nearest.x.rem_euclid(SIZE), tile.x.rem_euclid(SIZE), "{camera} {tile}");
+            assert_eq!(nearest.y.rem_euclid(SIZE), tile.y.rem_euclid(SIZE), "{camera} {tile}");
+            assert!((nearest - camera).abs().max_element() <= SIZE / 2, "{camera} {tile}");
+        }
+    }
+
+    #[test]
+    fn wrap_delta_is_antisymmetric_short_of_halfway() {
+        let settings = settings();
+        for (from, to) in pairs() {
+            let there = wrap_delta(from, to, &settings);
+            let back = wrap_delta(to, from, &settings);
+            if there.abs() == SIZE / 2 {
+                assert_eq!(there, back, "{from} <-> {to}");
+            } else {
+                assert_eq!(there, -back, "{from} <-> {to}");
+            }
+        }
+    }
+
+    #[test]
+    fn toroidal_distance_is_symmetric() {
+        let settings = settings();
+        for (x, y) in pairs() {
+            let (from, to) = (IVec2::new(x, y), IVec2::new(y, 3 - x));
+            assert_eq!(
+                toroidal_distance(from, to, &settings),
+                toroidal_distance(to, from, &settings),
+                "{from} <-> {to}"
+            );
+        }
+    }
+
+    #[test]
+    fn toroidal_distance_ignores_whole_worlds() {
+        let settings = settings();
+        let worlds = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y, IVec2::ONE];
+        let worlds = worlds.map(|world| world * SIZE);
+        for (x, y) in pairs() {
+            let (from, to) = (IVec2::new(x, y), IVec2::new(5 - y, x + 7));
+            let distance = toroidal_distance(from, to, &settings);
+            let longest = (SIZE / 2) as f32 * 2f32.sqrt();
+            assert!(distance <= longest, "{from} -> {to}: {distance}");
+            for world in worlds {
+                assert_eq!(toroidal_distance(from + world, to, &settings), distance);
+                assert_eq!(toroidal_distance(from, to + world, &settings), distance);
+                let delta = toroidal_delta(from, to, &settings);
+                assert_eq!(toroidal_delta(from, to + world, &settings), delta);
+            }
+        }
+    }
+
+    #[test]
+    fn toroidal_direction_points_the_short_way_round() {
+        let settings = settings();
+        for (x, y) in pairs() {
+            let (from, to) = (IVec2::new(x, y), IVec2::new(2 * y, x - 1));
+            let direction = toroidal_direction(from, to, &settings);
+            let delta = toroidal_delta(from, to, &settings);
+            if delta == IVec2::ZERO {
+                assert_eq!(direction, Vec2::ZERO, "{from} -> {to}");
+                continue;
             }
+            assert!((direction.length() - 1.0).abs() < 1e-5, "{from} -> {to}");
+            let along = direction * toroidal_distance(from, to, &settings);
+            assert!(along.distance(delta.as_vec2()) < 1e-4, "{from} -> {to}");
+            let world = IVec2::new(SIZE, -SIZE);
+            assert_eq!(toroidal_direction(from + world, to, &settings), direction);
         }
     }
 }
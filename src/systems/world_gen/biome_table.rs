@@ -0,0 +1,92 @@
+use std::{io, sync::Arc};
+
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, io::Reader};
+use bevy::prelude::*;
+
+pub use kingdom_worldgen::biome_table::BiomeTable;
+
+use crate::components::world_gen::WorldData;
+use crate::systems::world::{LoadedChunks, clear_loaded_chunks};
+
+// Under the asset folder, which the asset server watches for edits
+const BIOME_TABLE_PATH: &str = "biomes.ron";
+
+// The biome table as the asset server loads it from assets/biomes.ron
+#[derive(Asset, TypePath)]
+pub struct BiomeTableAsset(pub Arc<BiomeTable>);
+
+#[derive(Default, TypePath)]
+pub struct BiomeTableLoader;
+
+impl AssetLoader for BiomeTableLoader {
+    type Asset = BiomeTableAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<BiomeTableAsset, io::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        let table = BiomeTable::parse(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(BiomeTableAsset(Arc::new(table)))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+// The biome table new worlds are generated with. It starts as the built-in table
+// and takes the file's once that has loaded.
+#[derive(Resource)]
+pub struct Biomes {
+    pub table: Arc<BiomeTable>,
+    pub handle: Handle<BiomeTableAsset>,
+}
+
+pub fn load_biomes(asset_server: &AssetServer) -> Biomes {
+    Biomes {
+        table: Arc::new(BiomeTable::default()),
+        handle: asset_server.load(BIOME_TABLE_PATH),
+    }
+}
+
+// Swaps in the table whenever its file is loaded or saved. The current world takes
+// the new colours at once; its tiles keep their biomes until the world is
+// regenerated. A file that doesn't parse leaves the old table in place, as the
+// asset server keeps the last good one.
+pub fn reload_biome_table(
+    mut events: MessageReader<AssetEvent<BiomeTableAsset>>,
+    assets: Res<Assets<BiomeTableAsset>>,
+    mut biomes: ResMut<Biomes>,
+    mut world_data_query: Query<&mut WorldData>,
+    loaded: Option<ResMut<LoadedChunks>>,
+) {
+    let changed = events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == biomes.handle.id()
+        }
+        _ => false,
+    });
+    let Some(asset) = assets.get(&biomes.handle).filter(|_| changed) else {
+        return;
+    };
+    if Arc::ptr_eq(&asset.0, &biomes.table) {
+        return;
+    }
+
+    info!("Loaded {}", BIOME_TABLE_PATH);
+    biomes.table = asset.0.clone();
+    for mut world_data in &mut world_data_query {
+        world_data.biomes = biomes.table.clone();
+    }
+    if let Some(mut loaded) = loaded {
+        clear_loaded_chunks(&mut loaded);
+    }
+}
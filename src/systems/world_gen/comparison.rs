@@ -0,0 +1,30 @@
+use kingdom_worldgen::{
+    generate_logical_world,
+    survey::{WorldSurvey, survey_world},
+};
+
+use crate::components::world_gen::WorldData;
+
+// Side of each candidate world. Noise is laid out over the whole torus, so a small
+// world has the same continents as a full-size one of the seed, at lower detail.
+const SURVEY_SIZE: i32 = 256;
+
+// Generates one small world per seed with otherwise identical parameters, every
+// pass included, and ranks them best first
+pub fn compare_worlds(template: &WorldData, seeds: &[u32]) -> Vec<WorldSurvey> {
+    let mut surveys: Vec<WorldSurvey> = seeds
+        .iter()
+        .filter_map(|&seed| {
+            let world_data = WorldData {
+                seed,
+                seed_text: None,
+                ..template.clone()
+            };
+            let world_map = generate_logical_world(&world_data, SURVEY_SIZE, &(), None)?;
+            Some(survey_world(&world_map, seed))
+        })
+        .collect();
+
+    surveys.sort_by(|a, b| b.score.total_cmp(&a.score));
+    surveys
+}
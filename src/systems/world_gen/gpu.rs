@@ -0,0 +1,527 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderStartup, RenderSystems,
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        gpu_readback::{Readback, ReadbackComplete},
+        render_asset::RenderAssets,
+        render_graph::{self, RenderGraph, RenderLabel},
+        render_resource::{
+            binding_types::{storage_buffer, storage_buffer_read_only, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+        storage::{GpuShaderStorageBuffer, ShaderStorageBuffer},
+    },
+};
+use kingdom_worldgen::{MAX_ELEVATION, NoiseFields};
+use noise::permutationtable::{NoiseHasher, PermutationTable};
+
+use crate::components::world_gen::{GenerationMode, NoiseKind, WorldData};
+use crate::systems::world::WorldSettings;
+
+use super::{
+    progress::{WorldGenProgress, WorldGenStage},
+    spawn_world_generation,
+};
+
+const SHADER_ASSET_PATH: &str = "shaders/world_gen.wgsl";
+const WORKGROUP_SIZE: u32 = 64;
+// Tiles per dispatch. Keeps a band's buffer far inside the default storage binding
+// limit and its workgroup count under the per-dimension cap.
+const BAND_TILES: i32 = 1 << 21;
+const FIELDS_PER_TILE: usize = 3;
+// A band that hasn't come back by then means the GPU can't run the shader, and
+// the world is handed to the CPU instead
+const BAND_TIMEOUT_SECS: f32 = 10.0;
+
+// The shader covers OpenSimplex layers over noise elevation and nothing else
+pub fn gpu_supported(world_data: &WorldData) -> bool {
+    let layers = [
+        world_data.terrain_noise,
+        world_data.continental_noise,
+        world_data.temperature_noise,
+        world_data.moisture_noise,
+    ];
+
+    world_data.generation_mode == GenerationMode::Noise
+        && world_data.heightmap.is_none()
+        && layers.iter().all(|kind| *kind == NoiseKind::OpenSimplex)
+}
+
+#[derive(ShaderType, Clone, Copy)]
+struct BandParams {
+    world_size: u32,
+    first_row: u32,
+    rows: u32,
+    band: u32,
+    octaves: u32,
+    scaling_factor: f32,
+    terrain_scale: f32,
+    continental_scale: f32,
+    temperature_scale: f32,
+    moisture_scale: f32,
+    warp_strength: f32,
+    max_elevation: f32,
+}
+
+#[derive(Clone)]
+struct Band {
+    params: BandParams,
+    permutations: Handle<ShaderStorageBuffer>,
+    fields: Handle<ShaderStorageBuffer>,
+}
+
+// The rows the compute pass is filling, if any. Always present so the render
+// world sees the band end rather than keeping the last one it was sent.
+#[derive(Resource, ExtractResource, Clone, Default)]
+pub struct GpuWorldGenBand(Option<Band>);
+
+// A pregenerated world whose noise fields are coming back from the GPU a band at
+// a time, before the rest of generation runs on the CPU as usual
+#[derive(Resource)]
+pub struct GpuWorldGenJob {
+    world_data: WorldData,
+    settings: WorldSettings,
+    threads: usize,
+    fields: NoiseFields,
+    readback: Option<Entity>,
+    waited: f32,
+}
+
+impl GpuWorldGenJob {
+    pub fn new(world_data: WorldData, settings: WorldSettings, threads: usize) -> Self {
+        GpuWorldGenJob {
+            world_data,
+            settings,
+            threads,
+            fields: NoiseFields::default(),
+            readback: None,
+            waited: 0.0,
+        }
+    }
+
+    fn rows_done(&self) -> i32 {
+        (self.fields.tile_count() / self.settings.world_size as usize) as i32
+    }
+}
+
+#[derive(Component)]
+pub struct GpuWorldGenReadback;
+
+pub fn register_gpu_world_generation(app: &mut App) {
+    app.init_resource::<GpuWorldGenBand>()
+        .add_plugins(ExtractResourcePlugin::<GpuWorldGenBand>::default());
+
+    let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+        return;
+    };
+    render_app
+        .init_resource::<WorldGenBindGroup>()
+        .add_systems(
+            RenderStartup,
+            (init_world_gen_pipeline, add_world_gen_node),
+        )
+        .add_systems(
+            Render,
+            prepare_world_gen_bind_group.in_set(RenderSystems::PrepareBindGroups),
+        );
+}
+
+// The shader hashes lattice points through the same tables as the CPU noise, one
+// per generator, so a seed gives the same terrain on either path
+fn permutation_tables(seed: u32) -> Vec<u32> {
+    [0, 1, 2, 3, 11]
+        .into_iter()
+        .flat_map(|offset| {
+            let table = PermutationTable::new(seed.wrapping_add(offset));
+            (0..256).map(move |index| table.hash(&[index]) as u32)
+        })
+        .collect()
+}
+
+fn first_band(world_data: &WorldData, settings: &WorldSettings) -> BandParams {
+    BandParams {
+        world_size: settings.world_size as u32,
+        first_row: 0,
+        rows: (BAND_TILES / settings.world_size).clamp(1, settings.world_size) as u32,
+        // Band 0 would match a buffer the shader never wrote
+        band: 1,
+        octaves: world_data.num_of_octaves,
+        scaling_factor: world_data.scaling_factor as f32,
+        terrain_scale: world_data.terrain_scale as f32,
+        continental_scale: world_data.continental_scale as f32,
+        temperature_scale: world_data.temperature_scale as f32,
+        moisture_scale: world_data.moisture_scale as f32,
+        warp_strength: world_data.warp_strength as f32,
+        max_elevation: MAX_ELEVATION as f32,
+    }
+}
+
+pub fn drive_gpu_world_generation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut job: ResMut<GpuWorldGenJob>,
+    mut band: ResMut<GpuWorldGenBand>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    progress: Res<WorldGenProgress>,
+) {
+    let world_size = job.settings.world_size;
+
+    if job.readback.is_none() {
+        let params = first_band(&job.world_data, &job.settings);
+        let permutations = buffers.add(ShaderStorageBuffer::from(permutation_tables(
+            job.world_data.seed,
+        )));
+
+        // One buffer serves every band; the last one just leaves its tail unused
+        let band_floats = 1 + (params.rows * params.world_size) as usize * FIELDS_PER_TILE;
+        let mut fields = ShaderStorageBuffer::with_size(
+            band_floats * size_of::<f32>(),
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        fields.buffer_description.usage |= BufferUsages::COPY_SRC;
+        let fields = buffers.add(fields);
+
+        band.0 = Some(Band {
+            params,
+            permutations,
+            fields: fields.clone(),
+        });
+        job.readback = Some(
+            commands
+                .spawn((GpuWorldGenReadback, Readback::buffer(fields)))
+                .observe(receive_gpu_band)
+                .id(),
+        );
+        return;
+    }
+
+    let rows_done = job.rows_done();
+    // Erosion takes the last fifth of the elevation stage, as on the CPU
+    progress.report(
+        WorldGenStage::Elevation,
+        rows_done as f32 / world_size as f32 * 0.8,
+    );
+
+    let fields = if rows_done >= world_size {
+        Some(std::mem::take(&mut job.fields))
+    } else {
+        job.waited += time.delta_secs();
+        if job.waited < BAND_TIMEOUT_SECS {
+            return;
+        }
+        warn!("GPU world generation stalled; generating on the CPU instead");
+        None
+    };
+
+    band.0 = None;
+    if let Some(readback) = job.readback {
+        commands.entity(readback).despawn();
+    }
+    spawn_world_generation(
+        &mut commands,
+        job.world_data.clone(),
+        job.settings,
+        job.threads,
+        &progress,
+        fields,
+    );
+    commands.remove_resource::<GpuWorldGenJob>();
+}
+
+// Called with the buffer's contents every frame until the job ends. Copies that
+// were taken before the current band was dispatched carry an older band number.
+fn receive_gpu_band(
+    event: On<ReadbackComplete>,
+    mut band: ResMut<GpuWorldGenBand>,
+    job: Option<ResMut<GpuWorldGenJob>>,
+) {
+    let (Some(mut job), Some(current)) = (job, band.0.as_mut()) else {
+        return;
+    };
+    let values: Vec<f32> = event.to_shader_type();
+    let params = &mut current.params;
+    if values.first() != Some(&(params.band as f32)) {
+        return;
+    }
+
+    let tiles = (params.rows * params.world_size) as usize;
+    for tile in values[1..].chunks_exact(FIELDS_PER_TILE).take(tiles) {
+        job.fields.push(tile[0], tile[1], tile[2]);
+    }
+    job.waited = 0.0;
+
+    let next_row = params.first_row + params.rows;
+    if next_row >= params.world_size {
+        band.0 = None;
+    } else {
+        params.band += 1;
+        params.rows = params.rows.min(params.world_size - next_row);
+        params.first_row = next_row;
+    }
+}
+
+// Also runs on cancel, which leaves the job mid-band
+pub fn cleanup_gpu_world_generation(
+    mut commands: Commands,
+    mut band: ResMut<GpuWorldGenBand>,
+    query: Query<Entity, With<GpuWorldGenReadback>>,
+) {
+    band.0 = None;
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<GpuWorldGenJob>();
+}
+
+#[derive(Resource)]
+struct WorldGenPipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+// Rebuilt each frame a band is live, since the band's parameters change with it
+#[derive(Resource, Default)]
+struct WorldGenBindGroup(Option<(BindGroup, u32)>);
+
+fn world_gen_layout(render_device: &RenderDevice) -> BindGroupLayout {
+    render_device.create_bind_group_layout(
+        "world_gen_layout",
+        &BindGroupLayoutEntries::sequential(
+            ShaderStages::COMPUTE,
+            (
+                uniform_buffer::<BandParams>(false),
+                storage_buffer_read_only::<Vec<u32>>(false),
+                storage_buffer::<Vec<f32>>(false),
+            ),
+        ),
+    )
+}
+
+fn init_world_gen_pipeline(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    asset_server: Res<AssetServer>,
+    pipeline_cache: Res<PipelineCache>,
+) {
+    let layout = world_gen_layout(&render_device);
+    let pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+        label: Some("world_gen_pipeline".into()),
+        layout: vec![layout.clone()],
+        shader: asset_server.load(SHADER_ASSET_PATH),
+        ..default()
+    });
+
+    commands.insert_resource(WorldGenPipeline { layout, pipeline });
+}
+
+fn prepare_world_gen_bind_group(
+    band: Res<GpuWorldGenBand>,
+    pipeline: Res<WorldGenPipeline>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    buffers: Res<RenderAssets<GpuShaderStorageBuffer>>,
+    mut bind_group: ResMut<WorldGenBindGroup>,
+) {
+    bind_group.0 = None;
+    let Some(band) = &band.0 else {
+        return;
+    };
+    // Freshly added buffers reach the GPU a frame or so later
+    let (Some(permutations), Some(fields)) =
+        (buffers.get(&band.permutations), buffers.get(&band.fields))
+    else {
+        return;
+    };
+
+    let mut params = UniformBuffer::from(band.params);
+    params.write_buffer(&render_device, &render_queue);
+    let Some(params) = params.binding() else {
+        return;
+    };
+
+    let tiles = band.params.rows * band.params.world_size;
+    bind_group.0 = Some((
+        render_device.create_bind_group(
+            "world_gen_bind_group",
+            &pipeline.layout,
+            &BindGroupEntries::sequential((
+                params,
+                permutations.buffer.as_entire_buffer_binding(),
+                fields.buffer.as_entire_buffer_binding(),
+            )),
+        ),
+        tiles.div_ceil(WORKGROUP_SIZE),
+    ));
+}
+
+fn add_world_gen_node(mut render_graph: ResMut<RenderGraph>) {
+    render_graph.add_node(WorldGenNodeLabel, WorldGenNode);
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct WorldGenNodeLabel;
+
+// Dispatches the current band every frame until its readback arrives; the few
+// repeats cost less than tracking which frames already ran it
+struct WorldGenNode;
+
+impl render_graph::Node for WorldGenNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let Some((bind_group, workgroups)) = &world.resource::<WorldGenBindGroup>().0 else {
+            return Ok(());
+        };
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let pipeline = world.resource::<WorldGenPipeline>();
+        let Some(compute) = pipeline_cache.get_compute_pipeline(pipeline.pipeline) else {
+            return Ok(());
+        };
+
+        let mut pass = render_context
+            .command_encoder()
+            .begin_compute_pass(&ComputePassDescriptor {
+                label: Some("world_gen_pass"),
+                ..default()
+            });
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.set_pipeline(compute);
+        pass.dispatch_workgroups(*workgroups, 1, 1);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::render::{
+        renderer::initialize_renderer,
+        settings::{RenderResources, WgpuSettings},
+    };
+    use bevy::tasks::block_on;
+    use kingdom_worldgen::sampler::WorldSampler;
+
+    use super::*;
+
+    const SIZE: i32 = 64;
+
+    // Runs the shader once over a whole small world and reads its fields back
+    fn gpu_fields(world_data: &WorldData, settings: &WorldSettings) -> Vec<f32> {
+        let wgpu_settings = WgpuSettings::default();
+        let RenderResources(device, queue, ..) = block_on(initialize_renderer(
+            wgpu_settings.backends.unwrap_or_default(),
+            None,
+            &wgpu_settings,
+        ));
+
+        let module = device.create_and_validate_shader_module(ShaderModuleDescriptor {
+            label: Some("world_gen_test"),
+            source: ShaderSource::Wgsl(
+                include_str!("../../../assets/shaders/world_gen.wgsl").into(),
+            ),
+        });
+        let layout = world_gen_layout(&device);
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&RawComputePipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: default(),
+            cache: None,
+        });
+
+        let params = first_band(world_data, settings);
+        assert_eq!(
+            params.rows, SIZE as u32,
+            "the test world should fit one band"
+        );
+        let mut uniform = UniformBuffer::from(params);
+        uniform.write_buffer(&device, &queue);
+        let mut permutations = StorageBuffer::from(permutation_tables(world_data.seed));
+        permutations.write_buffer(&device, &queue);
+        let bytes = ((1 + SIZE * SIZE * FIELDS_PER_TILE as i32) as usize * size_of::<f32>())
+            as BufferAddress;
+        let fields = device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: bytes,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: bytes,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = device.create_bind_group(
+            None,
+            &layout,
+            &BindGroupEntries::sequential((
+                uniform.binding().unwrap(),
+                permutations.binding().unwrap(),
+                fields.as_entire_buffer_binding(),
+            )),
+        );
+
+        let mut encoder = device.create_command_encoder(&default());
+        {
+            let mut pass = encoder.begin_compute_pass(&default());
+            pass.set_bind_group(0, &*bind_group, &[]);
+            pass.set_pipeline(&pipeline);
+            pass.dispatch_workgroups((SIZE * SIZE) as u32 / WORKGROUP_SIZE, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&fields, 0, &staging, 0, bytes);
+        queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        device.map_buffer(&slice, MapMode::Read, |result| result.unwrap());
+        device.poll(PollType::Wait).unwrap();
+        slice
+            .get_mapped_range()
+            .chunks_exact(size_of::<f32>())
+            .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect()
+    }
+
+    // The shader works in f32 where the CPU noise uses f64, so the two only agree
+    // to within rounding. Run on Mesa's llvmpipe through wgpu's GL backend
+    // (`WGPU_BACKEND=gl` with bevy_render's `gles` feature), the widest gaps over
+    // this world were 0.035 elevation, 0.065 degrees and 0.0011 moisture.
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn gpu_noise_fields_match_the_cpu_sampler() {
+        let world_data = WorldData {
+            seed: 1234,
+            warp_strength: 0.2,
+            ..default()
+        };
+        assert!(gpu_supported(&world_data));
+        let settings = WorldSettings {
+            world_size: SIZE,
+            ..default()
+        };
+
+        let values = gpu_fields(&world_data, &settings);
+        assert_eq!(values[0], 1.0, "the band number is written first");
+        let sampler = WorldSampler::new(&world_data, SIZE);
+        for (tile, gpu) in values[1..].chunks_exact(FIELDS_PER_TILE).enumerate() {
+            let (x, y) = (tile as i32 % SIZE, tile as i32 / SIZE);
+            let cpu = sampler.square(x, y);
+            let at = format!("tile ({x}, {y})");
+            assert!((gpu[0] - cpu.elevation).abs() < 0.05, "{at}: elevation");
+            assert!((gpu[1] - cpu.temperature).abs() < 0.1, "{at}: temperature");
+            assert!((gpu[2] - cpu.moisture).abs() < 0.002, "{at}: moisture");
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use kingdom_worldgen::{MAX_ELEVATION, climate_square, is_below_sea_level, sampler::WorldSampler};
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+use crate::components::{
+    world::{Biome, Landmark, LandmarkKind},
+    world_gen::WorldData,
+};
+use crate::systems::world::{WorldSettings, nearest_copy, toroidal_distance};
+
+// Random tiles tried per world; each landmark kind stops once it hits its cap
+const PLACEMENT_ATTEMPTS: u32 = 4000;
+const MAX_VOLCANOES: usize = 6;
+const MAX_OASES: usize = 8;
+const MAX_CRATER_LAKES: usize = 4;
+const CRATER_LAKE_CHANCE: f64 = 0.02;
+// Landmarks are rare enough to be worth a detour; keep them apart
+const MIN_LANDMARK_SPACING: f32 = 150.0;
+// A volcano needs high ground around it, not a lone spike
+const MOUNTAIN_CLUSTER_RADIUS: i32 = 8;
+const MARKER_SIZE: f32 = 3.0;
+const MARKER_Z: f32 = 0.5;
+
+pub fn spawn_landmarks(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    query: Query<&WorldData>,
+) {
+    let world_data = match query.single() {
+        Ok(data) => data,
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+
+    for landmark in place_landmarks(world_data, &settings) {
+        let color = match landmark.kind {
+            LandmarkKind::Volcano => Color::srgb(0.85, 0.2, 0.05),
+            LandmarkKind::Oasis => Color::srgb(0.1, 0.75, 0.6),
+            LandmarkKind::CraterLake => Color::srgb(0.2, 0.35, 0.9),
+        };
+        let position = Vec3::new(landmark.x as f32 + 0.5, landmark.y as f32 + 0.5, MARKER_Z);
+
+        commands.spawn((
+            Sprite::from_color(color, Vec2::splat(MARKER_SIZE)),
+            Transform::from_translation(position),
+            landmark,
+        ));
+    }
+}
+
+// Moves each marker to the copy of its tile nearest the camera, so landmarks
+// across the world edge show up before the camera wraps round to them
+pub fn update_landmark_markers(
+    camera_query: Single<&Transform, With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut marker_query: Query<(&Landmark, &mut Transform), Without<Camera2d>>,
+) {
+    let camera = camera_query.translation.truncate().as_ivec2();
+
+    for (landmark, mut transform) in &mut marker_query {
+        let tile = IVec2::new(landmark.x, landmark.y);
+        let nearest = nearest_copy(camera, tile, &settings);
+        transform.translation = (nearest.as_vec2() + Vec2::splat(0.5)).extend(MARKER_Z);
+    }
+}
+
+// Deterministic for a seed: candidates come from a seeded RNG and every tile is
+// evaluated from the seed alone, so streamed and pregenerated worlds agree.
+pub fn place_landmarks(world_data: &WorldData, settings: &WorldSettings) -> Vec<Landmark> {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let mut rng = SmallRng::seed_from_u64(world_data.seed.wrapping_add(10) as u64);
+    let mut landmarks: Vec<Landmark> = Vec::new();
+
+    for _attempt in 0..PLACEMENT_ATTEMPTS {
+        let x = rng.random_range(0..settings.world_size);
+        let y = rng.random_range(0..settings.world_size);
+        let crater_roll = rng.random_bool(CRATER_LAKE_CHANCE);
+
+        let too_close = landmarks.iter().any(|landmark| {
+            toroidal_distance(
+                IVec2::new(x, y),
+                IVec2::new(landmark.x, landmark.y),
+                settings,
+            ) < MIN_LANDMARK_SPACING
+        });
+        if too_close {
+            continue;
+        }
+
+        let square = climate_square(&sampler, world_data, x, y);
+        let count = |kind: LandmarkKind| landmarks.iter().filter(|l| l.kind == kind).count();
+
+        let kind = if square.elevation as f64 > 0.75 * MAX_ELEVATION
+            && count(LandmarkKind::Volcano) < MAX_VOLCANOES
+            && in_mountain_cluster(&sampler, x, y)
+        {
+            Some(LandmarkKind::Volcano)
+        } else if square.biome == Biome::HotDesert && count(LandmarkKind::Oasis) < MAX_OASES {
+            Some(LandmarkKind::Oasis)
+        } else if crater_roll
+            && !is_below_sea_level(square.elevation as f64, world_data.sea_threshold)
+            && !matches!(square.biome, Biome::Ice | Biome::Snow)
+            && count(LandmarkKind::CraterLake) < MAX_CRATER_LAKES
+        {
+            Some(LandmarkKind::CraterLake)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            landmarks.push(Landmark { kind, x, y });
+        }
+    }
+
+    landmarks
+}
+
+fn in_mountain_cluster(sampler: &WorldSampler, x: i32, y: i32) -> bool {
+    let r = MOUNTAIN_CLUSTER_RADIUS;
+    let high_neighbours = [(r, 0), (-r, 0), (0, r), (0, -r)]
+        .iter()
+        .filter(|(dx, dy)| sampler.elevation_at(x + dx, y + dy) > 0.65 * MAX_ELEVATION)
+        .count();
+
+    high_neighbours >= 3
+}
@@ -0,0 +1,168 @@
+use bevy::{diagnostic::Diagnostics, prelude::*};
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use kingdom_worldgen::{NoiseFields, generate_logical_world};
+
+use crate::components::{loading_screen::LoadingCancelButton, world::WorldMap, world_gen::WorldData};
+use crate::states::game_state::GameState;
+use crate::systems::diagnostics::record_worldgen_times;
+use crate::systems::keybindings::{InputAction, InputMap};
+use crate::systems::pause_menu::Pause;
+use crate::systems::world::WorldSettings;
+use crate::systems::options::{GameOptions, run_on_workers};
+use progress::WorldGenProgress;
+
+// The generation itself lives in the `kingdom_worldgen` crate; these systems run
+// it off the main thread and place what it builds into the game
+pub mod biome_table;
+pub mod comparison;
+// GPU noise sampling has not yet been run against CPU output, so it is opt-in
+#[cfg(feature = "gpu-worldgen")]
+pub mod gpu;
+pub mod landmarks;
+pub mod names;
+pub mod overview;
+pub mod presets;
+pub mod progress;
+pub mod regions;
+pub mod settlements;
+pub mod specialties;
+pub mod statistics;
+pub mod wildlife;
+
+#[derive(Resource)]
+pub struct WorldGenTask(Task<Option<WorldMap>>);
+
+pub fn start_world_generation(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    settings: Res<WorldSettings>,
+    options: Res<GameOptions>,
+    query: Query<&WorldData>,
+) {
+    // Streamed worlds build each chunk as the camera reaches it
+    if settings.streaming {
+        next_state.set(GameState::Playing);
+        return;
+    }
+
+    let world_data = match query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let settings = *settings;
+    let progress = WorldGenProgress::default();
+    let threads = options.worker_threads;
+
+    #[cfg(feature = "gpu-worldgen")]
+    if settings.gpu_generation {
+        if gpu::gpu_supported(&world_data) {
+            commands.insert_resource(gpu::GpuWorldGenJob::new(world_data, settings, threads));
+            commands.insert_resource(progress);
+            return;
+        }
+        info!("GPU generation only covers OpenSimplex noise worlds; generating on the CPU");
+    }
+
+    spawn_world_generation(&mut commands, world_data, settings, threads, &progress, None);
+    commands.insert_resource(progress);
+}
+
+// Runs the rest of generation off the main thread, starting from the GPU's noise
+// fields when it has already sampled them
+fn spawn_world_generation(
+    commands: &mut Commands,
+    world_data: WorldData,
+    settings: WorldSettings,
+    threads: usize,
+    progress: &WorldGenProgress,
+    fields: Option<NoiseFields>,
+) {
+    let task_progress = progress.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        run_on_workers(threads, || {
+            let fields = fields.as_ref();
+            generate_logical_world(&world_data, settings.world_size, &task_progress, fields)
+        })
+    });
+
+    commands.insert_resource(WorldGenTask(task));
+}
+
+pub fn poll_world_generation(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut diagnostics: Diagnostics,
+    mut task: ResMut<WorldGenTask>,
+    progress: Option<Res<WorldGenProgress>>,
+) {
+    let Some(world_map) = check_ready(&mut task.0) else {
+        return;
+    };
+    commands.remove_resource::<WorldGenTask>();
+    commands.remove_resource::<WorldGenProgress>();
+    if let Some(progress) = progress {
+        record_worldgen_times(&mut diagnostics, &progress);
+    }
+
+    // None only when cancelled, and the cancel button has already left this state
+    if let Some(world_map) = world_map {
+        commands.spawn(world_map);
+        next_state.set(GameState::Playing);
+    }
+}
+
+// Cancel button on the loading screen: stops the generation task and returns to
+// the setup form with a fresh world
+pub fn cancel_world_generation(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    progress: Option<Res<WorldGenProgress>>,
+    button_query: Query<&Interaction, (With<LoadingCancelButton>, Changed<Interaction>)>,
+    world_data_query: Query<Entity, With<WorldData>>,
+) {
+    if !button_query.iter().any(|interaction| *interaction == Interaction::Pressed) {
+        return;
+    }
+
+    if let Some(progress) = progress {
+        progress.cancel();
+    }
+    commands.remove_resource::<WorldGenTask>();
+    commands.remove_resource::<WorldGenProgress>();
+    for entity in &world_data_query {
+        commands.entity(entity).despawn();
+    }
+
+    next_state.set(GameState::WorldGenSetup);
+}
+
+// Present while Playing is left to rebuild the world in place, so cleanup_world
+// keeps the WorldData entity for the next generation
+#[derive(Resource)]
+pub struct WorldRegeneration;
+
+// Regenerates the world with the same settings under a new random seed, or the
+// same seed with Shift held, skipping the menus. Everything built for the old
+// world is torn down on leaving Playing as usual.
+pub fn regenerate_world(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    pause: Res<Pause>,
+    mut world_data: Single<&mut WorldData>,
+) {
+    if pause.menu_open || !input_map.just_pressed(&input, InputAction::RegenerateWorld) {
+        return;
+    }
+
+    if !input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        world_data.seed = rand::random();
+        world_data.seed_text = None;
+    }
+    commands.insert_resource(WorldRegeneration);
+    next_state.set(GameState::WorldGenerating);
+}
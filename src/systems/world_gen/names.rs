@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+use crate::components::kingdoms::Culture;
+
+// The tongue geography is named in, and that of settlements no kingdom has
+// claimed yet
+const COMMON_ONSETS: &[&str] = &[
+    "b", "br", "c", "d", "dr", "f", "g", "gr", "h", "k", "kh", "l", "m", "n", "r", "s", "sh", "t",
+    "th", "v",
+];
+const COMMON_VOWELS: &[&str] = &["a", "e", "i", "o", "u", "ae", "ai", "ei", "ou", "y"];
+const COMMON_CODAS: &[&str] = &["", "", "", "n", "r", "l", "s", "nd", "rk", "th"];
+
+// Short and hard, heavy on stops and clusters
+const NORTHERN_ONSETS: &[&str] = &[
+    "b", "br", "d", "dr", "g", "gr", "h", "k", "kr", "sk", "st", "t", "th", "v", "y",
+];
+const NORTHERN_VOWELS: &[&str] = &["a", "e", "i", "o", "u", "au", "ei", "y"];
+const NORTHERN_CODAS: &[&str] = &["", "k", "r", "rk", "nd", "ld", "gg", "sk", "n", "th"];
+
+// Long and open, ending in vowels
+const SOUTHERN_ONSETS: &[&str] = &["", "c", "l", "m", "n", "p", "qu", "r", "s", "t", "v", "z"];
+const SOUTHERN_VOWELS: &[&str] = &["a", "e", "i", "o", "ia", "io", "ae", "u"];
+const SOUTHERN_CODAS: &[&str] = &["", "", "", "a", "o", "s", "n", "l"];
+
+// Even syllables with soft affricates and nasal endings
+const EASTERN_ONSETS: &[&str] = &[
+    "ch", "h", "j", "k", "l", "m", "n", "r", "sh", "t", "ts", "y", "z",
+];
+const EASTERN_VOWELS: &[&str] = &["a", "i", "u", "o", "ai", "ao", "ei", "ua"];
+const EASTERN_CODAS: &[&str] = &["", "", "n", "ng", "n"];
+
+// Endings that work what stands out about a place into its name, as in
+// "Rockhaven" or "Highcastle"
+const COMMON_AFFIXES: [&[&str]; 4] = [
+    &["haven", "mouth", "port"],
+    &["crag", "castle", "peak"],
+    &["wood", "holt", "grove"],
+    &["field", "ton", "stead"],
+];
+const NORTHERN_AFFIXES: [&[&str]; 4] = [
+    &["vik", "havn", "nes"],
+    &["berg", "fjell", "stein"],
+    &["skog", "lund", "holt"],
+    &["by", "heim", "stad"],
+];
+const SOUTHERN_AFFIXES: [&[&str]; 4] = [
+    &["marina", "costa", "porto"],
+    &["monte", "rocca", "alta"],
+    &["selva", "bosco", "silva"],
+    &["campo", "piana", "villa"],
+];
+const EASTERN_AFFIXES: [&[&str]; 4] = [
+    &["hai", "wan", "kou"],
+    &["shan", "feng", "ling"],
+    &["lin", "sen", "mu"],
+    &["yuan", "ping", "cheng"],
+];
+
+// What stands out about the land around a settlement
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Landform {
+    Coast,
+    Highland,
+    Forest,
+    Plain,
+}
+
+// Onsets, vowels, codas and syllable count of words in a culture's tongue
+fn phonology(
+    culture: Culture,
+) -> (
+    &'static [&'static str],
+    &'static [&'static str],
+    &'static [&'static str],
+    RangeInclusive<usize>,
+) {
+    match culture {
+        Culture::Common => (COMMON_ONSETS, COMMON_VOWELS, COMMON_CODAS, 2..=3),
+        Culture::Northern => (NORTHERN_ONSETS, NORTHERN_VOWELS, NORTHERN_CODAS, 1..=2),
+        Culture::Southern => (SOUTHERN_ONSETS, SOUTHERN_VOWELS, SOUTHERN_CODAS, 2..=4),
+        Culture::Eastern => (EASTERN_ONSETS, EASTERN_VOWELS, EASTERN_CODAS, 2..=3),
+    }
+}
+
+// Names drawn from a seeded RNG, never repeating for one generator. Seed it
+// from the world seed so a world gets the same names back every time.
+pub struct NameGenerator {
+    rng: SmallRng,
+    used: HashSet<String>,
+}
+
+impl NameGenerator {
+    pub fn new(seed: u64) -> Self {
+        NameGenerator {
+            rng: SmallRng::seed_from_u64(seed),
+            used: HashSet::new(),
+        }
+    }
+
+    pub fn name(&mut self, culture: Culture) -> String {
+        self.unused(|rng| word(rng, culture))
+    }
+
+    pub fn settlement_name(&mut self, culture: Culture, landform: Landform) -> String {
+        self.unused(|rng| settlement_word(rng, culture, landform))
+    }
+
+    fn unused(&mut self, mut make: impl FnMut(&mut SmallRng) -> String) -> String {
+        let mut name = make(&mut self.rng);
+        while self.used.contains(&name) {
+            name = make(&mut self.rng);
+        }
+        self.used.insert(name.clone());
+
+        name
+    }
+}
+
+// The name a settlement founded on `tile` gets, the same wherever and whenever
+// the same world founds one there
+pub fn place_name(seed: u32, culture: Culture, landform: Landform, tile: IVec2) -> String {
+    let place = ((tile.x as u32 as u64) << 32) | tile.y as u32 as u64;
+    let mut rng = SmallRng::seed_from_u64(place ^ (seed as u64).rotate_left(17));
+
+    settlement_word(&mut rng, culture, landform)
+}
+
+// A short root in the culture's tongue ending in what marks its landform
+fn settlement_word(rng: &mut SmallRng, culture: Culture, landform: Landform) -> String {
+    let affixes = match culture {
+        Culture::Common => COMMON_AFFIXES,
+        Culture::Northern => NORTHERN_AFFIXES,
+        Culture::Southern => SOUTHERN_AFFIXES,
+        Culture::Eastern => EASTERN_AFFIXES,
+    }[landform as usize];
+    let count = rng.random_range(1..=2);
+    let root = capitalise(syllables(rng, culture, count));
+
+    format!("{}{}", root, affixes[rng.random_range(0..affixes.len())])
+}
+
+// A capitalised made-up word in the culture's tongue
+pub fn word(rng: &mut SmallRng, culture: Culture) -> String {
+    let count = rng.random_range(phonology(culture).3);
+    capitalise(syllables(rng, culture, count))
+}
+
+// Syllables then a coda. A syllable is never repeated straight after itself.
+fn syllables(rng: &mut SmallRng, culture: Culture, count: usize) -> String {
+    let (onsets, vowels, codas, _) = phonology(culture);
+    let mut word = String::new();
+    let mut previous = String::new();
+
+    for _ in 0..count {
+        let mut syllable = previous.clone();
+        while syllable == previous {
+            syllable = format!(
+                "{}{}",
+                onsets[rng.random_range(0..onsets.len())],
+                vowels[rng.random_range(0..vowels.len())]
+            );
+        }
+        word.push_str(&syllable);
+        previous = syllable;
+    }
+    word.push_str(codas[rng.random_range(0..codas.len())]);
+
+    word
+}
+
+fn capitalise(word: String) -> String {
+    let mut letters = word.chars();
+    match letters.next() {
+        Some(first) => first.to_uppercase().chain(letters).collect(),
+        None => word,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_seed_gives_the_same_names_back() {
+        let names = |seed| {
+            let mut namer = NameGenerator::new(seed);
+            Culture::ALL
+                .iter()
+                .flat_map(|&culture| (0..8).map(move |_| culture))
+                .map(|culture| namer.name(culture))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(42), names(42));
+        assert_ne!(names(42), names(43));
+    }
+
+    #[test]
+    fn one_generator_never_repeats_a_name() {
+        let mut namer = NameGenerator::new(7);
+        let names: HashSet<String> = (0..200).map(|_| namer.name(Culture::Northern)).collect();
+        assert_eq!(names.len(), 200);
+    }
+
+    #[test]
+    fn place_names_depend_only_on_seed_culture_and_tile() {
+        let tile = IVec2::new(120, -5);
+        let name = place_name(9, Culture::Southern, Landform::Coast, tile);
+        let again = place_name(9, Culture::Southern, Landform::Coast, tile);
+        assert_eq!(again, name);
+        assert!(name.chars().next().is_some_and(char::is_uppercase));
+    }
+
+    #[test]
+    fn settlement_names_end_in_their_landform() {
+        let mut namer = NameGenerator::new(3);
+        for _ in 0..20 {
+            let name = namer.settlement_name(Culture::Northern, Landform::Highland);
+            let mut affixes = NORTHERN_AFFIXES[Landform::Highland as usize].iter();
+            assert!(affixes.any(|affix| name.ends_with(affix)), "{}", name);
+        }
+    }
+}
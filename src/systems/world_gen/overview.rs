@@ -0,0 +1,39 @@
+use kingdom_worldgen::{climate_square, sampler::WorldSampler};
+
+use crate::components::world_gen::WorldData;
+use crate::systems::{
+    palette::Palette,
+    parallel::*,
+    world::{MapOverlay, WorldSettings, tile_color},
+};
+
+// Low-resolution biome colours for the whole world, `size`×`size` samples in row
+// order from the bottom of the map, for whole-world previews
+pub fn overview_colors(
+    world_data: &WorldData,
+    settings: &WorldSettings,
+    palette: &Palette,
+    size: usize,
+) -> Vec<[u8; 4]> {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let step = settings.world_size as f32 / size as f32;
+
+    (0..size * size)
+        .into_par_iter()
+        .map(|index| {
+            let x = ((index % size) as f32 + 0.5) * step;
+            let y = ((index / size) as f32 + 0.5) * step;
+            let square = climate_square(&sampler, world_data, x as i32, y as i32);
+            let [r, g, b, a] = tile_color(
+                &square,
+                square.biome,
+                MapOverlay::Biome,
+                &world_data.biomes,
+                palette,
+                world_data.sea_level() as f32,
+            );
+
+            [r, g, b, a].map(|channel| (channel.clamp(0.0, 1.0) * 255.0) as u8)
+        })
+        .collect()
+}
@@ -0,0 +1,131 @@
+use std::fs;
+
+use crate::components::world_gen::{GenerationMode, WorldData};
+use crate::systems::startup::{read_user_config, user_data_dir, write_user_config};
+
+// Folder under the user data folder holding the player's own presets, one RON
+// file of `WorldData` per preset
+const SAVED_PRESETS_DIR: &str = "presets";
+
+pub struct WorldPreset {
+    pub name: &'static str,
+    pub terrain_scale: f64,
+    pub continental_scale: f64,
+    pub num_of_octaves: u32,
+    pub sea_threshold: f64,
+    pub mountain_threshold: f64,
+    pub temperature_scale: f64,
+    pub moisture_scale: f64,
+    pub scaling_factor: f64,
+    pub erosion_iterations: u32,
+    pub erosion_strength: f64,
+    pub warp_strength: f64,
+    pub generation_mode: GenerationMode,
+    pub plate_count: u32,
+}
+
+pub const PRESETS: &[WorldPreset] = &[
+    WorldPreset {
+        name: "Pangaea",
+        terrain_scale: 0.005,
+        continental_scale: 0.0003,
+        num_of_octaves: 5,
+        sea_threshold: 0.26,
+        mountain_threshold: 0.6,
+        temperature_scale: 0.005,
+        moisture_scale: 0.008,
+        scaling_factor: 100.0,
+        erosion_iterations: 10,
+        erosion_strength: 0.4,
+        warp_strength: 0.05,
+        generation_mode: GenerationMode::Tectonic,
+        plate_count: 5,
+    },
+    WorldPreset {
+        name: "Archipelago",
+        terrain_scale: 0.008,
+        continental_scale: 0.002,
+        num_of_octaves: 6,
+        sea_threshold: 0.52,
+        mountain_threshold: 0.62,
+        temperature_scale: 0.005,
+        moisture_scale: 0.01,
+        scaling_factor: 100.0,
+        erosion_iterations: 5,
+        erosion_strength: 0.3,
+        warp_strength: 0.15,
+        generation_mode: GenerationMode::Noise,
+        plate_count: 12,
+    },
+    WorldPreset {
+        name: "Inland Sea",
+        terrain_scale: 0.006,
+        continental_scale: 0.0008,
+        num_of_octaves: 5,
+        sea_threshold: 0.3,
+        mountain_threshold: 0.6,
+        temperature_scale: 0.005,
+        moisture_scale: 0.008,
+        scaling_factor: 100.0,
+        erosion_iterations: 10,
+        erosion_strength: 0.5,
+        warp_strength: 0.1,
+        generation_mode: GenerationMode::Tectonic,
+        plate_count: 8,
+    },
+    WorldPreset {
+        name: "Highlands",
+        terrain_scale: 0.007,
+        continental_scale: 0.0005,
+        num_of_octaves: 8,
+        sea_threshold: 0.24,
+        mountain_threshold: 0.55,
+        temperature_scale: 0.004,
+        moisture_scale: 0.008,
+        scaling_factor: 100.0,
+        erosion_iterations: 25,
+        erosion_strength: 0.3,
+        warp_strength: 0.2,
+        generation_mode: GenerationMode::Tectonic,
+        plate_count: 16,
+    },
+];
+
+// Names of the saved presets, alphabetical
+pub fn saved_preset_names() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(user_data_dir().join(SAVED_PRESETS_DIR)) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "ron"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+
+    names
+}
+
+pub fn load_saved_preset(name: &str) -> Option<WorldData> {
+    read_user_config(&saved_preset_file(name))
+}
+
+pub fn save_preset(name: &str, data: &WorldData) {
+    write_user_config(&saved_preset_file(name), data);
+}
+
+// Keeps letters, digits, spaces, dashes and underscores so a name can't reach
+// outside the presets folder
+pub fn sanitize_preset_name(name: &str) -> String {
+    name.chars()
+        .filter(|chr| chr.is_alphanumeric() || matches!(chr, ' ' | '-' | '_'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn saved_preset_file(name: &str) -> String {
+    format!("{}/{}.ron", SAVED_PRESETS_DIR, name)
+}
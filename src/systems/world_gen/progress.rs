@@ -0,0 +1,101 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+
+use bevy::{platform::time::Instant, prelude::*};
+
+pub use kingdom_worldgen::progress::WorldGenStage;
+use kingdom_worldgen::progress::GenerationProgress;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StageProgress {
+    pub stage: WorldGenStage,
+    // Completion of the current stage, 0.0 to 1.0
+    pub fraction: f32,
+}
+
+impl StageProgress {
+    pub fn overall(&self) -> f32 {
+        (self.stage.index() + self.fraction.clamp(0.0, 1.0)) / WorldGenStage::COUNT
+    }
+}
+
+// Wall-clock time spent in each stage, for the diagnostics overlay
+#[derive(Debug, Default)]
+struct StageTimes {
+    current: Option<(WorldGenStage, Instant)>,
+    secs: [f32; 3],
+}
+
+impl StageTimes {
+    fn enter(&mut self, stage: WorldGenStage) {
+        if self.current.is_some_and(|(current, _)| current == stage) {
+            return;
+        }
+        self.close();
+        self.current = Some((stage, Instant::now()));
+    }
+
+    fn close(&mut self) {
+        if let Some((stage, started)) = self.current.take() {
+            self.secs[stage.index() as usize] += started.elapsed().as_secs_f32();
+        }
+    }
+}
+
+// Shared with the generation task, which writes to it from worker threads while
+// the loading screen reads it every frame. The loading screen's cancel button
+// flags it back the other way.
+#[derive(Resource, Clone, Default)]
+pub struct WorldGenProgress {
+    progress: Arc<Mutex<StageProgress>>,
+    cancelled: Arc<AtomicBool>,
+    times: Arc<Mutex<StageTimes>>,
+}
+
+impl WorldGenProgress {
+    pub fn report(&self, stage: WorldGenStage, fraction: f32) {
+        if let Ok(mut progress) = self.progress.lock() {
+            *progress = StageProgress { stage, fraction };
+        }
+        if let Ok(mut times) = self.times.lock() {
+            times.enter(stage);
+        }
+    }
+
+    // Seconds spent in each stage, in `WorldGenStage::ALL` order. Ends the
+    // stage still running, so call it once generation is done.
+    pub fn stage_secs(&self) -> [f32; 3] {
+        self.times
+            .lock()
+            .map(|mut times| {
+                times.close();
+                times.secs
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn current(&self) -> StageProgress {
+        self.progress.lock().map(|progress| *progress).unwrap_or_default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    // Checked between passes; generation stops at the next one
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl GenerationProgress for WorldGenProgress {
+    fn report(&self, stage: WorldGenStage, fraction: f32) {
+        WorldGenProgress::report(self, stage, fraction);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        WorldGenProgress::is_cancelled(self)
+    }
+}
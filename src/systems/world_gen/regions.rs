@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, futures::check_ready};
+use kingdom_worldgen::{MAX_ELEVATION, is_below_sea_level, sampler::WorldSampler};
+
+use crate::components::{
+    kingdoms::Culture,
+    world::{Region, RegionKind},
+    world_gen::WorldData,
+};
+use crate::systems::localization::Locale;
+use crate::systems::parallel::*;
+use crate::systems::world::{WorldSettings, nearest_copy};
+
+use super::{names::NameGenerator, specialties};
+
+// Regions are found on a coarse grid with one elevation sample per cell, which
+// keeps the flood fill cheap enough for streamed worlds that never hold a full map
+const REGION_CELL: i32 = 16;
+// Connected areas smaller than this many cells stay unnamed
+const MIN_REGION_CELLS: usize = 12;
+// Share of all cells a land mass or water body needs to count as a continent or ocean
+const CONTINENT_SHARE: f32 = 0.015;
+const OCEAN_SHARE: f32 = 0.03;
+pub const MOUNTAIN_ELEVATION: f64 = 0.65 * MAX_ELEVATION;
+// Labels only appear once the camera is zoomed out past this projection scale
+const LABEL_MIN_ZOOM: f32 = 4.0;
+const LABEL_FONT_SIZE: f32 = 28.0;
+const LABEL_Z: f32 = 5.0;
+
+pub struct RegionLayout {
+    pub regions: Vec<Region>,
+    // Per coarse cell, the index into `regions` of the land mass or water body it
+    // belongs to, and of the mountain range on top of it
+    pub area: Vec<Option<u32>>,
+    pub mountain: Vec<Option<u32>>,
+}
+
+#[derive(Resource)]
+pub struct RegionTask(Task<RegionLayout>);
+
+// Coarse-grid lookup from a tile to the region entities covering it
+#[derive(Resource)]
+pub struct RegionMap {
+    cells_per_side: i32,
+    area: Vec<Option<Entity>>,
+    mountain: Vec<Option<Entity>>,
+}
+
+impl RegionMap {
+    // Mountain range first, then the land mass or water body underneath
+    pub fn regions_at(&self, x: i32, y: i32) -> [Option<Entity>; 2] {
+        let index = cell_index(
+            x.div_euclid(REGION_CELL),
+            y.div_euclid(REGION_CELL),
+            self.cells_per_side,
+        );
+        [self.mountain[index], self.area[index]]
+    }
+}
+
+pub fn start_region_identification(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    locale: Res<Locale>,
+    query: Query<&WorldData>,
+) {
+    let world_data = match query.single() {
+        Ok(data) => data.clone(),
+        Err(err) => {
+            error!("WorldData query failed: {:?}", err);
+            return;
+        }
+    };
+    let settings = *settings;
+    let patterns = RegionPatterns::new(&locale);
+
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { identify_regions(&world_data, &settings, &patterns) });
+    commands.insert_resource(RegionTask(task));
+}
+
+pub fn poll_region_identification(
+    mut commands: Commands,
+    settings: Res<WorldSettings>,
+    mut task: ResMut<RegionTask>,
+) {
+    let Some(layout) = check_ready(&mut task.0) else {
+        return;
+    };
+
+    let entities: Vec<Entity> = layout
+        .regions
+        .into_iter()
+        .map(|region| {
+            let center = (region.min + region.max + IVec2::ONE).as_vec2() / 2.0;
+            commands
+                .spawn((
+                    Text2d::new(region.name.clone()),
+                    TextFont {
+                        font_size: LABEL_FONT_SIZE,
+                        ..default()
+                    },
+                    TextColor(Color::srgba(1.0, 1.0, 0.9, 0.85)),
+                    Transform::from_translation(center.extend(LABEL_Z)),
+                    Visibility::Hidden,
+                    region,
+                ))
+                .id()
+        })
+        .collect();
+    let to_entities = |cells: Vec<Option<u32>>| -> Vec<Option<Entity>> {
+        cells
+            .into_iter()
+            .map(|index| index.map(|index| entities[index as usize]))
+            .collect()
+    };
+
+    commands.insert_resource(RegionMap {
+        cells_per_side: cells_per_side(&settings),
+        area: to_entities(layout.area),
+        mountain: to_entities(layout.mountain),
+    });
+    commands.remove_resource::<RegionTask>();
+}
+
+// Region names float over the map when zoomed out. Each label is moved to the copy
+// of its region nearest the camera, since the camera can roam past the world edge.
+pub fn update_region_labels(
+    camera_query: Single<(&Transform, &Projection), With<Camera2d>>,
+    settings: Res<WorldSettings>,
+    mut label_query: Query<(&Region, &mut Transform, &mut Visibility), Without<Camera2d>>,
+) {
+    let (camera_transform, projection) = *camera_query;
+    let zoom = match projection {
+        Projection::Orthographic(projection2d) => projection2d.scale,
+        _ => 1.0,
+    };
+    let camera = camera_transform.translation.truncate().as_ivec2();
+
+    for (region, mut transform, mut visibility) in &mut label_query {
+        // Islands are too small to be worth a label at this zoom
+        if zoom < LABEL_MIN_ZOOM || region.kind == RegionKind::Island {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        let center = (region.min + region.max + IVec2::ONE) / 2;
+        let nearest = nearest_copy(camera, center, &settings);
+
+        transform.translation = nearest.as_vec2().extend(LABEL_Z);
+        transform.scale = Vec3::splat(zoom);
+        *visibility = Visibility::Visible;
+    }
+}
+
+pub fn cleanup_regions(mut commands: Commands, query: Query<Entity, With<Region>>) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<RegionMap>();
+    commands.remove_resource::<RegionTask>();
+}
+
+// Flood-fills the coarse elevation grid into land masses, water bodies and
+// mountain ranges, then names every one big enough to matter. Deterministic for
+// a seed, so the same world always gets the same names.
+pub fn identify_regions(
+    world_data: &WorldData,
+    settings: &WorldSettings,
+    patterns: &RegionPatterns,
+) -> RegionLayout {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let cells = cells_per_side(settings);
+    let total = (cells * cells) as usize;
+
+    let elevations: Vec<f64> = (0..total)
+        .into_par_iter()
+        .map(|index| {
+            let cx = index as i32 % cells;
+            let cy = index as i32 / cells;
+            sampler.elevation_at(
+                cx * REGION_CELL + REGION_CELL / 2,
+                cy * REGION_CELL + REGION_CELL / 2,
+            )
+        })
+        .collect();
+    let is_water: Vec<bool> = elevations
+        .iter()
+        .map(|&e| is_below_sea_level(e, world_data.sea_threshold))
+        .collect();
+    let is_mountain: Vec<bool> = elevations.iter().map(|&e| e > MOUNTAIN_ELEVATION).collect();
+
+    let continent_cells = (total as f32 * CONTINENT_SHARE) as usize;
+    let ocean_cells = (total as f32 * OCEAN_SHARE) as usize;
+
+    let mut namer = RegionNamer::new(world_data.seed, patterns);
+    let mut regions = Vec::new();
+    let mut area = vec![None; total];
+    let mut mountain = vec![None; total];
+
+    let areas = connected_cells(cells, |_| true, |a, b| is_water[a] == is_water[b]);
+    for component in areas {
+        if component.cells.len() < MIN_REGION_CELLS {
+            continue;
+        }
+
+        let size = component.cells.len();
+        let kind = match is_water[component.cells[0]] {
+            true if size >= ocean_cells => RegionKind::Ocean,
+            true => RegionKind::Sea,
+            false if size >= continent_cells => RegionKind::Continent,
+            false => RegionKind::Island,
+        };
+
+        for &cell in &component.cells {
+            area[cell] = Some(regions.len() as u32);
+        }
+        regions.push(component.into_region(kind, namer.name(kind), cells));
+    }
+
+    specialties::assign_specialties(&mut regions, &area, cells, REGION_CELL, world_data, settings);
+
+    let ranges = connected_cells(cells, |cell| is_mountain[cell], |_, _| true);
+    for component in ranges {
+        if component.cells.len() < MIN_REGION_CELLS {
+            continue;
+        }
+
+        let kind = RegionKind::MountainRange;
+        for &cell in &component.cells {
+            mountain[cell] = Some(regions.len() as u32);
+        }
+        regions.push(component.into_region(kind, namer.name(kind), cells));
+    }
+
+    RegionLayout {
+        regions,
+        area,
+        mountain,
+    }
+}
+
+struct CellComponent {
+    cells: Vec<usize>,
+    // Unwrapped cell coordinates, so a component crossing the world edge keeps a
+    // contiguous box
+    min: IVec2,
+    max: IVec2,
+}
+
+impl CellComponent {
+    fn into_region(self, kind: RegionKind, name: String, cells_per_side: i32) -> Region {
+        let mut min = self.min;
+        let mut max = self.max;
+
+        // A component that wraps all the way around spans the whole axis
+        if max.x - min.x + 1 >= cells_per_side {
+            min.x = 0;
+            max.x = cells_per_side - 1;
+        }
+        if max.y - min.y + 1 >= cells_per_side {
+            min.y = 0;
+            max.y = cells_per_side - 1;
+        }
+
+        Region {
+            kind,
+            name,
+            min: min * REGION_CELL,
+            max: (max + IVec2::ONE) * REGION_CELL - IVec2::ONE,
+            specialties: Vec::new(),
+        }
+    }
+}
+
+// Breadth-first flood fill over the wrapping grid. `include` picks which cells take
+// part and `same` whether two neighbouring cells belong together.
+fn connected_cells(
+    cells: i32,
+    include: impl Fn(usize) -> bool,
+    same: impl Fn(usize, usize) -> bool,
+) -> Vec<CellComponent> {
+    let total = (cells * cells) as usize;
+    let mut visited = vec![false; total];
+    let mut components = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for start in 0..total {
+        if visited[start] || !include(start) {
+            continue;
+        }
+
+        let origin = IVec2::new(start as i32 % cells, start as i32 / cells);
+        let mut component = CellComponent {
+            cells: Vec::new(),
+            min: origin,
+            max: origin,
+        };
+        visited[start] = true;
+        queue.push_back((start, origin));
+
+        while let Some((index, position)) = queue.pop_front() {
+            component.cells.push(index);
+            component.min = component.min.min(position);
+            component.max = component.max.max(position);
+
+            for step in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+                let next = position + step;
+                let next_index = cell_index(next.x, next.y, cells);
+                if visited[next_index] || !include(next_index) || !same(index, next_index) {
+                    continue;
+                }
+
+                visited[next_index] = true;
+                queue.push_back((next_index, next));
+            }
+        }
+
+        components.push(component);
+    }
+
+    components
+}
+
+fn cells_per_side(settings: &WorldSettings) -> i32 {
+    (settings.world_size / REGION_CELL).max(1)
+}
+
+fn cell_index(cx: i32, cy: i32, cells: i32) -> usize {
+    (cy.rem_euclid(cells) * cells + cx.rem_euclid(cells)) as usize
+}
+
+// How each kind of region wraps its name, as in "Isle of {}", taken from the
+// player's language when the world is generated. The default leaves names bare,
+// for callers that only count regions.
+#[derive(Default)]
+pub struct RegionPatterns(Vec<(RegionKind, String)>);
+
+impl RegionPatterns {
+    pub fn new(locale: &Locale) -> Self {
+        let kinds = [
+            RegionKind::Continent,
+            RegionKind::Island,
+            RegionKind::Ocean,
+            RegionKind::Sea,
+            RegionKind::MountainRange,
+        ];
+        RegionPatterns(
+            kinds
+                .into_iter()
+                .map(|kind| (kind, locale.text(&format!("region.{:?}", kind)).to_string()))
+                .collect(),
+        )
+    }
+
+    fn apply(&self, kind: RegionKind, word: String) -> String {
+        match self.0.iter().find(|(pattern_kind, _)| *pattern_kind == kind) {
+            Some((_, pattern)) => pattern.replacen("{}", &word, 1),
+            None => word,
+        }
+    }
+}
+
+// Region names in the common tongue, never repeating within one world
+struct RegionNamer<'a> {
+    names: NameGenerator,
+    patterns: &'a RegionPatterns,
+}
+
+impl<'a> RegionNamer<'a> {
+    fn new(seed: u32, patterns: &'a RegionPatterns) -> Self {
+        RegionNamer {
+            names: NameGenerator::new(seed.wrapping_add(12) as u64),
+            patterns,
+        }
+    }
+
+    fn name(&mut self, kind: RegionKind) -> String {
+        let word = self.names.name(Culture::Common);
+        self.patterns.apply(kind, word)
+    }
+}
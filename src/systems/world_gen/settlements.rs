@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+use kingdom_worldgen::{
+    climate_square, is_below_sea_level, resources::ResourceNoise, sampler::WorldSampler,
+};
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
+use crate::components::{
+    kingdoms::Culture,
+    settlements::{FishingGrounds, Good, ProductionRates},
+    world::{Biome, ResourceKind, Square},
+    world_gen::WorldData,
+};
+use crate::systems::world::{WorldSettings, toroidal_distance};
+
+use super::{
+    names::{Landform, word},
+    regions::MOUNTAIN_ELEVATION,
+};
+
+const PLACEMENT_ATTEMPTS: u32 = 3000;
+// One settlement per this many tiles of world, up to the cap
+const TILES_PER_SETTLEMENT: i32 = 160 * 160;
+const MAX_SETTLEMENTS: usize = 32;
+pub const MIN_SETTLEMENT_SPACING: f32 = 60.0;
+// Founders want good land under their feet
+const MIN_SITE_FERTILITY: f32 = 0.5;
+// Farmland a settlement can work, sampled every few tiles
+const CATCHMENT_RADIUS: i32 = 12;
+const CATCHMENT_STEP: usize = 3;
+const FERTILE_SOIL_BONUS: f32 = 0.5;
+// People fed by one fully fertile sample of the catchment; a person eats one
+// unit of food a year, so this is also the sample's yearly grain
+const PEOPLE_PER_FERTILE_TILE: f32 = 60.0;
+// Yearly yield of one catchment sample for the other goods
+const FISH_PER_WATER_TILE: f32 = 20.0;
+const TIMBER_PER_FOREST_TILE: f32 = 15.0;
+const TIMBER_PER_TIMBER_DEPOSIT: f32 = 30.0;
+const STONE_PER_HIGHLAND_TILE: f32 = 5.0;
+const STONE_PER_STONE_DEPOSIT: f32 = 25.0;
+const ORE_PER_ORE_DEPOSIT: f32 = 20.0;
+
+pub struct SettlementSite {
+    pub name: String,
+    pub tile: IVec2,
+    pub food_capacity: f32,
+    pub production: ProductionRates,
+    pub fishing: FishingGrounds,
+}
+
+// Deterministic for a seed in the same way as `place_landmarks`, so a loaded or
+// streamed world gets the same settlements back
+pub fn place_settlements(world_data: &WorldData, settings: &WorldSettings) -> Vec<SettlementSite> {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let resource_noise = ResourceNoise::new(world_data, settings.world_size);
+    let mut rng = SmallRng::seed_from_u64(world_data.seed.wrapping_add(13) as u64);
+    let max_sites = ((settings.world_size * settings.world_size / TILES_PER_SETTLEMENT) as usize)
+        .clamp(1, MAX_SETTLEMENTS);
+    let mut sites: Vec<SettlementSite> = Vec::new();
+
+    for _attempt in 0..PLACEMENT_ATTEMPTS {
+        if sites.len() >= max_sites {
+            break;
+        }
+        let tile = IVec2::new(
+            rng.random_range(0..settings.world_size),
+            rng.random_range(0..settings.world_size),
+        );
+        let name = word(&mut rng, Culture::Common);
+
+        let too_close = sites
+            .iter()
+            .any(|site| toroidal_distance(tile, site.tile, settings) < MIN_SETTLEMENT_SPACING);
+        if too_close {
+            continue;
+        }
+
+        if let Some(site) = survey_catchment(&sampler, &resource_noise, world_data, tile, name) {
+            sites.push(site);
+        }
+    }
+
+    sites
+}
+
+// A single site away from world generation, for settlements founded in play
+pub fn survey_site(
+    world_data: &WorldData,
+    settings: &WorldSettings,
+    tile: IVec2,
+    name: String,
+) -> Option<SettlementSite> {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let resource_noise = ResourceNoise::new(world_data, settings.world_size);
+
+    survey_catchment(&sampler, &resource_noise, world_data, tile, name)
+}
+
+// The climate of one tile, as world generation left it
+pub fn survey_tile(world_data: &WorldData, settings: &WorldSettings, tile: IVec2) -> Square {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let tile = tile.rem_euclid(IVec2::splat(settings.world_size));
+
+    climate_square(&sampler, world_data, tile.x, tile.y)
+}
+
+// Every land tile within `radius` of a settlement bar its own, for choosing
+// where to clear farms
+pub fn survey_farmland(
+    world_data: &WorldData,
+    settings: &WorldSettings,
+    tile: IVec2,
+    radius: i32,
+) -> Vec<(IVec2, Square)> {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let size = IVec2::splat(settings.world_size);
+
+    (-radius..=radius)
+        .flat_map(|dy| (-radius..=radius).map(move |dx| IVec2::new(dx, dy)))
+        .filter(|offset| *offset != IVec2::ZERO)
+        .filter_map(|offset| {
+            let plot = (tile + offset).rem_euclid(size);
+            let square = climate_square(&sampler, world_data, plot.x, plot.y);
+            let land = !is_below_sea_level(square.elevation as f64, world_data.sea_threshold);
+            land.then_some((plot, square))
+        })
+        .collect()
+}
+
+// The sea right beside a tile, then mountains high enough to make a range on
+// the region map within reach of it, then forest over much of its catchment
+pub fn local_landform(world_data: &WorldData, settings: &WorldSettings, tile: IVec2) -> Landform {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let mut forest = 0;
+    let mut samples = 0;
+    let mut highland = false;
+    let mut coast = false;
+
+    for dy in (-CATCHMENT_RADIUS..=CATCHMENT_RADIUS).step_by(CATCHMENT_STEP) {
+        for dx in (-CATCHMENT_RADIUS..=CATCHMENT_RADIUS).step_by(CATCHMENT_STEP) {
+            let square = climate_square(&sampler, world_data, tile.x + dx, tile.y + dy);
+            let elevation = square.elevation as f64;
+            samples += 1;
+            if is_below_sea_level(elevation, world_data.sea_threshold) {
+                coast |= dx.abs().max(dy.abs()) <= CATCHMENT_STEP as i32;
+            } else if elevation > MOUNTAIN_ELEVATION {
+                highland = true;
+            } else if is_forest(square.biome) {
+                forest += 1;
+            }
+        }
+    }
+
+    if coast {
+        Landform::Coast
+    } else if highland {
+        Landform::Highland
+    } else if forest * 3 >= samples {
+        Landform::Forest
+    } else {
+        Landform::Plain
+    }
+}
+
+fn is_forest(biome: Biome) -> bool {
+    matches!(
+        biome,
+        Biome::Forest
+            | Biome::BorealForest
+            | Biome::Taiga
+            | Biome::TemperateForest
+            | Biome::TemperateRainforest
+            | Biome::SubtropicalForest
+            | Biome::TropicalRainforest
+    )
+}
+
+// Farmland, fishing water and deposits within reach of a tile, or None where no
+// one would settle: at sea or on poor land
+fn survey_catchment(
+    sampler: &WorldSampler,
+    resource_noise: &ResourceNoise,
+    world_data: &WorldData,
+    tile: IVec2,
+    name: String,
+) -> Option<SettlementSite> {
+    let square = climate_square(sampler, world_data, tile.x, tile.y);
+    if is_below_sea_level(square.elevation as f64, world_data.sea_threshold)
+        || world_data.biomes.fertility(square.biome) < MIN_SITE_FERTILITY
+    {
+        return None;
+    }
+
+    let mut fertility = 0.0;
+    let mut water_tiles = 0;
+    let mut production = ProductionRates::default();
+    for dy in (-CATCHMENT_RADIUS..=CATCHMENT_RADIUS).step_by(CATCHMENT_STEP) {
+        for dx in (-CATCHMENT_RADIUS..=CATCHMENT_RADIUS).step_by(CATCHMENT_STEP) {
+            let (x, y) = (tile.x + dx, tile.y + dy);
+            let square = climate_square(sampler, world_data, x, y);
+            if is_below_sea_level(square.elevation as f64, world_data.sea_threshold) {
+                water_tiles += 1;
+                continue;
+            }
+            fertility += world_data.biomes.fertility(square.biome);
+
+            let yields = match square.biome {
+                biome if is_forest(biome) => Some((Good::Timber, TIMBER_PER_FOREST_TILE)),
+                Biome::Hill | Biome::Mountain | Biome::Alpine => {
+                    Some((Good::Stone, STONE_PER_HIGHLAND_TILE))
+                }
+                _ => None,
+            };
+            if let Some((good, amount)) = yields {
+                production.0[good as usize] += amount;
+            }
+
+            let t_position = sampler.t_position(x, y);
+            let deposit = match resource_noise.resource_at(t_position, &square) {
+                ResourceKind::FertileSoil => {
+                    fertility += FERTILE_SOIL_BONUS;
+                    None
+                }
+                ResourceKind::Timber => Some((Good::Timber, TIMBER_PER_TIMBER_DEPOSIT)),
+                ResourceKind::Stone => Some((Good::Stone, STONE_PER_STONE_DEPOSIT)),
+                ResourceKind::Iron | ResourceKind::Gold => Some((Good::Ore, ORE_PER_ORE_DEPOSIT)),
+                ResourceKind::None => None,
+            };
+            if let Some((good, amount)) = deposit {
+                production.0[good as usize] += amount;
+            }
+        }
+    }
+
+    // Shoals start out fully stocked, like the rest of the wildlife
+    let fishing = FishingGrounds {
+        water_tiles,
+        full_yield: water_tiles as f32 * FISH_PER_WATER_TILE,
+        shoals: 1.0,
+    };
+    production.0[Good::Grain as usize] = fertility * PEOPLE_PER_FERTILE_TILE;
+    production.0[Good::Fish as usize] = fishing.full_yield;
+    let food_capacity = production.get(Good::Grain) + production.get(Good::Fish);
+    Some(SettlementSite {
+        name,
+        tile,
+        food_capacity,
+        production,
+        fishing,
+    })
+}
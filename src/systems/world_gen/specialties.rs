@@ -0,0 +1,102 @@
+use bevy::platform::collections::HashMap;
+use kingdom_worldgen::{climate_square, sampler::WorldSampler};
+
+use crate::components::{
+    world::{Biome, LandmarkKind, Region, RegionKind, TradeGood},
+    world_gen::WorldData,
+};
+use crate::systems::{parallel::*, world::WorldSettings};
+
+use super::landmarks::place_landmarks;
+
+// Biomes are tallied on every SAMPLE_STRIDE-th region cell along each axis
+const SAMPLE_STRIDE: i32 = 2;
+// Share of a land mass a biome must cover before its good counts as a local specialty
+const MIN_BIOME_SHARE: f32 = 0.2;
+// Each biome good comes from only this many regions, so most of the world has to trade for it
+const MAX_REGIONS_PER_GOOD: usize = 2;
+
+const BIOME_GOODS: [(TradeGood, &[Biome]); 5] = [
+    (TradeGood::Silk, &[Biome::SubtropicalForest]),
+    (TradeGood::Spices, &[Biome::TropicalRainforest]),
+    (TradeGood::Amber, &[Biome::BorealForest, Biome::Taiga]),
+    (TradeGood::Furs, &[Biome::Tundra]),
+    (TradeGood::Ivory, &[Biome::Savanna]),
+];
+
+// Gives land masses their rare goods: the regions where a good's biomes are most
+// widespread, plus whatever their landmarks yield (obsidian from volcanoes,
+// incense traded through desert oases)
+pub fn assign_specialties(
+    regions: &mut [Region],
+    area: &[Option<u32>],
+    cells: i32,
+    cell_size: i32,
+    world_data: &WorldData,
+    settings: &WorldSettings,
+) {
+    let sampler = WorldSampler::new(world_data, settings.world_size);
+    let is_land = |region: &Region| {
+        matches!(region.kind, RegionKind::Continent | RegionKind::Island)
+    };
+
+    let samples: Vec<(usize, Biome)> = (0..area.len())
+        .into_par_iter()
+        .filter(|&cell| {
+            let (cx, cy) = (cell as i32 % cells, cell as i32 / cells);
+            cx % SAMPLE_STRIDE == 0 && cy % SAMPLE_STRIDE == 0
+        })
+        .filter_map(|cell| {
+            let region = area[cell]? as usize;
+            if !is_land(&regions[region]) {
+                return None;
+            }
+
+            let x = (cell as i32 % cells) * cell_size + cell_size / 2;
+            let y = (cell as i32 / cells) * cell_size + cell_size / 2;
+            Some((region, climate_square(&sampler, world_data, x, y).biome))
+        })
+        .collect();
+
+    let mut sampled: HashMap<usize, u32> = HashMap::new();
+    let mut biome_counts: HashMap<(usize, Biome), u32> = HashMap::new();
+    for &(region, biome) in &samples {
+        *sampled.entry(region).or_insert(0) += 1;
+        *biome_counts.entry((region, biome)).or_insert(0) += 1;
+    }
+
+    for (good, biomes) in BIOME_GOODS {
+        let mut candidates: Vec<(usize, f32)> = sampled
+            .iter()
+            .map(|(&region, &total)| {
+                let covered: u32 = biomes
+                    .iter()
+                    .map(|&biome| biome_counts.get(&(region, biome)).copied().unwrap_or(0))
+                    .sum();
+                (region, covered as f32 / total as f32)
+            })
+            .filter(|&(_, share)| share >= MIN_BIOME_SHARE)
+            .collect();
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        for &(region, _) in candidates.iter().take(MAX_REGIONS_PER_GOOD) {
+            regions[region].specialties.push(good);
+        }
+    }
+
+    for landmark in place_landmarks(world_data, settings) {
+        let good = match landmark.kind {
+            LandmarkKind::Volcano => TradeGood::Obsidian,
+            LandmarkKind::Oasis => TradeGood::Incense,
+            LandmarkKind::CraterLake => continue,
+        };
+        let cell = (landmark.y / cell_size * cells + landmark.x / cell_size) as usize;
+
+        if let Some(region) = area[cell].map(|region| &mut regions[region as usize])
+            && is_land(region)
+            && !region.specialties.contains(&good)
+        {
+            region.specialties.push(good);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use kingdom_worldgen::{MAX_ELEVATION, climate_square, is_below_sea_level, sampler::WorldSampler};
+
+use crate::components::{
+    world::{Biome, RegionKind, Square, WorldMap},
+    world_gen::WorldData,
+};
+use crate::systems::{
+    parallel::*,
+    season::tile_latitude,
+    world::WorldSettings,
+};
+
+use super::regions::{RegionPatterns, identify_regions};
+
+// Tiles per side sampled for the figures; large worlds are read at a stride
+const SURVEY_SIDE: i32 = 256;
+pub const ELEVATION_BINS: usize = 10;
+// Twenty degrees of latitude each, from the south pole north
+pub const LATITUDE_BANDS: usize = 9;
+
+// Figures for judging a generated world's parameters at a glance
+pub struct WorldStatistics {
+    pub land_share: f32,
+    // Share of the world each biome covers, largest first, leaving out absent ones
+    pub biome_shares: Vec<(Biome, f32)>,
+    // Share of tiles in each equal step from the sea floor to the highest peak
+    pub elevation_histogram: [f32; ELEVATION_BINS],
+    // Mean yearly °C per latitude band; None for a band with no tiles
+    pub band_temperatures: [Option<f32>; LATITUDE_BANDS],
+    pub continents: usize,
+    pub islands: usize,
+    // The world's, for labelling elevations above and below it
+    pub sea_level: f32,
+}
+
+// Pregenerated worlds are read from the map; streamed worlds are surveyed from
+// the seed tile by tile, which leaves thermal erosion out
+pub fn world_statistics(
+    world_map: Option<&WorldMap>,
+    world_data: &WorldData,
+    settings: &WorldSettings,
+) -> WorldStatistics {
+    let size = settings.world_size;
+    let side = SURVEY_SIDE.min(size);
+    let step = size / side;
+    let sampler = world_map.is_none().then(|| WorldSampler::new(world_data, size));
+
+    let samples: Vec<(i32, Square)> = (0..side * side)
+        .into_par_iter()
+        .map(|index| {
+            let x = (index % side) * step;
+            let y = (index / side) * step;
+            let square = match world_map {
+                Some(world_map) => world_map.square((y * size + x) as usize),
+                None => {
+                    let sampler = sampler.as_ref().expect("streamed worlds are sampled");
+                    climate_square(sampler, world_data, x, y)
+                }
+            };
+            (y, square)
+        })
+        .collect();
+
+    let share = 1.0 / samples.len() as f32;
+    let mut land_share = 0.0;
+    let mut biome_shares = [0.0; Biome::ALL.len()];
+    let mut elevation_histogram = [0.0; ELEVATION_BINS];
+    let mut band_sums = [(0.0, 0); LATITUDE_BANDS];
+    for (y, square) in &samples {
+        if !is_below_sea_level(square.elevation as f64, world_data.sea_threshold) {
+            land_share += share;
+        }
+        biome_shares[square.biome as usize] += share;
+
+        let height = square.elevation / MAX_ELEVATION as f32;
+        let bin = (height * ELEVATION_BINS as f32) as usize;
+        elevation_histogram[bin.min(ELEVATION_BINS - 1)] += share;
+
+        let latitude = (tile_latitude(*y, size) + 1.0) / 2.0;
+        let band = ((latitude * LATITUDE_BANDS as f32) as usize).min(LATITUDE_BANDS - 1);
+        band_sums[band].0 += square.temperature;
+        band_sums[band].1 += 1;
+    }
+
+    let mut biome_shares: Vec<(Biome, f32)> = Biome::ALL
+        .into_iter()
+        .map(|biome| (biome, biome_shares[biome as usize]))
+        .filter(|&(_, share)| share > 0.0)
+        .collect();
+    biome_shares.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let regions = identify_regions(world_data, settings, &RegionPatterns::default()).regions;
+    let count = |kind| regions.iter().filter(|region| region.kind == kind).count();
+
+    WorldStatistics {
+        land_share,
+        biome_shares,
+        elevation_histogram,
+        band_temperatures: band_sums.map(|(sum, count)| (count > 0).then(|| sum / count as f32)),
+        continents: count(RegionKind::Continent),
+        islands: count(RegionKind::Island),
+        sea_level: world_data.sea_level() as f32,
+    }
+}
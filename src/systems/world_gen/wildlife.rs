@@ -0,0 +1,46 @@
+use kingdom_worldgen::{climate_square, sampler::WorldSampler};
+
+use crate::components::{wildlife::Species, world_gen::WorldData};
+use crate::systems::parallel::*;
+
+// Tiles sampled along each side of a cell
+const SAMPLES_PER_SIDE: i32 = 2;
+
+// Species and capacity of each cell of a grid, or None where nothing is hunted
+pub type Habitats = Vec<Option<(Species, f32)>>;
+
+// The species each cell of a `cell`-tile grid is home to and how many animals
+// it carries when left alone. A cell belongs to whichever species most of its
+// samples suit.
+pub fn survey_habitats(
+    world_data: &WorldData,
+    world_size: i32,
+    cells: i32,
+    cell: i32,
+) -> Habitats {
+    let sampler = WorldSampler::new(world_data, world_size);
+    let step = cell / SAMPLES_PER_SIDE;
+    let tiles_per_sample = (step * step) as f32;
+
+    (0..cells * cells)
+        .into_par_iter()
+        .map(|index| {
+            let mut found: Vec<(Species, f32)> = Vec::new();
+            for sy in 0..SAMPLES_PER_SIDE {
+                for sx in 0..SAMPLES_PER_SIDE {
+                    let x = index % cells * cell + sx * step + step / 2;
+                    let y = index / cells * cell + sy * step + step / 2;
+                    let square = climate_square(&sampler, world_data, x, y);
+                    let Some((species, density)) = Species::habitat(square.biome) else {
+                        continue;
+                    };
+                    match found.iter_mut().find(|(known, _)| *known == species) {
+                        Some((_, capacity)) => *capacity += density * tiles_per_sample,
+                        None => found.push((species, density * tiles_per_sample)),
+                    }
+                }
+            }
+            found.into_iter().max_by(|(_, a), (_, b)| a.total_cmp(b))
+        })
+        .collect()
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::settlements::{Population, Settlement};
+use crate::systems::localization::Locale;
+
+// Running totals for the world being played, kept in its save. The years it has
+// been simulated are its season's year, as every world starts in year 0.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldRecord {
+    pub wars_fought: u32,
+    pub peak_population: f32,
+    // Tiles cleared, farmed or built on
+    pub tiles_terraformed: u32,
+}
+
+impl WorldRecord {
+    pub fn lines(&self, years: u32, locale: &Locale) -> Vec<String> {
+        vec![
+            locale.format("record.years", &[&years]),
+            locale.format("record.wars", &[&self.wars_fought]),
+            locale.format(
+                "record.peak_population",
+                &[&format!("{:.0}", self.peak_population)],
+            ),
+            locale.format("record.terraformed", &[&self.tiles_terraformed]),
+        ]
+    }
+}
+
+pub fn tally_population(
+    mut record: ResMut<WorldRecord>,
+    settlement_query: Query<&Population, With<Settlement>>,
+) {
+    let population: f32 = settlement_query.iter().map(|population| population.0).sum();
+    if population > record.peak_population {
+        record.peak_population = population;
+    }
+}
+
+pub fn cleanup_world_record(mut record: ResMut<WorldRecord>) {
+    *record = WorldRecord::default();
+}
@@ -0,0 +1,255 @@
+use bevy::prelude::*;
+use kingdom_worldgen::MAX_ELEVATION;
+
+use crate::components::{
+    world::{Biome, WorldMap},
+    pause_menu::PausesSim,
+    world_gen::WorldData,
+    world_stats::WorldStatsPanel,
+};
+use crate::systems::{
+    keybindings::{InputAction, InputMap},
+    localization::Locale,
+    palette::MapPalette,
+    world::{METRES_PER_ELEVATION, MapOverlay, WorldSettings, heatmap_color},
+    world_gen::{
+        biome_table::Biomes,
+        statistics::{ELEVATION_BINS, LATITUDE_BANDS, WorldStatistics, world_statistics},
+    },
+};
+
+const PANEL_WIDTH: f32 = 640.0;
+// Length of a bar standing for the whole world
+const BAR_WIDTH: f32 = 160.0;
+const SWATCH_WIDTH: f32 = 16.0;
+const LAND_COLOR: Color = Color::srgb(0.6, 0.5, 0.35);
+
+pub fn setup_world_stats_panel(mut commands: Commands) {
+    commands.spawn((
+        WorldStatsPanel::default(),
+        PausesSim,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            left: Val::Percent(50.0),
+            width: Val::Px(PANEL_WIDTH),
+            margin: UiRect::left(Val::Px(-PANEL_WIDTH / 2.0)),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        Visibility::Hidden,
+    ));
+}
+
+pub fn toggle_world_stats_panel(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut panel_query: Single<&mut Visibility, With<WorldStatsPanel>>,
+) {
+    if input_map.just_pressed(&input, InputAction::ToggleWorldStats) {
+        let shown = **panel_query == Visibility::Hidden;
+        **panel_query = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// Surveys the world the first time the panel is shown, and writes the rows out
+// again if the language has changed since
+pub fn update_world_stats_panel(
+    mut commands: Commands,
+    locale: Res<Locale>,
+    (biomes, map_palette): (Res<Biomes>, Res<MapPalette>),
+    world_data: Single<&WorldData>,
+    settings: Res<WorldSettings>,
+    world_query: Query<&WorldMap>,
+    panel_query: Single<(Entity, &mut WorldStatsPanel, &Visibility)>,
+) {
+    let (entity, mut panel, visibility) = panel_query.into_inner();
+    if *visibility == Visibility::Hidden || panel.language.as_ref() == Some(&locale.code) {
+        return;
+    }
+
+    let stats = panel
+        .stats
+        .get_or_insert_with(|| world_statistics(world_query.single().ok(), &world_data, &settings));
+    let biome_color = |biome: Biome| {
+        let [r, g, b, _] = map_palette.palette.biome_color(biome, &biomes.table);
+        Color::srgb(r, g, b)
+    };
+
+    commands
+        .entity(entity)
+        .despawn_related::<Children>()
+        .with_children(|parent| {
+            parent.spawn(heading(locale.text("stats.title")));
+            parent.spawn(line(locale.format(
+                "stats.land",
+                &[
+                    &format!("{:.1}", stats.land_share * 100.0),
+                    &format!("{:.1}", (1.0 - stats.land_share) * 100.0),
+                ],
+            )));
+            parent.spawn(line(locale.format(
+                "stats.landmasses",
+                &[&(stats.continents + stats.islands), &stats.continents, &stats.islands],
+            )));
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(24.0),
+                    ..default()
+                })
+                .with_children(|columns| {
+                    columns.spawn(column()).with_children(|column| {
+                        column.spawn(heading(locale.text("stats.biomes")));
+                        for &(biome, share) in &stats.biome_shares {
+                            let label = format!("{} {:.1}%", locale.biome(biome), share * 100.0);
+                            column.spawn(bar_row(label, biome_color(biome), share * BAR_WIDTH));
+                        }
+                    });
+                    columns.spawn(column()).with_children(|column| {
+                        column.spawn(heading(locale.text("stats.elevation")));
+                        for row in elevation_rows(stats, &map_palette) {
+                            column.spawn(row);
+                        }
+                        column.spawn(heading(locale.text("stats.temperature")));
+                        for row in temperature_rows(stats, &locale) {
+                            column.spawn(row);
+                        }
+                    });
+                });
+        });
+    panel.language = Some(locale.code.clone());
+}
+
+// Land bins are labelled in metres above sea level and water bins below it,
+// matching the elevation overlay
+fn elevation_rows(stats: &WorldStatistics, map_palette: &MapPalette) -> Vec<impl Bundle> {
+    let sea_level = stats.sea_level;
+    let bin_height = MAX_ELEVATION as f32 / ELEVATION_BINS as f32;
+
+    (0..ELEVATION_BINS)
+        .map(|bin| {
+            let low = bin as f32 * bin_height;
+            let high = low + bin_height;
+            let metres = |elevation: f32| ((elevation - sea_level) * METRES_PER_ELEVATION).round();
+            let color = if high <= sea_level {
+                let [r, g, b, _] = map_palette
+                    .palette
+                    .ocean_color((low + high) / 2.0, sea_level);
+                Color::srgb(r, g, b)
+            } else {
+                LAND_COLOR
+            };
+            let share = stats.elevation_histogram[bin];
+            let label = format!(
+                "{} to {} m  {:.1}%",
+                metres(low),
+                metres(high),
+                share * 100.0
+            );
+            bar_row(label, color, share * BAR_WIDTH)
+        })
+        .collect()
+}
+
+// Northernmost band first, as on the map
+fn temperature_rows(stats: &WorldStatistics, locale: &Locale) -> Vec<impl Bundle> {
+    let scale = MapOverlay::Temperature
+        .heatmap_scale(stats.sea_level)
+        .unwrap();
+    let band_degrees = 180 / LATITUDE_BANDS as i32;
+    let latitude = |degrees: i32| match degrees {
+        0 => "0°".to_string(),
+        north if north > 0 => format!("{}°{}", north, locale.text("compass.north")),
+        south => format!("{}°{}", -south, locale.text("compass.south")),
+    };
+
+    (0..LATITUDE_BANDS)
+        .rev()
+        .map(|band| {
+            let south = band as i32 * band_degrees - 90;
+            let band_label = format!("{} to {}", latitude(south), latitude(south + band_degrees));
+            match stats.band_temperatures[band] {
+                Some(temperature) => {
+                    let t = (temperature - scale.min) / (scale.max - scale.min);
+                    let [r, g, b, _] = heatmap_color(t.clamp(0.0, 1.0));
+                    let label = format!("{}  {:.1}°C", band_label, temperature);
+                    bar_row(label, Color::srgb(r, g, b), SWATCH_WIDTH)
+                }
+                None => bar_row(band_label, Color::NONE, SWATCH_WIDTH),
+            }
+        })
+        .collect()
+}
+
+fn heading(text: &str) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.9, 0.6)),
+        Node {
+            margin: UiRect::top(Val::Px(6.0)),
+            ..default()
+        },
+    )
+}
+
+fn line(text: String) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+    )
+}
+
+fn column() -> Node {
+    Node {
+        flex_direction: FlexDirection::Column,
+        row_gap: Val::Px(2.0),
+        ..default()
+    }
+}
+
+// A bar, as long as the share of the world it stands for or a fixed colour
+// swatch, then its label
+fn bar_row(label: String, color: Color, width: f32) -> impl Bundle {
+    (
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(6.0),
+            ..default()
+        },
+        children![
+            (
+                Node {
+                    width: Val::Px(width.max(1.0)),
+                    height: Val::Px(10.0),
+                    ..default()
+                },
+                BackgroundColor(color),
+            ),
+            line(label),
+        ],
+    )
+}
+
+pub fn cleanup_world_stats_panel(
+    mut commands: Commands,
+    query: Query<Entity, With<WorldStatsPanel>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn();
+    }
+}
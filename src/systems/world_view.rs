@@ -0,0 +1,290 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    tasks::{AsyncComputeTaskPool, Task, futures::check_ready},
+};
+
+use crate::components::{
+    world_gen::WorldData,
+    world_view::{WorldViewCaption, WorldViewUI},
+};
+use crate::systems::{
+    keybindings::{InputAction, InputMap, key_name},
+    localization::Locale,
+    palette::MapPalette,
+    world::WorldSettings,
+    world_gen::overview::overview_colors,
+};
+
+// Samples per side of the cached biome overview the projections are drawn from
+const OVERVIEW_SIZE: usize = 256;
+const VIEW_WIDTH: u32 = 512;
+const VIEW_HEIGHT: u32 = 256;
+const MERIDIAN_STEP: f32 = 30.0;
+const SPACE_COLOR: [u8; 4] = [8, 8, 16, 255];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MapProjection {
+    // The torus cut open along the world edges: x is longitude, y latitude
+    #[default]
+    Equirectangular,
+    // One hemisphere as seen from space, centred on the equator
+    Azimuthal,
+}
+
+// Whole-world preview panel (M). Projection and central meridian are switchable;
+// the biome overview behind it is sampled once per world, off the main thread.
+#[derive(Resource)]
+pub struct WorldView {
+    projection: MapProjection,
+    // Degrees east of the world's left edge at the centre of the view
+    central_meridian: f32,
+    overview: Option<Vec<[u8; 4]>>,
+    task: Option<Task<Vec<[u8; 4]>>>,
+    image: Handle<Image>,
+    dirty: bool,
+}
+
+impl WorldView {
+    // Drops the overview so the next opening samples it in the current colours
+    pub fn forget_overview(&mut self) {
+        self.overview = None;
+        self.task = None;
+    }
+}
+
+pub fn setup_world_view(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = Image::new_fill(
+        Extent3d {
+            width: VIEW_WIDTH,
+            height: VIEW_HEIGHT,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &SPACE_COLOR,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    commands.insert_resource(WorldView {
+        projection: MapProjection::default(),
+        central_meridian: 180.0,
+        overview: None,
+        task: None,
+        image: images.add(image),
+        dirty: true,
+    });
+}
+
+pub fn toggle_world_view(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    (settings, map_palette, locale): (Res<WorldSettings>, Res<MapPalette>, Res<Locale>),
+    mut view: ResMut<WorldView>,
+    ui_query: Query<Entity, With<WorldViewUI>>,
+    world_data_query: Query<&WorldData>,
+) {
+    if !input_map.just_pressed(&input, InputAction::ToggleWorldView) {
+        return;
+    }
+
+    if !ui_query.is_empty() {
+        for entity in &ui_query {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    if view.overview.is_none() && view.task.is_none() {
+        let world_data = match world_data_query.single() {
+            Ok(data) => data.clone(),
+            Err(err) => {
+                error!("WorldData query failed: {:?}", err);
+                return;
+            }
+        };
+        let settings = *settings;
+        let palette = map_palette.palette.clone();
+        view.task = Some(AsyncComputeTaskPool::get().spawn(async move {
+            overview_colors(&world_data, &settings, &palette, OVERVIEW_SIZE)
+        }));
+    }
+
+    commands.spawn((
+        WorldViewUI,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        children![
+            (
+                WorldViewCaption,
+                Text::new(locale.text("world_view.sampling")),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ),
+            (
+                ImageNode::new(view.image.clone()),
+                Node {
+                    width: Val::Px(VIEW_WIDTH as f32),
+                    height: Val::Px(VIEW_HEIGHT as f32),
+                    ..default()
+                },
+            ),
+            (
+                Text::new(locale.format(
+                    "world_view.keys",
+                    &[
+                        &key_name(input_map.key(InputAction::WorldViewProjection)),
+                        &key_name(input_map.key(InputAction::WorldViewMeridianWest)),
+                        &key_name(input_map.key(InputAction::WorldViewMeridianEast)),
+                        &key_name(input_map.key(InputAction::ToggleWorldView)),
+                    ],
+                )),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ),
+        ],
+    ));
+}
+
+pub fn world_view_controls(
+    input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
+    mut view: ResMut<WorldView>,
+    ui_query: Query<(), With<WorldViewUI>>,
+) {
+    if ui_query.is_empty() {
+        return;
+    }
+
+    if input_map.just_pressed(&input, InputAction::WorldViewProjection) {
+        view.projection = match view.projection {
+            MapProjection::Equirectangular => MapProjection::Azimuthal,
+            MapProjection::Azimuthal => MapProjection::Equirectangular,
+        };
+        view.dirty = true;
+    }
+    if input_map.just_pressed(&input, InputAction::WorldViewMeridianWest) {
+        view.central_meridian = (view.central_meridian - MERIDIAN_STEP).rem_euclid(360.0);
+        view.dirty = true;
+    }
+    if input_map.just_pressed(&input, InputAction::WorldViewMeridianEast) {
+        view.central_meridian = (view.central_meridian + MERIDIAN_STEP).rem_euclid(360.0);
+        view.dirty = true;
+    }
+}
+
+pub fn update_world_view(
+    mut view: ResMut<WorldView>,
+    mut images: ResMut<Assets<Image>>,
+    mut caption_query: Query<&mut Text, With<WorldViewCaption>>,
+    locale: Res<Locale>,
+) {
+    if let Some(task) = view.task.as_mut()
+        && let Some(overview) = check_ready(task)
+    {
+        view.overview = Some(overview);
+        view.task = None;
+        view.dirty = true;
+    }
+
+    if !view.dirty || caption_query.is_empty() {
+        return;
+    }
+    let Some(overview) = view.overview.as_ref() else {
+        return;
+    };
+
+    let pixels = project(overview, view.projection, view.central_meridian);
+    if let Some(image) = images.get_mut(&view.image) {
+        image.data = Some(pixels);
+    }
+
+    let english = match view.projection {
+        MapProjection::Equirectangular => "Equirectangular",
+        MapProjection::Azimuthal => "Azimuthal",
+    };
+    let name = locale.name("projection", view.projection, english);
+    let longitude = longitude_label(view.central_meridian, &locale);
+    for mut text in &mut caption_query {
+        text.0 = locale.format("world_view.caption", &[&name, &longitude]);
+    }
+    view.dirty = false;
+}
+
+pub fn cleanup_world_view(mut commands: Commands, ui_query: Query<Entity, With<WorldViewUI>>) {
+    for entity in &ui_query {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<WorldView>();
+}
+
+// Draws the overview into an RGBA buffer of VIEW_WIDTH×VIEW_HEIGHT, top row first
+fn project(overview: &[[u8; 4]], projection: MapProjection, central_meridian: f32) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity((VIEW_WIDTH * VIEW_HEIGHT * 4) as usize);
+    let meridian = central_meridian.to_radians();
+
+    for row in 0..VIEW_HEIGHT {
+        for column in 0..VIEW_WIDTH {
+            // Pixel centre in [-1, 1], y pointing up
+            let u = (column as f32 + 0.5) / VIEW_WIDTH as f32 * 2.0 - 1.0;
+            let v = 1.0 - (row as f32 + 0.5) / VIEW_HEIGHT as f32 * 2.0;
+
+            let lon_lat = match projection {
+                MapProjection::Equirectangular => Some((meridian + u * PI, v * FRAC_PI_2)),
+                MapProjection::Azimuthal => {
+                    // A disc filling the view's height, squeezed back to a circle horizontally
+                    let x = u * VIEW_WIDTH as f32 / VIEW_HEIGHT as f32;
+                    let depth_squared = 1.0 - x * x - v * v;
+                    (depth_squared >= 0.0)
+                        .then(|| (meridian + x.atan2(depth_squared.sqrt()), v.asin()))
+                }
+            };
+
+            let color = match lon_lat {
+                Some((lon, lat)) => overview_at(overview, lon, lat),
+                None => SPACE_COLOR,
+            };
+            pixels.extend_from_slice(&color);
+        }
+    }
+
+    pixels
+}
+
+// Longitude runs once around the world's x axis and latitude from the bottom
+// edge (-90°) to the top (90°), matching `WorldSampler::signed_latitude`
+fn overview_at(overview: &[[u8; 4]], lon: f32, lat: f32) -> [u8; 4] {
+    let size = OVERVIEW_SIZE as f32;
+    let x = (lon.rem_euclid(TAU) / TAU * size) as usize % OVERVIEW_SIZE;
+    let y = (((lat / FRAC_PI_2 + 1.0) / 2.0 * size) as usize).min(OVERVIEW_SIZE - 1);
+
+    overview[y * OVERVIEW_SIZE + x]
+}
+
+fn longitude_label(central_meridian: f32, locale: &Locale) -> String {
+    let longitude = central_meridian - 180.0;
+    if longitude < 0.0 {
+        locale.format("world_view.west", &[&format!("{:.0}", -longitude)])
+    } else {
+        locale.format("world_view.east", &[&format!("{:.0}", longitude)])
+    }
+}